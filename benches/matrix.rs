@@ -0,0 +1,34 @@
+//! Benchmarks multiplying square matrices, which switches to the blocked algorithm for larger sizes.
+//!
+//! Run with `cargo bench --bench matrix`.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mathvis::api::matrix::Matrix;
+
+/// Creates a square matrix with deterministic values.
+fn matrix(size: usize) -> Matrix<f64> {
+    Matrix::new(
+        (0..size)
+            .map(|i| {
+                (0..size)
+                    .map(|j| ((i * 31 + j * 17) % 101) as f64 / 101.0)
+                    .collect()
+            })
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn bench_multiply(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matrix_multiply");
+    group.sample_size(10);
+    for size in [16, 64, 256, 512] {
+        let (a, b) = (matrix(size), matrix(size));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |bench, _| {
+            bench.iter(|| (a.clone() * b.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_multiply);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+//! Benchmarks rendering a short 4K animation with different numbers of worker threads.
+//!
+//! Run with `cargo bench`, the frames are written to a temporary directory.
+use std::{
+    fs::{create_dir_all, remove_dir_all},
+    sync::{Arc, Mutex},
+    thread::available_parallelism,
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use imageproc::image::Rgb;
+use mathvis::{
+    animation::{renderer::Renderer, show::Show2D, vector::Vector2D},
+    api::{
+        point::{Point, PointLike},
+        screen::Screen2D,
+    },
+};
+
+/// Renders 12 frames at 3840x2160 with the specified number of worker threads.
+fn render_4k(directory: &str, jobs: usize) {
+    let mut screen = Screen2D::new(
+        (-5.0, 5.0),
+        (-5.0, 5.0),
+        directory.to_string(),
+        60,
+        3840,
+        2160,
+    )
+    .unwrap();
+    screen.set_quiet(true);
+    screen.set_jobs(jobs).unwrap();
+    let screen = Arc::new(Mutex::new(screen));
+
+    let mut vector = Vector2D::new(1.0, 0.0, Rgb([255, 136, 0]));
+    vector.add_context(screen.clone()).unwrap();
+    Renderer::new(screen)
+        .unwrap()
+        .play(&vector.move_to(0.2, Point::new(vec![3.0, 2.0]).unwrap()))
+        .unwrap();
+}
+
+fn bench_jobs(c: &mut Criterion) {
+    let directory = std::env::temp_dir().join("mathvis-bench");
+    create_dir_all(directory.join("tmp")).unwrap();
+    let directory = directory.to_str().unwrap().to_string();
+
+    let cores = available_parallelism().map_or(1, |cores| cores.get());
+    let mut group = c.benchmark_group("render_4k");
+    group.sample_size(10);
+    // One thread per frame of a second was the old default
+    let mut counts = vec![1, cores, 60];
+    counts.dedup();
+    for jobs in counts {
+        group.bench_with_input(BenchmarkId::from_parameter(jobs), &jobs, |b, &jobs| {
+            b.iter(|| render_4k(&directory, jobs))
+        });
+    }
+    group.finish();
+
+    remove_dir_all(&directory).unwrap();
+}
+
+criterion_group!(benches, bench_jobs);
+criterion_main!(benches);
@@ -0,0 +1,310 @@
+//! Python bindings of mathvis, exposing screens, scenes, vectors, matrices and their animations,
+//! so scenes can be scripted from Python while they're rendered in Rust.
+//!
+//! ```python
+//! from mathvis_py import Matrix, Vector2D, quick_scene
+//!
+//! scene = quick_scene((-3.0, 3.0), (-3.0, 3.0), "low", 30, "./output")
+//! v = Vector2D(1.0, 0.0, (255, 0, 0))
+//! scene.add(v)
+//! scene.play(v.move_to(1.0, 2.0, 2.0).then(v.multiply_by_matrix(1.0, Matrix.rotation(1.57))))
+//! ```
+use imageproc::image::Rgb;
+use mathvis::{
+    animation::{clip::Animation, show::Show2D, vector::Vector2D},
+    api::{
+        matrix::Matrix,
+        point::{Point, PointLike},
+        screen::Screen2D,
+        util::Quality,
+    },
+    error::MathvisError,
+    scene::{quick_scene as scene_quick_scene, Scene},
+};
+use pyo3::{
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+};
+
+/// Converts an error of the renderer into the closest Python exception.
+fn to_py_err(err: MathvisError) -> PyErr {
+    match err {
+        MathvisError::Io(err) => PyIOError::new_err(err.to_string()),
+        err => PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// Parses the name of a quality, like the CLI does.
+fn parse_quality(quality: &str) -> PyResult<Quality> {
+    match quality {
+        "low" => Ok(Quality::LOW),
+        "medium" => Ok(Quality::MEDIUM),
+        "high" => Ok(Quality::HIGH),
+        "ultra" => Ok(Quality::ULTRA),
+        _ => Err(PyValueError::new_err(format!(
+            "Unknown quality {}, expected low, medium, high or ultra.",
+            quality
+        ))),
+    }
+}
+
+fn point(x: f64, y: f64) -> PyResult<Point<f64>> {
+    Point::new(vec![x, y]).map_err(to_py_err)
+}
+
+/// A 2D screen, with the options of a [Screen2D] builder.
+#[pyclass(name = "Screen")]
+#[derive(Clone)]
+struct PyScreen {
+    inner: Screen2D,
+}
+
+#[pymethods]
+impl PyScreen {
+    #[new]
+    #[pyo3(signature = (x_axis = (-3.0, 3.0), y_axis = (-3.0, 3.0), quality = "high", fps = 30, save_directory = ".", background = None))]
+    fn new(
+        x_axis: (f32, f32),
+        y_axis: (f32, f32),
+        quality: &str,
+        fps: u32,
+        save_directory: &str,
+        background: Option<(u8, u8, u8)>,
+    ) -> PyResult<Self> {
+        let mut builder = Screen2D::builder()
+            .x_axis(x_axis.0, x_axis.1)
+            .y_axis(y_axis.0, y_axis.1)
+            .quality(parse_quality(quality)?)
+            .fps(fps)
+            .save_directory(save_directory);
+        if let Some((r, g, b)) = background {
+            builder = builder.background(Rgb([r, g, b]));
+        }
+        Ok(PyScreen {
+            inner: builder.build().map_err(to_py_err)?,
+        })
+    }
+
+    /// Hides the progress bar while rendering.
+    fn set_quiet(&mut self, quiet: bool) {
+        self.inner.set_quiet(quiet);
+    }
+
+    #[getter]
+    fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    #[getter]
+    fn height(&self) -> u32 {
+        self.inner.height()
+    }
+}
+
+/// A scene played on a screen, which objects are added to and animations are played on.
+#[pyclass(name = "Scene")]
+struct PyScene {
+    inner: Scene,
+}
+
+#[pymethods]
+impl PyScene {
+    #[new]
+    fn new(screen: &PyScreen) -> PyResult<Self> {
+        Ok(PyScene {
+            inner: Scene::new(screen.inner.clone()).map_err(to_py_err)?,
+        })
+    }
+
+    /// Adds a vector to the scene, failing if it doesn't fit on the screen.
+    fn add(&self, vector: &mut PyVector2D) -> PyResult<()> {
+        self.inner.add(&mut vector.inner).map_err(to_py_err)
+    }
+
+    /// Renders an animation after everything played so far, releasing the GIL while the frames are drawn.
+    fn play(&self, py: Python<'_>, animation: &PyAnimation) -> PyResult<()> {
+        let animation = animation.inner.clone();
+        py.allow_threads(|| self.inner.play(&animation))
+            .map_err(to_py_err)
+    }
+
+    #[getter]
+    fn frame_count(&self) -> PyResult<u32> {
+        self.inner.frame_count().map_err(to_py_err)
+    }
+}
+
+/// Creates a scene with the specified axes, quality and fps, saving its frames in the output directory.
+#[pyfunction]
+#[pyo3(signature = (x_axis, y_axis, quality = "high", fps = 30, output_dir = "."))]
+fn quick_scene(
+    x_axis: (f32, f32),
+    y_axis: (f32, f32),
+    quality: &str,
+    fps: u32,
+    output_dir: &str,
+) -> PyResult<PyScene> {
+    Ok(PyScene {
+        inner: scene_quick_scene((x_axis, y_axis), parse_quality(quality)?, fps, output_dir)
+            .map_err(to_py_err)?,
+    })
+}
+
+/// A deferred animation, rendered when it's played on a scene.
+#[pyclass(name = "Animation")]
+#[derive(Clone)]
+struct PyAnimation {
+    inner: Animation,
+}
+
+#[pymethods]
+impl PyAnimation {
+    /// Returns an animation that plays this one and then the next one.
+    fn then(&self, next: &PyAnimation) -> PyAnimation {
+        PyAnimation {
+            inner: self.inner.clone().then(next.inner.clone()),
+        }
+    }
+
+    /// Returns an animation that plays this one and the other one at the same time, `with` being a Python keyword.
+    fn with_(&self, other: &PyAnimation) -> PyAnimation {
+        PyAnimation {
+            inner: self.inner.clone().with(other.inner.clone()),
+        }
+    }
+
+    #[getter]
+    fn duration(&self) -> f32 {
+        self.inner.duration()
+    }
+}
+
+/// A 2D vector drawn from the origin, whose methods return animations.
+#[pyclass(name = "Vector2D")]
+struct PyVector2D {
+    inner: Vector2D<f64>,
+}
+
+#[pymethods]
+impl PyVector2D {
+    #[new]
+    #[pyo3(signature = (x, y, color = (255, 255, 255)))]
+    fn new(x: f64, y: f64, color: (u8, u8, u8)) -> Self {
+        PyVector2D {
+            inner: Vector2D::new(x, y, Rgb([color.0, color.1, color.2])),
+        }
+    }
+
+    #[getter]
+    fn x(&self) -> f64 {
+        self.inner.x()
+    }
+
+    #[getter]
+    fn y(&self) -> f64 {
+        self.inner.y()
+    }
+
+    fn move_to(&self, duration: f32, x: f64, y: f64) -> PyResult<PyAnimation> {
+        Ok(PyAnimation {
+            inner: self.inner.move_to(duration, point(x, y)?),
+        })
+    }
+
+    #[pyo3(signature = (duration, angle, center = (0.0, 0.0)))]
+    fn rotate(&self, duration: f32, angle: f64, center: (f64, f64)) -> PyResult<PyAnimation> {
+        Ok(PyAnimation {
+            inner: self
+                .inner
+                .rotate(duration, angle, point(center.0, center.1)?),
+        })
+    }
+
+    fn wait(&self, duration: f32) -> PyAnimation {
+        PyAnimation {
+            inner: self.inner.wait(duration),
+        }
+    }
+
+    fn multiply_by_matrix(&self, duration: f32, matrix: &PyMatrix) -> PyResult<PyAnimation> {
+        Ok(PyAnimation {
+            inner: self
+                .inner
+                .multiply_by_matrix(duration, matrix.inner.clone())
+                .map_err(to_py_err)?,
+        })
+    }
+}
+
+/// A matrix of floats, created from its rows.
+#[pyclass(name = "Matrix")]
+#[derive(Clone)]
+struct PyMatrix {
+    inner: Matrix<f64>,
+}
+
+#[pymethods]
+impl PyMatrix {
+    #[new]
+    fn new(rows: Vec<Vec<f64>>) -> PyResult<Self> {
+        Ok(PyMatrix {
+            inner: Matrix::new(rows).map_err(to_py_err)?,
+        })
+    }
+
+    #[staticmethod]
+    fn identity(dimensions: usize) -> PyResult<Self> {
+        Ok(PyMatrix {
+            inner: Matrix::identity(dimensions)
+                .ok_or_else(|| PyValueError::new_err("The dimensions must be positive."))?,
+        })
+    }
+
+    /// Returns the matrix of a counterclockwise rotation by an angle in radians.
+    #[staticmethod]
+    fn rotation(angle: f32) -> Self {
+        PyMatrix {
+            inner: Matrix::<f32>::rotation_matrix_2d(angle).map(|val| val as f64),
+        }
+    }
+
+    fn rows(&self) -> Vec<Vec<f64>> {
+        self.inner.to_rows()
+    }
+
+    fn determinant(&self) -> PyResult<f64> {
+        self.inner.determinant().map_err(to_py_err)
+    }
+
+    fn transpose(&self) -> PyMatrix {
+        PyMatrix {
+            inner: self.inner.transpose(),
+        }
+    }
+
+    fn __mul__(&self, other: &PyMatrix) -> PyResult<PyMatrix> {
+        Ok(PyMatrix {
+            inner: (&self.inner * &other.inner).map_err(to_py_err)?,
+        })
+    }
+
+    fn __eq__(&self, other: &PyMatrix) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Matrix({:?})", self.inner.to_rows())
+    }
+}
+
+/// Python module of the bindings.
+#[pymodule]
+fn mathvis_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyScreen>()?;
+    module.add_class::<PyScene>()?;
+    module.add_class::<PyAnimation>()?;
+    module.add_class::<PyVector2D>()?;
+    module.add_class::<PyMatrix>()?;
+    module.add_function(wrap_pyfunction!(quick_scene, module)?)?;
+    Ok(())
+}
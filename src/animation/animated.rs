@@ -0,0 +1,285 @@
+//! Module containing animated values, which change over time through keyframes and can stand in for any parameter of an object,
+//! like the radius of a circle, a color or an entry of a matrix.
+#![warn(missing_docs)]
+use imageproc::image::Rgb;
+
+use super::{clip::Animation, drawable::Drawable, easing::Easing};
+use crate::{
+    api::{angle::Angle, color::Color, matrix::Matrix, transform::Transform2D},
+    error::MathvisError,
+};
+
+/// Trait for values that can be blended smoothly from one to another, which is what an [Animated] value needs.
+pub trait Interpolate: Clone {
+    /// Returns the value a fraction `t` of the way from this one to the other, this one at 0 and the other at 1.
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+
+    /// Returns whether the value can be blended with the other one, like matrices with the same dimensions.
+    fn compatible(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t as f32
+    }
+}
+
+impl Interpolate for (f64, f64) {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        (
+            self.0.interpolate(&other.0, t),
+            self.1.interpolate(&other.1, t),
+        )
+    }
+}
+
+impl Interpolate for Angle {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        *self + (*other - *self) * t
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(*other, t as f32)
+    }
+}
+
+impl Interpolate for Rgb<u8> {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Color::from(*self)
+            .interpolate(&Color::from(*other), t)
+            .into()
+    }
+}
+
+impl Interpolate for Matrix<f64> {
+    /// Blends the matrices entry by entry.
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Matrix {
+            values: self
+                .values
+                .iter()
+                .zip(&other.values)
+                .map(|(a, b)| a.interpolate(b, t))
+                .collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    fn compatible(&self, other: &Self) -> bool {
+        self.get_dimensions() == other.get_dimensions()
+    }
+}
+
+impl Interpolate for Transform2D {
+    /// Blends the transforms like [Transform2D::lerp], separating their rotations from their scales and shears.
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(other, t)
+    }
+}
+
+/// A value that changes over time, starting from an initial value and moving through keyframes,
+/// each reached at its time in seconds with its own easing on the way there.
+///
+/// Before the first keyframe the value moves from the initial value, which it has at time 0,
+/// and after the last one it stays at the last keyframe's value.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::{animated::Animated, easing::Easing, geometry::Circle, updater::Updatable};
+///
+/// let radius = Animated::new(1.0)
+///     .with_keyframe(1.0, 2.0, Easing::Linear)
+///     .unwrap()
+///     .with_keyframe(3.0, 0.5, Easing::EaseInOut)
+///     .unwrap();
+/// assert_eq!(radius.at(0.5), 1.5);
+/// assert_eq!(radius.at(2.0), 1.25);
+/// assert_eq!(radius.at(10.0), 0.5);
+///
+/// // Drawn as it changes, for as long as it takes to reach the last keyframe
+/// let animation = radius.animate(|radius| Circle::new((0.0, 0.0), radius).unwrap());
+/// assert_eq!(animation.duration(), 3.0);
+///
+/// // Or through an updater, where it can be combined with other values
+/// let circle = Circle::new((0.0, 0.0), 1.0)
+///     .unwrap()
+///     .add_updater(move |circle, t| *circle = Circle::new((0.0, 0.0), radius.at(t)).unwrap());
+/// assert_eq!(circle.at(3.0).radius(), 0.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animated<T> {
+    initial: T,
+    keyframes: Vec<Keyframe<T>>,
+}
+
+/// A value an [Animated] value reaches at a time, and the easing it gets there with.
+#[derive(Debug, Clone, PartialEq)]
+struct Keyframe<T> {
+    time: f64,
+    value: T,
+    easing: Easing,
+}
+
+impl<T: Interpolate> Animated<T> {
+    /// Creates a value that stays the same until keyframes are added.
+    pub fn new(initial: T) -> Self {
+        Animated {
+            initial,
+            keyframes: Vec::new(),
+        }
+    }
+
+    /// Adds a keyframe, the value reached at a time in seconds, eased on the way from the keyframe before it.
+    /// Keyframes can be added in any order, and a keyframe at the same time as another one replaces it.
+    ///
+    /// Returns an Err if the time is negative or not finite or the value can't be blended with the initial value,
+    /// like a matrix with different dimensions, and an Ok otherwise.
+    pub fn with_keyframe(
+        mut self,
+        time: f64,
+        value: T,
+        easing: Easing,
+    ) -> Result<Self, MathvisError> {
+        if !time.is_finite() || time < 0.0 {
+            return Err(MathvisError::InvalidArgument(format!(
+                "Keyframes can't be at a negative time, but one was at {}.",
+                time
+            )));
+        }
+        if !self.initial.compatible(&value) {
+            return Err(MathvisError::DimensionMismatch(
+                "Every keyframe of an animated value must have the same dimensions as its initial value.".into(),
+            ));
+        }
+        let keyframe = Keyframe {
+            time,
+            value,
+            easing,
+        };
+        match self
+            .keyframes
+            .binary_search_by(|other| other.time.total_cmp(&time))
+        {
+            Ok(i) => self.keyframes[i] = keyframe,
+            Err(i) => self.keyframes.insert(i, keyframe),
+        }
+        Ok(self)
+    }
+
+    /// Returns the value at time 0, before any keyframe.
+    pub fn initial(&self) -> &T {
+        &self.initial
+    }
+
+    /// Returns the time in seconds of the last keyframe, after which the value stops changing, or 0 if there are none.
+    pub fn duration(&self) -> f64 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// Returns the value at the specified time in seconds.
+    pub fn at(&self, time: f64) -> T {
+        let next = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time);
+        let (start, value) = match next.checked_sub(1) {
+            Some(i) => (self.keyframes[i].time, &self.keyframes[i].value),
+            None => (0.0, &self.initial),
+        };
+        let Some(keyframe) = self.keyframes.get(next) else {
+            return value.clone();
+        };
+        let progress = (time - start) / (keyframe.time - start);
+        value.interpolate(&keyframe.value, keyframe.easing.apply(progress))
+    }
+
+    /// Returns the value on the specified frame, counted from time 0, at the specified fps.
+    pub fn at_frame(&self, frame: u32, fps: u32) -> T {
+        self.at(frame as f64 / fps as f64)
+    }
+
+    /// Returns an animation drawing the object built from the value on every frame, lasting until the last keyframe.
+    pub fn animate<D, F>(&self, build: F) -> Animation
+    where
+        T: Send + Sync + 'static,
+        D: Drawable,
+        F: Fn(T) -> D + Send + Sync + 'static,
+    {
+        let (value, duration) = (self.clone(), self.duration());
+        Animation::new(duration as f32, move |progress, canvas, _, map| {
+            build(value.at(progress * duration)).draw(canvas, map)
+        })
+    }
+}
+
+impl<T: Interpolate> From<T> for Animated<T> {
+    /// Returns a value that never changes.
+    fn from(value: T) -> Self {
+        Animated::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_animated() {
+        // Keyframes out of order are sorted, and a keyframe at the same time replaces the old one
+        let position = Animated::new((0.0, 0.0))
+            .with_keyframe(2.0, (4.0, 2.0), Easing::Linear)
+            .unwrap()
+            .with_keyframe(1.0, (0.0, 0.0), Easing::Linear)
+            .unwrap()
+            .with_keyframe(1.0, (2.0, 0.0), Easing::EaseIn)
+            .unwrap();
+        assert_eq!(position.at(-1.0), (0.0, 0.0));
+        assert_eq!(position.at(0.5), (0.25, 0.0));
+        assert_eq!(position.at(1.0), (2.0, 0.0));
+        assert_eq!(position.at_frame(15, 10), (3.0, 1.0));
+        assert_eq!(position.at(3.0), (4.0, 2.0));
+        assert!(position
+            .clone()
+            .with_keyframe(f64::NAN, (0.0, 0.0), Easing::Linear)
+            .is_err());
+
+        // A keyframe at time 0 replaces the initial value right away
+        let color = Animated::from(Rgb([0, 0, 0]))
+            .with_keyframe(0.0, Rgb([255, 255, 255]), Easing::Linear)
+            .unwrap();
+        assert_eq!(color.at(0.0), Rgb([255, 255, 255]));
+        assert_eq!(Animated::new(Angle::ZERO).duration(), 0.0);
+
+        let identity = Matrix::<f64>::identity(2).unwrap();
+        let matrix = Animated::new(identity.clone())
+            .with_keyframe(
+                1.0,
+                Matrix::new(vec![vec![3.0, 2.0], vec![0.0, 1.0]]).unwrap(),
+                Easing::Linear,
+            )
+            .unwrap();
+        assert_eq!(
+            matrix.at(0.5),
+            Matrix::new(vec![vec![2.0, 1.0], vec![0.0, 1.0]]).unwrap()
+        );
+        assert!(matches!(
+            Animated::new(identity).with_keyframe(
+                1.0,
+                Matrix::<f64>::identity(3).unwrap(),
+                Easing::Linear
+            ),
+            Err(MathvisError::DimensionMismatch(_))
+        ));
+    }
+}
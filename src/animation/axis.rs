@@ -1,24 +1,21 @@
 //! Module containing functions for drawing axes on the screen.
 //! Should not be used outside of the internal API for now.
-use std::sync::Arc;
+use std::f32::consts::{PI, TAU};
 
-use imageproc::{
-    drawing::{draw_line_segment, draw_line_segment_mut, draw_polygon_mut, Canvas},
-    image::{GenericImageView, Rgb, RgbImage},
-    point::Point,
-};
+use imageproc::{image::Rgb, point::Point};
 
+use super::canvas::Canvas;
 use crate::api::{
     point::PointLike,
-    screen::{Screen2D, ScreenLike},
-    util::{interpolate, Number, Quality},
+    screen::{AxisStyle, Screen2D, ScreenLike, ScreenMode, TickMode},
+    util::CoordinateMap,
 };
 
-fn draw_lines(img: &mut RgbImage, color: Rgb<u8>, screen: Arc<Screen2D>, quality: Quality) {
+fn draw_lines<C: Canvas + ?Sized>(img: &mut C, color: Rgb<u8>, map: &CoordinateMap) {
+    let quality = map.quality();
     let usable_res = quality.usable();
-    let center = screen.get_center_pixels();
-    draw_line_segment_mut(
-        img,
+    let center = map.center();
+    img.draw_line(
         (
             center.0,
             quality.resolution().values()[1] - usable_res.values()[1],
@@ -26,8 +23,7 @@ fn draw_lines(img: &mut RgbImage, color: Rgb<u8>, screen: Arc<Screen2D>, quality
         (center.0, usable_res.values()[1]),
         color,
     );
-    draw_line_segment_mut(
-        img,
+    img.draw_line(
         (
             quality.resolution().values()[0] - usable_res.values()[0],
             center.1,
@@ -37,12 +33,12 @@ fn draw_lines(img: &mut RgbImage, color: Rgb<u8>, screen: Arc<Screen2D>, quality
     );
 }
 
-fn draw_arrow_tips(img: &mut RgbImage, color: Rgb<u8>, screen: Arc<Screen2D>, quality: Quality) {
-    let center = screen.get_center_pixels();
+fn draw_arrow_tips<C: Canvas + ?Sized>(img: &mut C, color: Rgb<u8>, map: &CoordinateMap) {
+    let quality = map.quality();
+    let center = map.center();
     let usable = quality.usable();
 
-    draw_polygon_mut(
-        img,
+    img.draw_polygon(
         &[
             Point::new(usable.values()[0] as i32, center.1 as i32),
             Point::new(usable.values()[0] as i32 - 20, center.1 as i32 + 10),
@@ -50,8 +46,7 @@ fn draw_arrow_tips(img: &mut RgbImage, color: Rgb<u8>, screen: Arc<Screen2D>, qu
         ],
         color,
     );
-    draw_polygon_mut(
-        img,
+    img.draw_polygon(
         &[
             Point::new(
                 center.0 as i32,
@@ -70,33 +65,176 @@ fn draw_arrow_tips(img: &mut RgbImage, color: Rgb<u8>, screen: Arc<Screen2D>, qu
     );
 }
 
-fn draw_markers(img: &mut RgbImage, color: Rgb<u8>, screen: Arc<Screen2D>, quality: Quality) {
-    let (xstart, xend) = (
-        ScreenLike::<f32>::x_axis(&*screen).0.ceil() as i32 + 1,
-        ScreenLike::<f32>::x_axis(&*screen).1.floor() as i32 - 1,
+fn draw_markers<C: Canvas + ?Sized>(
+    img: &mut C,
+    style: AxisStyle,
+    map: &CoordinateMap,
+    screen: &Screen2D,
+) {
+    let (xs, ys) = (x_ticks(screen, style.tick_mode), y_ticks(screen));
+    for (x, _) in xs {
+        let (x, y) = map.to_pixels((x, 0.0));
+        img.draw_line((x, y - 10.0), (x, y + 10.0), style.color);
+    }
+    for y in ys {
+        let (x, y) = map.to_pixels((0.0, y as f32));
+        img.draw_line((x - 10.0, y), (x + 10.0, y), style.color);
+    }
+}
+
+/// Returns the coordinates of the ticks on the x axis with their labels, leaving out the origin and the ends of the axis.
+fn x_ticks(screen: &Screen2D, mode: TickMode) -> Vec<(f32, String)> {
+    let (start, end) = ScreenLike::<f32>::x_axis(screen);
+    match mode {
+        TickMode::Integer => (start.ceil() as i32 + 1..=end.floor() as i32 - 1)
+            .filter(|&tick| tick != 0)
+            .map(|tick| (tick as f32, tick.to_string()))
+            .collect(),
+        TickMode::Pi => {
+            // Ticks keep the same distance from the ends as the integer ones
+            let step = PI / 2.0;
+            (((start + 1.0) / step).ceil() as i32..=((end - 1.0) / step).floor() as i32)
+                .filter(|&halves| halves != 0)
+                .map(|halves| (halves as f32 * step, pi_label(halves)))
+                .collect()
+        }
+    }
+}
+
+/// Returns the integer coordinates of the ticks on the y axis, leaving out the origin and the ends of the axis.
+fn y_ticks(screen: &Screen2D) -> Vec<i32> {
+    let (start, end) = ScreenLike::<f32>::y_axis(screen);
+    (start.ceil() as i32 + 1..=end.floor() as i32 - 1)
+        .filter(|&tick| tick != 0)
+        .collect()
+}
+
+/// Returns the label of a multiple of π/2 from the number of halves of π, like `3π/2` or `-π`.
+fn pi_label(halves: i32) -> String {
+    let sign = if halves < 0 { "-" } else { "" };
+    match (halves.unsigned_abs(), halves % 2 == 0) {
+        (2, _) => format!("{}π", sign),
+        (1, _) => format!("{}π/2", sign),
+        (n, true) => format!("{}{}π", sign, n / 2),
+        (n, false) => format!("{}{}π/2", sign, n),
+    }
+}
+
+/// Draws the labels of the ticks on the x axis below them.
+fn draw_x_labels<C: Canvas + ?Sized>(
+    img: &mut C,
+    style: AxisStyle,
+    map: &CoordinateMap,
+    screen: &Screen2D,
+) {
+    let size = map.quality().resolution().values()[1] * 0.03;
+    for (x, label) in x_ticks(screen, style.tick_mode) {
+        let (px, py) = map.to_pixels((x, 0.0));
+        // Labels are roughly centered, as wide as half their height per character
+        let width = label.chars().count() as f32 * size / 2.0;
+        img.draw_text(
+            &label,
+            (px - width / 2.0, py + size * 0.6),
+            size,
+            style.color,
+        );
+    }
+}
+
+/// Draws the unit circle, the labels of the real and imaginary axes and the labels of their ticks.
+fn draw_complex_plane<C: Canvas + ?Sized>(
+    img: &mut C,
+    style: AxisStyle,
+    map: &CoordinateMap,
+    screen: &Screen2D,
+) {
+    // Dimmer than the axes, so the circle stays in the background
+    let Rgb([r, g, b]) = style.color;
+    let circle_color = Rgb([r / 2, g / 2, b / 2]);
+    let segments = 120;
+    for i in 0..segments {
+        let (a0, a1) = (
+            TAU * i as f32 / segments as f32,
+            TAU * (i + 1) as f32 / segments as f32,
+        );
+        img.draw_line(
+            map.to_pixels((a0.cos(), a0.sin())),
+            map.to_pixels((a1.cos(), a1.sin())),
+            circle_color,
+        );
+    }
+
+    let size = map.quality().resolution().values()[1] * 0.03;
+    let usable = map.quality().usable();
+    let resolution = map.quality().resolution();
+    let center = map.center();
+    img.draw_text(
+        "Re",
+        (usable.values()[0] - size * 1.5, center.1 + size),
+        size,
+        style.color,
     );
-    let (ystart, yend) = (
-        ScreenLike::<f32>::y_axis(&*screen).0.ceil() as i32 + 1,
-        ScreenLike::<f32>::y_axis(&*screen).1.floor() as i32 - 1,
+    img.draw_text(
+        "Im",
+        (center.0 + size, resolution.values()[1] - usable.values()[1]),
+        size,
+        style.color,
     );
+    if !style.ticks {
+        return;
+    }
+    draw_x_labels(img, style, map, screen);
+    for y in y_ticks(screen) {
+        let label = match y {
+            1 => String::from("i"),
+            -1 => String::from("-i"),
+            y => format!("{}i", y),
+        };
+        let (px, py) = map.to_pixels((0.0, y as f32));
+        img.draw_text(
+            &label,
+            (px + size * 0.6, py - size / 2.0),
+            size,
+            style.color,
+        );
+    }
+}
 
-    let pairs: Vec<(f32, f32)> = (ystart..=yend)
-        .flat_map(move |y| (xstart..=xend).map(move |x| (x as f32, y as f32)))
-        .filter(|(x, y)| (*x == 0.0 || *y == 0.0) && *x != *y)
-        .collect();
-    for pair in pairs {
-        let (x, y) = interpolate(quality.clone(), screen.clone(), pair);
-        if pair.1 == 0.0 {
-            draw_line_segment_mut(img, (x, y - 10.0), (x, y + 10.0), color);
-        } else {
-            draw_line_segment_mut(img, (x - 10.0, y), (x + 10.0, y), color);
-        }
+pub(crate) fn draw_axis<C: Canvas + ?Sized>(
+    img: &mut C,
+    style: AxisStyle,
+    map: &CoordinateMap,
+    screen: &Screen2D,
+) {
+    if !style.visible {
+        return;
+    }
+    draw_lines(img, style.color, map);
+    draw_arrow_tips(img, style.color, map);
+    if style.ticks {
+        draw_markers(img, style, map, screen);
+    }
+    if screen.mode == ScreenMode::ComplexPlane {
+        draw_complex_plane(img, style, map, screen);
+    } else if style.ticks && style.tick_mode == TickMode::Pi {
+        draw_x_labels(img, style, map, screen);
     }
 }
 
-pub(crate) fn draw_axis(img: &mut RgbImage, color: Rgb<u8>, screen: Arc<Screen2D>) {
-    let quality = Quality::new(img.width(), img.height()).unwrap();
-    draw_lines(img, color, screen.clone(), quality);
-    draw_arrow_tips(img, color, screen.clone(), quality);
-    draw_markers(img, color, screen, quality);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pi_ticks() {
+        let screen = Screen2D::new((-5.0, 8.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let labels: Vec<String> = x_ticks(&screen, TickMode::Pi)
+            .into_iter()
+            .map(|(_, label)| label)
+            .collect();
+        assert_eq!(labels, ["-π", "-π/2", "π/2", "π", "3π/2", "2π"]);
+        assert_eq!(x_ticks(&screen, TickMode::Integer).len(), 11);
+        assert_eq!(pi_label(-5), "-5π/2");
+        assert_eq!(pi_label(6), "3π");
+    }
 }
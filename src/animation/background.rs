@@ -2,14 +2,28 @@
 //! Currently should not be used outside of internal API.
 use imageproc::{
     drawing::draw_filled_rect_mut,
-    image::{Rgb, RgbImage},
+    image::{Rgb, RgbImage, Rgba, RgbaImage},
     rect::Rect,
 };
 
-pub(crate) fn fill_background(img: &mut RgbImage) {
+/// Default color used to fill the background of every frame.
+pub(crate) const BACKGROUND_COLOR: Rgb<u8> = Rgb([43, 42, 51]);
+
+pub(crate) fn fill_background(img: &mut RgbImage, color: Rgb<u8>) {
     draw_filled_rect_mut(
         img,
         Rect::at(0, 0).of_size(img.width(), img.height()),
-        Rgb([43, 42, 51]),
+        color,
     );
 }
+
+/// Converts a frame into an RGBA image where every pixel of the background color is fully transparent.
+///
+/// Works because nothing is anti-aliased, so any pixel that still has the exact background color was never drawn over.
+pub(crate) fn remove_background(img: &RgbImage, background: Rgb<u8>) -> RgbaImage {
+    RgbaImage::from_fn(img.width(), img.height(), |x, y| {
+        let Rgb([r, g, b]) = *img.get_pixel(x, y);
+        let alpha = if Rgb([r, g, b]) == background { 0 } else { 255 };
+        Rgba([r, g, b, alpha])
+    })
+}
@@ -0,0 +1,422 @@
+//! Module containing slope fields and high level animations that visualize calculus, drawing every object they need on their own.
+#![warn(missing_docs)]
+use std::sync::Arc;
+
+use imageproc::{image::Rgb, point::Point};
+
+use super::{
+    canvas::Canvas, clip::Animation, drawable::Drawable, field::dot_center, graph::FunctionGraph,
+    path::Path2D,
+};
+use crate::{
+    api::{
+        bounds::BoundingBox, calculus::nth_derivative, ode::solve, point::PointLike,
+        util::CoordinateMap,
+    },
+    error::MathvisError,
+};
+
+/// Highest degree of the Taylor polynomials, past which the numerical derivatives are too noisy to be useful.
+pub const MAX_TAYLOR_DEGREE: u32 = 15;
+/// Color of the graph of the function being approximated.
+const FUNCTION_COLOR: Rgb<u8> = Rgb([200, 200, 200]);
+/// Color of the Taylor polynomials.
+const POLYNOMIAL_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+/// Color of the solutions drawn over a slope field by default.
+const SOLUTION_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+/// Number of RK4 steps per unit along x the solutions of a slope field are solved with.
+const STEPS_PER_UNIT: f64 = 100.0;
+
+/// The slope y' = f(x, y) of a first order differential equation at every point (x, y).
+pub type Slope = Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>;
+
+/// The slope field of a differential equation y' = f(x, y), drawn as a grid of short segments over a rectangle of the screen,
+/// each one with the slope of the solution through its center, optionally with some of the solutions drawn over it.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::calculus::SlopeField;
+///
+/// // y' = y, whose solutions are the exponentials c·e^x
+/// let field = SlopeField::new(|_, y| y, (-3.0, -3.0), (3.0, 3.0), Rgb([90, 90, 90]))
+///     .unwrap()
+///     .with_solutions(vec![(0.0, 0.5), (0.0, -1.0)])
+///     .unwrap();
+/// let solution = field.solution((0.0, 1.0)).unwrap();
+/// // It stops once it leaves the rectangle, at y = 3
+/// let &(x, y) = solution.last().unwrap();
+/// assert!((x - 3f64.ln()).abs() < 0.02 && y >= 3.0);
+/// assert!(field.with_spacing(0.0).is_err());
+/// ```
+#[derive(Clone)]
+pub struct SlopeField {
+    slope: Slope,
+    bottom_left: (f64, f64),
+    top_right: (f64, f64),
+    spacing: f64,
+    color: Rgb<u8>,
+    solutions: Vec<Vec<(f64, f64)>>,
+    solution_color: Rgb<u8>,
+}
+
+impl SlopeField {
+    /// Creates the slope field of y' = f(x, y) drawn over the rectangle between `bottom_left` and `top_right`,
+    /// with segments every half unit.
+    ///
+    /// Returns an Err if the corners don't make a rectangle and an Ok with the field otherwise.
+    pub fn new<F>(
+        slope: F,
+        bottom_left: (f64, f64),
+        top_right: (f64, f64),
+        color: Rgb<u8>,
+    ) -> Result<Self, MathvisError>
+    where
+        F: Fn(f64, f64) -> f64 + Send + Sync + 'static,
+    {
+        if bottom_left.0 >= top_right.0 || bottom_left.1 >= top_right.1 {
+            return Err(MathvisError::InvalidArgument(
+                "The bottom left corner of a slope field must be below and to the left of its top right corner."
+                    .into(),
+            ));
+        }
+        Ok(SlopeField {
+            slope: Arc::new(slope),
+            bottom_left,
+            top_right,
+            spacing: 0.5,
+            color,
+            solutions: Vec::new(),
+            solution_color: SOLUTION_COLOR,
+        })
+    }
+
+    /// Sets the distance between the centers of the segments, in screen units.
+    ///
+    /// Returns an Err if the spacing isn't positive and an Ok with the field otherwise.
+    pub fn with_spacing(mut self, spacing: f64) -> Result<Self, MathvisError> {
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The spacing of a slope field must be positive.".into(),
+            ));
+        }
+        self.spacing = spacing;
+        Ok(self)
+    }
+
+    /// Sets the points whose solutions are drawn over the field, each one across the whole width of the rectangle.
+    /// The solutions are solved once here, like in [SlopeField::solution], instead of on every frame.
+    ///
+    /// Returns an Err if any point is outside the rectangle and an Ok with the field otherwise.
+    pub fn with_solutions(mut self, starts: Vec<(f64, f64)>) -> Result<Self, MathvisError> {
+        self.solutions = starts
+            .into_iter()
+            .map(|start| self.solution(start))
+            .collect::<Result<_, _>>()?;
+        Ok(self)
+    }
+
+    /// Sets the color the solutions are drawn with.
+    pub fn with_solution_color(mut self, color: Rgb<u8>) -> Self {
+        self.solution_color = color;
+        self
+    }
+
+    /// Returns the slope of the field at a point.
+    pub fn slope(&self, x: f64, y: f64) -> f64 {
+        (self.slope)(x, y)
+    }
+
+    /// Returns the points of the solution through a point, from left to right, solved with RK4 both ways from it
+    /// and stopping at the first point that leaves the rectangle or isn't finite.
+    /// Solutions only exist up to vertical tangents, where y stops being a function of x, so the points past one aren't meaningful.
+    ///
+    /// Returns an Err if the point is outside the rectangle and an Ok with the solution otherwise.
+    pub fn solution(&self, start: (f64, f64)) -> Result<Vec<(f64, f64)>, MathvisError> {
+        if !BoundingBox::new(self.bottom_left, self.top_right).contains_point(start) {
+            return Err(MathvisError::OutOfBounds(format!(
+                "A solution of a slope field must start inside it, but {:?} is outside.",
+                start
+            )));
+        }
+        let half = |end: f64| -> Result<Vec<(f64, f64)>, MathvisError> {
+            let steps = ((end - start.0).abs() * STEPS_PER_UNIT).ceil() as u32;
+            if steps == 0 {
+                return Ok(Vec::new());
+            }
+            let solution = solve(
+                |x, y: &[f64]| vec![(self.slope)(x, y[0])],
+                start.0,
+                vec![start.1],
+                end,
+                steps,
+            )?;
+            let mut points = Vec::new();
+            // Keeps the first point outside, so the curve reaches the edge of the rectangle
+            for (x, y) in solution.into_iter().skip(1).map(|(x, y)| (x, y[0])) {
+                if !y.is_finite() {
+                    break;
+                }
+                points.push((x, y));
+                if y < self.bottom_left.1 || y > self.top_right.1 {
+                    break;
+                }
+            }
+            Ok(points)
+        };
+        let mut points = half(self.bottom_left.0)?;
+        points.reverse();
+        points.push(start);
+        points.extend(half(self.top_right.0)?);
+        Ok(points)
+    }
+
+    /// Returns the centers of the segments, on the multiples of the spacing inside the rectangle.
+    fn grid(&self) -> Vec<(f64, f64)> {
+        let steps = |start: f64, end: f64| {
+            let first = (start / self.spacing).ceil() as i64;
+            let last = (end / self.spacing).floor() as i64;
+            (first..=last).map(|i| i as f64 * self.spacing)
+        };
+        steps(self.bottom_left.1, self.top_right.1)
+            .flat_map(|y| steps(self.bottom_left.0, self.top_right.0).map(move |x| (x, y)))
+            .collect()
+    }
+}
+
+impl Drawable for SlopeField {
+    /// Returns the center of the rectangle the field is drawn over.
+    fn position(&self) -> (f64, f64) {
+        BoundingBox::new(self.bottom_left, self.top_right).center()
+    }
+
+    /// Returns the rectangle the field is drawn over, widened by half the spacing, which is as far as the segments reach.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        Some(BoundingBox::new(self.bottom_left, self.top_right).expanded(self.spacing / 2.0))
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        // Segments are a bit shorter than the spacing, so neighbours don't touch
+        let half = 0.4 * self.spacing;
+        for (x, y) in self.grid() {
+            let slope = (self.slope)(x, y);
+            let (dx, dy) = if slope.is_infinite() {
+                (0.0, 1.0)
+            } else if slope.is_finite() {
+                let length = slope.hypot(1.0);
+                (1.0 / length, slope / length)
+            } else {
+                continue;
+            };
+            canvas.draw_line(
+                map.to_pixels(((x - dx * half) as f32, (y - dy * half) as f32)),
+                map.to_pixels(((x + dx * half) as f32, (y + dy * half) as f32)),
+                self.color,
+            );
+        }
+        Path2D::new(self.solutions.clone(), self.solution_color).draw(canvas, map)
+    }
+}
+
+/// Returns the coefficients of the Taylor polynomial of the specified degree of a function around a center,
+/// from the constant term up, computed from central finite differences.
+///
+/// Each derivative uses the step size that balances truncation and rounding errors for its order,
+/// so the coefficients are accurate to a few digits up to [MAX_TAYLOR_DEGREE].
+///
+/// Returns an Err if the degree is higher than [MAX_TAYLOR_DEGREE] and an Ok with the coefficients otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::calculus::taylor_coefficients;
+///
+/// // e^x = 1 + x + x²/2 + x³/6 + ...
+/// let coefficients = taylor_coefficients(f64::exp, 0.0, 3).unwrap();
+/// for (coefficient, expected) in coefficients.iter().zip([1.0, 1.0, 0.5, 1.0 / 6.0]) {
+///     assert!((coefficient - expected).abs() < 1e-4);
+/// }
+/// assert!(taylor_coefficients(f64::exp, 0.0, 16).is_err());
+/// ```
+pub fn taylor_coefficients<F>(f: F, center: f64, degree: u32) -> Result<Vec<f64>, MathvisError>
+where
+    F: Fn(f64) -> f64,
+{
+    if degree > MAX_TAYLOR_DEGREE {
+        return Err(MathvisError::InvalidArgument(format!(
+            "Taylor polynomials can have a degree of at most {}, but {} was requested.",
+            MAX_TAYLOR_DEGREE, degree
+        )));
+    }
+    let mut factorial = 1.0;
+    Ok((0..=degree)
+        .map(|n| {
+            if n > 0 {
+                factorial *= n as f64;
+            }
+            nth_derivative(&f, center, n) / factorial
+        })
+        .collect())
+}
+
+/// Evaluates a polynomial given by its coefficients around a center, from the constant term up.
+fn evaluate(coefficients: &[f64], center: f64, x: f64) -> f64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0.0, |sum, coefficient| sum * (x - center) + coefficient)
+}
+
+/// Returns an animation of the specified duration showing the Taylor polynomials of a function around a center,
+/// from degree 0 up to the specified degree, converging onto the graph of the function.
+///
+/// Every term is added smoothly while the previous polynomial morphs into the next one,
+/// and the degree of the polynomial is shown as a counter in the top left corner.
+///
+/// Returns an Err if the degree is higher than [MAX_TAYLOR_DEGREE] and an Ok with the animation otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::calculus::taylor;
+///
+/// let animation = taylor(f64::sin, 0.0, 9, 6.0).unwrap();
+/// assert_eq!(animation.duration(), 6.0);
+/// ```
+pub fn taylor<F>(f: F, center: f64, degree: u32, duration: f32) -> Result<Animation, MathvisError>
+where
+    F: Fn(f64) -> f64 + Send + Sync + 'static,
+{
+    let coefficients = taylor_coefficients(&f, center, degree)?;
+    let function = FunctionGraph::new(f, FUNCTION_COLOR);
+    Ok(Animation::new(duration, move |progress, canvas, _, map| {
+        function.draw(canvas, map)?;
+        // Degree 0 is shown from the start, and every other degree takes an equal part of the animation
+        let steps = progress * degree as f64;
+        let shown = (steps.floor() as u32).min(degree);
+        let step = steps - shown as f64;
+        let mut polynomial = coefficients[..=shown as usize].to_vec();
+        if shown < degree {
+            polynomial.push(coefficients[shown as usize + 1] * step);
+        }
+        FunctionGraph::new(move |x| evaluate(&polynomial, center, x), POLYNOMIAL_COLOR)
+            .draw(canvas, map)?;
+        draw_center(canvas, map, (center, function.evaluate(center)));
+
+        let counter = if step >= 0.5 && shown < degree {
+            shown + 1
+        } else {
+            shown
+        };
+        let resolution = map.quality().resolution();
+        let usable = map.quality().usable();
+        let size = resolution.values()[1] * 0.05;
+        canvas.draw_text(
+            &format!("n = {}", counter),
+            (
+                resolution.values()[0] - usable.values()[0],
+                resolution.values()[1] - usable.values()[1],
+            ),
+            size,
+            POLYNOMIAL_COLOR,
+        );
+        Ok(())
+    }))
+}
+
+/// Draws a small diamond on the point of the graph the polynomials are centered on.
+fn draw_center(canvas: &mut dyn Canvas, map: &CoordinateMap, (x, y): (f64, f64)) {
+    let r = 6;
+    let Some((px, py)) = dot_center(canvas, map.to_pixels((x as f32, y as f32)), r) else {
+        return;
+    };
+    canvas.draw_polygon(
+        &[
+            Point::new(px + r, py),
+            Point::new(px, py + r),
+            Point::new(px - r, py),
+            Point::new(px, py - r),
+        ],
+        POLYNOMIAL_COLOR,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_taylor_coefficients() {
+        // sin around π/2 is cos around 0: 1 - x²/2 + x⁴/24
+        let coefficients = taylor_coefficients(f64::sin, std::f64::consts::FRAC_PI_2, 4).unwrap();
+        for (coefficient, expected) in coefficients.iter().zip([1.0, 0.0, -0.5, 0.0, 1.0 / 24.0]) {
+            assert!((coefficient - expected).abs() < 1e-4);
+        }
+        let coefficients = taylor_coefficients(f64::exp, 0.0, MAX_TAYLOR_DEGREE).unwrap();
+        assert!((evaluate(&coefficients, 0.0, 1.0) - 1f64.exp()).abs() < 1e-3);
+        // A polynomial is its own Taylor polynomial
+        let cubic = |x: f64| 2.0 * x * x * x - x + 3.0;
+        let coefficients = taylor_coefficients(cubic, 1.0, 3).unwrap();
+        assert!((evaluate(&coefficients, 1.0, -2.0) - cubic(-2.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_slope_field() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let gray = Rgb([90, 90, 90]);
+        // y' = -x / y, whose solutions are circles around the origin, vertical on the x axis
+        let circles = SlopeField::new(|x, y| -x / y, (-2.0, -2.0), (2.0, 2.0), gray)
+            .unwrap()
+            .with_solutions(vec![(0.0, 1.5)])
+            .unwrap();
+        let solution = circles.solution((0.0, 1.5)).unwrap();
+        // Solutions as functions of x only exist up to the vertical tangents at x = ±1.5
+        assert!(solution
+            .iter()
+            .filter(|(x, _)| x.abs() < 1.4)
+            .all(|(x, y)| (x.hypot(*y) - 1.5).abs() < 1e-6));
+        assert!(circles.solution((3.0, 0.0)).is_err());
+        assert!(circles
+            .clone()
+            .with_solutions(vec![(0.0, 1.0), (3.0, 0.0)])
+            .is_err());
+
+        let mut img = RgbImage::new(854, 480);
+        circles.draw(&mut img, &map).unwrap();
+        // Segments are horizontal on the y axis and vertical on the x axis
+        let (x, y) = map.to_pixels((0.15, 1.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), gray);
+        let (x, y) = map.to_pixels((1.0, 0.15));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), gray);
+        let corner = 1.5 * std::f32::consts::FRAC_1_SQRT_2;
+        let (x, y) = map.to_pixels((corner, corner));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), SOLUTION_COLOR);
+    }
+
+    #[test]
+    fn test_taylor() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let animation = taylor(f64::cos, 0.0, 2, 1.0).unwrap();
+        let mut img = RgbImage::new(854, 480);
+        // The constant term is a horizontal line at y = 1
+        animation.draw_frame(0, &mut img, &screen, &map).unwrap();
+        let (x, y) = map.to_pixels((-2.5, 1.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), POLYNOMIAL_COLOR);
+        // By the end it's the parabola 1 - x²/2
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        let (x, y) = map.to_pixels((2.0, -1.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), POLYNOMIAL_COLOR);
+        // The center is far above the screen
+        taylor(|x| 1e10 + x, 0.0, 1, 1.0)
+            .unwrap()
+            .draw_frame(9, &mut img, &screen, &map)
+            .unwrap();
+        assert!(taylor(f64::cos, 0.0, MAX_TAYLOR_DEGREE + 1, 1.0).is_err());
+    }
+}
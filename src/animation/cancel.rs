@@ -0,0 +1,73 @@
+//! Module containing cancellation tokens, which stop a render that's in progress from another thread, like a Ctrl-C handler.
+#![warn(missing_docs)]
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::error::MathvisError;
+
+/// A flag shared between a render and whatever may want to stop it.
+///
+/// Once a screen's token is cancelled (see [Screen2D::set_cancellation](crate::api::screen::Screen2D::set_cancellation)),
+/// frames that haven't started drawing are skipped and the renderer returns [MathvisError::Cancelled]
+/// instead of scheduling the animation, so no more frames are saved. Clones share the same flag.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::cancel::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let handle = token.clone();
+/// std::thread::spawn(move || handle.cancel()).join().unwrap();
+/// assert!(token.is_cancelled());
+/// assert!(token.check().is_err());
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that isn't cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels everything the token was given to. Cancelling more than once does nothing.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns an Err if the token was cancelled and an Ok otherwise, to stop work with `?`.
+    pub fn check(&self) -> Result<(), MathvisError> {
+        match self.is_cancelled() {
+            true => Err(MathvisError::Cancelled),
+            false => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl PartialEq for CancellationToken {
+    /// Tokens are equal when they share the same flag, like clones of each other.
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+    }
+}
@@ -0,0 +1,133 @@
+//! Module containing the drawing surface that the axes and every object are drawn on.
+#![warn(missing_docs)]
+use imageproc::{
+    drawing::{draw_line_segment_mut, draw_polygon_mut},
+    image::{Rgb, RgbImage},
+    point::Point,
+};
+
+use super::{
+    background::fill_background,
+    text::{font, text_pixels},
+};
+
+/// A surface that frames are drawn on, in pixel coordinates with the origin on the top left corner.
+///
+/// Images are drawn on directly on the CPU, other implementations can record the primitives and rasterize them elsewhere.
+pub trait Canvas {
+    /// Returns the width of the canvas in pixels.
+    fn width(&self) -> u32;
+    /// Returns the height of the canvas in pixels.
+    fn height(&self) -> u32;
+    /// Fills the whole canvas with a color.
+    fn fill(&mut self, color: Rgb<u8>);
+    /// Draws a one pixel wide line segment between two points.
+    fn draw_line(&mut self, start: (f32, f32), end: (f32, f32), color: Rgb<u8>);
+    /// Draws a filled convex polygon, whose first and last points must be different.
+    fn draw_polygon(&mut self, points: &[Point<i32>], color: Rgb<u8>);
+    /// Draws a single line of text with its top left corner at the specified position and the specified height in pixels.
+    ///
+    /// Nothing is drawn if there's no font available.
+    fn draw_text(&mut self, text: &str, position: (f32, f32), size: f32, color: Rgb<u8>) {
+        if let Some(font) = font() {
+            text_pixels(font, text, position, size, |x, y| {
+                let pixel = (x as f32, y as f32);
+                self.draw_line(pixel, pixel, color);
+            });
+        }
+    }
+}
+
+impl Canvas for RgbImage {
+    fn width(&self) -> u32 {
+        RgbImage::width(self)
+    }
+
+    fn height(&self) -> u32 {
+        RgbImage::height(self)
+    }
+
+    fn fill(&mut self, color: Rgb<u8>) {
+        fill_background(self, color);
+    }
+
+    fn draw_line(&mut self, start: (f32, f32), end: (f32, f32), color: Rgb<u8>) {
+        if let Some((start, end)) = clip_line(self, start, end, 1.0) {
+            draw_line_segment_mut(self, start, end, color);
+        }
+    }
+
+    fn draw_polygon(&mut self, points: &[Point<i32>], color: Rgb<u8>) {
+        draw_polygon_mut(self, points, color);
+    }
+}
+
+/// Returns the part of a line segment within the specified number of pixels of the canvas,
+/// or None if it doesn't reach that far or isn't finite.
+///
+/// Endpoints far away from the canvas are past the pixels imageproc can step between, so segments are cut down first.
+/// The intersections are computed in f64, so they stay accurate unless the endpoints are extremely far away.
+pub(crate) fn clip_line<C: Canvas + ?Sized>(
+    canvas: &C,
+    start: (f32, f32),
+    end: (f32, f32),
+    margin: f32,
+) -> Option<((f32, f32), (f32, f32))> {
+    let (x0, y0) = (start.0 as f64, start.1 as f64);
+    let (dx, dy) = (end.0 as f64 - x0, end.1 as f64 - y0);
+    if !(x0.is_finite() && y0.is_finite() && dx.is_finite() && dy.is_finite()) {
+        return None;
+    }
+    let (width, height, margin) = (canvas.width() as f64, canvas.height() as f64, margin as f64);
+    // Liang-Barsky, against every side of the widened canvas
+    let (mut t0, mut t1) = (0.0, 1.0);
+    for (p, q) in [
+        (-dx, x0 + margin),
+        (dx, width + margin - x0),
+        (-dy, y0 + margin),
+        (dy, height + margin - y0),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = f64::max(t0, q / p);
+        } else {
+            t1 = f64::min(t1, q / p);
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    // Clamped, since the rounding errors of far away endpoints can land the ends past the sides they're cut at
+    let at = |t: f64| {
+        (
+            (x0 + t * dx).clamp(-margin, width + margin) as f32,
+            (y0 + t * dy).clamp(-margin, height + margin) as f32,
+        )
+    };
+    Some((at(t0), at(t1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_line() {
+        let mut img = RgbImage::new(10, 10);
+        assert_eq!(
+            clip_line(&img, (1.0, 2.0), (5.0, 6.0), 1.0),
+            Some(((1.0, 2.0), (5.0, 6.0)))
+        );
+        let (start, end) = clip_line(&img, (-1e12, 5.0), (1e12, 5.0), 1.0).unwrap();
+        assert_eq!((start.0.round(), end.0.round()), (-1.0, 11.0));
+        assert_eq!(clip_line(&img, (20.0, 0.0), (30.0, 5.0), 1.0), None);
+        assert!(clip_line(&img, (20.0, 0.0), (30.0, 5.0), 10.0).is_some());
+        assert_eq!(clip_line(&img, (0.0, 0.0), (f32::NAN, 5.0), 1.0), None);
+        // Huge lines are drawn without overflowing
+        img.draw_line((5.0, -3e12), (5.0, 3e12), Rgb([255, 255, 255]));
+        assert_eq!(*img.get_pixel(5, 5), Rgb([255, 255, 255]));
+    }
+}
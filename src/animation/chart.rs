@@ -0,0 +1,141 @@
+//! Module containing bar charts, drawn as bars standing on the x axis.
+#![warn(missing_docs)]
+use imageproc::{image::Rgb, point::Point};
+
+use super::{canvas::Canvas, drawable::Drawable};
+use crate::{
+    api::{bounds::BoundingBox, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// A bar chart, with bars centered on x coordinates and rising from the x axis to their heights, or down for negative heights.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::{chart::BarChart, drawable::Drawable};
+///
+/// let chart = BarChart::new(vec![(0.0, 1.0), (1.0, 2.5), (2.0, 0.5)], Rgb([0, 200, 255]));
+/// assert_eq!(chart.position(), (1.0, 0.0));
+/// assert!(chart.with_width(0.0).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarChart {
+    bars: Vec<(f64, f64)>,
+    width: f64,
+    color: Rgb<u8>,
+}
+
+impl BarChart {
+    /// Creates a chart with bars at the specified x coordinates and heights, 0.8 units wide.
+    pub fn new(bars: Vec<(f64, f64)>, color: Rgb<u8>) -> Self {
+        BarChart {
+            bars,
+            width: 0.8,
+            color,
+        }
+    }
+
+    /// Sets the width of the bars in screen units.
+    ///
+    /// Returns an Err if the width isn't positive and an Ok with the chart otherwise.
+    pub fn with_width(mut self, width: f64) -> Result<Self, MathvisError> {
+        if width.is_nan() || width <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The width of the bars must be positive.".into(),
+            ));
+        }
+        self.width = width;
+        Ok(self)
+    }
+
+    /// Returns the x coordinate and height of every bar.
+    pub fn bars(&self) -> &[(f64, f64)] {
+        &self.bars
+    }
+}
+
+impl Drawable for BarChart {
+    /// Returns the point of the x axis in the middle of the bars, or the origin if there are none.
+    fn position(&self) -> (f64, f64) {
+        let xs = self.bars.iter().map(|&(x, _)| x);
+        match (xs.clone().reduce(f64::min), xs.reduce(f64::max)) {
+            (Some(min), Some(max)) => ((min + max) / 2.0, 0.0),
+            _ => (0.0, 0.0),
+        }
+    }
+
+    /// Returns the box of the bars, from the x axis to the top of the highest one and the bottom of the lowest one.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let half = self.width / 2.0;
+        BoundingBox::union_all(
+            self.bars
+                .iter()
+                .filter(|(x, height)| x.is_finite() && height.is_finite())
+                .map(|&(x, height)| BoundingBox::new((x - half, 0.0), (x + half, height))),
+        )
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        for &(x, height) in &self.bars {
+            if !height.is_finite() {
+                continue;
+            }
+            let (left, bottom) = map.to_pixels(((x - self.width / 2.0) as f32, 0.0));
+            let (right, top) = map.to_pixels(((x + self.width / 2.0) as f32, height as f32));
+            let (left, right) = (left.round() as i32, right.round() as i32);
+            let (top, bottom) = (
+                top.min(bottom).round() as i32,
+                top.max(bottom).round() as i32,
+            );
+            if top == bottom || left == right {
+                canvas.draw_line(
+                    (left as f32, top as f32),
+                    (right as f32, bottom as f32),
+                    self.color,
+                );
+                continue;
+            }
+            canvas.draw_polygon(
+                &[
+                    Point::new(left, top),
+                    Point::new(right, top),
+                    Point::new(right, bottom),
+                    Point::new(left, bottom),
+                ],
+                self.color,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_draw() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let color = Rgb([0, 200, 255]);
+        let mut img = RgbImage::new(854, 480);
+        BarChart::new(vec![(1.0, 2.0), (-1.0, -1.0)], color)
+            .draw(&mut img, &map)
+            .unwrap();
+        for (point, expected) in [
+            ((1.0, 1.9), color),
+            ((1.3, 1.0), color),
+            ((1.0, 2.1), Rgb([0, 0, 0])),
+            ((1.5, 1.0), Rgb([0, 0, 0])),
+            ((-1.0, -0.5), color),
+        ] {
+            let (x, y) = map.to_pixels(point);
+            assert_eq!(*img.get_pixel(x as u32, y as u32), expected);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Module containing render manifests, which record the frames of a scene that are already saved so an interrupted render can be resumed.
+#![warn(missing_docs)]
+use std::{
+    fs::{read_to_string, rename, write},
+    ops::Range,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::MathvisError;
+
+/// Name of the manifest file, saved next to the frames in the output directory's `tmp` directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// The frames of a scene that were completely saved, along with a hash of the scene and the settings it's rendered with.
+///
+/// Renderers on a screen with a checkpoint (see [Screen2D::set_checkpoint](crate::api::screen::Screen2D::set_checkpoint))
+/// record every frame in the manifest as soon as it's saved, and skip the frames it already has,
+/// so rendering the same scene again after an interruption only renders the missing frames.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::checkpoint::{hash_scene, RenderManifest};
+///
+/// let mut manifest = RenderManifest::new(hash_scene(&[b"scene source", b"1920x1080 30fps"]));
+/// manifest.record(0..30);
+/// manifest.record(45..60);
+/// manifest.record(30..45);
+/// assert_eq!(manifest.completed(), &[(0, 60)]);
+/// assert_eq!(manifest.resume_frame(), 60);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderManifest {
+    scene_hash: u64,
+    /// Ranges of saved frames as their start (inclusive) and end (exclusive), sorted and without overlaps.
+    completed: Vec<(u32, u32)>,
+}
+
+impl RenderManifest {
+    /// Creates an empty manifest for the scene with the specified hash.
+    pub fn new(scene_hash: u64) -> Self {
+        RenderManifest {
+            scene_hash,
+            completed: Vec::new(),
+        }
+    }
+
+    /// Returns the path of the manifest for the frames saved in the specified directory.
+    pub fn path(save_directory: &str) -> String {
+        format!("{}/tmp/{}", save_directory, MANIFEST_FILE)
+    }
+
+    /// Loads the manifest of the frames saved in the specified directory, if it's for the scene with the specified hash.
+    ///
+    /// Returns an Err if the manifest exists but can't be read or parsed and an Ok with the manifest,
+    /// or None if there's no manifest or it's for a different scene, otherwise.
+    pub fn load(save_directory: &str, scene_hash: u64) -> Result<Option<Self>, MathvisError> {
+        let path = Self::path(save_directory);
+        if !Path::new(&path).is_file() {
+            return Ok(None);
+        }
+        let manifest: RenderManifest =
+            serde_json::from_str(&read_to_string(&path)?).map_err(|err| {
+                MathvisError::InvalidArgument(format!("Invalid render manifest {}: {}", path, err))
+            })?;
+        Ok((manifest.scene_hash == scene_hash).then_some(manifest))
+    }
+
+    /// Saves the manifest in the specified directory, replacing the previous one at once
+    /// so an interruption never leaves a partially written manifest.
+    ///
+    /// Returns an Err if the manifest can't be written and an Ok otherwise.
+    pub fn save(&self, save_directory: &str) -> Result<(), MathvisError> {
+        let path = Self::path(save_directory);
+        let partial = format!("{}.partial", path);
+        let json =
+            serde_json::to_string(self).map_err(|err| MathvisError::Render(err.to_string()))?;
+        write(&partial, json)?;
+        rename(&partial, &path)?;
+        Ok(())
+    }
+
+    /// Returns the hash of the scene the frames belong to.
+    pub fn scene_hash(&self) -> u64 {
+        self.scene_hash
+    }
+
+    /// Returns the ranges of saved frames as their start (inclusive) and end (exclusive), in order.
+    pub fn completed(&self) -> &[(u32, u32)] {
+        &self.completed
+    }
+
+    /// Returns whether the specified frame was saved.
+    pub fn contains(&self, frame: u32) -> bool {
+        self.completed
+            .iter()
+            .any(|&(start, end)| (start..end).contains(&frame))
+    }
+
+    /// Returns the first frame that wasn't saved, which is where a render in order would resume.
+    pub fn resume_frame(&self) -> u32 {
+        match self.completed.first() {
+            Some(&(0, end)) => end,
+            _ => 0,
+        }
+    }
+
+    /// Records a range of frames as saved, merging it with the ranges it overlaps or touches.
+    pub fn record(&mut self, frames: Range<u32>) {
+        if frames.is_empty() {
+            return;
+        }
+        let (mut start, mut end) = (frames.start, frames.end);
+        self.completed.retain(|&(s, e)| {
+            let separate = e < start || s > end;
+            if !separate {
+                start = start.min(s);
+                end = end.max(e);
+            }
+            separate
+        });
+        let index = self.completed.partition_point(|&(s, _)| s < start);
+        self.completed.insert(index, (start, end));
+    }
+}
+
+/// Returns a hash of the parts that make up a scene, like its source and the settings it's rendered with,
+/// to check that a manifest is for the same scene.
+///
+/// The hash is FNV-1a, which unlike the standard library's hasher doesn't change between builds,
+/// so a render can be resumed by a newer version of the program.
+pub fn hash_scene(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // The length keeps ["ab", "c"] and ["a", "bc"] apart
+        for byte in (part.len() as u64).to_le_bytes().iter().chain(part.iter()) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, remove_dir_all};
+
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut manifest = RenderManifest::new(1);
+        manifest.record(10..20);
+        manifest.record(30..40);
+        manifest.record(5..5);
+        assert_eq!(manifest.completed(), &[(10, 20), (30, 40)]);
+        assert_eq!(manifest.resume_frame(), 0);
+        manifest.record(15..31);
+        assert_eq!(manifest.completed(), &[(10, 40)]);
+        manifest.record(0..3);
+        assert_eq!(manifest.completed(), &[(0, 3), (10, 40)]);
+        assert_eq!(manifest.resume_frame(), 3);
+        assert!(manifest.contains(39) && !manifest.contains(40) && !manifest.contains(5));
+        assert_ne!(hash_scene(&[b"ab", b"c"]), hash_scene(&[b"a", b"bc"]));
+    }
+
+    #[test]
+    fn test_save() {
+        let directory = std::env::temp_dir().join("mathvis_checkpoint_test");
+        create_dir_all(directory.join("tmp")).unwrap();
+        let directory_str = directory.to_str().unwrap();
+        let mut manifest = RenderManifest::new(42);
+        manifest.record(0..12);
+        manifest.save(directory_str).unwrap();
+        assert_eq!(
+            RenderManifest::load(directory_str, 42).unwrap(),
+            Some(manifest)
+        );
+        assert_eq!(RenderManifest::load(directory_str, 43).unwrap(), None);
+
+        write(RenderManifest::path(directory_str), "not json").unwrap();
+        assert!(RenderManifest::load(directory_str, 42).is_err());
+        remove_dir_all(&directory).unwrap();
+        assert_eq!(RenderManifest::load(directory_str, 42).unwrap(), None);
+    }
+}
@@ -0,0 +1,664 @@
+//! Module containing deferred animations, which describe what happens on every frame without rendering anything.
+//!
+//! Animations are created by the methods of [Show2D](super::show::Show2D) objects, composed with
+//! [Animation::then], [Animation::with] and [Animation::play_together], and rendered with [Renderer::play](super::renderer::Renderer::play).
+#![warn(missing_docs)]
+use std::{fmt, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{screen::Screen2D, util::CoordinateMap},
+    error::MathvisError,
+};
+
+use super::{
+    canvas::Canvas,
+    drawable::{is_visible, Drawable},
+};
+
+/// Draw function of a single animation, called with the progress of the animation from 0 to 1.
+type DrawFn = dyn Fn(f64, &mut dyn Canvas, &Screen2D, &CoordinateMap) -> Result<(), MathvisError>
+    + Send
+    + Sync;
+
+/// How an animation is repeated, see [Animation::repeat].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayMode {
+    /// Every cycle starts over from the beginning.
+    #[default]
+    Loop,
+    /// Every other cycle plays backwards, so the animation goes back and forth without jumping.
+    PingPong,
+}
+
+/// A description of an animation, rendered later by a [Renderer](super::renderer::Renderer).
+///
+/// Animations are cheap to clone and can be played any number of times, on any screen.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::{renderer::Renderer, show::Show2D, vector::Vector2D};
+/// use mathvis::api::{point::{Point, PointLike}, screen::Screen2D};
+///
+/// let screen = Arc::new(Mutex::new(Screen2D::builder().save_directory("./output").build().unwrap()));
+/// let v = Vector2D::new(1.0, 0.0, Rgb([255, 255, 255]));
+/// let w = Vector2D::new(0.0, 1.0, Rgb([255, 0, 0]));
+///
+/// let animation = v
+///     .move_to(1.0, Point::new(vec![2.0, 2.0]).unwrap())
+///     .with(w.rotate(1.0, 3.14, Point::new(vec![0.0, 0.0]).unwrap()))
+///     .then(v.wait(0.5));
+/// assert_eq!(animation.duration(), 1.5);
+/// Renderer::new(screen).unwrap().play(&animation).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Animation {
+    kind: Kind,
+}
+
+#[derive(Clone)]
+enum Kind {
+    Single {
+        duration: f32,
+        /// Whether every frame is the same, so the renderer only has to draw one.
+        still: bool,
+        draw: Arc<DrawFn>,
+    },
+    Sequence(Vec<Animation>),
+    Parallel(Vec<Animation>),
+}
+
+impl Animation {
+    /// Creates an animation of the specified duration, whose draw function is called on every frame with the progress of the animation,
+    /// from 0 on the first frame to 1 on the last one.
+    pub fn new<F>(duration: f32, draw: F) -> Self
+    where
+        F: Fn(f64, &mut dyn Canvas, &Screen2D, &CoordinateMap) -> Result<(), MathvisError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Animation {
+            kind: Kind::Single {
+                duration,
+                still: false,
+                draw: Arc::new(draw),
+            },
+        }
+    }
+
+    /// Creates an animation of the specified duration where every frame is the same.
+    pub fn still<F>(duration: f32, draw: F) -> Self
+    where
+        F: Fn(&mut dyn Canvas, &Screen2D, &CoordinateMap) -> Result<(), MathvisError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Animation {
+            kind: Kind::Single {
+                duration,
+                still: true,
+                draw: Arc::new(move |_, canvas, screen, map| draw(canvas, screen, map)),
+            },
+        }
+    }
+
+    /// Creates an animation of the specified duration where every object is drawn still, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{clip::Animation, drawable::Drawable, vector::Vector2D};
+    ///
+    /// let objects: Vec<Arc<dyn Drawable>> = vec![
+    ///     Arc::new(Vector2D::new(1.0, 2.0, Rgb([255, 0, 0]))),
+    ///     Arc::new(Vector2D::new(-1, 1, Rgb([0, 255, 0]))),
+    /// ];
+    /// assert!(Animation::show(1.0, objects).is_still());
+    /// ```
+    pub fn show(duration: f32, objects: impl IntoIterator<Item = Arc<dyn Drawable>>) -> Self {
+        let objects: Vec<_> = objects.into_iter().collect();
+        Animation::still(duration, move |canvas, _, map| {
+            // Objects entirely outside of the frame aren't drawn at all
+            objects
+                .iter()
+                .filter(|object| is_visible(object.as_ref(), map))
+                .try_for_each(|object| object.draw(canvas, map))
+        })
+    }
+
+    /// Creates an animation that plays every animation one after the other.
+    pub fn sequence(animations: impl IntoIterator<Item = Animation>) -> Self {
+        Animation {
+            kind: Kind::Sequence(animations.into_iter().collect()),
+        }
+    }
+
+    /// Creates an animation that plays every animation at the same time, each one keeping its last frame once it's over.
+    pub fn parallel(animations: impl IntoIterator<Item = Animation>) -> Self {
+        Animation {
+            kind: Kind::Parallel(animations.into_iter().collect()),
+        }
+    }
+
+    /// Creates an animation of the specified duration that plays every animation at the same time off a single clock,
+    /// compositing them into each frame in order.
+    ///
+    /// Every animation is stretched or squeezed to the duration, so they all start and end together,
+    /// and they're driven by the same progress on every frame instead of counting their own frames,
+    /// so objects transformed side by side stay exactly in sync.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{clip::Animation, show::Show2D, vector::Vector2D};
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let v = Vector2D::new(1.0, 1.0, Rgb([255, 255, 255]));
+    /// let shear = Matrix::shear_matrix_2d(1.0, 0.0);
+    /// let w = (&shear * &v).unwrap();
+    /// let together = Animation::play_together(
+    ///     2.0,
+    ///     [v.shear(1.0, 1.0, 0.0).unwrap(), w.multiply_by_matrix(1.5, shear).unwrap()],
+    /// );
+    /// assert_eq!(together.duration(), 2.0);
+    /// ```
+    pub fn play_together(duration: f32, animations: impl IntoIterator<Item = Animation>) -> Self {
+        let animations: Vec<Animation> = animations.into_iter().collect();
+        let still = animations.iter().all(Animation::is_still);
+        Animation {
+            kind: Kind::Single {
+                duration,
+                still,
+                draw: Arc::new(move |progress, canvas, screen, map| {
+                    animations
+                        .iter()
+                        .try_for_each(|animation| animation.draw_at(progress, canvas, screen, map))
+                }),
+            },
+        }
+    }
+
+    /// Returns an animation that plays this animation and then the next one.
+    pub fn then(self, next: Animation) -> Self {
+        match self.kind {
+            Kind::Sequence(mut animations) => {
+                animations.push(next);
+                Animation::sequence(animations)
+            }
+            kind => Animation::sequence([Animation { kind }, next]),
+        }
+    }
+
+    /// Returns an animation that plays this animation and the other one at the same time.
+    pub fn with(self, other: Animation) -> Self {
+        match self.kind {
+            Kind::Parallel(mut animations) => {
+                animations.push(other);
+                Animation::parallel(animations)
+            }
+            kind => Animation::parallel([Animation { kind }, other]),
+        }
+    }
+
+    /// Returns an animation of the specified duration that plays this one with its time remapped, without changing how it's drawn.
+    ///
+    /// The warp is called on every frame with the time in seconds since the start of the new animation,
+    /// and returns the time of this animation to draw, which is clamped to its duration.
+    /// Warps that grow slower than the clock slow this animation down, and faster ones speed it up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{show::Show2D, vector::Vector2D};
+    /// use mathvis::api::point::{Point, PointLike};
+    ///
+    /// let vector = Vector2D::new(1.0, 0.0, Rgb([255, 255, 255]));
+    /// let spin = vector.rotate(2.0, 3.14, Point::new(vec![0.0, 0.0]).unwrap());
+    /// // Eases into the rotation, taking twice as long
+    /// let warped = spin.time_warp(4.0, |t| t * t / 8.0);
+    /// assert_eq!(warped.duration(), 4.0);
+    /// ```
+    pub fn time_warp<F>(self, duration: f32, warp: F) -> Self
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        let still = self.is_still();
+        let source = self.duration() as f64;
+        Animation {
+            kind: Kind::Single {
+                duration,
+                still,
+                draw: Arc::new(move |progress, canvas, screen, map| {
+                    let time = warp(progress * duration as f64);
+                    let progress = if source > 0.0 { time / source } else { 1.0 };
+                    self.draw_at(progress, canvas, screen, map)
+                }),
+            },
+        }
+    }
+
+    /// Returns an animation that plays this one at the specified speed between two of its times in seconds,
+    /// and at normal speed before and after, so a critical moment can be shown in slow motion.
+    ///
+    /// The animation gets longer when the speed is below 1 and shorter when it's above.
+    ///
+    /// Returns an Err if the speed isn't positive or the interval isn't inside the animation and an Ok with the animation otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{show::Show2D, vector::Vector2D};
+    /// use mathvis::api::point::{Point, PointLike};
+    ///
+    /// let vector = Vector2D::new(1.0, 0.0, Rgb([255, 255, 255]));
+    /// let spin = vector.rotate(3.0, 3.14, Point::new(vec![0.0, 0.0]).unwrap());
+    /// // The second in the middle plays at a fifth of the speed, taking 5 seconds
+    /// let slowed = spin.slow_motion(1.0, 2.0, 0.2).unwrap();
+    /// assert!((slowed.duration() - 7.0).abs() < 1e-6);
+    /// ```
+    pub fn slow_motion(self, start: f32, end: f32, speed: f64) -> Result<Self, MathvisError> {
+        let total = self.duration();
+        if speed.is_nan() || speed <= 0.0 || speed.is_infinite() {
+            return Err(MathvisError::InvalidArgument(format!(
+                "The speed of a slow motion must be positive, but it was {}.",
+                speed
+            )));
+        }
+        if !(0.0 <= start && start <= end && end <= total) {
+            return Err(MathvisError::InvalidArgument(format!(
+                "A slow motion must be inside the animation, from 0 to {} seconds, but it goes from {} to {}.",
+                total, start, end
+            )));
+        }
+        let (start, end) = (start as f64, end as f64);
+        let slowed = (end - start) / speed;
+        let duration = total as f64 - (end - start) + slowed;
+        Ok(self.time_warp(duration as f32, move |t| {
+            if t < start {
+                t
+            } else if t < start + slowed {
+                start + (t - start) * speed
+            } else {
+                t - slowed + (end - start)
+            }
+        }))
+    }
+
+    /// Returns an animation that plays this one the specified number of times in a row, starting over or going back and forth.
+    ///
+    /// Returns an Err if there are no cycles and an Ok with the animation otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{clip::PlayMode, show::Show2D, vector::Vector2D};
+    /// use mathvis::api::point::{Point, PointLike};
+    ///
+    /// let vector = Vector2D::new(1.0, 0.0, Rgb([255, 255, 255]));
+    /// let swing = vector.rotate(1.0, 1.0, Point::new(vec![0.0, 0.0]).unwrap());
+    /// // Swings back and forth twice, ending where it started
+    /// let pendulum = swing.repeat(PlayMode::PingPong, 4).unwrap();
+    /// assert_eq!(pendulum.duration(), 4.0);
+    /// ```
+    pub fn repeat(self, mode: PlayMode, cycles: u32) -> Result<Self, MathvisError> {
+        if cycles == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "An animation must be repeated at least once.".into(),
+            ));
+        }
+        let duration = self.duration() * cycles as f32;
+        self.repeat_for(mode, duration)
+    }
+
+    /// Returns an animation of the specified duration that repeats this one for as long as it lasts,
+    /// starting over or going back and forth, and stopping partway through a cycle if it doesn't fit a whole number of them.
+    ///
+    /// Returns an Err if the duration is negative or this animation has no duration to repeat and an Ok with the animation otherwise.
+    pub fn repeat_for(self, mode: PlayMode, duration: f32) -> Result<Self, MathvisError> {
+        let cycle = self.duration() as f64;
+        if cycle <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "Only animations that take some time can be repeated.".into(),
+            ));
+        }
+        if duration.is_nan() || duration < 0.0 {
+            return Err(MathvisError::InvalidArgument(format!(
+                "Repeated animations can't last a negative time, but it was {}.",
+                duration
+            )));
+        }
+        Ok(self.time_warp(duration, move |t| {
+            // The ends of cycles belong to the cycle that's ending, so the last frame finishes it
+            let index = ((t / cycle).ceil() - 1.0).max(0.0);
+            let local = t - index * cycle;
+            match mode {
+                PlayMode::PingPong if index % 2.0 == 1.0 => cycle - local,
+                _ => local,
+            }
+        }))
+    }
+
+    /// Returns the duration of the animation in seconds.
+    pub fn duration(&self) -> f32 {
+        match &self.kind {
+            Kind::Single { duration, .. } => *duration,
+            Kind::Sequence(animations) => animations.iter().map(Animation::duration).sum(),
+            Kind::Parallel(animations) => animations
+                .iter()
+                .map(Animation::duration)
+                .fold(0.0, f32::max),
+        }
+    }
+
+    /// Returns the number of frames the animation takes at the specified fps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::clip::Animation;
+    ///
+    /// let animation = Animation::still(1.0, |_, _, _| Ok(())).then(Animation::still(0.5, |_, _, _| Ok(())));
+    /// assert_eq!(animation.frames(30), 45);
+    /// ```
+    pub fn frames(&self, fps: u32) -> u32 {
+        match &self.kind {
+            Kind::Single { duration, .. } => (duration * fps as f32) as u32,
+            Kind::Sequence(animations) => animations.iter().map(|a| a.frames(fps)).sum(),
+            Kind::Parallel(animations) => {
+                animations.iter().map(|a| a.frames(fps)).max().unwrap_or(0)
+            }
+        }
+    }
+
+    /// Returns whether every frame of the animation is the same.
+    pub fn is_still(&self) -> bool {
+        match &self.kind {
+            Kind::Single { still, .. } => *still,
+            Kind::Sequence(animations) | Kind::Parallel(animations) => {
+                animations.iter().all(Animation::is_still)
+            }
+        }
+    }
+
+    /// Draws the specified frame of the animation, counted from its start.
+    ///
+    /// Returns an Err if the frame fails to draw and an Ok otherwise.
+    pub fn draw_frame(
+        &self,
+        frame: u32,
+        canvas: &mut dyn Canvas,
+        screen: &Screen2D,
+        map: &CoordinateMap,
+    ) -> Result<(), MathvisError> {
+        match &self.kind {
+            Kind::Single { draw, .. } => {
+                let frames = self.frames(screen.fps);
+                let progress = if frames > 1 {
+                    frame.min(frames - 1) as f64 / (frames - 1) as f64
+                } else {
+                    1.0
+                };
+                draw(progress, canvas, screen, map)
+            }
+            Kind::Sequence(animations) => {
+                let mut frame = frame;
+                for (i, animation) in animations.iter().enumerate() {
+                    let frames = animation.frames(screen.fps);
+                    if frame < frames || i == animations.len() - 1 {
+                        return animation.draw_frame(frame, canvas, screen, map);
+                    }
+                    frame -= frames;
+                }
+                Ok(())
+            }
+            Kind::Parallel(animations) => animations.iter().try_for_each(|animation| {
+                let last = animation.frames(screen.fps).saturating_sub(1);
+                animation.draw_frame(frame.min(last), canvas, screen, map)
+            }),
+        }
+    }
+}
+
+impl Animation {
+    /// Draws the animation at the specified progress from 0 to 1 through its whole duration, instead of at a frame.
+    ///
+    /// Sequences hand the progress to the animation playing at that time, and parallel animations
+    /// to every animation, each keeping its last frame once it's over.
+    fn draw_at(
+        &self,
+        progress: f64,
+        canvas: &mut dyn Canvas,
+        screen: &Screen2D,
+        map: &CoordinateMap,
+    ) -> Result<(), MathvisError> {
+        let time = progress.clamp(0.0, 1.0) * self.duration() as f64;
+        // Progress of an animation starting at the specified time, past its end if the time is
+        let local = |animation: &Animation, start: f64| {
+            let duration = animation.duration() as f64;
+            if duration > 0.0 {
+                ((time - start) / duration).clamp(0.0, 1.0)
+            } else {
+                1.0
+            }
+        };
+        match &self.kind {
+            Kind::Single { draw, .. } => draw(progress.clamp(0.0, 1.0), canvas, screen, map),
+            Kind::Sequence(animations) => {
+                let mut start = 0.0;
+                for (i, animation) in animations.iter().enumerate() {
+                    let end = start + animation.duration() as f64;
+                    if time < end || i == animations.len() - 1 {
+                        return animation.draw_at(local(animation, start), canvas, screen, map);
+                    }
+                    start = end;
+                }
+                Ok(())
+            }
+            Kind::Parallel(animations) => animations.iter().try_for_each(|animation| {
+                animation.draw_at(local(animation, 0.0), canvas, screen, map)
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for Animation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            Kind::Single {
+                duration, still, ..
+            } => f
+                .debug_struct("Animation")
+                .field("duration", duration)
+                .field("still", still)
+                .finish_non_exhaustive(),
+            Kind::Sequence(animations) => f.debug_tuple("Sequence").field(animations).finish(),
+            Kind::Parallel(animations) => f.debug_tuple("Parallel").field(animations).finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use imageproc::image::RgbImage;
+
+    use super::*;
+
+    /// Returns an animation that records the progress of every frame it draws.
+    fn recorder(duration: f32, log: &Arc<Mutex<Vec<(u8, f64)>>>, id: u8) -> Animation {
+        let log = Arc::clone(log);
+        Animation::new(duration, move |progress, _, _, _| {
+            log.lock().unwrap().push((id, progress));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_composition() {
+        let screen = Screen2D::builder().fps(4).build().unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let mut canvas = RgbImage::new(1, 1);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let animation = recorder(0.75, &log, 0)
+            .then(recorder(0.5, &log, 1))
+            .with(recorder(0.5, &log, 2));
+        assert_eq!(animation.frames(4), 5);
+        assert_eq!(animation.duration(), 1.25);
+        assert!(!animation.is_still());
+
+        for frame in [0, 2, 3, 4] {
+            animation
+                .draw_frame(frame, &mut canvas, &screen, &map)
+                .unwrap();
+        }
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                (0, 0.0),
+                (2, 0.0),
+                (0, 1.0),
+                (2, 1.0),
+                (1, 0.0),
+                (2, 1.0),
+                (1, 1.0),
+                (2, 1.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_play_together() {
+        let screen = Screen2D::builder().fps(4).build().unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let mut canvas = RgbImage::new(1, 1);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        // Durations that don't even take a whole number of frames on their own still move in lockstep
+        let animation = Animation::play_together(
+            1.0,
+            [
+                recorder(0.3, &log, 0),
+                recorder(0.5, &log, 1).then(recorder(1.5, &log, 2)),
+            ],
+        );
+        assert_eq!(animation.frames(4), 4);
+        for frame in [0, 2, 3] {
+            animation
+                .draw_frame(frame, &mut canvas, &screen, &map)
+                .unwrap();
+        }
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                (0, 0.0),
+                (1, 0.0),
+                (0, 2.0 / 3.0),
+                // Two thirds through the 2 second sequence is 5/6 of a second into its second part
+                (2, (2.0 / 3.0 * 2.0 - 0.5) / 1.5),
+                (0, 1.0),
+                (2, 1.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_warp() {
+        let screen = Screen2D::builder().fps(4).build().unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let mut canvas = RgbImage::new(1, 1);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        // Two seconds of animation with the second one slowed to half speed, taking 3 seconds
+        let animation = recorder(1.0, &log, 0)
+            .then(recorder(1.0, &log, 1))
+            .slow_motion(1.0, 2.0, 0.5)
+            .unwrap();
+        assert_eq!(animation.duration(), 3.0);
+        assert_eq!(animation.frames(4), 12);
+        for frame in [0, 11] {
+            animation
+                .draw_frame(frame, &mut canvas, &screen, &map)
+                .unwrap();
+        }
+        assert_eq!(*log.lock().unwrap(), vec![(0, 0.0), (1, 1.0)]);
+
+        // A warp freezing the first half a second
+        log.lock().unwrap().clear();
+        let frozen = recorder(1.0, &log, 0).time_warp(1.0, |t| (t - 0.5).max(0.0) * 2.0);
+        for frame in [0, 1] {
+            frozen
+                .draw_frame(frame, &mut canvas, &screen, &map)
+                .unwrap();
+        }
+        assert_eq!(*log.lock().unwrap(), vec![(0, 0.0), (0, 0.0)]);
+
+        let still = || Animation::still(1.0, |_, _, _| Ok(()));
+        assert!(still().slow_motion(0.5, 0.2, 0.5).is_err());
+        assert!(still().slow_motion(0.5, 1.5, 0.5).is_err());
+        assert!(still().slow_motion(0.2, 0.5, 0.0).is_err());
+        assert!(still().time_warp(2.0, |t| t).is_still());
+    }
+
+    #[test]
+    fn test_repeat() {
+        let screen = Screen2D::builder().fps(4).build().unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let mut canvas = RgbImage::new(1, 1);
+        let log: Arc<Mutex<Vec<(u8, f64)>>> = Arc::new(Mutex::new(Vec::new()));
+        let mut progress = |animation: &Animation, frames: &[u32]| {
+            log.lock().unwrap().clear();
+            for &frame in frames {
+                animation
+                    .draw_frame(frame, &mut canvas, &screen, &map)
+                    .unwrap();
+            }
+            let progress: Vec<f64> = log.lock().unwrap().iter().map(|entry| entry.1).collect();
+            progress
+        };
+
+        let close = |actual: Vec<f64>, expected: Vec<f64>| {
+            assert_eq!(actual.len(), expected.len());
+            for (a, e) in actual.into_iter().zip(expected) {
+                assert!((a - e).abs() < 1e-9, "{} != {}", a, e);
+            }
+        };
+
+        // Three cycles of a second take 12 frames, 3/11 of a second apart
+        let looped = recorder(1.0, &log, 0).repeat(PlayMode::Loop, 3).unwrap();
+        assert_eq!(looped.duration(), 3.0);
+        close(
+            progress(&looped, &[0, 3, 4, 11]),
+            vec![0.0, 9.0 / 11.0, 1.0 / 11.0, 1.0],
+        );
+        let pendulum = recorder(1.0, &log, 0)
+            .repeat(PlayMode::PingPong, 3)
+            .unwrap();
+        close(
+            progress(&pendulum, &[0, 4, 8, 11]),
+            vec![0.0, 10.0 / 11.0, 2.0 / 11.0, 1.0],
+        );
+        // One and a half cycles over 6 frames, 0.3 seconds apart, end halfway back
+        let half = recorder(1.0, &log, 0)
+            .repeat_for(PlayMode::PingPong, 1.5)
+            .unwrap();
+        close(progress(&half, &[3, 5]), vec![0.9, 0.5]);
+
+        assert!(recorder(1.0, &log, 0).repeat(PlayMode::Loop, 0).is_err());
+        assert!(recorder(0.0, &log, 0).repeat(PlayMode::Loop, 2).is_err());
+        assert!(recorder(1.0, &log, 0)
+            .repeat_for(PlayMode::Loop, -1.0)
+            .is_err());
+    }
+}
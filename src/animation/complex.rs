@@ -0,0 +1,274 @@
+//! Module containing high level animations that visualize complex numbers, drawing every object they need on their own.
+//!
+//! They're meant to be played on a screen in [ComplexPlane](crate::api::screen::ScreenMode::ComplexPlane) mode,
+//! so the axes are labelled as the real and imaginary axes and the unit circle is drawn around the origin.
+#![warn(missing_docs)]
+use std::f64::consts::TAU;
+
+use imageproc::{image::Rgb, point::Point};
+
+use super::{
+    canvas::Canvas, clip::Animation, drawable::Drawable, field::dot_center, vector::Vector2D,
+};
+use crate::{
+    api::{complex::Complex, point::PointLike, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// Color of the numbers being multiplied, rotated or reached.
+const NUMBER_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+/// Color of the number every other one is multiplied by.
+const FACTOR_COLOR: Rgb<u8> = Rgb([0, 200, 255]);
+/// Color of the numbers before they're multiplied, and of the powers and roots already reached.
+const TRACE_COLOR: Rgb<u8> = Rgb([110, 110, 110]);
+
+/// Returns an animation of the specified duration that multiplies every number by a factor,
+/// showing that multiplying by a complex number rotates by its argument and scales by its modulus.
+///
+/// The numbers are multiplied by the factor interpolated in polar form from 1, so they rotate and scale at the same time,
+/// turning the shortest way around. Where they started is left behind in gray, and the factor is drawn as a labelled vector.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::complex::multiply;
+/// use mathvis::api::complex::Complex;
+///
+/// let points = vec![Complex::new(1.0, 0.0), Complex::new(1.0, 1.0)];
+/// assert_eq!(multiply(points, Complex::new(0.0, 2.0), 2.0).duration(), 2.0);
+/// ```
+pub fn multiply(numbers: Vec<Complex>, factor: Complex, duration: f32) -> Animation {
+    let (norm, arg) = (factor.norm(), factor.arg());
+    Animation::new(duration, move |progress, canvas, _, map| {
+        draw_labelled(
+            factor,
+            FACTOR_COLOR,
+            format!("w = {:.2}", factor),
+            canvas,
+            map,
+        )?;
+        let current = Complex::from_polar(norm.powf(progress), arg * progress);
+        for &z in &numbers {
+            draw_number(z, TRACE_COLOR, canvas, map)?;
+            draw_number(z * current, NUMBER_COLOR, canvas, map)?;
+        }
+        Ok(())
+    })
+}
+
+/// Returns an animation of the specified duration that walks through the powers of a number from z⁰ = 1 up to zⁿ.
+///
+/// Each power is reached from the previous one by multiplying by z, so the vector rotates and scales into place
+/// and leaves a trail of segments between consecutive powers, spiraling in or out unless z is on the unit circle.
+///
+/// Returns an Err if the exponent is 0 and an Ok with the animation otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::complex::powers;
+/// use mathvis::api::complex::Complex;
+///
+/// let z = Complex::from_polar(1.1, 0.5);
+/// assert!(powers(z, 8, 4.0).is_ok());
+/// assert!(powers(z, 0, 4.0).is_err());
+/// ```
+pub fn powers(z: Complex, n: u32, duration: f32) -> Result<Animation, MathvisError> {
+    if n == 0 {
+        return Err(MathvisError::InvalidArgument(
+            "At least one power is needed.".into(),
+        ));
+    }
+    let (norm, arg) = (z.norm(), z.arg());
+    Ok(Animation::new(duration, move |progress, canvas, _, map| {
+        let (reached, step) = split_progress(progress, n);
+        let mut power = Complex::ONE;
+        for _ in 0..reached {
+            let next = power * z;
+            canvas.draw_line(to_pixels(power, map), to_pixels(next, map), TRACE_COLOR);
+            draw_dot(power, TRACE_COLOR, canvas, map);
+            power = next;
+        }
+        draw_dot(power, TRACE_COLOR, canvas, map);
+        if reached == n {
+            return draw_labelled(power, NUMBER_COLOR, format!("z^{}", n), canvas, map);
+        }
+        let current = power * Complex::from_polar(norm.powf(step), arg * step);
+        canvas.draw_line(to_pixels(power, map), to_pixels(current, map), TRACE_COLOR);
+        draw_labelled(
+            current,
+            NUMBER_COLOR,
+            format!("z^{}", reached + 1),
+            canvas,
+            map,
+        )
+    }))
+}
+
+/// Returns an animation of the specified duration that shows the n-th roots of unity.
+///
+/// A vector starts at 1 and turns around the unit circle by multiplying by ω = e^(2πi/n) over and over,
+/// labelling each root as it reaches it and drawing the edges of the regular polygon they make behind it.
+///
+/// Returns an Err if n is 0 and an Ok with the animation otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::complex::roots_of_unity;
+///
+/// assert_eq!(roots_of_unity(5, 3.0).unwrap().duration(), 3.0);
+/// assert!(roots_of_unity(0, 3.0).is_err());
+/// ```
+pub fn roots_of_unity(n: u32, duration: f32) -> Result<Animation, MathvisError> {
+    if n == 0 {
+        return Err(MathvisError::InvalidArgument(
+            "There are no 0th roots of unity.".into(),
+        ));
+    }
+    let roots = Complex::roots_of_unity(n);
+    Ok(Animation::new(duration, move |progress, canvas, _, map| {
+        let (reached, step) = split_progress(progress, n);
+        for k in 0..reached as usize {
+            let next = roots[(k + 1) % roots.len()];
+            canvas.draw_line(to_pixels(roots[k], map), to_pixels(next, map), TRACE_COLOR);
+        }
+        if reached < n {
+            let (from, to) = (
+                roots[reached as usize],
+                roots[(reached as usize + 1) % roots.len()],
+            );
+            canvas.draw_line(
+                to_pixels(from, map),
+                to_pixels(from + (to - from) * step, map),
+                TRACE_COLOR,
+            );
+        }
+        let size = map.quality().resolution().values()[1] * 0.03;
+        for (k, &root) in roots.iter().enumerate().take(reached as usize + 1) {
+            draw_dot(root, NUMBER_COLOR, canvas, map);
+            let (x, y) = to_pixels(root * 1.15, map);
+            canvas.draw_text(
+                &format!("ω^{}", k),
+                (x - size / 2.0, y - size / 2.0),
+                size,
+                NUMBER_COLOR,
+            );
+        }
+        let current = Complex::from_polar(1.0, TAU * (reached as f64 + step) / n as f64);
+        draw_number(current, FACTOR_COLOR, canvas, map)
+    }))
+}
+
+/// Splits the progress of an animation into n equal steps, returning how many steps are done
+/// and the progress of the current one.
+fn split_progress(progress: f64, n: u32) -> (u32, f64) {
+    let steps = progress * n as f64;
+    let reached = (steps.floor() as u32).min(n);
+    (reached, steps - reached as f64)
+}
+
+/// Returns the pixel a complex number is drawn on.
+fn to_pixels(z: Complex, map: &CoordinateMap) -> (f32, f32) {
+    map.to_pixels((z.re as f32, z.im as f32))
+}
+
+/// Draws a complex number as a vector from the origin.
+fn draw_number(
+    z: Complex,
+    color: Rgb<u8>,
+    canvas: &mut dyn Canvas,
+    map: &CoordinateMap,
+) -> Result<(), MathvisError> {
+    Vector2D::new(z.re, z.im, color).draw(canvas, map)
+}
+
+/// Draws a complex number as a thick vector with a label next to its tip.
+fn draw_labelled(
+    z: Complex,
+    color: Rgb<u8>,
+    label: String,
+    canvas: &mut dyn Canvas,
+    map: &CoordinateMap,
+) -> Result<(), MathvisError> {
+    Vector2D::builder()
+        .components(z.re, z.im)
+        .color(color)
+        .thickness(3.0)
+        .label(label)
+        .build()?
+        .draw(canvas, map)
+}
+
+/// Draws a small diamond on a complex number.
+fn draw_dot(z: Complex, color: Rgb<u8>, canvas: &mut dyn Canvas, map: &CoordinateMap) {
+    let r = 5;
+    let Some((x, y)) = dot_center(canvas, to_pixels(z, map), r) else {
+        return;
+    };
+    canvas.draw_polygon(
+        &[
+            Point::new(x + r, y),
+            Point::new(x, y + r),
+            Point::new(x - r, y),
+            Point::new(x, y - r),
+        ],
+        color,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::{Screen2D, ScreenMode};
+
+    fn screen() -> (Screen2D, CoordinateMap) {
+        let mut screen =
+            Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        screen.set_mode(ScreenMode::ComplexPlane);
+        let map = CoordinateMap::new(&screen).unwrap();
+        (screen, map)
+    }
+
+    #[test]
+    fn test_multiply() {
+        let (screen, map) = screen();
+        let animation = multiply(vec![Complex::new(1.0, 0.0)], Complex::new(0.0, 2.0), 1.0);
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        // 1 is rotated a quarter turn and doubled, ending up on 2i
+        let (x, y) = map.to_pixels((0.0, 1.5));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), NUMBER_COLOR);
+        let (x, y) = map.to_pixels((0.5, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), TRACE_COLOR);
+    }
+
+    #[test]
+    fn test_powers_and_roots() {
+        let (screen, map) = screen();
+        let mut img = RgbImage::new(854, 480);
+        let animation = powers(Complex::I, 2, 1.0).unwrap();
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        // i² = -1
+        let (x, y) = map.to_pixels((-0.5, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), NUMBER_COLOR);
+        // The last powers are far away from the screen
+        let animation = powers(Complex::new(10.0, 0.0), 12, 1.0).unwrap();
+        for frame in 0..10 {
+            animation
+                .draw_frame(frame, &mut img, &screen, &map)
+                .unwrap();
+        }
+
+        let mut img = RgbImage::new(854, 480);
+        let animation = roots_of_unity(4, 1.0).unwrap();
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        // The square's edges are all drawn by the end
+        let (x, y) = map.to_pixels((-0.5, -0.5));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), TRACE_COLOR);
+        assert_eq!(split_progress(1.0, 4), (4, 0.0));
+        assert_eq!(split_progress(0.5, 4), (2, 0.0));
+    }
+}
@@ -0,0 +1,573 @@
+//! Module containing drawables for scalar fields of the plane, as heatmaps and contour plots,
+//! and a gradient descent animation drawn over them.
+#![warn(missing_docs)]
+use std::sync::Arc;
+
+use imageproc::{image::Rgb, point::Point};
+
+use super::{
+    canvas::Canvas,
+    clip::Animation,
+    drawable::Drawable,
+    field::{visible_prefix, PointCloud},
+};
+use crate::{
+    api::{bounds::BoundingBox, colormap::Colormap, point::PointLike, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// Size in pixels of the cells heatmaps are filled with and contour plots are traced on.
+const CELL_SIZE: u32 = 6;
+/// Color of the steps taken by gradient descent.
+const STEP_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+/// Color of the point descending.
+const DESCENT_COLOR: Rgb<u8> = Rgb([0, 220, 255]);
+
+/// A real function of the plane, giving a value at every point (x, y).
+pub type ScalarField = Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>;
+
+/// Values of a scalar field on a grid of cell corners covering a whole frame.
+struct Samples {
+    /// Values by row from the top, then by column from the left.
+    values: Vec<Vec<f64>>,
+    /// Smallest and largest finite values.
+    range: (f64, f64),
+}
+
+impl Samples {
+    /// Samples a scalar field on the corners of the cells covering the frame of a map.
+    fn new(field: &ScalarField, map: &CoordinateMap) -> Self {
+        let resolution = map.quality().resolution();
+        let (columns, rows) = (
+            (resolution.values()[0] as u32).div_ceil(CELL_SIZE),
+            (resolution.values()[1] as u32).div_ceil(CELL_SIZE),
+        );
+        let values: Vec<Vec<f64>> = (0..=rows)
+            .map(|row| {
+                (0..=columns)
+                    .map(|column| {
+                        let (x, y) = map.to_coordinates((
+                            (column * CELL_SIZE) as f32,
+                            (row * CELL_SIZE) as f32,
+                        ));
+                        field(x as f64, y as f64)
+                    })
+                    .collect()
+            })
+            .collect();
+        let range = values
+            .iter()
+            .flatten()
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &value| {
+                (low.min(value), high.max(value))
+            });
+        Samples { values, range }
+    }
+
+    /// Returns the value range to color with, the one given or else the one of the samples.
+    fn range(&self, range: Option<(f64, f64)>) -> Option<(f64, f64)> {
+        let (low, high) = range.unwrap_or(self.range);
+        (low < high).then_some((low, high))
+    }
+}
+
+/// A scalar field drawn as a heatmap filling the whole frame, colored with a colormap from the lowest values to the highest,
+/// [Colormap::heat] by default.
+///
+/// Since it fills the frame, it covers the axes and anything drawn before it.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::contour::Heatmap;
+///
+/// use mathvis::api::colormap::Colormap;
+///
+/// let bowl = Heatmap::new(|x, y| x * x + y * y)
+///     .with_colormap(Colormap::viridis())
+///     .with_range(0.0, 8.0)
+///     .unwrap();
+/// assert!(bowl.with_range(1.0, 0.0).is_err());
+/// ```
+#[derive(Clone)]
+pub struct Heatmap {
+    field: ScalarField,
+    range: Option<(f64, f64)>,
+    colormap: Colormap,
+}
+
+impl Heatmap {
+    /// Creates the heatmap of a scalar field, colored from its lowest to its highest value on the frame.
+    pub fn new<F>(field: F) -> Self
+    where
+        F: Fn(f64, f64) -> f64 + Send + Sync + 'static,
+    {
+        Self::from_field(Arc::new(field))
+    }
+
+    /// Creates the heatmap of a shared scalar field.
+    pub fn from_field(field: ScalarField) -> Self {
+        Heatmap {
+            field,
+            range: None,
+            colormap: Colormap::default(),
+        }
+    }
+
+    /// Sets the colormap the values are colored with.
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Sets the values colored as the lowest and the highest, so values outside of them are clamped.
+    ///
+    /// Returns an Err if the range is empty and an Ok with the heatmap otherwise.
+    pub fn with_range(mut self, low: f64, high: f64) -> Result<Self, MathvisError> {
+        self.range = Some(check_range(low, high)?);
+        Ok(self)
+    }
+}
+
+impl Drawable for Heatmap {
+    /// Returns the origin, since a heatmap fills the whole frame.
+    fn position(&self) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+
+    /// Returns None, since a heatmap fills the whole frame.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        None
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        let samples = Samples::new(&self.field, map);
+        let Some((low, high)) = samples.range(self.range) else {
+            return Ok(());
+        };
+        let size = CELL_SIZE as i32;
+        for (row, values) in samples.values.windows(2).enumerate() {
+            for column in 0..values[0].len() - 1 {
+                // The cell takes the average of its corners, ignoring the ones that aren't finite
+                let corners = [
+                    values[0][column],
+                    values[0][column + 1],
+                    values[1][column],
+                    values[1][column + 1],
+                ];
+                let finite: Vec<f64> = corners.into_iter().filter(|v| v.is_finite()).collect();
+                if finite.is_empty() {
+                    continue;
+                }
+                let value = finite.iter().sum::<f64>() / finite.len() as f64;
+                let (x, y) = (column as i32 * size, row as i32 * size);
+                canvas.draw_polygon(
+                    &[
+                        Point::new(x, y),
+                        Point::new(x + size - 1, y),
+                        Point::new(x + size - 1, y + size - 1),
+                        Point::new(x, y + size - 1),
+                    ],
+                    self.colormap.map(value, low, high),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A scalar field drawn as a contour plot, with the curves where it takes evenly spaced values across the frame.
+///
+/// Curves are traced with marching squares and colored like a [Heatmap] with the same colormap would color their value.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::contour::ContourPlot;
+///
+/// let saddle = ContourPlot::new(|x, y| x * x - y * y).with_levels(vec![-1.0, 0.0, 1.0]);
+/// assert_eq!(saddle.levels(), Some(&[-1.0, 0.0, 1.0][..]));
+/// ```
+#[derive(Clone)]
+pub struct ContourPlot {
+    field: ScalarField,
+    levels: Option<Vec<f64>>,
+    count: u32,
+    colormap: Colormap,
+}
+
+impl ContourPlot {
+    /// Creates the contour plot of a scalar field, with 12 curves evenly spaced between its lowest and highest value on the frame.
+    pub fn new<F>(field: F) -> Self
+    where
+        F: Fn(f64, f64) -> f64 + Send + Sync + 'static,
+    {
+        Self::from_field(Arc::new(field))
+    }
+
+    /// Creates the contour plot of a shared scalar field.
+    pub fn from_field(field: ScalarField) -> Self {
+        ContourPlot {
+            field,
+            levels: None,
+            count: 12,
+            colormap: Colormap::default(),
+        }
+    }
+
+    /// Sets the colormap the curves are colored with, from the lowest value on the frame to the highest.
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Sets the number of curves evenly spaced between the lowest and highest values on the frame.
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = count;
+        self.levels = None;
+        self
+    }
+
+    /// Sets the values the curves are drawn at, instead of spacing them evenly.
+    pub fn with_levels(mut self, levels: Vec<f64>) -> Self {
+        self.levels = Some(levels);
+        self
+    }
+
+    /// Returns the values the curves are drawn at, if they were set.
+    pub fn levels(&self) -> Option<&[f64]> {
+        self.levels.as_deref()
+    }
+
+    /// Returns the line segments of the curve where the samples take the specified value, in pixels.
+    fn segments(samples: &Samples, level: f64) -> Vec<((f32, f32), (f32, f32))> {
+        let size = CELL_SIZE as f32;
+        let mut segments = Vec::new();
+        for (row, values) in samples.values.windows(2).enumerate() {
+            for column in 0..values[0].len() - 1 {
+                let (x, y) = (column as f32 * size, row as f32 * size);
+                // Corners clockwise from the top left, with their pixels
+                let corners = [
+                    (values[0][column], (x, y)),
+                    (values[0][column + 1], (x + size, y)),
+                    (values[1][column + 1], (x + size, y + size)),
+                    (values[1][column], (x, y + size)),
+                ];
+                if corners.iter().any(|(value, _)| !value.is_finite()) {
+                    continue;
+                }
+                let crossings: Vec<(f32, f32)> = (0..4)
+                    .filter_map(|i| {
+                        let ((a, pa), (b, pb)) = (corners[i], corners[(i + 1) % 4]);
+                        if (a < level) == (b < level) {
+                            return None;
+                        }
+                        let t = ((level - a) / (b - a)) as f32;
+                        Some((pa.0 + (pb.0 - pa.0) * t, pa.1 + (pb.1 - pa.1) * t))
+                    })
+                    .collect();
+                // Saddle cells cross all four edges, and are joined in order around the cell
+                for pair in crossings.chunks_exact(2) {
+                    segments.push((pair[0], pair[1]));
+                }
+            }
+        }
+        segments
+    }
+}
+
+impl Drawable for ContourPlot {
+    /// Returns the origin, since a contour plot covers the whole frame.
+    fn position(&self) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+
+    /// Returns None, since a contour plot covers the whole frame.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        None
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        let samples = Samples::new(&self.field, map);
+        let Some((low, high)) = samples.range(None) else {
+            return Ok(());
+        };
+        let levels = self.levels.clone().unwrap_or_else(|| {
+            // Levels are inside the range, since the extremes are usually single points
+            (1..=self.count)
+                .map(|i| low + (high - low) * i as f64 / (self.count + 1) as f64)
+                .collect()
+        });
+        for level in levels {
+            let color = self.colormap.map(level, low, high);
+            for (start, end) in Self::segments(&samples, level) {
+                canvas.draw_line(start, end, color);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the range if it's not empty, or the reason it's invalid.
+fn check_range(low: f64, high: f64) -> Result<(f64, f64), MathvisError> {
+    if low.partial_cmp(&high) != Some(std::cmp::Ordering::Less) {
+        return Err(MathvisError::InvalidArgument(format!(
+            "The lowest value must be less than the highest, but they're {} and {}.",
+            low, high
+        )));
+    }
+    Ok((low, high))
+}
+
+/// Gradient descent on a scalar field, animated as a point stepping downhill over the field's contour plot or heatmap.
+///
+/// Every step moves the point against the gradient, scaled by the learning rate, and is drawn as an arrow from the previous point,
+/// so too large learning rates show up as steps that overshoot and bounce across the valley.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::contour::GradientDescent;
+///
+/// // An elongated bowl, whose minimum is at the origin
+/// let descent = GradientDescent::new(|x, y| x * x + 4.0 * y * y, |x, y| (2.0 * x, 8.0 * y), (2.5, 1.5))
+///     .with_learning_rate(0.1)
+///     .with_steps(15);
+/// let path = descent.path();
+/// assert_eq!(path.len(), 16);
+/// let (x, y) = path[15];
+/// assert!(x.abs() < 0.1 && y.abs() < 0.01);
+/// assert_eq!(descent.animate(5.0).unwrap().duration(), 5.0);
+/// ```
+#[derive(Clone)]
+pub struct GradientDescent {
+    field: ScalarField,
+    gradient: Arc<dyn Fn(f64, f64) -> (f64, f64) + Send + Sync>,
+    start: (f64, f64),
+    learning_rate: f64,
+    steps: u32,
+    heatmap: bool,
+    colormap: Colormap,
+}
+
+impl GradientDescent {
+    /// Creates a gradient descent of a scalar field with its gradient, from the specified starting point.
+    /// By default, 20 steps are taken with a learning rate of 0.1 over the field's contour plot.
+    pub fn new<F, G>(field: F, gradient: G, start: (f64, f64)) -> Self
+    where
+        F: Fn(f64, f64) -> f64 + Send + Sync + 'static,
+        G: Fn(f64, f64) -> (f64, f64) + Send + Sync + 'static,
+    {
+        GradientDescent {
+            field: Arc::new(field),
+            gradient: Arc::new(gradient),
+            start,
+            learning_rate: 0.1,
+            steps: 20,
+            heatmap: false,
+            colormap: Colormap::default(),
+        }
+    }
+
+    /// Sets how far every step goes against the gradient.
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Sets the number of steps taken.
+    pub fn with_steps(mut self, steps: u32) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Sets whether the field is drawn as a heatmap instead of a contour plot.
+    pub fn with_heatmap(mut self, heatmap: bool) -> Self {
+        self.heatmap = heatmap;
+        self
+    }
+
+    /// Sets the colormap the field's contour plot or heatmap is colored with.
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Returns every point the descent goes through, starting with the starting point.
+    pub fn path(&self) -> Vec<(f64, f64)> {
+        let mut point = self.start;
+        let mut path = vec![point];
+        for _ in 0..self.steps {
+            let (dx, dy) = (self.gradient)(point.0, point.1);
+            point = (
+                point.0 - self.learning_rate * dx,
+                point.1 - self.learning_rate * dy,
+            );
+            path.push(point);
+        }
+        path
+    }
+
+    /// Returns an animation of the specified duration in which the point takes every step, one after the other.
+    ///
+    /// Returns an Err if there are no steps or the learning rate isn't positive and an Ok with the animation otherwise.
+    pub fn animate(&self, duration: f32) -> Result<Animation, MathvisError> {
+        if self.steps == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "Gradient descent needs at least one step.".into(),
+            ));
+        }
+        if self.learning_rate.is_nan() || self.learning_rate <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The learning rate must be positive.".into(),
+            ));
+        }
+        let path = self.path();
+        let background: Box<dyn Drawable> = if self.heatmap {
+            Box::new(Heatmap::from_field(self.field.clone()).with_colormap(self.colormap.clone()))
+        } else {
+            Box::new(
+                ContourPlot::from_field(self.field.clone()).with_colormap(self.colormap.clone()),
+            )
+        };
+        let steps = self.steps;
+        Ok(Animation::new(
+            duration,
+            move |progress, canvas, screen, map| {
+                background.draw(canvas, map)?;
+                // A diverging descent stops being drawn once it leaves the screen far behind
+                let path = visible_prefix(&path, screen);
+                if path.is_empty() {
+                    return Ok(());
+                }
+                let position = progress * steps as f64;
+                let taken = (position.floor() as usize).min(path.len() - 1);
+                let step = position - taken as f64;
+                let to_pixels = |(x, y): (f64, f64)| map.to_pixels((x as f32, y as f32));
+                for pair in path[..=taken].windows(2) {
+                    draw_step(canvas, to_pixels(pair[0]), to_pixels(pair[1]));
+                }
+                let (x, y) = path[taken];
+                let current = match path.get(taken + 1) {
+                    Some(&(nx, ny)) => {
+                        let current = (x + (nx - x) * step, y + (ny - y) * step);
+                        canvas.draw_line(to_pixels((x, y)), to_pixels(current), STEP_COLOR);
+                        current
+                    }
+                    None => (x, y),
+                };
+                PointCloud::new(path[..=taken].to_vec(), STEP_COLOR)
+                    .with_radius(3)
+                    .draw(canvas, map)?;
+                PointCloud::new(vec![current], DESCENT_COLOR)
+                    .with_radius(6)
+                    .draw(canvas, map)
+            },
+        ))
+    }
+}
+
+/// Draws a step of the descent as an arrow between two pixels.
+fn draw_step(canvas: &mut dyn Canvas, start: (f32, f32), end: (f32, f32)) {
+    canvas.draw_line(start, end, STEP_COLOR);
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length < 1.0 {
+        return;
+    }
+    // The head is at most 12 pixels long, and shorter on short steps
+    let head = (length / 3.0).min(12.0);
+    let (ux, uy) = (dx / length * head, dy / length * head);
+    for side in [1.0, -1.0] {
+        canvas.draw_line(
+            end,
+            (end.0 - ux + side * uy / 2.0, end.1 - uy - side * ux / 2.0),
+            STEP_COLOR,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    fn map() -> CoordinateMap {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        CoordinateMap::new(&screen).unwrap()
+    }
+
+    #[test]
+    fn test_drawables() {
+        let map = map();
+        let mut img = RgbImage::new(854, 480);
+        Heatmap::new(|x, _| x)
+            .with_range(-1.0, 1.0)
+            .unwrap()
+            .draw(&mut img, &map)
+            .unwrap();
+        let (x, y) = map.to_pixels((-2.0, 0.0));
+        assert_eq!(
+            *img.get_pixel(x as u32, y as u32),
+            Colormap::heat().color(0.0)
+        );
+        let (x, y) = map.to_pixels((2.0, 0.0));
+        assert_eq!(
+            *img.get_pixel(x as u32, y as u32),
+            Colormap::heat().color(1.0)
+        );
+
+        // The unit circle is the level 1 curve of x² + y²
+        let samples = Samples::new(
+            &(Arc::new(|x: f64, y: f64| x * x + y * y) as ScalarField),
+            &map,
+        );
+        let segments = ContourPlot::segments(&samples, 1.0);
+        assert!(!segments.is_empty());
+        for (start, _) in segments {
+            let (x, y) = map.to_coordinates(start);
+            assert!((x.hypot(y) - 1.0).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_gradient_descent() {
+        let map = map();
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let descent =
+            GradientDescent::new(|x, y| x * x + y * y, |x, y| (2.0 * x, 2.0 * y), (2.0, 0.0))
+                .with_learning_rate(0.25)
+                .with_steps(2)
+                .with_heatmap(true);
+        assert_eq!(descent.path(), vec![(2.0, 0.0), (1.0, 0.0), (0.5, 0.0)]);
+        let mut img = RgbImage::new(854, 480);
+        descent
+            .animate(1.0)
+            .unwrap()
+            .draw_frame(9, &mut img, &screen, &map)
+            .unwrap();
+        let (x, y) = map.to_pixels((0.5, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), DESCENT_COLOR);
+        let (x, y) = map.to_pixels((1.5, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), STEP_COLOR);
+
+        // Overshooting doubles the distance to the minimum on every step, far past the screen
+        let diverging = descent
+            .clone()
+            .with_learning_rate(1.5)
+            .with_steps(40)
+            .with_heatmap(false)
+            .animate(1.0)
+            .unwrap();
+        for frame in 0..10 {
+            diverging
+                .draw_frame(frame, &mut img, &screen, &map)
+                .unwrap();
+        }
+        assert!(descent.clone().with_steps(0).animate(1.0).is_err());
+        assert!(descent.with_learning_rate(0.0).animate(1.0).is_err());
+    }
+}
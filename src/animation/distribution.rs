@@ -0,0 +1,370 @@
+//! Module containing drawables for common probability distributions, whose parameters can be animated.
+#![warn(missing_docs)]
+use std::f64::consts::TAU;
+
+use imageproc::image::Rgb;
+
+use super::{
+    canvas::Canvas, chart::BarChart, clip::Animation, drawable::Drawable, graph::FunctionGraph,
+};
+use crate::{
+    api::{bounds::BoundingBox, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// A probability distribution with its parameters.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::distribution::Distribution;
+///
+/// let coin_flips = Distribution::Binomial { trials: 4, probability: 0.5 };
+/// assert!((coin_flips.density(2.0) - 0.375).abs() < 1e-12);
+/// assert_eq!(coin_flips.mean(), 2.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// The normal distribution, a continuous bell curve around its mean.
+    Normal {
+        /// The mean, where the curve peaks.
+        mean: f64,
+        /// The standard deviation, how wide the curve is.
+        std_dev: f64,
+    },
+    /// The binomial distribution, the number of successes in a number of independent trials.
+    Binomial {
+        /// The number of trials.
+        trials: u32,
+        /// The probability of success of every trial.
+        probability: f64,
+    },
+    /// The Poisson distribution, the number of events happening in an interval at a known average rate.
+    Poisson {
+        /// The average number of events.
+        rate: f64,
+    },
+}
+
+impl Distribution {
+    /// Checks the parameters of the distribution.
+    ///
+    /// Returns an Err if the standard deviation or rate isn't positive or the probability isn't between 0 and 1,
+    /// and an Ok otherwise.
+    pub fn validate(&self) -> Result<(), MathvisError> {
+        let valid = match *self {
+            Distribution::Normal { mean, std_dev } => mean.is_finite() && std_dev > 0.0,
+            Distribution::Binomial { probability, .. } => (0.0..=1.0).contains(&probability),
+            Distribution::Poisson { rate } => rate > 0.0 && rate.is_finite(),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(MathvisError::InvalidArgument(format!(
+                "Invalid parameters for a distribution: {:?}.",
+                self
+            )))
+        }
+    }
+
+    /// Returns whether the distribution only takes integer values.
+    pub fn is_discrete(&self) -> bool {
+        !matches!(self, Distribution::Normal { .. })
+    }
+
+    /// Returns the mean of the distribution.
+    pub fn mean(&self) -> f64 {
+        match *self {
+            Distribution::Normal { mean, .. } => mean,
+            Distribution::Binomial {
+                trials,
+                probability,
+            } => trials as f64 * probability,
+            Distribution::Poisson { rate } => rate,
+        }
+    }
+
+    /// Returns the probability density at x for continuous distributions, or the probability of x for discrete ones,
+    /// which is 0 when x isn't one of their values.
+    pub fn density(&self, x: f64) -> f64 {
+        match *self {
+            Distribution::Normal { mean, std_dev } => {
+                let z = (x - mean) / std_dev;
+                (-z * z / 2.0).exp() / (std_dev * TAU.sqrt())
+            }
+            _ if x.fract() != 0.0 || x < 0.0 => 0.0,
+            Distribution::Binomial {
+                trials,
+                probability,
+            } => {
+                let k = x as u32;
+                if k > trials {
+                    return 0.0;
+                }
+                // Certain outcomes would take the logarithm of 0
+                match probability {
+                    0.0 => (k == 0) as u32 as f64,
+                    1.0 => (k == trials) as u32 as f64,
+                    p => (ln_factorial(trials) - ln_factorial(k) - ln_factorial(trials - k)
+                        + k as f64 * p.ln()
+                        + (trials - k) as f64 * (1.0 - p).ln())
+                    .exp(),
+                }
+            }
+            Distribution::Poisson { rate } => {
+                let k = x as u32;
+                (k as f64 * rate.ln() - rate - ln_factorial(k)).exp()
+            }
+        }
+    }
+
+    /// Returns the values of a discrete distribution worth drawing, the ones that aren't vanishingly unlikely.
+    fn support(&self) -> std::ops::RangeInclusive<u32> {
+        match *self {
+            Distribution::Normal { .. } => 0..=0,
+            Distribution::Binomial { trials, .. } => 0..=trials,
+            Distribution::Poisson { rate } => 0..=(rate + 6.0 * rate.sqrt() + 6.0).ceil() as u32,
+        }
+    }
+
+    /// Returns the distribution with its parameters blended from this one's to another's,
+    /// rounding the number of trials of binomial distributions.
+    ///
+    /// Returns None if the distributions aren't of the same kind and Some with the blended distribution otherwise.
+    fn interpolate(&self, other: &Distribution, t: f64) -> Option<Distribution> {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        match (*self, *other) {
+            (
+                Distribution::Normal { mean, std_dev },
+                Distribution::Normal {
+                    mean: mean2,
+                    std_dev: std_dev2,
+                },
+            ) => Some(Distribution::Normal {
+                mean: lerp(mean, mean2),
+                std_dev: lerp(std_dev, std_dev2),
+            }),
+            (
+                Distribution::Binomial {
+                    trials,
+                    probability,
+                },
+                Distribution::Binomial {
+                    trials: trials2,
+                    probability: probability2,
+                },
+            ) => Some(Distribution::Binomial {
+                trials: lerp(trials as f64, trials2 as f64).round() as u32,
+                probability: lerp(probability, probability2),
+            }),
+            (Distribution::Poisson { rate }, Distribution::Poisson { rate: rate2 }) => {
+                Some(Distribution::Poisson {
+                    rate: lerp(rate, rate2),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the natural logarithm of n!.
+fn ln_factorial(n: u32) -> f64 {
+    (2..=n).map(|i| (i as f64).ln()).sum()
+}
+
+/// A probability distribution drawn as its density curve if it's continuous, or as a bar chart of its probabilities if it's discrete.
+///
+/// Heights are the densities or probabilities multiplied by a vertical scale, since they're often too small to see otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::distribution::{Distribution, DistributionPlot};
+///
+/// let narrow = Distribution::Normal { mean: 0.0, std_dev: 0.5 };
+/// let wide = Distribution::Normal { mean: 0.0, std_dev: 2.0 };
+/// let plot = DistributionPlot::new(narrow, Rgb([255, 200, 0])).unwrap().with_scale(4.0);
+///
+/// // The bell curve widens and flattens as σ grows
+/// assert_eq!(plot.animate(wide, 3.0).unwrap().duration(), 3.0);
+/// let poisson = Distribution::Poisson { rate: 2.0 };
+/// assert!(plot.animate(poisson, 3.0).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistributionPlot {
+    distribution: Distribution,
+    color: Rgb<u8>,
+    scale: f64,
+}
+
+impl DistributionPlot {
+    /// Creates the plot of a distribution, with its heights unscaled.
+    ///
+    /// Returns an Err if the parameters of the distribution aren't valid and an Ok with the plot otherwise.
+    pub fn new(distribution: Distribution, color: Rgb<u8>) -> Result<Self, MathvisError> {
+        distribution.validate()?;
+        Ok(DistributionPlot {
+            distribution,
+            color,
+            scale: 1.0,
+        })
+    }
+
+    /// Sets the factor the densities or probabilities are multiplied by to get the heights drawn.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Returns the distribution plotted.
+    pub fn distribution(&self) -> Distribution {
+        self.distribution
+    }
+
+    /// Returns an animation of the specified duration in which the parameters of the distribution change
+    /// smoothly to the ones of another distribution of the same kind.
+    ///
+    /// Returns an Err if the distributions aren't of the same kind or the target's parameters aren't valid,
+    /// and an Ok with the animation otherwise.
+    pub fn animate(&self, to: Distribution, duration: f32) -> Result<Animation, MathvisError> {
+        to.validate()?;
+        if self.distribution.interpolate(&to, 0.0).is_none() {
+            return Err(MathvisError::InvalidArgument(format!(
+                "Can't animate a {:?} distribution into a {:?} one.",
+                self.distribution, to
+            )));
+        }
+        let plot = self.clone();
+        Ok(Animation::new(duration, move |progress, canvas, _, map| {
+            let distribution = plot.distribution.interpolate(&to, progress).unwrap_or(to);
+            DistributionPlot {
+                distribution,
+                ..plot.clone()
+            }
+            .draw(canvas, map)
+        }))
+    }
+}
+
+impl Drawable for DistributionPlot {
+    /// Returns the point of the x axis under the mean.
+    fn position(&self) -> (f64, f64) {
+        (self.distribution.mean(), 0.0)
+    }
+
+    /// Returns the box of the bars of discrete distributions, or of the curve of continuous ones
+    /// from 4 standard deviations before the mean to 4 after it, beyond which it's indistinguishable from the x axis.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let (distribution, scale) = (self.distribution, self.scale);
+        match distribution {
+            Distribution::Normal { mean, std_dev } => {
+                let peak = distribution.density(mean) * scale;
+                Some(BoundingBox::new(
+                    (mean - 4.0 * std_dev, 0.0),
+                    (mean + 4.0 * std_dev, peak),
+                ))
+            }
+            _ => {
+                let bars = distribution
+                    .support()
+                    .map(|k| (k as f64, distribution.density(k as f64) * scale))
+                    .collect();
+                BarChart::new(bars, self.color).bounding_box()
+            }
+        }
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        let (distribution, scale) = (self.distribution, self.scale);
+        if distribution.is_discrete() {
+            let bars = distribution
+                .support()
+                .map(|k| (k as f64, distribution.density(k as f64) * scale))
+                .collect();
+            BarChart::new(bars, self.color).draw(canvas, map)
+        } else {
+            FunctionGraph::new(move |x| distribution.density(x) * scale, self.color)
+                .draw(canvas, map)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_density() {
+        let normal = Distribution::Normal {
+            mean: 1.0,
+            std_dev: 2.0,
+        };
+        assert!((normal.density(1.0) - 1.0 / (2.0 * TAU.sqrt())).abs() < 1e-12);
+        let binomial = Distribution::Binomial {
+            trials: 10,
+            probability: 0.3,
+        };
+        let poisson = Distribution::Poisson { rate: 3.0 };
+        for distribution in [binomial, poisson] {
+            let total: f64 = distribution
+                .support()
+                .map(|k| distribution.density(k as f64))
+                .sum();
+            assert!((total - 1.0).abs() < 1e-6);
+            assert_eq!(distribution.density(1.5), 0.0);
+        }
+        assert!((poisson.density(2.0) - 4.5 * (-3f64).exp()).abs() < 1e-12);
+        let certain = Distribution::Binomial {
+            trials: 3,
+            probability: 1.0,
+        };
+        assert_eq!(certain.density(3.0), 1.0);
+        assert!(Distribution::Poisson { rate: 0.0 }.validate().is_err());
+        assert!(Distribution::Binomial {
+            trials: 3,
+            probability: 1.5
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_animate() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let color = Rgb([255, 200, 0]);
+        let plot = DistributionPlot::new(
+            Distribution::Binomial {
+                trials: 1,
+                probability: 0.5,
+            },
+            color,
+        )
+        .unwrap()
+        .with_scale(2.0);
+        let animation = plot
+            .animate(
+                Distribution::Binomial {
+                    trials: 1,
+                    probability: 0.9,
+                },
+                1.0,
+            )
+            .unwrap();
+        // The bar of 0 shrinks from a height of 1 to 0.2 while the bar of 1 grows to 1.8
+        let (x0, y0) = map.to_pixels((0.0, 0.5));
+        let (x1, y1) = map.to_pixels((1.0, 1.5));
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(0, &mut img, &screen, &map).unwrap();
+        assert_eq!(*img.get_pixel(x0 as u32, y0 as u32), color);
+        assert_eq!(*img.get_pixel(x1 as u32, y1 as u32), Rgb([0, 0, 0]));
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        assert_eq!(*img.get_pixel(x0 as u32, y0 as u32), Rgb([0, 0, 0]));
+        assert_eq!(*img.get_pixel(x1 as u32, y1 as u32), color);
+    }
+}
@@ -0,0 +1,62 @@
+//! Module containing an object-safe trait for anything that can be drawn on a frame.
+//!
+//! Unlike [Show2D](super::show::Show2D), [Drawable] doesn't depend on the number type of the object,
+//! so objects of different kinds and number types can be kept together as `Box<dyn Drawable>`.
+#![warn(missing_docs)]
+use crate::{
+    api::{bounds::BoundingBox, util::CoordinateMap},
+    error::MathvisError,
+};
+
+use super::canvas::Canvas;
+
+/// An object that can draw itself on a [Canvas].
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::{Rgb, RgbImage};
+/// use mathvis::animation::{drawable::Drawable, vector::Vector2D};
+/// use mathvis::api::{screen::Screen2D, util::CoordinateMap};
+///
+/// let screen = Screen2D::builder().build().unwrap();
+/// let map = CoordinateMap::new(&screen).unwrap();
+/// let objects: Vec<Box<dyn Drawable>> = vec![
+///     Box::new(Vector2D::new(1.0, 2.0, Rgb([255, 0, 0]))),
+///     Box::new(Vector2D::new(-1, 1, Rgb([0, 255, 0]))),
+/// ];
+///
+/// let mut img = RgbImage::new(1920, 1080);
+/// for object in &objects {
+///     object.draw(&mut img, &map).unwrap();
+/// }
+/// assert_eq!(objects[1].position(), (-1.0, 1.0));
+/// // Vectors go from the origin to their tip
+/// assert_eq!(objects[1].bounding_box().unwrap().min(), (-1.0, 0.0));
+/// ```
+pub trait Drawable: Send + Sync {
+    /// Returns the position of the object in screen coordinates.
+    fn position(&self) -> (f64, f64);
+    /// Returns the smallest box containing the object in screen coordinates,
+    /// leaving out what's drawn at a fixed size in pixels, like arrow tips, dots and labels.
+    ///
+    /// Returns None if the object has no finite extent, either because it's empty
+    /// or because it spans the whole plane, like a line or a heatmap, or if its whole size is in pixels,
+    /// like a matrix display, and Some with the box otherwise.
+    fn bounding_box(&self) -> Option<BoundingBox>;
+    /// Draws the object on a canvas, using the map to convert its coordinates to pixels.
+    ///
+    /// Returns an Err if the object can't be drawn and an Ok otherwise.
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError>;
+}
+
+/// Returns whether any of the object may be visible in the frame the map covers.
+///
+/// The frame is widened by a tenth of its height on every side, since the parts drawn at a fixed size in pixels
+/// (see [Drawable::bounding_box]) can reach into it from objects just outside, and objects without a box are always visible.
+pub(crate) fn is_visible(object: &dyn Drawable, map: &CoordinateMap) -> bool {
+    let frame = map.visible_bounds();
+    object
+        .bounding_box()
+        .is_none_or(|bounds| bounds.intersects(&frame.expanded(frame.height() / 10.0)))
+}
@@ -0,0 +1,45 @@
+//! Module containing easing curves, which reshape the progress of an animation so it speeds up and slows down smoothly.
+#![warn(missing_docs)]
+use serde::{Deserialize, Serialize};
+
+/// A curve mapping the linear progress of an animation, from 0 to 1, onto the progress of its motion, also from 0 to 1.
+///
+/// The curves that ease in or out are cubic, so they start or end with a speed of 0.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::easing::Easing;
+///
+/// assert_eq!(Easing::Linear.apply(0.25), 0.25);
+/// assert_eq!(Easing::EaseIn.apply(0.5), 0.125);
+/// assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+/// assert_eq!(Easing::EaseOut.apply(1.0), 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Starts slowly and speeds up.
+    EaseIn,
+    /// Starts quickly and slows down.
+    EaseOut,
+    /// Starts slowly, speeds up through the middle and slows down again.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Returns the progress of the motion at the specified progress of the animation, which is clamped between 0 and 1.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+        }
+    }
+}
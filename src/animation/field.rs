@@ -0,0 +1,577 @@
+//! Module containing drawables for vector fields and clouds of points, and phase portraits that animate points flowing along a field.
+#![warn(missing_docs)]
+use std::sync::Arc;
+
+use imageproc::{image::Rgb, point::Point};
+
+use super::{canvas::Canvas, clip::Animation, drawable::Drawable, path::Path2D};
+use crate::{
+    api::{
+        bounds::BoundingBox,
+        color::Color,
+        colormap::Colormap,
+        ode::solve_2d,
+        screen::{Screen2D, ScreenLike},
+        util::CoordinateMap,
+    },
+    error::MathvisError,
+};
+
+/// Color of the arrows of a vector field by default.
+const FIELD_COLOR: Rgb<u8> = Rgb([90, 90, 90]);
+/// Color of the points flowing along a phase portrait by default.
+const POINT_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+/// Number of RK4 steps per time unit the trajectories of a phase portrait are solved with.
+const STEPS_PER_TIME: f64 = 100.0;
+
+/// A planar vector field, giving the vector (x', y') at every point (x, y).
+pub type Field = Arc<dyn Fn(f64, f64) -> (f64, f64) + Send + Sync>;
+
+/// A vector field drawn as a grid of arrows over a rectangle of the screen.
+///
+/// Every arrow points along the field, and the longest one fills most of the space between grid points,
+/// so the lengths show how strong the field is relative to the rest of the rectangle.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::field::VectorField;
+///
+/// let rotation = VectorField::new(|x, y| (-y, x), (-2.0, -2.0), (2.0, 2.0), Rgb([90, 90, 90])).unwrap();
+/// assert!(rotation.with_spacing(0.0).is_err());
+/// ```
+#[derive(Clone)]
+pub struct VectorField {
+    field: Field,
+    bottom_left: (f64, f64),
+    top_right: (f64, f64),
+    spacing: f64,
+    color: Rgb<u8>,
+    coloring: ArrowColoring,
+}
+
+/// How the arrows of a vector field are colored.
+#[derive(Clone)]
+enum ArrowColoring {
+    /// Every arrow has the field's color.
+    Uniform,
+    /// Arrows are colored by their direction.
+    Angle,
+    /// Arrows are colored by their length on a colormap, from 0 to the longest arrow.
+    Magnitude(Colormap),
+}
+
+impl VectorField {
+    /// Creates a field drawn over the rectangle between `bottom_left` and `top_right`, with arrows every half unit.
+    ///
+    /// Returns an Err if the corners don't make a rectangle and an Ok with the field otherwise.
+    pub fn new<F>(
+        field: F,
+        bottom_left: (f64, f64),
+        top_right: (f64, f64),
+        color: Rgb<u8>,
+    ) -> Result<Self, MathvisError>
+    where
+        F: Fn(f64, f64) -> (f64, f64) + Send + Sync + 'static,
+    {
+        Self::from_field(Arc::new(field), bottom_left, top_right, color)
+    }
+
+    fn from_field(
+        field: Field,
+        bottom_left: (f64, f64),
+        top_right: (f64, f64),
+        color: Rgb<u8>,
+    ) -> Result<Self, MathvisError> {
+        if bottom_left.0 >= top_right.0 || bottom_left.1 >= top_right.1 {
+            return Err(MathvisError::InvalidArgument(
+                "The bottom left corner of a vector field must be below and to the left of its top right corner."
+                    .into(),
+            ));
+        }
+        Ok(VectorField {
+            field,
+            bottom_left,
+            top_right,
+            spacing: 0.5,
+            color,
+            coloring: ArrowColoring::Uniform,
+        })
+    }
+
+    /// Sets the distance between the arrows, in screen units.
+    ///
+    /// Returns an Err if the spacing isn't positive and an Ok with the field otherwise.
+    pub fn with_spacing(mut self, spacing: f64) -> Result<Self, MathvisError> {
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The spacing of a vector field must be positive.".into(),
+            ));
+        }
+        self.spacing = spacing;
+        Ok(self)
+    }
+
+    /// Sets whether every arrow is colored by its direction instead of the field's color,
+    /// with the hue going around the color wheel as the arrow turns (see [Color::from_angle]).
+    pub fn with_color_by_angle(mut self, color_by_angle: bool) -> Self {
+        self.coloring = match color_by_angle {
+            true => ArrowColoring::Angle,
+            false => ArrowColoring::Uniform,
+        };
+        self
+    }
+
+    /// Colors every arrow by the strength of the field on a colormap instead of the field's color,
+    /// from 0 for the weakest to 1 for the strongest arrow drawn.
+    pub fn with_magnitude_colormap(mut self, colormap: Colormap) -> Self {
+        self.coloring = ArrowColoring::Magnitude(colormap);
+        self
+    }
+
+    /// Returns the points of the grid the arrows start from, on the multiples of the spacing inside the rectangle.
+    fn grid(&self) -> Vec<(f64, f64)> {
+        let steps = |start: f64, end: f64| {
+            let first = (start / self.spacing).ceil() as i64;
+            let last = (end / self.spacing).floor() as i64;
+            (first..=last).map(|i| i as f64 * self.spacing)
+        };
+        steps(self.bottom_left.1, self.top_right.1)
+            .flat_map(|y| steps(self.bottom_left.0, self.top_right.0).map(move |x| (x, y)))
+            .collect()
+    }
+}
+
+impl Drawable for VectorField {
+    /// Returns the center of the rectangle the field is drawn over.
+    fn position(&self) -> (f64, f64) {
+        (
+            (self.bottom_left.0 + self.top_right.0) / 2.0,
+            (self.bottom_left.1 + self.top_right.1) / 2.0,
+        )
+    }
+
+    /// Returns the rectangle the field is drawn over, widened by the spacing of the arrows, which is as far as they reach.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        Some(BoundingBox::new(self.bottom_left, self.top_right).expanded(self.spacing))
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        let arrows: Vec<((f64, f64), (f64, f64))> = self
+            .grid()
+            .into_iter()
+            .map(|point| (point, (self.field)(point.0, point.1)))
+            .filter(|(_, (dx, dy))| dx.is_finite() && dy.is_finite())
+            .collect();
+        let longest = arrows
+            .iter()
+            .map(|(_, (dx, dy))| dx.hypot(*dy))
+            .fold(0.0, f64::max);
+        if longest == 0.0 {
+            return Ok(());
+        }
+        let scale = 0.8 * self.spacing / longest;
+        for ((x, y), (dx, dy)) in arrows {
+            let start = map.to_pixels((x as f32, y as f32));
+            let end = map.to_pixels(((x + dx * scale) as f32, (y + dy * scale) as f32));
+            let color = match &self.coloring {
+                ArrowColoring::Uniform => self.color,
+                ArrowColoring::Angle => Color::from_angle(dy.atan2(dx)).to_rgb(),
+                ArrowColoring::Magnitude(colormap) => colormap.color(dx.hypot(dy) / longest),
+            };
+            canvas.draw_line(start, end, color);
+            draw_arrow_head(canvas, start, end, color);
+        }
+        Ok(())
+    }
+}
+
+/// Draws the two sides of an arrow head at the end of a line, a quarter of its length long.
+fn draw_arrow_head(canvas: &mut dyn Canvas, start: (f32, f32), end: (f32, f32), color: Rgb<u8>) {
+    let (dx, dy) = ((start.0 - end.0) / 4.0, (start.1 - end.1) / 4.0);
+    // The sides are the line turned back by 30 degrees either way
+    let (cos, sin) = (0.866, 0.5);
+    for sin in [sin, -sin] {
+        canvas.draw_line(
+            end,
+            (end.0 + dx * cos - dy * sin, end.1 + dx * sin + dy * cos),
+            color,
+        );
+    }
+}
+
+/// A set of points drawn as small dots.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::{drawable::Drawable, field::PointCloud};
+///
+/// let cloud = PointCloud::new(vec![(0.0, 0.0), (2.0, 1.0)], Rgb([255, 200, 0]));
+/// assert_eq!(cloud.position(), (1.0, 0.5));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointCloud {
+    points: Vec<(f64, f64)>,
+    color: Rgb<u8>,
+    radius: i32,
+}
+
+impl PointCloud {
+    /// Creates a cloud of the specified points, in screen coordinates.
+    pub fn new(points: Vec<(f64, f64)>, color: Rgb<u8>) -> Self {
+        PointCloud {
+            points,
+            color,
+            radius: 4,
+        }
+    }
+
+    /// Sets the radius of the dots in pixels, 4 by default.
+    pub fn with_radius(mut self, radius: u32) -> Self {
+        self.radius = radius as i32;
+        self
+    }
+
+    /// Returns the points of the cloud.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+}
+
+impl Drawable for PointCloud {
+    /// Returns the centroid of the points, or the origin for an empty cloud.
+    fn position(&self) -> (f64, f64) {
+        if self.points.is_empty() {
+            return (0.0, 0.0);
+        }
+        let (x, y) = self
+            .points
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+        let n = self.points.len() as f64;
+        (x / n, y / n)
+    }
+
+    /// Returns the box of the centers of the dots, or None for an empty cloud.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(self.points.iter().copied())
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        let r = self.radius;
+        for &(x, y) in &self.points {
+            let Some((px, py)) = dot_center(canvas, map.to_pixels((x as f32, y as f32)), r) else {
+                continue;
+            };
+            if r == 0 {
+                canvas.draw_line((px as f32, py as f32), (px as f32, py as f32), self.color);
+                continue;
+            }
+            // An octagon is round enough at the size of a dot
+            let d = (r as f32 * 0.41).round() as i32;
+            canvas.draw_polygon(
+                &[
+                    Point::new(px + r, py - d),
+                    Point::new(px + r, py + d),
+                    Point::new(px + d, py + r),
+                    Point::new(px - d, py + r),
+                    Point::new(px - r, py + d),
+                    Point::new(px - r, py - d),
+                    Point::new(px - d, py - r),
+                    Point::new(px + d, py - r),
+                ],
+                self.color,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Returns the pixel a dot of the specified radius is centered on, or None if the dot doesn't reach the canvas.
+///
+/// Points far away from the screen map to pixels past what the corners of the dot can be computed in.
+pub(crate) fn dot_center(
+    canvas: &dyn Canvas,
+    (x, y): (f32, f32),
+    radius: i32,
+) -> Option<(i32, i32)> {
+    let r = radius as f32;
+    let reaches =
+        x >= -r && x <= canvas.width() as f32 + r && y >= -r && y <= canvas.height() as f32 + r;
+    reaches.then(|| (x.round() as i32, y.round() as i32))
+}
+
+/// A phase portrait of the autonomous planar system (x', y') = f(x, y), animating many initial conditions flowing along the field.
+///
+/// The field is drawn as a [VectorField] over the whole screen and the points as a [PointCloud], with the trail
+/// of every point drawn behind it. Trajectories are solved with RK4 (see [ode](crate::api::ode)) before the animation starts,
+/// and points that escape far away from the screen stop being drawn.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::field::PhasePortrait;
+///
+/// // A damped pendulum, with every point spiraling into a resting position
+/// let pendulum = PhasePortrait::new(|x, y| (y, -x.sin() - 0.3 * y))
+///     .with_starts(PhasePortrait::grid((-3.0, -3.0), (3.0, 3.0), 7, 7))
+///     .with_time(10.0);
+/// assert_eq!(pendulum.animate(5.0).unwrap().duration(), 5.0);
+/// ```
+#[derive(Clone)]
+pub struct PhasePortrait {
+    field: Field,
+    starts: Vec<(f64, f64)>,
+    time: f64,
+    trails: bool,
+    field_color: Rgb<u8>,
+    point_color: Rgb<u8>,
+}
+
+impl PhasePortrait {
+    /// Creates a phase portrait of the field with no initial conditions, which follows them for 5 time units with their trails drawn.
+    pub fn new<F>(field: F) -> Self
+    where
+        F: Fn(f64, f64) -> (f64, f64) + Send + Sync + 'static,
+    {
+        PhasePortrait {
+            field: Arc::new(field),
+            starts: Vec::new(),
+            time: 5.0,
+            trails: true,
+            field_color: FIELD_COLOR,
+            point_color: POINT_COLOR,
+        }
+    }
+
+    /// Returns a grid of initial conditions evenly spread over the rectangle between `bottom_left` and `top_right`, corners included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::field::PhasePortrait;
+    ///
+    /// let grid = PhasePortrait::grid((0.0, 0.0), (1.0, 2.0), 2, 3);
+    /// assert_eq!(grid, vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.0, 2.0), (1.0, 2.0)]);
+    /// ```
+    pub fn grid(
+        bottom_left: (f64, f64),
+        top_right: (f64, f64),
+        columns: u32,
+        rows: u32,
+    ) -> Vec<(f64, f64)> {
+        let step = |start: f64, end: f64, count: u32, i: u32| {
+            if count <= 1 {
+                (start + end) / 2.0
+            } else {
+                start + (end - start) * i as f64 / (count - 1) as f64
+            }
+        };
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    (
+                        step(bottom_left.0, top_right.0, columns, column),
+                        step(bottom_left.1, top_right.1, rows, row),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Sets the initial conditions that flow along the field.
+    pub fn with_starts(mut self, starts: Vec<(f64, f64)>) -> Self {
+        self.starts = starts;
+        self
+    }
+
+    /// Sets for how long the system is followed, in the time units of the field, over the whole animation.
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Sets whether the path every point has taken is drawn behind it.
+    pub fn with_trails(mut self, trails: bool) -> Self {
+        self.trails = trails;
+        self
+    }
+
+    /// Sets the colors of the field's arrows and of the points with their trails.
+    pub fn with_colors(mut self, field: Rgb<u8>, points: Rgb<u8>) -> Self {
+        self.field_color = field;
+        self.point_color = points;
+        self
+    }
+
+    /// Returns an animation of the specified duration flowing the initial conditions along the field.
+    ///
+    /// Returns an Err if the time the system is followed for isn't positive and an Ok with the animation otherwise.
+    pub fn animate(&self, duration: f32) -> Result<Animation, MathvisError> {
+        if self.time.is_nan() || self.time <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "A phase portrait must follow the system for a positive time.".into(),
+            ));
+        }
+        let steps = (self.time * STEPS_PER_TIME).ceil() as u32;
+        let field = self.field.clone();
+        let trajectories = self
+            .starts
+            .iter()
+            .map(|&start| solve_2d(|x, y| field(x, y), start, self.time, steps))
+            .collect::<Result<Vec<_>, _>>()?;
+        let portrait = self.clone();
+
+        Ok(Animation::new(
+            duration,
+            move |progress, canvas, screen, map| {
+                let ((x0, x1), (y0, y1)) = (
+                    <Screen2D as ScreenLike<f32>>::x_axis(screen),
+                    <Screen2D as ScreenLike<f32>>::y_axis(screen),
+                );
+                VectorField::from_field(
+                    portrait.field.clone(),
+                    (x0 as f64, y0 as f64),
+                    (x1 as f64, y1 as f64),
+                    portrait.field_color,
+                )?
+                .draw(canvas, map)?;
+
+                let position = progress * steps as f64;
+                let (index, step) = (position.floor() as usize, position.fract());
+                let mut trails = Vec::new();
+                let mut points = Vec::new();
+                for trajectory in &trajectories {
+                    let visible = visible_prefix(trajectory, screen);
+                    let Some(&(x, y)) = visible.get(index) else {
+                        continue;
+                    };
+                    let current = match visible.get(index + 1) {
+                        Some(&(nx, ny)) => (x + (nx - x) * step, y + (ny - y) * step),
+                        None if index + 1 < trajectory.len() => continue,
+                        None => (x, y),
+                    };
+                    if portrait.trails {
+                        let mut trail = visible[..=index].to_vec();
+                        trail.push(current);
+                        trails.push(trail);
+                    }
+                    points.push(current);
+                }
+                Path2D::new(trails, portrait.point_color).draw(canvas, map)?;
+                PointCloud::new(points, portrait.point_color).draw(canvas, map)
+            },
+        ))
+    }
+}
+
+/// Returns the start of a trajectory up to the first point that's far away from the screen or isn't finite.
+///
+/// Points within one screen's size of its edges are still drawn, since lines towards them cross the screen.
+pub(crate) fn visible_prefix<'a>(
+    trajectory: &'a [(f64, f64)],
+    screen: &Screen2D,
+) -> &'a [(f64, f64)] {
+    let ((x0, x1), (y0, y1)) = (
+        <Screen2D as ScreenLike<f32>>::x_axis(screen),
+        <Screen2D as ScreenLike<f32>>::y_axis(screen),
+    );
+    let (width, height) = ((x1 - x0) as f64, (y1 - y0) as f64);
+    let end = trajectory
+        .iter()
+        .position(|&(x, y)| {
+            !(x >= x0 as f64 - width
+                && x <= x1 as f64 + width
+                && y >= y0 as f64 - height
+                && y <= y1 as f64 + height)
+        })
+        .unwrap_or(trajectory.len());
+    &trajectory[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+
+    #[test]
+    fn test_drawables() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let mut img = RgbImage::new(854, 480);
+        let field = VectorField::new(|_, _| (1.0, 0.0), (-1.0, -1.0), (1.0, 1.0), FIELD_COLOR)
+            .unwrap()
+            .with_spacing(1.0)
+            .unwrap();
+        assert_eq!(field.grid().len(), 9);
+        field.draw(&mut img, &map).unwrap();
+        // The arrows point right and are 0.8 units long
+        let (x, y) = map.to_pixels((0.4, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), FIELD_COLOR);
+        let (x, y) = map.to_pixels((-0.1, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([0, 0, 0]));
+        assert!(VectorField::new(|_, _| (0.0, 0.0), (1.0, 0.0), (0.0, 1.0), FIELD_COLOR).is_err());
+
+        // Arrows pointing up are a quarter of the way around the color wheel
+        VectorField::new(|_, _| (0.0, 1.0), (1.5, -1.0), (2.5, 1.0), FIELD_COLOR)
+            .unwrap()
+            .with_color_by_angle(true)
+            .draw(&mut img, &map)
+            .unwrap();
+        let (x, y) = map.to_pixels((2.0, 0.4));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([128, 255, 0]));
+        // The strongest arrows take the end of the colormap
+        VectorField::new(|x, _| (0.0, x), (1.5, -1.0), (2.5, 1.0), FIELD_COLOR)
+            .unwrap()
+            .with_magnitude_colormap(Colormap::viridis())
+            .draw(&mut img, &map)
+            .unwrap();
+        let (x, y) = map.to_pixels((2.5, 0.2));
+        assert_eq!(
+            *img.get_pixel(x as u32, y as u32),
+            Colormap::viridis().color(1.0)
+        );
+
+        PointCloud::new(vec![(2.0, 2.0)], POINT_COLOR)
+            .draw(&mut img, &map)
+            .unwrap();
+        let (x, y) = map.to_pixels((2.0, 2.0));
+        assert_eq!(*img.get_pixel(x as u32 + 3, y as u32), POINT_COLOR);
+        // Dots far away from the screen and not finite aren't drawn
+        PointCloud::new(
+            vec![
+                (1e10, 0.0),
+                (0.0, -1e30),
+                (f64::NAN, 0.0),
+                (f64::INFINITY, 1.0),
+            ],
+            POINT_COLOR,
+        )
+        .draw(&mut img, &map)
+        .unwrap();
+    }
+
+    #[test]
+    fn test_phase_portrait() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        // Points move right at unit speed, or blow up past the screen
+        let portrait = PhasePortrait::new(|_, y| (1.0, y * y))
+            .with_starts(vec![(-1.0, 0.0), (-2.0, 2.0)])
+            .with_time(2.0)
+            .with_trails(true);
+        let animation = portrait.animate(1.0).unwrap();
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        let (x, y) = map.to_pixels((1.0, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), POINT_COLOR);
+        // The trail is left behind the point
+        let (x, y) = map.to_pixels((0.0, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), POINT_COLOR);
+        assert!(portrait.with_time(0.0).animate(1.0).is_err());
+    }
+}
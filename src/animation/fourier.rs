@@ -0,0 +1,275 @@
+//! Module containing Fourier series animations, which trace closed paths with rotating epicycles.
+#![warn(missing_docs)]
+use std::f64::consts::TAU;
+
+use imageproc::image::Rgb;
+
+use super::{canvas::Canvas, clip::Animation, drawable::Drawable, path::Path2D};
+use crate::{
+    api::{complex::Complex, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// Number of evenly spaced samples of the path the coefficients are computed from.
+const SAMPLES: usize = 512;
+/// Number of points of the trail drawn for a full turn of the epicycles.
+const TRAIL_POINTS: usize = 600;
+/// Number of line segments each epicycle is drawn with.
+const CIRCLE_SEGMENTS: usize = 48;
+/// Color of the circles and of the radii joining them.
+const EPICYCLE_COLOR: Rgb<u8> = Rgb([110, 110, 110]);
+/// Color of the path traced by the last epicycle.
+const TRAIL_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+
+/// A Fourier series of a closed path, animated as a chain of epicycles whose last point traces the path.
+///
+/// The path is sampled evenly along its length and its discrete Fourier transform gives one rotating circle per frequency,
+/// with the radius and starting angle of its coefficient. Circles are chained from the largest to the smallest,
+/// so the few terms that matter the most are drawn first, and the trail is drawn behind the tip of the chain.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::fourier::FourierAnimation;
+///
+/// let square = vec![(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+/// let fourier = FourierAnimation::new(square).unwrap().with_terms(20);
+/// assert_eq!(fourier.coefficients().len(), 20);
+/// assert_eq!(fourier.animate(6.0).duration(), 6.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FourierAnimation {
+    /// Frequencies and coefficients of the series, by decreasing magnitude.
+    coefficients: Vec<(i64, Complex)>,
+    terms: usize,
+    epicycle_color: Rgb<u8>,
+    trail_color: Rgb<u8>,
+}
+
+impl FourierAnimation {
+    /// Computes the Fourier series of the closed polyline through the specified points, which is closed back to the first point.
+    /// 100 epicycles are drawn by default.
+    ///
+    /// Returns an Err if there are less than 2 different points and an Ok with the series otherwise.
+    pub fn new(points: Vec<(f64, f64)>) -> Result<Self, MathvisError> {
+        let samples = resample(&points, SAMPLES).ok_or_else(|| {
+            MathvisError::InvalidArgument(
+                "A Fourier series needs a path through at least 2 different points.".into(),
+            )
+        })?;
+        let n = samples.len() as i64;
+        let mut coefficients: Vec<(i64, Complex)> = (-n / 2..n - n / 2)
+            .map(|frequency| {
+                let sum =
+                    samples
+                        .iter()
+                        .enumerate()
+                        .fold(Complex::default(), |sum, (i, &point)| {
+                            let angle = -TAU * frequency as f64 * i as f64 / n as f64;
+                            sum + Complex::from(point) * Complex::from_polar(1.0, angle)
+                        });
+                (frequency, sum * (1.0 / n as f64))
+            })
+            .collect();
+        // The constant term goes first since it's where the chain starts, then by decreasing radius
+        coefficients.sort_by(|(f1, c1), (f2, c2)| {
+            (*f1 != 0)
+                .cmp(&(*f2 != 0))
+                .then(c2.norm().total_cmp(&c1.norm()))
+        });
+        Ok(FourierAnimation {
+            coefficients,
+            terms: 100,
+            epicycle_color: EPICYCLE_COLOR,
+            trail_color: TRAIL_COLOR,
+        })
+    }
+
+    /// Computes the Fourier series of a path, joining its subpaths one after the other into a single closed path.
+    ///
+    /// Returns an Err if the path doesn't have at least 2 different points and an Ok with the series otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{fourier::FourierAnimation, path::Path2D};
+    ///
+    /// let triangle = Path2D::new(vec![vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]], Rgb([255, 255, 255]));
+    /// assert!(FourierAnimation::from_path(&triangle).is_ok());
+    /// ```
+    pub fn from_path(path: &Path2D) -> Result<Self, MathvisError> {
+        Self::new(path.subpaths().concat())
+    }
+
+    /// Sets the number of epicycles drawn, the constant term included, which is capped to the number of coefficients.
+    pub fn with_terms(mut self, terms: usize) -> Self {
+        self.terms = terms;
+        self
+    }
+
+    /// Sets the colors of the epicycles and of the traced path.
+    pub fn with_colors(mut self, epicycles: Rgb<u8>, trail: Rgb<u8>) -> Self {
+        self.epicycle_color = epicycles;
+        self.trail_color = trail;
+        self
+    }
+
+    /// Returns the frequencies and coefficients of the epicycles drawn, in the order they're chained.
+    pub fn coefficients(&self) -> &[(i64, Complex)] {
+        &self.coefficients[..self.terms.min(self.coefficients.len())]
+    }
+
+    /// Returns the point traced by the chain of epicycles after the specified fraction of a full turn.
+    pub fn trace(&self, turn: f64) -> (f64, f64) {
+        self.chain(turn).last().copied().unwrap_or_default().into()
+    }
+
+    /// Returns the center of every epicycle after the specified fraction of a full turn, followed by the tip of the chain.
+    fn chain(&self, turn: f64) -> Vec<Complex> {
+        let mut point = Complex::default();
+        let mut centers = vec![point];
+        for &(frequency, coefficient) in self.coefficients() {
+            point = point + coefficient * Complex::from_polar(1.0, TAU * frequency as f64 * turn);
+            centers.push(point);
+        }
+        centers
+    }
+
+    /// Returns an animation of the specified duration in which the epicycles make a full turn, tracing the path.
+    pub fn animate(&self, duration: f32) -> Animation {
+        let fourier = self.clone();
+        Animation::new(duration, move |progress, canvas, _, map| {
+            let chain = fourier.chain(progress);
+            // The constant term only moves the chain's start, so it's drawn as a radius but not as a circle
+            for (i, pair) in chain.windows(2).enumerate() {
+                let (center, next) = (pair[0], pair[1]);
+                if i > 0 {
+                    draw_circle(
+                        canvas,
+                        map,
+                        center,
+                        (next - center).norm(),
+                        fourier.epicycle_color,
+                    );
+                }
+                canvas.draw_line(
+                    to_pixels(center, map),
+                    to_pixels(next, map),
+                    fourier.epicycle_color,
+                );
+            }
+            let points = (progress * TRAIL_POINTS as f64).ceil() as usize;
+            let trail = (0..=points)
+                .map(|i| fourier.trace(progress * i as f64 / points.max(1) as f64))
+                .collect();
+            Path2D::new(vec![trail], fourier.trail_color)
+                .with_thickness(2.0)
+                .draw(canvas, map)
+        })
+    }
+}
+
+/// Returns the specified number of points evenly spaced along the closed polyline through the points, starting from the first one.
+///
+/// Returns None if the polyline has no length and Some with the points otherwise.
+fn resample(points: &[(f64, f64)], count: usize) -> Option<Vec<(f64, f64)>> {
+    let first = *points.first()?;
+    let closed: Vec<(f64, f64)> = points.iter().copied().chain([first]).collect();
+    let lengths: Vec<f64> = closed
+        .windows(2)
+        .map(|pair| (pair[1].0 - pair[0].0).hypot(pair[1].1 - pair[0].1))
+        .collect();
+    let total: f64 = lengths.iter().sum();
+    if total == 0.0 || !total.is_finite() {
+        return None;
+    }
+    let mut samples = Vec::with_capacity(count);
+    let (mut segment, mut start) = (0, 0.0);
+    for i in 0..count {
+        let distance = total * i as f64 / count as f64;
+        while segment < lengths.len() - 1 && start + lengths[segment] <= distance {
+            start += lengths[segment];
+            segment += 1;
+        }
+        let t = if lengths[segment] == 0.0 {
+            0.0
+        } else {
+            ((distance - start) / lengths[segment]).min(1.0)
+        };
+        let ((x0, y0), (x1, y1)) = (closed[segment], closed[segment + 1]);
+        samples.push((x0 + (x1 - x0) * t, y0 + (y1 - y0) * t));
+    }
+    Some(samples)
+}
+
+/// Returns the pixel a point of the plane, as a complex number, is drawn on.
+fn to_pixels(z: Complex, map: &CoordinateMap) -> (f32, f32) {
+    map.to_pixels((z.re as f32, z.im as f32))
+}
+
+/// Draws the outline of a circle in screen coordinates.
+fn draw_circle(
+    canvas: &mut dyn Canvas,
+    map: &CoordinateMap,
+    center: Complex,
+    radius: f64,
+    color: Rgb<u8>,
+) {
+    let point =
+        |i: usize| center + Complex::from_polar(radius, TAU * i as f64 / CIRCLE_SEGMENTS as f64);
+    for i in 0..CIRCLE_SEGMENTS {
+        canvas.draw_line(
+            to_pixels(point(i), map),
+            to_pixels(point(i + 1), map),
+            color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_coefficients() {
+        // A circle of radius 2 around (1, 1), traced counterclockwise, is a single epicycle
+        let circle: Vec<(f64, f64)> = (0..64)
+            .map(|i| Complex::from_polar(2.0, TAU * i as f64 / 64.0) + Complex::new(1.0, 1.0))
+            .map(Into::into)
+            .collect();
+        let fourier = FourierAnimation::new(circle).unwrap();
+        let [(f0, c0), (f1, c1), (_, c2)] = fourier.coefficients()[..3] else {
+            panic!("Missing coefficients");
+        };
+        assert_eq!((f0, f1), (0, 1));
+        assert!((c0 - Complex::new(1.0, 1.0)).norm() < 1e-9);
+        assert!((c1.norm() - 2.0).abs() < 1e-2);
+        assert!(c2.norm() < 1e-2);
+        let (x, y) = fourier.trace(0.25);
+        assert!((x - 1.0).abs() < 1e-2 && (y - 3.0).abs() < 1e-2);
+
+        assert!(FourierAnimation::new(vec![]).is_err());
+        assert!(FourierAnimation::new(vec![(1.0, 1.0), (1.0, 1.0)]).is_err());
+        assert_eq!(
+            resample(&[(0.0, 0.0), (1.0, 0.0)], 4).unwrap()[1],
+            (0.5, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_animate() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let square = vec![(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+        let animation = FourierAnimation::new(square).unwrap().animate(1.0);
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        // After a full turn the whole square is traced
+        let (x, y) = map.to_pixels((0.0, 1.0));
+        assert!((y as u32 - 2..=y as u32 + 2).any(|y| *img.get_pixel(x as u32, y) == TRAIL_COLOR));
+    }
+}
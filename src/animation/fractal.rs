@@ -0,0 +1,286 @@
+//! Module containing escape-time fractals, the Mandelbrot set and Julia sets, rendered pixel by pixel across worker threads.
+#![warn(missing_docs)]
+use std::{sync::Arc, thread::available_parallelism};
+
+use imageproc::image::Rgb;
+
+use super::{canvas::Canvas, clip::Animation, drawable::Drawable};
+use crate::{
+    api::{
+        bounds::BoundingBox, colormap::Colormap, complex::Complex, point::PointLike,
+        util::CoordinateMap,
+    },
+    error::MathvisError,
+    misc::render::map_rows,
+};
+
+/// Color of the points that never escape, the ones in the set.
+const INSIDE_COLOR: Rgb<u8> = Rgb([0, 0, 0]);
+/// Squared norm past which a point has escaped, far beyond 2 so the smooth iteration counts are accurate.
+const ESCAPE_NORM_SQR: f64 = 256.0 * 256.0;
+
+/// The iteration z ↦ z² + c that decides whether a point is in a fractal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FractalKind {
+    /// The Mandelbrot set, of the points c for which the iteration starting at z = 0 stays bounded.
+    Mandelbrot,
+    /// The Julia set of the constant c, of the points z for which the iteration starting at them stays bounded.
+    Julia(Complex),
+}
+
+/// An escape-time fractal filling the whole frame, viewed around a center with a zoom level.
+///
+/// The point of the complex plane drawn at screen coordinates (x, y) is `center + (x + iy) / zoom`,
+/// so a fractal with the default view lines up with the axes of a complex plane.
+/// Points outside of the set are colored by how fast they escape, with smooth iteration counts
+/// from 0 to the maximum number of iterations mapped onto the colormap, and points in the set are black.
+///
+/// The rows of pixels are computed in parallel, spread across worker threads where there are any.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::fractal::{Fractal, FractalKind};
+/// use mathvis::api::complex::Complex;
+///
+/// let mandelbrot = Fractal::new(FractalKind::Mandelbrot).with_iterations(200).unwrap();
+/// assert_eq!(mandelbrot.escape_time(Complex::new(-1.0, 0.0)), None);
+/// assert!(mandelbrot.escape_time(Complex::new(1.0, 1.0)).is_some());
+///
+/// let seahorses = mandelbrot.zoom_to(Complex::new(-0.745, 0.1), 50.0, 8.0).unwrap();
+/// assert_eq!(seahorses.duration(), 8.0);
+/// ```
+#[derive(Clone)]
+pub struct Fractal {
+    kind: FractalKind,
+    center: Complex,
+    zoom: f64,
+    iterations: u32,
+    colormap: Colormap,
+}
+
+impl Fractal {
+    /// Creates a fractal centered on the origin with a zoom of 1, iterated at most 100 times and
+    /// colored from dark blue for the points escaping first to yellow for the last ones.
+    pub fn new(kind: FractalKind) -> Self {
+        Fractal {
+            kind,
+            center: Complex::default(),
+            zoom: 1.0,
+            iterations: 100,
+            colormap: Colormap::default(),
+        }
+    }
+
+    /// Sets the point of the complex plane drawn at the origin of the screen.
+    pub fn with_center(mut self, center: Complex) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Sets how many times the fractal is magnified.
+    ///
+    /// Returns an Err if the zoom isn't positive and an Ok with the fractal otherwise.
+    pub fn with_zoom(mut self, zoom: f64) -> Result<Self, MathvisError> {
+        self.zoom = check_zoom(zoom)?;
+        Ok(self)
+    }
+
+    /// Sets the maximum number of iterations, after which a point is considered to be in the set.
+    /// Deeper zooms need more of them to show detail.
+    ///
+    /// Returns an Err if there are no iterations and an Ok with the fractal otherwise.
+    pub fn with_iterations(mut self, iterations: u32) -> Result<Self, MathvisError> {
+        if iterations == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "A fractal must be iterated at least once.".into(),
+            ));
+        }
+        self.iterations = iterations;
+        Ok(self)
+    }
+
+    /// Sets the colormap of the points outside of the set, from 0 for the ones escaping first to 1 for the last ones.
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Returns the smooth number of iterations it takes a point of the complex plane to escape.
+    ///
+    /// Returns None if the point doesn't escape within the maximum number of iterations and Some with the count otherwise.
+    pub fn escape_time(&self, point: Complex) -> Option<f64> {
+        let (mut z, c) = match self.kind {
+            FractalKind::Mandelbrot => (Complex::default(), point),
+            FractalKind::Julia(c) => (point, c),
+        };
+        for n in 0..self.iterations {
+            let norm_sqr = z.re * z.re + z.im * z.im;
+            if norm_sqr > ESCAPE_NORM_SQR {
+                // Interpolates between integer counts from how far past the escape radius the point got
+                let smooth = n as f64 + 1.0 - (norm_sqr.ln() / 2.0).ln() / 2f64.ln();
+                return Some(smooth.max(0.0));
+            }
+            z = z * z + c;
+        }
+        None
+    }
+
+    /// Returns the color of a point of the complex plane.
+    fn color(&self, point: Complex) -> Rgb<u8> {
+        match self.escape_time(point) {
+            Some(time) => self.colormap.color(time / self.iterations as f64),
+            None => INSIDE_COLOR,
+        }
+    }
+
+    /// Returns an animation of the specified duration zooming in or out from the current view to another one.
+    ///
+    /// The zoom changes geometrically, so every second magnifies by the same factor, and the center moves along
+    /// so the target drifts steadily to the middle of the screen instead of being pushed off it first.
+    ///
+    /// Returns an Err if the target zoom isn't positive and an Ok with the animation otherwise.
+    pub fn zoom_to(
+        &self,
+        center: Complex,
+        zoom: f64,
+        duration: f32,
+    ) -> Result<Animation, MathvisError> {
+        let zoom = check_zoom(zoom)?;
+        let fractal = self.clone();
+        Ok(Animation::new(duration, move |progress, canvas, _, map| {
+            let current = fractal.zoom * (zoom / fractal.zoom).powf(progress);
+            let moved = if zoom == fractal.zoom {
+                progress
+            } else {
+                (1.0 - fractal.zoom / current) / (1.0 - fractal.zoom / zoom)
+            };
+            Fractal {
+                center: fractal.center + (center - fractal.center) * moved,
+                zoom: current,
+                ..fractal.clone()
+            }
+            .draw(canvas, map)
+        }))
+    }
+}
+
+/// Returns the zoom if it's positive.
+///
+/// Returns an Err if it isn't and an Ok with the zoom otherwise.
+fn check_zoom(zoom: f64) -> Result<f64, MathvisError> {
+    if zoom.is_nan() || zoom <= 0.0 || zoom.is_infinite() {
+        return Err(MathvisError::InvalidArgument(format!(
+            "The zoom of a fractal must be positive, but it was {}.",
+            zoom
+        )));
+    }
+    Ok(zoom)
+}
+
+impl Drawable for Fractal {
+    /// Returns the screen coordinates of the center of the view, the origin.
+    fn position(&self) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+
+    /// Returns None, since a fractal fills the whole frame.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        None
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        let resolution = map.quality().resolution();
+        let (width, height) = (resolution.values()[0] as u32, resolution.values()[1] as u32);
+        // The map is linear, so every column has the same real part and every row the same imaginary part
+        let res: Arc<Vec<f64>> = Arc::new(
+            (0..width)
+                .map(|column| {
+                    self.center.re + map.to_coordinates((column as f32, 0.0)).0 as f64 / self.zoom
+                })
+                .collect(),
+        );
+        let ims: Vec<f64> = (0..height)
+            .map(|row| self.center.im + map.to_coordinates((0.0, row as f32)).1 as f64 / self.zoom)
+            .collect();
+        let fractal = self.clone();
+        // Inside a frame worker the rows are computed on its thread, since the other cores are drawing other frames
+        let jobs = available_parallelism().map_or(1, |jobs| jobs.get());
+        let rows = map_rows(height, jobs, move |row| {
+            res.iter()
+                .map(|&re| fractal.color(Complex::new(re, ims[row as usize])))
+                .collect::<Vec<_>>()
+        })?;
+
+        for (row, colors) in rows.iter().enumerate() {
+            // Runs of the same color are drawn as a single line
+            let mut start = 0;
+            for column in 1..=colors.len() {
+                if column == colors.len() || colors[column] != colors[start] {
+                    canvas.draw_line(
+                        (start as f32, row as f32),
+                        ((column - 1) as f32, row as f32),
+                        colors[start],
+                    );
+                    start = column;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_escape_time() {
+        let mandelbrot = Fractal::new(FractalKind::Mandelbrot);
+        for inside in [(0.0, 0.0), (-1.0, 0.0), (0.25, 0.0), (-0.1, 0.6)] {
+            assert_eq!(mandelbrot.escape_time(inside.into()), None);
+        }
+        // Points further out escape sooner
+        let near = mandelbrot.escape_time(Complex::new(0.3, 0.0)).unwrap();
+        let far = mandelbrot.escape_time(Complex::new(1.0, 0.0)).unwrap();
+        assert!(far < near);
+
+        // Julia sets of c = 0 are the unit disk
+        let disk = Fractal::new(FractalKind::Julia(Complex::default()));
+        assert_eq!(disk.escape_time(Complex::new(0.0, 0.99)), None);
+        assert!(disk.escape_time(Complex::new(0.0, 1.01)).is_some());
+        assert!(disk.with_iterations(0).is_err());
+    }
+
+    #[test]
+    fn test_draw() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let fractal = Fractal::new(FractalKind::Julia(Complex::default()))
+            .with_colormap(Colormap::from_fn(|_| Rgb([255, 255, 255])))
+            .with_center(Complex::new(1.0, 0.0))
+            .with_zoom(2.0)
+            .unwrap();
+        let mut img = RgbImage::new(854, 480);
+        fractal.draw(&mut img, &map).unwrap();
+        // The unit disk is centered on screen coordinates (-2, 0) with a radius of 2
+        for (point, expected) in [
+            ((-2.0, 1.5), INSIDE_COLOR),
+            ((-0.5, 0.0), INSIDE_COLOR),
+            ((0.5, 0.0), Rgb([255, 255, 255])),
+            ((-2.0, 2.5), Rgb([255, 255, 255])),
+        ] {
+            let (x, y) = map.to_pixels(point);
+            assert_eq!(*img.get_pixel(x as u32, y as u32), expected);
+        }
+
+        let animation = fractal.zoom_to(Complex::new(-1.0, 0.0), 1.0, 1.0).unwrap();
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        let (x, y) = map.to_pixels((0.5, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), INSIDE_COLOR);
+        assert!(fractal.zoom_to(Complex::default(), 0.0, 1.0).is_err());
+    }
+}
@@ -0,0 +1,945 @@
+//! Module containing classical compass-and-straightedge constructions, with drawables for their lines and circles
+//! and animations showing every step taken to construct them.
+#![warn(missing_docs)]
+use std::f64::consts::{PI, TAU};
+
+use imageproc::image::Rgb;
+
+use super::{canvas::Canvas, clip::Animation, drawable::Drawable, field::PointCloud, path::Path2D};
+use crate::{
+    api::{
+        angle::Angle,
+        bounds::BoundingBox,
+        point::{Point, PointLike},
+        util::CoordinateMap,
+    },
+    error::MathvisError,
+};
+
+/// Number of line segments a whole circle is drawn with.
+const CIRCLE_SEGMENTS: usize = 96;
+/// Angle an arc drawn with the compass goes past the points it's drawn around, on each side.
+const ARC_MARGIN: f64 = PI / 12.0;
+/// Default color of lines and circles, and of the objects a construction starts from.
+const GIVEN_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+/// Color of the arcs and lines drawn along the way to the result of a construction.
+const GUIDE_COLOR: Rgb<u8> = Rgb([110, 110, 110]);
+/// Color of the result of a construction.
+const RESULT_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+
+/// Returns the distance between two points.
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Returns the point a fraction of the way from a to b.
+fn lerp(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// Returns the length of the diagonal of the visible area, so lines drawn that far from any visible point cross the whole screen.
+fn reach(map: &CoordinateMap) -> f64 {
+    let resolution = map.quality().resolution();
+    let (left, top) = map.to_coordinates((0.0, 0.0));
+    let (right, bottom) = map.to_coordinates((resolution.values()[0], resolution.values()[1]));
+    ((right - left) as f64).hypot((top - bottom) as f64)
+}
+
+/// Returns the screen coordinates of the middle of the frame.
+fn map_center(map: &CoordinateMap) -> (f64, f64) {
+    let resolution = map.quality().resolution();
+    let (x, y) = map.to_coordinates((resolution.values()[0] / 2.0, resolution.values()[1] / 2.0));
+    (x as f64, y as f64)
+}
+
+/// Returns the points where two circles meet, none if they don't, and the same point twice if they touch.
+pub(crate) fn circle_intersections(
+    c1: (f64, f64),
+    r1: f64,
+    c2: (f64, f64),
+    r2: f64,
+) -> Vec<(f64, f64)> {
+    let d = distance(c1, c2);
+    if d == 0.0 || d > r1 + r2 + 1e-12 || d < (r1 - r2).abs() - 1e-12 {
+        return Vec::new();
+    }
+    // Distance from c1 to the chord between the intersections, and half the chord's length
+    let along = (d * d + r1 * r1 - r2 * r2) / (2.0 * d);
+    let half = (r1 * r1 - along * along).max(0.0).sqrt();
+    let (ux, uy) = ((c2.0 - c1.0) / d, (c2.1 - c1.1) / d);
+    let foot = (c1.0 + ux * along, c1.1 + uy * along);
+    vec![
+        (foot.0 - uy * half, foot.1 + ux * half),
+        (foot.0 + uy * half, foot.1 - ux * half),
+    ]
+}
+
+/// An infinite straight line, drawn across the whole screen.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::Line;
+///
+/// let diagonal = Line::through((0.0, 0.0), (2.0, 2.0)).unwrap();
+/// assert!(diagonal.distance_to((1.0, 1.0)) < 1e-12);
+/// assert!((diagonal.distance_to((2.0, 0.0)) - 2f64.sqrt()).abs() < 1e-12);
+/// assert!(Line::through((1.0, 1.0), (1.0, 1.0)).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line {
+    point: (f64, f64),
+    direction: (f64, f64),
+    color: Rgb<u8>,
+}
+
+impl Line {
+    /// Creates the line through two points.
+    ///
+    /// Returns an Err if the points are the same and an Ok with the line otherwise.
+    pub fn through(a: (f64, f64), b: (f64, f64)) -> Result<Self, MathvisError> {
+        let length = distance(a, b);
+        if length == 0.0 || !length.is_finite() {
+            return Err(MathvisError::InvalidArgument(format!(
+                "A line needs two different points, but it was given {:?} and {:?}.",
+                a, b
+            )));
+        }
+        Ok(Line {
+            point: a,
+            direction: ((b.0 - a.0) / length, (b.1 - a.1) / length),
+            color: GIVEN_COLOR,
+        })
+    }
+
+    /// Sets the color of the line, white by default.
+    pub fn with_color(mut self, color: Rgb<u8>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the point the line was created through first.
+    pub fn point(&self) -> (f64, f64) {
+        self.point
+    }
+
+    /// Returns the unit vector along the line.
+    pub fn direction(&self) -> (f64, f64) {
+        self.direction
+    }
+
+    /// Returns the distance from a point to the line.
+    pub fn distance_to(&self, point: (f64, f64)) -> f64 {
+        let (dx, dy) = (point.0 - self.point.0, point.1 - self.point.1);
+        (dx * self.direction.1 - dy * self.direction.0).abs()
+    }
+
+    /// Returns the foot of the perpendicular from a point to the line, the closest point of the line to it.
+    pub fn project(&self, point: (f64, f64)) -> (f64, f64) {
+        let along = (point.0 - self.point.0) * self.direction.0
+            + (point.1 - self.point.1) * self.direction.1;
+        (
+            self.point.0 + along * self.direction.0,
+            self.point.1 + along * self.direction.1,
+        )
+    }
+
+    /// Returns the mirror image of a point across the line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::geometry::Line;
+    ///
+    /// let diagonal = Line::through((0.0, 0.0), (1.0, 1.0)).unwrap();
+    /// let (x, y) = diagonal.reflect((2.0, 0.0));
+    /// assert!(x.abs() < 1e-12 && (y - 2.0).abs() < 1e-12);
+    /// ```
+    pub fn reflect(&self, point: (f64, f64)) -> (f64, f64) {
+        let foot = self.project(point);
+        (2.0 * foot.0 - point.0, 2.0 * foot.1 - point.1)
+    }
+
+    /// Returns the part of the line reaching the specified distance from its point on both sides.
+    fn span(&self, length: f64) -> ((f64, f64), (f64, f64)) {
+        let (x, y) = self.point;
+        let (dx, dy) = (self.direction.0 * length, self.direction.1 * length);
+        ((x - dx, y - dy), (x + dx, y + dy))
+    }
+}
+
+impl Drawable for Line {
+    /// Returns the point the line was created through first.
+    fn position(&self) -> (f64, f64) {
+        self.point
+    }
+
+    /// Returns None, since a line goes on forever.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        None
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        let (start, end) = self.span(reach(map) + distance(self.point, map_center(map)));
+        Path2D::new(vec![vec![start, end]], self.color)
+            .with_thickness(2.0)
+            .draw(canvas, map)
+    }
+}
+
+/// A circle, drawn as its outline.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::Circle;
+///
+/// let unit = Circle::new((0.0, 0.0), 1.0).unwrap();
+/// assert_eq!(unit.radius(), 1.0);
+/// assert!(Circle::new((0.0, 0.0), -1.0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    center: (f64, f64),
+    radius: f64,
+    color: Rgb<u8>,
+}
+
+impl Circle {
+    /// Creates a circle from its center and radius.
+    ///
+    /// Returns an Err if the radius isn't positive and an Ok with the circle otherwise.
+    pub fn new(center: (f64, f64), radius: f64) -> Result<Self, MathvisError> {
+        if radius.is_nan() || radius <= 0.0 || radius.is_infinite() {
+            return Err(MathvisError::InvalidArgument(format!(
+                "The radius of a circle must be positive, but it was {}.",
+                radius
+            )));
+        }
+        Ok(Circle {
+            center,
+            radius,
+            color: GIVEN_COLOR,
+        })
+    }
+
+    /// Sets the color of the circle, white by default.
+    pub fn with_color(mut self, color: Rgb<u8>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the center of the circle.
+    pub fn center(&self) -> (f64, f64) {
+        self.center
+    }
+
+    /// Returns the radius of the circle.
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Returns the point of the circle at an angle from the positive x direction.
+    fn point_at(&self, angle: f64) -> (f64, f64) {
+        (
+            self.center.0 + self.radius * angle.cos(),
+            self.center.1 + self.radius * angle.sin(),
+        )
+    }
+
+    /// Returns the arc of the circle starting at an angle from the positive x direction and sweeping counterclockwise by another,
+    /// or clockwise if it's negative, with plain numbers taken as radians (see [Angle]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::geometry::Circle;
+    /// use mathvis::api::angle::Angle;
+    ///
+    /// let circle = Circle::new((0.0, 0.0), 2.0).unwrap();
+    /// let quarter = circle.arc(Angle::degrees(90.0), Angle::degrees(-90.0));
+    /// let end = *quarter.subpaths()[0].last().unwrap();
+    /// assert!((end.0 - 2.0).abs() < 1e-12 && end.1.abs() < 1e-12);
+    /// ```
+    pub fn arc(&self, start: impl Into<Angle>, sweep: impl Into<Angle>) -> Path2D {
+        let (start, sweep) = (start.into().to_radians(), sweep.into().to_radians());
+        let segments = ((sweep.abs() / TAU * CIRCLE_SEGMENTS as f64).ceil() as usize).max(1);
+        let points = (0..=segments)
+            .map(|i| self.point_at(start + sweep * i as f64 / segments as f64))
+            .collect();
+        Path2D::new(vec![points], self.color).with_thickness(2.0)
+    }
+}
+
+impl Drawable for Circle {
+    /// Returns the center of the circle.
+    fn position(&self) -> (f64, f64) {
+        self.center
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let (x, y) = self.center;
+        Some(BoundingBox::new(
+            (x - self.radius, y - self.radius),
+            (x + self.radius, y + self.radius),
+        ))
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        self.arc(0.0, TAU).draw(canvas, map)
+    }
+}
+
+/// Returns the perpendicular bisector of the segment between two points, the line of the points as far from both.
+///
+/// Returns an Err if the points are the same and an Ok with the line otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::perpendicular_bisector;
+///
+/// let bisector = perpendicular_bisector((0.0, 0.0), (2.0, 0.0)).unwrap();
+/// assert!(bisector.distance_to((1.0, 5.0)) < 1e-12);
+/// ```
+pub fn perpendicular_bisector(a: (f64, f64), b: (f64, f64)) -> Result<Line, MathvisError> {
+    Line::through(a, b)?;
+    let middle = lerp(a, b, 0.5);
+    Line::through(middle, (middle.0 - (b.1 - a.1), middle.1 + (b.0 - a.0)))
+}
+
+/// Returns the bisector of the angle at `vertex` between the rays towards `a` and `b`, the line splitting it in two equal angles.
+///
+/// Returns an Err if either point is the vertex or the rays point in opposite directions and an Ok with the line otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::angle_bisector;
+///
+/// let bisector = angle_bisector((1.0, 0.0), (0.0, 0.0), (0.0, 3.0)).unwrap();
+/// assert!(bisector.distance_to((2.0, 2.0)) < 1e-12);
+/// ```
+pub fn angle_bisector(
+    a: (f64, f64),
+    vertex: (f64, f64),
+    b: (f64, f64),
+) -> Result<Line, MathvisError> {
+    let (da, db) = (distance(vertex, a), distance(vertex, b));
+    if da == 0.0 || db == 0.0 {
+        return Err(MathvisError::InvalidArgument(
+            "The sides of an angle must be rays away from its vertex.".into(),
+        ));
+    }
+    let along = (
+        (a.0 - vertex.0) / da + (b.0 - vertex.0) / db,
+        (a.1 - vertex.1) / da + (b.1 - vertex.1) / db,
+    );
+    Line::through(vertex, (vertex.0 + along.0, vertex.1 + along.1)).map_err(|_| {
+        MathvisError::InvalidArgument(
+            "A straight angle has no single bisector to construct.".into(),
+        )
+    })
+}
+
+/// Returns an Err if the three points are on the same line, so they aren't the corners of a triangle, and an Ok otherwise.
+fn check_triangle(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Result<(), MathvisError> {
+    let area = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+    if area.abs() <= 1e-12 * distance(a, b).max(distance(a, c)).powi(2) || !area.is_finite() {
+        return Err(MathvisError::InvalidArgument(format!(
+            "The points {:?}, {:?} and {:?} are on the same line, so they aren't the corners of a triangle.",
+            a, b, c
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the circumcircle of the triangle with the specified corners, the circle going through all three.
+///
+/// Returns an Err if the points are on the same line and an Ok with the circle otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::circumcircle;
+///
+/// let circle = circumcircle((0.0, 0.0), (2.0, 0.0), (0.0, 2.0)).unwrap();
+/// assert_eq!(circle.center(), (1.0, 1.0));
+/// assert!(circumcircle((0.0, 0.0), (1.0, 1.0), (2.0, 2.0)).is_err());
+/// ```
+pub fn circumcircle(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Result<Circle, MathvisError> {
+    check_triangle(a, b, c)?;
+    let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+    let (sa, sb, sc) = (
+        a.0 * a.0 + a.1 * a.1,
+        b.0 * b.0 + b.1 * b.1,
+        c.0 * c.0 + c.1 * c.1,
+    );
+    let center = (
+        (sa * (b.1 - c.1) + sb * (c.1 - a.1) + sc * (a.1 - b.1)) / d,
+        (sa * (c.0 - b.0) + sb * (a.0 - c.0) + sc * (b.0 - a.0)) / d,
+    );
+    Circle::new(center, distance(center, a))
+}
+
+/// Returns the incircle of the triangle with the specified corners, the largest circle inside it, touching all three sides.
+///
+/// Returns an Err if the points are on the same line and an Ok with the circle otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::incircle;
+///
+/// // The 3-4-5 right triangle has an inradius of 1
+/// let circle = incircle((0.0, 0.0), (4.0, 0.0), (0.0, 3.0)).unwrap();
+/// assert_eq!(circle.center(), (1.0, 1.0));
+/// assert_eq!(circle.radius(), 1.0);
+/// ```
+pub fn incircle(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Result<Circle, MathvisError> {
+    check_triangle(a, b, c)?;
+    // Every corner is weighted by the length of the side opposite to it
+    let (la, lb, lc) = (distance(b, c), distance(a, c), distance(a, b));
+    let perimeter = la + lb + lc;
+    let center = (
+        (la * a.0 + lb * b.0 + lc * c.0) / perimeter,
+        (la * a.1 + lb * b.1 + lc * c.1) / perimeter,
+    );
+    let area = ((b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)).abs() / 2.0;
+    Circle::new(center, 2.0 * area / perimeter)
+}
+
+/// Returns the points where the tangent lines from a point outside of a circle touch it.
+///
+/// Returns an Err if the point is inside the circle and an Ok with the points otherwise, which are the same point twice
+/// if it's on the circle.
+fn tangent_points(point: (f64, f64), circle: &Circle) -> Result<Vec<(f64, f64)>, MathvisError> {
+    let d = distance(point, circle.center);
+    if d < circle.radius - 1e-12 {
+        return Err(MathvisError::InvalidArgument(format!(
+            "There are no tangent lines to a circle from {:?}, which is inside it.",
+            point
+        )));
+    }
+    // The tangent points see the center and the point at a right angle, so they're on the circle with them as a diameter
+    let middle = lerp(point, circle.center, 0.5);
+    Ok(circle_intersections(
+        circle.center,
+        circle.radius,
+        middle,
+        d / 2.0,
+    ))
+}
+
+/// Returns the lines through a point that are tangent to a circle, two if it's outside of it and one if it's on it.
+///
+/// Returns an Err if the point is inside the circle and an Ok with the lines otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::{tangent_lines, Circle};
+///
+/// let circle = Circle::new((0.0, 0.0), 1.0).unwrap();
+/// let tangents = tangent_lines((2.0, 0.0), &circle).unwrap();
+/// assert_eq!(tangents.len(), 2);
+/// for tangent in tangents {
+///     assert!((tangent.distance_to((0.0, 0.0)) - 1.0).abs() < 1e-12);
+/// }
+/// assert_eq!(tangent_lines((0.0, 1.0), &circle).unwrap().len(), 1);
+/// assert!(tangent_lines((0.5, 0.0), &circle).is_err());
+/// ```
+pub fn tangent_lines(point: (f64, f64), circle: &Circle) -> Result<Vec<Line>, MathvisError> {
+    let points = tangent_points(point, circle)?;
+    if distance(point, circle.center) <= circle.radius + 1e-12 {
+        // On the circle the tangent is perpendicular to the radius
+        let (dx, dy) = (point.0 - circle.center.0, point.1 - circle.center.1);
+        return Ok(vec![Line::through(point, (point.0 - dy, point.1 + dx))?]);
+    }
+    points
+        .into_iter()
+        .map(|touch| Line::through(point, touch))
+        .collect()
+}
+
+/// What an object is for in a construction, which decides its color and when it's drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    /// Given from the start, drawn during the whole animation.
+    Given,
+    /// Drawn along the way to the result.
+    Guide,
+    /// The result, drawn last.
+    Result,
+}
+
+impl Role {
+    fn color(self) -> Rgb<u8> {
+        match self {
+            Role::Given => GIVEN_COLOR,
+            Role::Guide => GUIDE_COLOR,
+            Role::Result => RESULT_COLOR,
+        }
+    }
+}
+
+/// A single mark made during a construction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stroke {
+    /// A point, marked where lines or arcs meet.
+    Point((f64, f64)),
+    /// A segment drawn with the straightedge, from its first point.
+    Segment((f64, f64), (f64, f64)),
+    /// A whole line drawn with the straightedge, out from its point.
+    Line(Line),
+    /// An arc drawn with the compass, from a start angle by a sweep.
+    Arc(Circle, f64, f64),
+}
+
+impl Stroke {
+    /// Draws the part of the stroke done after the specified fraction of it.
+    fn draw(
+        &self,
+        done: f64,
+        color: Rgb<u8>,
+        canvas: &mut dyn Canvas,
+        map: &CoordinateMap,
+    ) -> Result<(), MathvisError> {
+        match *self {
+            Stroke::Point(point) => PointCloud::new(vec![point], color).draw(canvas, map),
+            Stroke::Segment(a, b) => Path2D::new(vec![vec![a, lerp(a, b, done)]], color)
+                .with_thickness(2.0)
+                .draw(canvas, map),
+            Stroke::Line(line) => {
+                let (start, end) =
+                    line.span((reach(map) + distance(line.point, map_center(map))) * done);
+                Path2D::new(vec![vec![start, end]], color)
+                    .with_thickness(2.0)
+                    .draw(canvas, map)
+            }
+            Stroke::Arc(circle, start, sweep) => circle
+                .with_color(color)
+                .arc(start, sweep * done)
+                .draw(canvas, map),
+        }
+    }
+
+    /// Returns the share of the animation the stroke takes, with points taking less time than lines and arcs.
+    fn weight(&self) -> f64 {
+        match self {
+            Stroke::Point(_) => 0.25,
+            _ => 1.0,
+        }
+    }
+}
+
+/// A compass-and-straightedge construction, recorded as the strokes taken from the given objects to the result.
+///
+/// Given objects are white, the arcs and lines drawn along the way are gray and the result is yellow.
+/// Drawing a construction draws every stroke, and animating it draws them one after the other.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::Construction;
+///
+/// let construction = Construction::circumcircle((-2.0, -1.0), (2.0, -1.0), (0.5, 2.0)).unwrap();
+/// assert_eq!(construction.animate(6.0).duration(), 6.0);
+/// assert!(Construction::incircle((0.0, 0.0), (1.0, 0.0), (2.0, 0.0)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Construction {
+    strokes: Vec<(Stroke, Role)>,
+}
+
+impl Construction {
+    /// Creates a construction from nothing.
+    fn empty() -> Self {
+        Construction {
+            strokes: Vec::new(),
+        }
+    }
+
+    /// Adds a stroke with its role.
+    fn push(&mut self, stroke: Stroke, role: Role) {
+        self.strokes.push((stroke, role));
+    }
+
+    /// Adds an arc of a circle drawn with the compass around the specified points on it, going a bit past them.
+    fn arc_around(&mut self, center: (f64, f64), radius: f64, points: &[(f64, f64)]) {
+        let Ok(circle) = Circle::new(center, radius) else {
+            return;
+        };
+        let angle = |p: (f64, f64)| (p.1 - center.1).atan2(p.0 - center.0);
+        let first = angle(points[0]);
+        // Angles are measured from the first point, so the arc goes the short way between the points
+        let (low, high) = points.iter().fold((0.0f64, 0.0f64), |(low, high), &p| {
+            let relative = (angle(p) - first + PI).rem_euclid(TAU) - PI;
+            (low.min(relative), high.max(relative))
+        });
+        self.push(
+            Stroke::Arc(
+                circle,
+                first + low - ARC_MARGIN,
+                high - low + 2.0 * ARC_MARGIN,
+            ),
+            Role::Guide,
+        );
+    }
+
+    /// Adds the steps of constructing the perpendicular bisector of a segment: arcs of the same radius around both ends
+    /// meet at two points, and the line through them is the bisector.
+    ///
+    /// Returns an Err if the arcs don't meet at two different points, which only happens when the ends are too close
+    /// or too far apart to compute where they meet, and an Ok with the bisector otherwise.
+    fn add_perpendicular_bisector(
+        &mut self,
+        a: (f64, f64),
+        b: (f64, f64),
+        role: Role,
+    ) -> Result<Line, MathvisError> {
+        let radius = distance(a, b);
+        let meets = circle_intersections(a, radius, b, radius);
+        let line = match meets[..] {
+            [p, q] => Line::through(p, q).ok(),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            MathvisError::InvalidArgument(format!(
+                "The perpendicular bisector of {:?} and {:?} can't be constructed.",
+                a, b
+            ))
+        })?;
+        self.arc_around(a, radius, &meets);
+        self.arc_around(b, radius, &meets);
+        for &point in &meets {
+            self.push(Stroke::Point(point), Role::Guide);
+        }
+        self.push(Stroke::Line(line), role);
+        Ok(line)
+    }
+
+    /// Adds the steps of constructing the bisector of an angle: an arc around the vertex crosses both sides,
+    /// arcs of the same radius around those crossings meet on the bisector, and the line through there and the vertex is drawn.
+    fn add_angle_bisector(
+        &mut self,
+        a: (f64, f64),
+        vertex: (f64, f64),
+        b: (f64, f64),
+        role: Role,
+    ) -> Line {
+        let radius = distance(vertex, a).min(distance(vertex, b)) / 2.0;
+        let (p, q) = (
+            lerp(vertex, a, radius / distance(vertex, a)),
+            lerp(vertex, b, radius / distance(vertex, b)),
+        );
+        self.arc_around(vertex, radius, &[p, q]);
+        self.push(Stroke::Point(p), Role::Guide);
+        self.push(Stroke::Point(q), Role::Guide);
+        // The arcs meet at the fourth corner of the rhombus with the vertex, p and q
+        let meet = (p.0 + q.0 - vertex.0, p.1 + q.1 - vertex.1);
+        self.arc_around(p, radius, &[meet]);
+        self.arc_around(q, radius, &[meet]);
+        self.push(Stroke::Point(meet), Role::Guide);
+        let line = Line::through(vertex, meet).expect("The bisector was checked to exist");
+        self.push(Stroke::Line(line), role);
+        line
+    }
+
+    /// Adds the sides of a triangle as given objects.
+    fn add_triangle(&mut self, a: (f64, f64), b: (f64, f64), c: (f64, f64)) {
+        for (start, end) in [(a, b), (b, c), (c, a)] {
+            self.push(Stroke::Segment(start, end), Role::Given);
+        }
+    }
+
+    /// Returns the construction of the perpendicular bisector of the segment between two points.
+    ///
+    /// Returns an Err if the points are the same or too close or far apart for the arcs to be constructed and an Ok with the construction otherwise.
+    pub fn perpendicular_bisector(a: (f64, f64), b: (f64, f64)) -> Result<Self, MathvisError> {
+        perpendicular_bisector(a, b)?;
+        let mut construction = Construction::empty();
+        construction.push(Stroke::Segment(a, b), Role::Given);
+        construction.add_perpendicular_bisector(a, b, Role::Result)?;
+        Ok(construction)
+    }
+
+    /// Returns the construction of the bisector of the angle at `vertex` between the rays towards `a` and `b`.
+    ///
+    /// Returns an Err if the angle has no single bisector, like [angle_bisector], and an Ok with the construction otherwise.
+    pub fn angle_bisector(
+        a: (f64, f64),
+        vertex: (f64, f64),
+        b: (f64, f64),
+    ) -> Result<Self, MathvisError> {
+        angle_bisector(a, vertex, b)?;
+        let mut construction = Construction::empty();
+        construction.push(Stroke::Segment(vertex, a), Role::Given);
+        construction.push(Stroke::Segment(vertex, b), Role::Given);
+        construction.add_angle_bisector(a, vertex, b, Role::Result);
+        Ok(construction)
+    }
+
+    /// Returns the construction of the circumcircle of a triangle, centered where the perpendicular bisectors of two sides meet.
+    ///
+    /// Returns an Err if the corners are on the same line or too close or far apart for the arcs to be constructed and an Ok with the construction otherwise.
+    pub fn circumcircle(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Result<Self, MathvisError> {
+        let circle = circumcircle(a, b, c)?;
+        let mut construction = Construction::empty();
+        construction.add_triangle(a, b, c);
+        construction.add_perpendicular_bisector(a, b, Role::Guide)?;
+        construction.add_perpendicular_bisector(b, c, Role::Guide)?;
+        construction.push(Stroke::Point(circle.center), Role::Result);
+        construction.push(Stroke::Arc(circle, 0.0, TAU), Role::Result);
+        Ok(construction)
+    }
+
+    /// Returns the construction of the incircle of a triangle, centered where the bisectors of two angles meet.
+    ///
+    /// Returns an Err if the corners are on the same line and an Ok with the construction otherwise.
+    pub fn incircle(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Result<Self, MathvisError> {
+        let circle = incircle(a, b, c)?;
+        let mut construction = Construction::empty();
+        construction.add_triangle(a, b, c);
+        construction.add_angle_bisector(b, a, c, Role::Guide);
+        construction.add_angle_bisector(a, b, c, Role::Guide);
+        construction.push(Stroke::Point(circle.center), Role::Result);
+        construction.push(Stroke::Arc(circle, 0.0, TAU), Role::Result);
+        Ok(construction)
+    }
+
+    /// Returns the construction of the tangent lines from a point to a circle: the midpoint between the point
+    /// and the center is found with a perpendicular bisector, and the circle around it through both meets the given
+    /// circle where the tangents touch it.
+    ///
+    /// Returns an Err if the point is on or inside the circle or too close or far from its center for the arcs to be constructed
+    /// and an Ok with the construction otherwise.
+    pub fn tangent_lines(point: (f64, f64), circle: &Circle) -> Result<Self, MathvisError> {
+        let touches = tangent_points(point, circle)?;
+        if touches.len() < 2 || touches[0] == touches[1] {
+            return Err(MathvisError::InvalidArgument(
+                "The tangent lines can only be constructed from a point outside of the circle."
+                    .into(),
+            ));
+        }
+        let mut construction = Construction::empty();
+        construction.push(Stroke::Arc(*circle, 0.0, TAU), Role::Given);
+        construction.push(Stroke::Point(point), Role::Given);
+        construction.push(Stroke::Point(circle.center), Role::Given);
+        construction.push(Stroke::Segment(point, circle.center), Role::Guide);
+        construction.add_perpendicular_bisector(point, circle.center, Role::Guide)?;
+        let middle = lerp(point, circle.center, 0.5);
+        construction.push(Stroke::Point(middle), Role::Guide);
+        construction.arc_around(middle, distance(point, middle), &touches);
+        for touch in touches {
+            construction.push(Stroke::Point(touch), Role::Guide);
+            let tangent = Line::through(point, touch)?;
+            construction.push(Stroke::Line(tangent), Role::Result);
+        }
+        Ok(construction)
+    }
+
+    /// Returns an animation of the specified duration drawing the strokes of the construction one after the other,
+    /// on top of the given objects.
+    pub fn animate(&self, duration: f32) -> Animation {
+        let construction = self.clone();
+        Animation::new(duration, move |progress, canvas, _, map| {
+            let steps: Vec<&(Stroke, Role)> = construction
+                .strokes
+                .iter()
+                .filter(|(_, role)| *role != Role::Given)
+                .collect();
+            for (stroke, role) in &construction.strokes {
+                if *role == Role::Given {
+                    stroke.draw(1.0, role.color(), canvas, map)?;
+                }
+            }
+            let total: f64 = steps.iter().map(|(stroke, _)| stroke.weight()).sum();
+            let mut remaining = progress * total;
+            for (stroke, role) in steps {
+                if remaining <= 0.0 {
+                    break;
+                }
+                let done = (remaining / stroke.weight()).min(1.0);
+                stroke.draw(done, role.color(), canvas, map)?;
+                remaining -= stroke.weight();
+            }
+            Ok(())
+        })
+    }
+}
+
+impl Drawable for Construction {
+    /// Returns the center of the given objects' points.
+    fn position(&self) -> (f64, f64) {
+        let points = self
+            .strokes
+            .iter()
+            .filter(|(_, role)| *role == Role::Given)
+            .flat_map(|(stroke, _)| match *stroke {
+                Stroke::Point(point) => vec![point],
+                Stroke::Segment(a, b) => vec![a, b],
+                Stroke::Line(line) => vec![line.point],
+                Stroke::Arc(circle, _, _) => vec![circle.center],
+            })
+            .collect();
+        PointCloud::new(points, GIVEN_COLOR).position()
+    }
+
+    /// Returns the box of every stroke but the lines, which go on forever, with arcs taking up their whole circle.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::union_all(self.strokes.iter().filter_map(|(stroke, _)| match *stroke {
+            Stroke::Point(point) => Some(BoundingBox::point(point)),
+            Stroke::Segment(a, b) => Some(BoundingBox::new(a, b)),
+            Stroke::Line(_) => None,
+            Stroke::Arc(circle, _, _) => circle.bounding_box(),
+        }))
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        for (stroke, role) in &self.strokes {
+            stroke.draw(1.0, role.color(), canvas, map)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns an animation of the specified duration marking with dots the points `intersections` returns for its progress, from 0 to 1,
+/// so the intersections of moving objects can be followed as they move.
+///
+/// The objects themselves aren't drawn, so the marks are usually played [with](Animation::with) the animations moving them,
+/// with both computing the objects' positions from the progress the same way. Points outside the visible area aren't marked.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::{mark_intersections, Circle, Line};
+/// use mathvis::api::geometry::line_circle;
+///
+/// // A line through the origin turning half a turn, crossing the unit circle at two points that go around with it
+/// let circle = Circle::new((0.0, 0.0), 1.0).unwrap();
+/// let marks = mark_intersections(2.0, move |t| {
+///     let angle = t * std::f64::consts::PI;
+///     let line = Line::through((0.0, 0.0), (angle.cos(), angle.sin())).unwrap();
+///     line_circle(&line, &circle)
+/// });
+/// assert_eq!(marks.duration(), 2.0);
+/// ```
+pub fn mark_intersections<F>(duration: f32, intersections: F) -> Animation
+where
+    F: Fn(f64) -> Vec<Point<f64>> + Send + Sync + 'static,
+{
+    Animation::new(duration, move |progress, canvas, _, map| {
+        let visible = map.visible_bounds();
+        let points = intersections(progress)
+            .iter()
+            .filter_map(|point| match point.values()[..] {
+                [x, y] => Some((x, y)),
+                _ => None,
+            })
+            .filter(|&point| visible.contains_point(point))
+            .collect();
+        PointCloud::new(points, RESULT_COLOR)
+            .with_radius(5)
+            .draw(canvas, map)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_constructions() {
+        let (a, b, c) = ((-1.0, -1.0), (3.0, 0.0), (0.0, 2.0));
+        let circle = circumcircle(a, b, c).unwrap();
+        for corner in [a, b, c] {
+            assert!((distance(circle.center(), corner) - circle.radius()).abs() < 1e-12);
+        }
+        // The incircle is as far from every side
+        let inner = incircle(a, b, c).unwrap();
+        for (start, end) in [(a, b), (b, c), (c, a)] {
+            let side = Line::through(start, end).unwrap();
+            assert!((side.distance_to(inner.center()) - inner.radius()).abs() < 1e-12);
+        }
+        let bisector = angle_bisector(b, a, c).unwrap();
+        assert!(bisector.distance_to(inner.center()) < 1e-12);
+        assert!(angle_bisector((1.0, 0.0), (0.0, 0.0), (-1.0, 0.0)).is_err());
+
+        assert_eq!(
+            circle_intersections((0.0, 0.0), 1.0, (2.0, 0.0), 1.0),
+            [(1.0, 0.0), (1.0, 0.0)]
+        );
+        assert!(circle_intersections((0.0, 0.0), 1.0, (3.0, 0.0), 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_animate() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let construction = Construction::perpendicular_bisector((-1.0, 0.0), (1.0, 0.0)).unwrap();
+        let animation = construction.animate(1.0);
+        let (x, y) = map.to_pixels((0.0, 2.5));
+        // The segment is given from the start, and the bisector is only drawn at the end
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(0, &mut img, &screen, &map).unwrap();
+        let (sx, sy) = map.to_pixels((0.5, 0.0));
+        assert_eq!(*img.get_pixel(sx as u32, sy as u32), GIVEN_COLOR);
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([0, 0, 0]));
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        assert_eq!(*img.get_pixel(x as u32, y as u32), RESULT_COLOR);
+        // The arcs around both ends pass through the points they meet at
+        let (x, y) = map.to_pixels((0.0, -(3f32.sqrt())));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), RESULT_COLOR);
+        let (x, y) = map.to_pixels((1.0 - 2f32 * 0.6f32.cos(), 2f32 * 0.6f32.sin()));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), GUIDE_COLOR);
+
+        assert!(
+            Construction::tangent_lines((0.5, 0.0), &Circle::new((0.0, 0.0), 1.0).unwrap())
+                .is_err()
+        );
+        // Arcs around points this close or far apart can't be intersected in floating point
+        for (a, b) in [((0.0, 0.0), (1e-200, 0.0)), ((-1e200, 0.0), (1e200, 0.0))] {
+            assert!(matches!(
+                Construction::perpendicular_bisector(a, b),
+                Err(MathvisError::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_mark_intersections() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        // A circle sliding right along the x axis, crossing it at its leftmost and rightmost points
+        let marks = mark_intersections(1.0, |t| {
+            let circle = Circle::new((t, 0.0), 1.0).unwrap();
+            let axis = Line::through((0.0, 0.0), (1.0, 0.0)).unwrap();
+            crate::api::geometry::line_circle(&axis, &circle)
+        });
+        let mut img = RgbImage::new(854, 480);
+        marks.draw_frame(9, &mut img, &screen, &map).unwrap();
+        for x in [0.0, 2.0] {
+            let (px, py) = map.to_pixels((x, 0.0));
+            assert_eq!(*img.get_pixel(px as u32, py as u32), RESULT_COLOR);
+        }
+        let (px, py) = map.to_pixels((-1.0, 0.0));
+        assert_eq!(*img.get_pixel(px as u32, py as u32), Rgb([0, 0, 0]));
+
+        // Almost parallel lines meet far away from the screen
+        let marks = mark_intersections(1.0, |t| {
+            let a = Line::through((0.0, 0.0), (1.0, 0.0)).unwrap();
+            let b = Line::through((0.0, 1.0), (1.0, 1.0 - 1e-12 * t)).unwrap();
+            crate::api::geometry::line_line(&a, &b)
+                .into_iter()
+                .collect()
+        });
+        for frame in 0..10 {
+            marks.draw_frame(frame, &mut img, &screen, &map).unwrap();
+        }
+    }
+}
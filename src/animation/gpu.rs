@@ -0,0 +1,431 @@
+//! Module containing the GPU rasterization backend, only available with the `wgpu` feature.
+//!
+//! Frames are recorded as a list of colored triangles on the CPU, then rasterized into an offscreen texture
+//! on the GPU and read back, so they can be saved like any other frame.
+//! Lines are drawn as one pixel wide quads, which keeps the drawing order of every primitive in a single draw call.
+#![warn(missing_docs)]
+use std::sync::{Mutex, OnceLock};
+
+use imageproc::{
+    image::{Rgb, RgbImage},
+    point::Point,
+};
+use wgpu::util::DeviceExt;
+
+use super::canvas::Canvas;
+use crate::error::MathvisError;
+
+/// Shader that passes vertices through unchanged, positions are already in normalized device coordinates.
+const SHADER: &str = r#"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>, @location(1) color: vec4<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
+"#;
+
+/// Number of floats in a vertex, two for the position and four for the color.
+const VERTEX_FLOATS: usize = 6;
+
+/// Texture format of the frames, not sRGB so colors are read back exactly as they were drawn.
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// A frame recorded as triangles, to be rasterized by a [GpuRasterizer].
+#[derive(Debug, Clone)]
+pub struct DrawList {
+    width: u32,
+    height: u32,
+    background: Rgb<u8>,
+    vertices: Vec<f32>,
+}
+
+impl DrawList {
+    /// Creates an empty frame of the specified size, with a black background.
+    pub fn new(width: u32, height: u32) -> Self {
+        DrawList {
+            width,
+            height,
+            background: Rgb([0, 0, 0]),
+            vertices: Vec::new(),
+        }
+    }
+
+    /// Adds a triangle in pixel coordinates.
+    fn push_triangle(&mut self, points: [(f32, f32); 3], color: Rgb<u8>) {
+        let Rgb([r, g, b]) = color;
+        for (x, y) in points {
+            // Pixel centers are at half coordinates, and the y axis points up in device coordinates
+            self.vertices.extend([
+                (x + 0.5) / self.width as f32 * 2.0 - 1.0,
+                1.0 - (y + 0.5) / self.height as f32 * 2.0,
+                r as f32 / 255.0,
+                g as f32 / 255.0,
+                b as f32 / 255.0,
+                1.0,
+            ]);
+        }
+    }
+}
+
+impl Canvas for DrawList {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn fill(&mut self, color: Rgb<u8>) {
+        // Everything drawn so far would be covered anyway
+        self.background = color;
+        self.vertices.clear();
+    }
+
+    fn draw_line(&mut self, start: (f32, f32), end: (f32, f32), color: Rgb<u8>) {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        // Half a pixel to each side of the line, or a single pixel square for a point
+        let (nx, ny, tx, ty) = if length == 0.0 {
+            (0.5, 0.0, 0.0, 0.5)
+        } else {
+            (
+                -dy / length * 0.5,
+                dx / length * 0.5,
+                dx / length * 0.5,
+                dy / length * 0.5,
+            )
+        };
+        let (a, b) = ((start.0 - tx, start.1 - ty), (end.0 + tx, end.1 + ty));
+        let corners = [
+            (a.0 + nx, a.1 + ny),
+            (a.0 - nx, a.1 - ny),
+            (b.0 - nx, b.1 - ny),
+            (b.0 + nx, b.1 + ny),
+        ];
+        self.push_triangle([corners[0], corners[1], corners[2]], color);
+        self.push_triangle([corners[0], corners[2], corners[3]], color);
+    }
+
+    fn draw_polygon(&mut self, points: &[Point<i32>], color: Rgb<u8>) {
+        let points: Vec<(f32, f32)> = points.iter().map(|p| (p.x as f32, p.y as f32)).collect();
+        for i in 1..points.len().saturating_sub(1) {
+            self.push_triangle([points[0], points[i], points[i + 1]], color);
+        }
+    }
+}
+
+/// Offscreen texture and readback buffer that a frame is rasterized into.
+struct Target {
+    size: (u32, u32),
+    texture: wgpu::Texture,
+    readback: wgpu::Buffer,
+    padded_row: u32,
+}
+
+/// Rasterizes [DrawList]s on the GPU.
+pub struct GpuRasterizer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::RenderPipeline,
+    /// Targets that aren't in use, reused by the next frames so workers don't allocate GPU memory for every frame.
+    targets: Mutex<Vec<Target>>,
+}
+
+impl GpuRasterizer {
+    /// Returns the rasterizer shared by every render, or an Err with why there's no usable GPU,
+    /// in which case frames are drawn on the CPU instead.
+    ///
+    /// The GPU is only looked for the first time this is called.
+    pub fn shared() -> Result<&'static GpuRasterizer, &'static MathvisError> {
+        static RASTERIZER: OnceLock<Result<GpuRasterizer, MathvisError>> = OnceLock::new();
+        RASTERIZER
+            .get_or_init(|| pollster::block_on(GpuRasterizer::new()))
+            .as_ref()
+    }
+
+    /// Creates a rasterizer on the most powerful available GPU.
+    ///
+    /// Returns an Err if there's no GPU or it can't be used and an Ok with the rasterizer otherwise.
+    pub async fn new() -> Result<Self, MathvisError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or_else(|| MathvisError::Render("No GPU adapter found".into()))?;
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("mathvis"),
+                    required_features: wgpu::Features::empty(),
+                    // 4K frames need larger textures than the default limits allow
+                    required_limits: adapter.limits(),
+                    memory_hints: wgpu::MemoryHints::default(),
+                },
+                None,
+            )
+            .await
+            .map_err(|err| MathvisError::Render(err.to_string()))?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("mathvis shader"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("mathvis pipeline"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: (VERTEX_FLOATS * size_of::<f32>()) as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4],
+                }],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+            cache: None,
+        });
+        Ok(GpuRasterizer {
+            device,
+            queue,
+            pipeline,
+            targets: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Creates the render target and readback buffer for frames of the specified size.
+    fn target(&self, (width, height): (u32, u32)) -> Target {
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("mathvis frame"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        // Rows copied into buffers must be aligned
+        let padded_row = (width * 4).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("mathvis readback"),
+            size: (padded_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        Target {
+            size: (width, height),
+            texture,
+            readback,
+            padded_row,
+        }
+    }
+
+    /// Rasterizes a frame on the GPU and copies the result into the image, which must have the same size.
+    ///
+    /// Returns an Err if the sizes don't match or anything fails on the GPU and an Ok otherwise.
+    pub fn rasterize(&self, frame: &DrawList, img: &mut RgbImage) -> Result<(), MathvisError> {
+        let size = (frame.width, frame.height);
+        if img.dimensions() != size {
+            return Err(MathvisError::InvalidArgument(
+                "The frame and image sizes don't match".into(),
+            ));
+        }
+        let reused = {
+            let mut targets = self
+                .targets
+                .lock()
+                .map_err(|_| MathvisError::Render("Failed to lock GPU targets".into()))?;
+            // Targets of other sizes are left over from previous renders
+            targets.retain(|target| target.size == size);
+            targets.pop()
+        };
+        let target = reused.unwrap_or_else(|| self.target(size));
+        self.draw(frame, &target);
+        let result = self.read_back(&target, img);
+        if let Ok(mut targets) = self.targets.lock() {
+            targets.push(target);
+        }
+        result
+    }
+
+    /// Draws a frame into the target's texture and copies it to the readback buffer.
+    fn draw(&self, frame: &DrawList, target: &Target) {
+        let vertices: Vec<u8> = frame
+            .vertices
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect();
+        let vertex_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("mathvis vertices"),
+                contents: &vertices,
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+        let view = target
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let Rgb([r, g, b]) = frame.background;
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("mathvis pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: r as f64 / 255.0,
+                            g: g as f64 / 255.0,
+                            b: b as f64 / 255.0,
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            if !frame.vertices.is_empty() {
+                pass.set_pipeline(&self.pipeline);
+                pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                pass.draw(0..(frame.vertices.len() / VERTEX_FLOATS) as u32, 0..1);
+            }
+        }
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &target.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &target.readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(target.padded_row),
+                    rows_per_image: Some(target.size.1),
+                },
+            },
+            wgpu::Extent3d {
+                width: target.size.0,
+                height: target.size.1,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit([encoder.finish()]);
+    }
+
+    /// Waits for the readback buffer and copies its pixels into the image, dropping the alpha channel.
+    fn read_back(&self, target: &Target, img: &mut RgbImage) -> Result<(), MathvisError> {
+        let slice = target.readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|err| MathvisError::Render(err.to_string()))?
+            .map_err(|err| MathvisError::Render(err.to_string()))?;
+        {
+            let data = slice.get_mapped_range();
+            let width = target.size.0 as usize;
+            for (y, row) in data.chunks(target.padded_row as usize).enumerate() {
+                for (x, pixel) in row[..width * 4].chunks_exact(4).enumerate() {
+                    img.put_pixel(x as u32, y as u32, Rgb([pixel[0], pixel[1], pixel[2]]));
+                }
+            }
+        }
+        target.readback.unmap();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_list() {
+        let mut frame = DrawList::new(10, 10);
+        frame.draw_line((0.0, 0.0), (9.0, 0.0), Rgb([255, 255, 255]));
+        frame.draw_polygon(
+            &[
+                Point::new(0, 0),
+                Point::new(5, 0),
+                Point::new(5, 5),
+                Point::new(0, 5),
+            ],
+            Rgb([255, 0, 0]),
+        );
+        assert_eq!(frame.vertices.len(), 4 * 3 * VERTEX_FLOATS);
+        frame.fill(Rgb([1, 2, 3]));
+        assert!(frame.vertices.is_empty());
+        assert_eq!(frame.background, Rgb([1, 2, 3]));
+    }
+
+    #[test]
+    fn test_rasterize() {
+        // Nothing to compare against on machines without a GPU
+        let Ok(gpu) = GpuRasterizer::shared() else {
+            return;
+        };
+        let mut frame = DrawList::new(64, 32);
+        frame.fill(Rgb([43, 42, 51]));
+        frame.draw_polygon(
+            &[
+                Point::new(10, 10),
+                Point::new(30, 10),
+                Point::new(30, 20),
+                Point::new(10, 20),
+            ],
+            Rgb([255, 0, 0]),
+        );
+        let mut img = RgbImage::new(64, 32);
+        gpu.rasterize(&frame, &mut img).unwrap();
+        assert_eq!(*img.get_pixel(0, 0), Rgb([43, 42, 51]));
+        assert_eq!(*img.get_pixel(20, 15), Rgb([255, 0, 0]));
+    }
+}
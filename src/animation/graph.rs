@@ -0,0 +1,326 @@
+//! Module containing graphs of real functions, drawn across the visible part of the screen.
+#![warn(missing_docs)]
+use std::sync::Arc;
+
+use imageproc::image::Rgb;
+
+use super::{canvas::Canvas, clip::Animation, drawable::Drawable, path::Path2D};
+use crate::{
+    api::{bounds::BoundingBox, interval::Interval, point::PointLike, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// Distance in pixels between the points a graph is sampled at.
+const SAMPLE_SPACING: f32 = 2.0;
+/// Number of intervals the domain of a graph is split into to find its bounding box.
+const BOUNDS_SAMPLES: u32 = 256;
+/// Color of the guides of a graph tracker by default.
+const TRACKER_COLOR: Rgb<u8> = Rgb([170, 170, 170]);
+
+/// A real function of a real variable.
+pub type Function = Arc<dyn Fn(f64) -> f64 + Send + Sync>;
+
+/// The graph y = f(x) of a function, drawn as a curve across the screen or over a domain.
+///
+/// The function is sampled every couple of pixels, and the curve is broken wherever the function isn't finite
+/// or shoots far off the screen, so poles and gaps aren't joined by vertical lines.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::graph::FunctionGraph;
+///
+/// let parabola = FunctionGraph::new(|x| x * x, Rgb([255, 255, 255]))
+///     .with_domain(-1.0, 2.0)
+///     .unwrap();
+/// assert_eq!(parabola.evaluate(2.0), 4.0);
+/// assert!(parabola.with_domain(1.0, 1.0).is_err());
+/// ```
+#[derive(Clone)]
+pub struct FunctionGraph {
+    function: Function,
+    domain: Option<Interval>,
+    color: Rgb<u8>,
+    thickness: f32,
+}
+
+impl FunctionGraph {
+    /// Creates the graph of a function, drawn across the whole width of the screen.
+    pub fn new<F>(function: F, color: Rgb<u8>) -> Self
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        Self::from_function(Arc::new(function), color)
+    }
+
+    /// Creates the graph of a shared function, drawn across the whole width of the screen.
+    pub fn from_function(function: Function, color: Rgb<u8>) -> Self {
+        FunctionGraph {
+            function,
+            domain: None,
+            color,
+            thickness: 2.0,
+        }
+    }
+
+    /// Restricts the graph to the x values from `start` to `end`.
+    ///
+    /// Returns an Err if the domain is empty and an Ok with the graph otherwise.
+    pub fn with_domain(mut self, start: f64, end: f64) -> Result<Self, MathvisError> {
+        self.domain = Some(Interval::named("domain of a graph", start, end)?);
+        Ok(self)
+    }
+
+    /// Restricts the graph to the x values in an interval, leaving out its open ends,
+    /// so functions like 1/x can be drawn right up to where they're undefined.
+    pub fn with_domain_interval(mut self, domain: Interval) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+
+    /// Returns the x values the graph is restricted to, or None if it spans the whole screen.
+    pub fn domain(&self) -> Option<Interval> {
+        self.domain
+    }
+
+    /// Sets the width of the curve in pixels, 2 by default.
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Returns the value of the function at x.
+    pub fn evaluate(&self, x: f64) -> f64 {
+        (self.function)(x)
+    }
+
+    /// Returns the function of the graph.
+    pub fn function(&self) -> &Function {
+        &self.function
+    }
+
+    /// Returns the pieces of the curve visible with the specified map, as polylines in screen coordinates.
+    fn pieces(&self, map: &CoordinateMap) -> Vec<Vec<(f64, f64)>> {
+        let resolution = map.quality().resolution();
+        let (width, height) = (resolution.values()[0], resolution.values()[1]);
+        let (left, top) = map.to_coordinates((0.0, 0.0));
+        let (right, bottom) = map.to_coordinates((width, height));
+        let (mut start, mut end) = (left as f64, right as f64);
+        if let Some(domain) = self.domain {
+            start = start.max(domain.start());
+            end = end.min(domain.end());
+        }
+        if start >= end {
+            return Vec::new();
+        }
+        // Points up to a screen away from the edges still give the right slope to the lines leaving the screen
+        let span = (top - bottom) as f64;
+        let (low, high) = (bottom as f64 - span, top as f64 + span);
+        let samples = ((end - start) / (right - left) as f64 * (width / SAMPLE_SPACING) as f64)
+            .ceil()
+            .max(1.0) as u32;
+
+        let mut pieces = vec![Vec::new()];
+        for i in 0..=samples {
+            let x = start + (end - start) * i as f64 / samples as f64;
+            let y = self.evaluate(x);
+            let inside = self.domain.is_none_or(|domain| domain.contains(x));
+            if inside && y.is_finite() && y >= low && y <= high {
+                pieces.last_mut().unwrap().push((x, y));
+            } else if !pieces.last().unwrap().is_empty() {
+                pieces.push(Vec::new());
+            }
+        }
+        pieces.retain(|piece| !piece.is_empty());
+        pieces
+    }
+}
+
+impl Drawable for FunctionGraph {
+    /// Returns the point of the graph in the middle of its domain, or above the origin if it has none.
+    fn position(&self) -> (f64, f64) {
+        let x = self.domain.map_or(0.0, |domain| domain.midpoint());
+        (x, self.evaluate(x))
+    }
+
+    /// Returns the box of the graph sampled across its domain, or None if it has none and spans the whole x axis.
+    /// The points of the domain where the function isn't finite are left out.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let domain = self.domain?;
+        BoundingBox::from_points((0..=BOUNDS_SAMPLES).map(|i| {
+            let x = domain.clamp(domain.lerp(i as f64 / BOUNDS_SAMPLES as f64));
+            (x, self.evaluate(x))
+        }))
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        Path2D::new(self.pieces(map), self.color)
+            .with_thickness(self.thickness)
+            .draw(canvas, map)
+    }
+}
+
+/// Guides following a point as it moves along the graph of a function, see [Show2D::move_along_graph_with](super::show::Show2D::move_along_graph_with).
+///
+/// The drop line goes straight down or up from the point to the x axis, and the labels give its coordinates,
+/// the x one under the axis and the y one next to the point. Neither is drawn by default.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::graph::GraphTracker;
+///
+/// let tracker = GraphTracker::new().with_drop_line(true).with_labels(true);
+/// assert_eq!(tracker.animate(|x: f64| x.sin(), 0.0, 3.0, 2.0).duration(), 2.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphTracker {
+    drop_line: bool,
+    labels: bool,
+    color: Rgb<u8>,
+}
+
+impl Default for GraphTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphTracker {
+    /// Creates a tracker without a drop line or labels, drawn in gray once they're enabled.
+    pub fn new() -> Self {
+        GraphTracker {
+            drop_line: false,
+            labels: false,
+            color: TRACKER_COLOR,
+        }
+    }
+
+    /// Sets whether a line is dropped from the point to the x axis.
+    pub fn with_drop_line(mut self, drop_line: bool) -> Self {
+        self.drop_line = drop_line;
+        self
+    }
+
+    /// Sets whether the coordinates of the point are written next to it.
+    pub fn with_labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Sets the color of the drop line and labels.
+    pub fn with_color(mut self, color: Rgb<u8>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns an animation of the specified duration drawing the guides of the point of the graph
+    /// whose x goes steadily from `x_start` to `x_end`, without the point itself.
+    ///
+    /// Nothing is drawn at the x values where the function isn't finite.
+    pub fn animate<F>(&self, function: F, x_start: f64, x_end: f64, duration: f32) -> Animation
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        let tracker = *self;
+        Animation::new(duration, move |progress, canvas, _, map| {
+            let x = x_start + progress * (x_end - x_start);
+            let y = function(x);
+            if !y.is_finite() {
+                return Ok(());
+            }
+            let foot = map.to_pixels((x as f32, 0.0));
+            let point = map.to_pixels((x as f32, y as f32));
+            if tracker.drop_line {
+                canvas.draw_line(foot, point, tracker.color);
+            }
+            if tracker.labels {
+                let size = map.quality().resolution().values()[1] * 0.04;
+                // The x label goes on the other side of the axis, away from the point
+                let x_label = if y >= 0.0 {
+                    foot.1 + size / 4.0
+                } else {
+                    foot.1 - size * 1.25
+                };
+                canvas.draw_text(
+                    &format!("x = {:.2}", x),
+                    (foot.0 + size / 4.0, x_label),
+                    size,
+                    tracker.color,
+                );
+                canvas.draw_text(
+                    &format!("y = {:.2}", y),
+                    (point.0 + size / 2.0, point.1 - size / 2.0),
+                    size,
+                    tracker.color,
+                );
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_draw() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let color = Rgb([255, 0, 0]);
+        let mut img = RgbImage::new(854, 480);
+        let hyperbola = FunctionGraph::new(|x| 1.0 / x, color);
+        // The curve is broken at the pole instead of joining both branches
+        assert_eq!(hyperbola.pieces(&map).len(), 2);
+        hyperbola.draw(&mut img, &map).unwrap();
+        let (x, y) = map.to_pixels((2.0, 0.5));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), color);
+        let (x, y) = map.to_pixels((0.0, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([0, 0, 0]));
+
+        let line = FunctionGraph::new(|x| x, color)
+            .with_domain(0.0, 1.0)
+            .unwrap();
+        let pieces = line.pieces(&map);
+        assert_eq!(pieces[0].first(), Some(&(0.0, 0.0)));
+        assert_eq!(pieces[0].last(), Some(&(1.0, 1.0)));
+        assert_eq!(
+            line.bounding_box(),
+            Some(BoundingBox::new((0.0, 0.0), (1.0, 1.0)))
+        );
+        assert_eq!(hyperbola.bounding_box(), None);
+
+        let open = FunctionGraph::new(|x| x, color)
+            .with_domain_interval(Interval::closed(0.0, 1.0).unwrap().with_open_end());
+        assert_ne!(open.pieces(&map)[0].last(), Some(&(1.0, 1.0)));
+        assert_eq!(open.domain().unwrap().end(), 1.0);
+    }
+
+    #[test]
+    fn test_tracker() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let color = Rgb([0, 255, 0]);
+        let tracker = GraphTracker::new().with_drop_line(true).with_color(color);
+        let mut img = RgbImage::new(854, 480);
+        // The point ends at (2, 2), with a line down to (2, 0)
+        tracker
+            .animate(|x| x, 0.0, 2.0, 1.0)
+            .draw_frame(9, &mut img, &screen, &map)
+            .unwrap();
+        let (x, y) = map.to_pixels((2.0, 1.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), color);
+
+        let mut img = RgbImage::new(854, 480);
+        GraphTracker::new()
+            .animate(|x| x, 0.0, 2.0, 1.0)
+            .draw_frame(9, &mut img, &screen, &map)
+            .unwrap();
+        assert!(img.pixels().all(|pixel| *pixel == Rgb([0, 0, 0])));
+    }
+}
@@ -0,0 +1,248 @@
+//! Module containing the keyframe exporter, which records what every sampled frame draws instead of rasterizing it.
+//!
+//! The export is plain JSON, so lightweight web players can replay animations as vector shapes:
+//! the axes are recorded once as the background, and every keyframe has the shapes drawn on it, in pixel coordinates.
+#![warn(missing_docs)]
+use std::{fs::write, path::Path};
+
+use imageproc::{image::Rgb, point::Point};
+use serde::Serialize;
+
+use super::{axis::draw_axis, canvas::Canvas, clip::Animation};
+use crate::{
+    api::{screen::Screen2D, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// A shape drawn on a frame, in pixel coordinates with the origin on the top left corner.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Shape {
+    /// A one pixel wide line segment.
+    Line {
+        /// Start of the segment.
+        from: [f32; 2],
+        /// End of the segment.
+        to: [f32; 2],
+        /// Color of the segment.
+        color: [u8; 3],
+    },
+    /// A filled convex polygon.
+    Polygon {
+        /// Vertices of the polygon, in order.
+        points: Vec<[i32; 2]>,
+        /// Color of the polygon.
+        color: [u8; 3],
+    },
+    /// A single line of text.
+    Text {
+        /// The text itself.
+        text: String,
+        /// Position of the top left corner of the text.
+        position: [f32; 2],
+        /// Height of the text in pixels.
+        size: f32,
+        /// Color of the text.
+        color: [u8; 3],
+    },
+}
+
+/// Every shape drawn on a sampled frame.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Keyframe {
+    /// Index of the frame, counted from the start of the animation.
+    pub frame: u32,
+    /// Time of the frame in seconds.
+    pub time: f32,
+    /// Shapes drawn on the frame, in drawing order.
+    pub shapes: Vec<Shape>,
+}
+
+/// An animation exported as keyframes, with everything needed to replay it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyframeExport {
+    /// Width of the frames in pixels.
+    pub width: u32,
+    /// Height of the frames in pixels.
+    pub height: u32,
+    /// Frames per second of the animation.
+    pub fps: u32,
+    /// Number of frames of the animation, including the ones that weren't sampled.
+    pub frames: u32,
+    /// Color the background of every frame is filled with.
+    pub background: [u8; 3],
+    /// Shapes drawn below every frame, like the axes.
+    pub static_shapes: Vec<Shape>,
+    /// The sampled frames, in order.
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl KeyframeExport {
+    /// Returns the export as a JSON string.
+    ///
+    /// Returns an Err if it can't be serialized and an Ok with the JSON otherwise.
+    pub fn to_json(&self) -> Result<String, MathvisError> {
+        serde_json::to_string(self).map_err(|err| MathvisError::Encode(err.to_string()))
+    }
+
+    /// Saves the export as a JSON file at the specified path.
+    ///
+    /// Returns an Err if it can't be serialized or written and an Ok otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), MathvisError> {
+        write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// A canvas that records the shapes drawn on it instead of rasterizing them.
+struct Recorder {
+    width: u32,
+    height: u32,
+    background: Rgb<u8>,
+    shapes: Vec<Shape>,
+}
+
+impl Canvas for Recorder {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn fill(&mut self, color: Rgb<u8>) {
+        // Everything drawn so far would be covered anyway
+        self.background = color;
+        self.shapes.clear();
+    }
+
+    fn draw_line(&mut self, start: (f32, f32), end: (f32, f32), color: Rgb<u8>) {
+        self.shapes.push(Shape::Line {
+            from: [start.0, start.1],
+            to: [end.0, end.1],
+            color: color.0,
+        });
+    }
+
+    fn draw_polygon(&mut self, points: &[Point<i32>], color: Rgb<u8>) {
+        self.shapes.push(Shape::Polygon {
+            points: points.iter().map(|point| [point.x, point.y]).collect(),
+            color: color.0,
+        });
+    }
+
+    fn draw_text(&mut self, text: &str, position: (f32, f32), size: f32, color: Rgb<u8>) {
+        self.shapes.push(Shape::Text {
+            text: text.into(),
+            position: [position.0, position.1],
+            size,
+            color: color.0,
+        });
+    }
+}
+
+/// Exports an animation played on a screen as keyframes, sampling one frame out of every `step`.
+///
+/// The last frame is always sampled, so the animation ends where it's supposed to.
+///
+/// Returns an Err if the step is 0, the screen has an unsupported resolution or a frame fails to draw
+/// and an Ok with the keyframes otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::{keyframes::export_keyframes, show::Show2D, vector::Vector2D};
+/// use mathvis::api::{point::{Point, PointLike}, screen::Screen2D};
+///
+/// let screen = Screen2D::builder().build().unwrap();
+/// let vector = Vector2D::new(1.0, 0.0, Rgb([255, 0, 0]));
+/// let export = export_keyframes(&vector.move_to(1.0, Point::new(vec![0.0, 1.0]).unwrap()), &screen, 10).unwrap();
+/// assert_eq!(export.keyframes.len(), 4);
+/// let json = export.to_json().unwrap();
+/// ```
+pub fn export_keyframes(
+    animation: &Animation,
+    screen: &Screen2D,
+    step: u32,
+) -> Result<KeyframeExport, MathvisError> {
+    if step == 0 {
+        return Err(MathvisError::InvalidArgument(
+            "The step between keyframes must be positive.".into(),
+        ));
+    }
+    let map = CoordinateMap::new(screen)
+        .ok_or_else(|| MathvisError::InvalidArgument("Unsupported screen resolution".into()))?;
+    let recorder = || Recorder {
+        width: screen.width,
+        height: screen.height,
+        background: screen.background,
+        shapes: Vec::new(),
+    };
+
+    let mut axes = recorder();
+    draw_axis(&mut axes, screen.axis_style, &map, screen);
+
+    let frames = animation.frames(screen.fps);
+    let mut sampled: Vec<u32> = (0..frames).step_by(step as usize).collect();
+    if frames > 0 && sampled.last() != Some(&(frames - 1)) {
+        sampled.push(frames - 1);
+    }
+    let keyframes = sampled
+        .into_iter()
+        .map(|frame| {
+            let mut canvas = recorder();
+            animation.draw_frame(frame, &mut canvas, screen, &map)?;
+            Ok(Keyframe {
+                frame,
+                time: frame as f32 / screen.fps as f32,
+                shapes: canvas.shapes,
+            })
+        })
+        .collect::<Result<_, MathvisError>>()?;
+
+    Ok(KeyframeExport {
+        width: screen.width,
+        height: screen.height,
+        fps: screen.fps,
+        frames,
+        background: screen.background.0,
+        static_shapes: axes.shapes,
+        keyframes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_keyframes() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let animation = Animation::new(1.0, |progress, canvas, _, _| {
+            let x = (progress * 9.0) as f32;
+            canvas.draw_line((0.0, 0.0), (x, 0.0), Rgb([255, 0, 0]));
+            canvas.draw_text("v", (x, 1.0), 12.0, Rgb([255, 255, 255]));
+            Ok(())
+        });
+        let export = export_keyframes(&animation, &screen, 4).unwrap();
+        assert_eq!(export.frames, 10);
+        assert!(!export.static_shapes.is_empty());
+        let frames: Vec<u32> = export.keyframes.iter().map(|k| k.frame).collect();
+        assert_eq!(frames, vec![0, 4, 8, 9]);
+        assert_eq!(
+            export.keyframes[3].shapes[0],
+            Shape::Line {
+                from: [0.0, 0.0],
+                to: [9.0, 0.0],
+                color: [255, 0, 0]
+            }
+        );
+        assert!(matches!(&export.keyframes[3].shapes[1], Shape::Text { text, .. } if text == "v"));
+
+        let json = export.to_json().unwrap();
+        assert!(json.contains(r#""type":"line""#));
+        assert!(export_keyframes(&animation, &screen, 0).is_err());
+    }
+}
@@ -0,0 +1,641 @@
+//! Module containing high level animations that visualize linear algebra, drawing every object they need on their own.
+#![warn(missing_docs)]
+use imageproc::{image::Rgb, point::Point};
+
+use super::{
+    canvas::Canvas, clip::Animation, drawable::Drawable, replace::fade, text::draw_centered,
+    vector::Vector2D,
+};
+use crate::{
+    api::{
+        bounds::BoundingBox,
+        matrix::Matrix,
+        point::PointLike,
+        screen::{Screen2D, ScreenLike},
+        util::{CoordinateMap, Number, Signed},
+    },
+    error::MathvisError,
+};
+
+/// Colors of the first and second eigenvectors.
+const EIGENVECTOR_COLORS: [Rgb<u8>; 2] = [Rgb([255, 200, 0]), Rgb([0, 200, 255])];
+/// Color of the lines spanned by the eigenvectors.
+const SPAN_COLOR: Rgb<u8> = Rgb([90, 90, 90]);
+/// Color of the basis vectors, which are knocked off their span unless they're eigenvectors.
+const BASIS_COLOR: Rgb<u8> = Rgb([170, 170, 170]);
+/// Color of the entries of a matrix.
+const ENTRY_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+/// Color of the entries in the highlighted row, column and product entry.
+const HIGHLIGHT_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+/// Color of the cells behind the highlighted entries.
+const HIGHLIGHT_BACKGROUND: Rgb<u8> = Rgb([70, 60, 20]);
+
+/// Returns an animation of the specified duration that transforms the plane by a 2x2 matrix,
+/// showing that its eigenvectors only scale while every other vector is knocked off its span.
+///
+/// The lines spanned by the eigenvectors are drawn across the screen, and the eigenvectors are labelled with their eigenvalues.
+/// Every vector is multiplied by the matrix interpolated from the identity, so each eigenvector stays on its line
+/// and is scaled smoothly from 1 to its eigenvalue, while the standard basis vectors are transformed alongside them for comparison.
+///
+/// Returns an Err if the matrix isn't 2x2 or doesn't have real eigenvalues and an Ok with the animation otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::linear::show_eigenvectors;
+/// use mathvis::api::matrix::Matrix;
+///
+/// let stretch = Matrix::new(vec![vec![2.0, 1.0], vec![1.0, 2.0]]).unwrap();
+/// assert_eq!(show_eigenvectors(stretch, 3.0).unwrap().duration(), 3.0);
+///
+/// let rotation = Matrix::new(vec![vec![0.0, -1.0], vec![1.0, 0.0]]).unwrap();
+/// assert!(show_eigenvectors(rotation, 3.0).is_err());
+/// ```
+pub fn show_eigenvectors<T: Signed>(
+    matrix: Matrix<T>,
+    duration: f32,
+) -> Result<Animation, MathvisError> {
+    let matrix = matrix.map(|value| value.to_f64());
+    let (l1, l2) = matrix.eigenvalues_2d()?;
+    if l1.is_nan() || l2.is_nan() {
+        return Err(MathvisError::InvalidArgument(
+            "The matrix has no real eigenvalues, so it has no eigenvectors to show.".into(),
+        ));
+    }
+    let (v1, v2) = matrix.eigenvectors_2d()?;
+    let eigenvectors = [
+        (l1, (v1.values()[0], v1.values()[1])),
+        (l2, (v2.values()[0], v2.values()[1])),
+    ];
+    let (a, b, c, d) = (
+        matrix.at(0, 0),
+        matrix.at(0, 1),
+        matrix.at(1, 0),
+        matrix.at(1, 1),
+    );
+
+    Ok(Animation::new(
+        duration,
+        move |progress, canvas, screen, map| {
+            // The matrix interpolated from the identity, which only scales the eigenvectors
+            let transform = |(x, y): (f64, f64)| {
+                (
+                    (1.0 - progress) * x + progress * (a * x + b * y),
+                    (1.0 - progress) * y + progress * (c * x + d * y),
+                )
+            };
+            let reach = span_reach(screen);
+            for (_, (x, y)) in eigenvectors {
+                canvas.draw_line(
+                    map.to_pixels((-(reach * x) as f32, -(reach * y) as f32)),
+                    map.to_pixels(((reach * x) as f32, (reach * y) as f32)),
+                    SPAN_COLOR,
+                );
+            }
+            for basis in [(1.0, 0.0), (0.0, 1.0)] {
+                let (x, y) = transform(basis);
+                Vector2D::new(x, y, BASIS_COLOR).draw(canvas, map)?;
+            }
+            for ((eigenvalue, eigenvector), color) in
+                eigenvectors.into_iter().zip(EIGENVECTOR_COLORS)
+            {
+                let (x, y) = transform(eigenvector);
+                draw_labelled(x, y, color, format!("λ = {:.2}", eigenvalue), canvas, map)?;
+            }
+            Ok(())
+        },
+    ))
+}
+
+/// Returns an animation of the specified duration that multiplies two matrices step by step, drawing them and their product as grids of numbers.
+///
+/// The entries of the product are filled in one at a time, row by row. While an entry is being computed,
+/// the row of the left matrix and the column of the right matrix it comes from are highlighted,
+/// and the dot product that gives its value is written below the grids. Every value comes from the product computed by [Matrix]'s multiplication.
+///
+/// Returns an Err if the matrices can't be multiplied and an Ok with the animation otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::linear::show_matrix_multiplication;
+/// use mathvis::api::matrix::Matrix;
+///
+/// let a = Matrix::new(vec![vec![1, 2], vec![3, 4]]).unwrap();
+/// let b = Matrix::new(vec![vec![5, 6, 7], vec![8, 9, 10]]).unwrap();
+/// assert!(show_matrix_multiplication(a.clone(), b.clone(), 6.0).is_ok());
+/// assert!(show_matrix_multiplication(b, a, 6.0).is_err());
+/// ```
+pub fn show_matrix_multiplication<T: Number>(
+    a: Matrix<T>,
+    b: Matrix<T>,
+    duration: f32,
+) -> Result<Animation, MathvisError> {
+    let product = (&a * &b)?;
+    let [a, b, product] = [a, b, product].map(|matrix| matrix.map(|value| value.to_f64()));
+    let (rows, cols) = product.get_dimensions();
+    let entries = rows * cols;
+
+    Ok(Animation::new(duration, move |progress, canvas, _, _| {
+        let [a_grid, b_grid, product_grid] = grid_layout(canvas, [&a, &b, &product]);
+        let filled = ((progress * entries as f64).floor() as usize).min(entries);
+        // The entry being computed, unless they're all filled in
+        let current = (filled < entries).then(|| (filled / cols, filled % cols));
+
+        if let Some((i, j)) = current {
+            for k in 0..a.get_dimensions().1 {
+                a_grid.fill_cell(canvas, i, k, HIGHLIGHT_BACKGROUND);
+                b_grid.fill_cell(canvas, k, j, HIGHLIGHT_BACKGROUND);
+            }
+            product_grid.fill_cell(canvas, i, j, HIGHLIGHT_BACKGROUND);
+        }
+        let highlighted = |row: usize, col: usize, in_row: bool| match current {
+            Some((i, j)) if (in_row && row == i) || (!in_row && col == j) => HIGHLIGHT_COLOR,
+            _ => ENTRY_COLOR,
+        };
+        a_grid.draw(canvas, &a, ENTRY_COLOR, |i, k| {
+            Some(highlighted(i, k, true))
+        });
+        b_grid.draw(canvas, &b, ENTRY_COLOR, |k, j| {
+            Some(highlighted(k, j, false))
+        });
+        product_grid.draw(canvas, &product, ENTRY_COLOR, |i, j| {
+            (i * cols + j < filled).then_some(ENTRY_COLOR)
+        });
+
+        let size = a_grid.size;
+        let symbol_y = canvas.height() as f32 / 2.0 - size / 2.0;
+        draw_centered(
+            canvas,
+            "×",
+            ((a_grid.right() + b_grid.left) / 2.0, symbol_y),
+            size,
+            ENTRY_COLOR,
+        );
+        draw_centered(
+            canvas,
+            "=",
+            ((b_grid.right() + product_grid.left) / 2.0, symbol_y),
+            size,
+            ENTRY_COLOR,
+        );
+
+        if let Some((i, j)) = current {
+            let terms: Vec<String> = (0..a.get_dimensions().1)
+                .map(|k| format!("{}·{}", format_entry(a.at(i, k)), format_entry(b.at(k, j))))
+                .collect();
+            let expression = format!("{} = {}", terms.join(" + "), format_entry(product.at(i, j)));
+            let bottom = [&a_grid, &b_grid, &product_grid]
+                .iter()
+                .map(|grid| grid.bottom())
+                .fold(0.0, f32::max);
+            let center = canvas.width() as f32 / 2.0;
+            draw_centered(
+                canvas,
+                &expression,
+                (center, bottom + size),
+                size,
+                HIGHLIGHT_COLOR,
+            );
+        }
+        Ok(())
+    }))
+}
+
+/// A matrix drawn as a bracketed grid of numbers centered on a point of the screen, next to the geometric picture.
+///
+/// Whole numbers are written without decimals and every other entry with two.
+/// The text is 5% of the frame's height unless it's given another size.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::linear::MatrixDisplay;
+/// use mathvis::api::matrix::Matrix;
+///
+/// let display = MatrixDisplay::new(Matrix::new(vec![vec![1, 2], vec![3, 4]]).unwrap(), (-2.0, 1.5));
+/// let reduced = Matrix::new(vec![vec![1, 2], vec![0, -2]]).unwrap();
+/// assert_eq!(display.animate_to(reduced, 1.0).unwrap().duration(), 1.0);
+/// assert!(display.animate_to(Matrix::<i32>::identity(3).unwrap(), 1.0).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixDisplay {
+    values: Matrix<f64>,
+    center: (f64, f64),
+    size: Option<f32>,
+    color: Rgb<u8>,
+}
+
+impl MatrixDisplay {
+    /// Creates a display of a matrix centered on a point in screen coordinates, written in white.
+    pub fn new<T: Number>(matrix: Matrix<T>, center: (f64, f64)) -> Self {
+        MatrixDisplay {
+            values: matrix.map(|value| value.to_f64()),
+            center,
+            size: None,
+            color: ENTRY_COLOR,
+        }
+    }
+
+    /// Sets the height of the entries' text in pixels.
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Sets the color of the entries and brackets.
+    pub fn with_color(mut self, color: Rgb<u8>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the matrix that's displayed.
+    pub fn matrix(&self) -> &Matrix<f64> {
+        &self.values
+    }
+
+    /// Returns an animation of the specified duration changing the displayed matrix into another one of the same dimensions.
+    ///
+    /// Entries that keep their value stay still, and every other one slides up and fades out while its new value slides in from below.
+    ///
+    /// Returns an Err if the matrices have different dimensions and an Ok with the animation otherwise.
+    pub fn animate_to<T: Number>(
+        &self,
+        matrix: Matrix<T>,
+        duration: f32,
+    ) -> Result<Animation, MathvisError> {
+        let target = matrix.map(|value| value.to_f64());
+        if target.get_dimensions() != self.values.get_dimensions() {
+            return Err(MathvisError::DimensionMismatch(format!(
+                "A {:?} matrix can't be animated into a {:?} one.",
+                self.values.get_dimensions(),
+                target.get_dimensions()
+            )));
+        }
+        let display = self.clone();
+        Ok(Animation::new(
+            duration,
+            move |progress, canvas, screen, map| {
+                let (from, color) = (&display.values, display.color);
+                let grid = display.grid(canvas, map);
+                let same = |row, col| from.at(row, col) == target.at(row, col);
+                grid.draw(canvas, from, color, |row, col| {
+                    same(row, col).then_some(color)
+                });
+                let distance = grid.cell_height() * 0.6;
+                for row in 0..grid.rows {
+                    for col in 0..grid.cols {
+                        if same(row, col) {
+                            continue;
+                        }
+                        for (value, shift, opacity) in [
+                            (
+                                from.at(row, col),
+                                -distance * progress as f32,
+                                1.0 - progress,
+                            ),
+                            (
+                                target.at(row, col),
+                                distance * (1.0 - progress as f32),
+                                progress,
+                            ),
+                        ] {
+                            // Colors faded all the way would cover what's behind with the background
+                            if opacity > 0.0 {
+                                let faded = fade(color, screen.background, opacity);
+                                grid.draw_entry(canvas, value, row, col, shift, faded);
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            },
+        ))
+    }
+
+    /// Lays out the grid of the matrix centered on its point.
+    fn grid(&self, canvas: &dyn Canvas, map: &CoordinateMap) -> Grid {
+        let (rows, cols) = self.values.get_dimensions();
+        let mut grid = Grid {
+            left: 0.0,
+            top: 0.0,
+            rows,
+            cols,
+            size: self.size.unwrap_or(canvas.height() as f32 * 0.05),
+        };
+        let (x, y) = map.to_pixels((self.center.0 as f32, self.center.1 as f32));
+        grid.left = x - grid.width() / 2.0;
+        grid.top = y - grid.height() / 2.0;
+        grid
+    }
+}
+
+impl Drawable for MatrixDisplay {
+    /// Returns the point the grid is centered on.
+    fn position(&self) -> (f64, f64) {
+        self.center
+    }
+
+    /// Returns None, since the size of the grid is in pixels, so a grid centered anywhere may reach into the frame.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        None
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        let grid = self.grid(canvas, map);
+        grid.draw(canvas, &self.values, self.color, |_, _| Some(self.color));
+        Ok(())
+    }
+}
+
+/// A matrix laid out as a grid of cells, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Grid {
+    left: f32,
+    top: f32,
+    rows: usize,
+    cols: usize,
+    /// Height of the entries' text.
+    size: f32,
+}
+
+impl Grid {
+    fn cell_width(&self) -> f32 {
+        self.size * 3.5
+    }
+
+    fn cell_height(&self) -> f32 {
+        self.size * 1.6
+    }
+
+    fn right(&self) -> f32 {
+        self.left + self.width()
+    }
+
+    fn bottom(&self) -> f32 {
+        self.top + self.height()
+    }
+
+    fn width(&self) -> f32 {
+        self.cols as f32 * self.cell_width()
+    }
+
+    fn height(&self) -> f32 {
+        self.rows as f32 * self.cell_height()
+    }
+
+    /// Returns the top left corner of a cell.
+    fn cell(&self, row: usize, col: usize) -> (f32, f32) {
+        (
+            self.left + col as f32 * self.cell_width(),
+            self.top + row as f32 * self.cell_height(),
+        )
+    }
+
+    fn fill_cell(&self, canvas: &mut dyn Canvas, row: usize, col: usize, color: Rgb<u8>) {
+        let (x, y) = self.cell(row, col);
+        let (x1, y1) = (x + self.cell_width(), y + self.cell_height());
+        canvas.draw_polygon(
+            &[
+                Point::new(x as i32, y as i32),
+                Point::new(x1 as i32, y as i32),
+                Point::new(x1 as i32, y1 as i32),
+                Point::new(x as i32, y1 as i32),
+            ],
+            color,
+        );
+    }
+
+    /// Draws the brackets of the grid in their color and every entry that has a color, centered in its cell.
+    fn draw<F>(&self, canvas: &mut dyn Canvas, matrix: &Matrix<f64>, brackets: Rgb<u8>, color: F)
+    where
+        F: Fn(usize, usize) -> Option<Rgb<u8>>,
+    {
+        let serif = self.size * 0.4;
+        for (x, direction) in [(self.left, 1.0), (self.right(), -1.0)] {
+            canvas.draw_line((x, self.top), (x, self.bottom()), brackets);
+            for y in [self.top, self.bottom()] {
+                canvas.draw_line((x, y), (x + direction * serif, y), brackets);
+            }
+        }
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                if let Some(color) = color(row, col) {
+                    self.draw_entry(canvas, matrix.at(row, col), row, col, 0.0, color);
+                }
+            }
+        }
+    }
+
+    /// Draws an entry centered in its cell, shifted down by the specified number of pixels.
+    fn draw_entry(
+        &self,
+        canvas: &mut dyn Canvas,
+        value: f64,
+        row: usize,
+        col: usize,
+        shift: f32,
+        color: Rgb<u8>,
+    ) {
+        let (x, y) = self.cell(row, col);
+        let center = (
+            x + self.cell_width() / 2.0,
+            y + (self.cell_height() - self.size) / 2.0 + shift,
+        );
+        draw_centered(canvas, &format_entry(value), center, self.size, color);
+    }
+}
+
+/// Lays out the grids of the matrices side by side, centered on the canvas with room for the symbols between them.
+fn grid_layout(canvas: &dyn Canvas, matrices: [&Matrix<f64>; 3]) -> [Grid; 3] {
+    let (width, height) = (canvas.width() as f32, canvas.height() as f32);
+    let size = height * 0.05;
+    let mut grids = matrices.map(|matrix| {
+        let (rows, cols) = matrix.get_dimensions();
+        let mut grid = Grid {
+            left: 0.0,
+            top: 0.0,
+            rows,
+            cols,
+            size,
+        };
+        grid.top = (height - grid.height()) / 2.0;
+        grid
+    });
+    let gap = size * 2.0;
+    let total = grids.iter().map(|grid| grid.width()).sum::<f32>() + 2.0 * gap;
+    let mut left = (width - total) / 2.0;
+    for grid in &mut grids {
+        grid.left = left;
+        left = grid.right() + gap;
+    }
+    grids
+}
+
+/// Formats an entry of a matrix, without decimals if it's a whole number.
+fn format_entry(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Returns a length that's longer than the screen's diagonal, so lines through the origin drawn that far cross the whole screen.
+fn span_reach(screen: &Screen2D) -> f64 {
+    let ((x0, x1), (y0, y1)) = (
+        <Screen2D as ScreenLike<f32>>::x_axis(screen),
+        <Screen2D as ScreenLike<f32>>::y_axis(screen),
+    );
+    ((x1 - x0) + (y1 - y0)) as f64
+}
+
+/// Draws a thick vector with a label next to its tip.
+fn draw_labelled(
+    x: f64,
+    y: f64,
+    color: Rgb<u8>,
+    label: String,
+    canvas: &mut dyn Canvas,
+    map: &CoordinateMap,
+) -> Result<(), MathvisError> {
+    Vector2D::builder()
+        .components(x, y)
+        .color(color)
+        .thickness(3.0)
+        .label(label)
+        .build()?
+        .draw(canvas, map)
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::animation::drawable::is_visible;
+
+    #[test]
+    fn test_show_eigenvectors() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let matrix = Matrix::new(vec![vec![2, 1], vec![1, 2]]).unwrap();
+        let animation = show_eigenvectors(matrix, 1.0).unwrap();
+
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        // The first eigenvector is scaled by 3 along its span, past (1, 1)
+        let (x, y) = map.to_pixels((1.0, 1.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), EIGENVECTOR_COLORS[0]);
+        // The basis vectors end up on the columns of the matrix
+        let (x, y) = map.to_pixels((1.0, 0.5));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), BASIS_COLOR);
+
+        let singular = Matrix::new(vec![vec![1.0, 1.0], vec![1.0, 1.0]]).unwrap();
+        let animation = show_eigenvectors(singular, 1.0).unwrap();
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        assert!(show_eigenvectors(Matrix::<f64>::identity(3).unwrap(), 1.0).is_err());
+    }
+
+    #[test]
+    fn test_show_matrix_multiplication() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let a = Matrix::new(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        let b = Matrix::new(vec![vec![5, 6, 7], vec![8, 9, 10]]).unwrap();
+        let product = (&a * &b).unwrap();
+        let animation = show_matrix_multiplication(a.clone(), b.clone(), 1.0).unwrap();
+
+        let mut img = RgbImage::new(854, 480);
+        let [a_grid, b_grid, product_grid] = grid_layout(
+            &img,
+            [&a, &b, &product]
+                .map(|matrix| matrix.map(|value| value as f64))
+                .each_ref(),
+        );
+        let inside = |grid: &Grid, row: usize, col: usize| {
+            let (x, y) = grid.cell(row, col);
+            (x as u32 + 2, y as u32 + 2)
+        };
+        // The first entry of the product comes from the first row and the first column
+        animation.draw_frame(0, &mut img, &screen, &map).unwrap();
+        for (x, y) in [
+            inside(&a_grid, 0, 1),
+            inside(&b_grid, 1, 0),
+            inside(&product_grid, 0, 0),
+        ] {
+            assert_eq!(*img.get_pixel(x, y), HIGHLIGHT_BACKGROUND);
+        }
+        let (x, y) = inside(&a_grid, 1, 0);
+        assert_eq!(*img.get_pixel(x, y), Rgb([0, 0, 0]));
+
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        let (x, y) = inside(&product_grid, 1, 2);
+        assert_eq!(*img.get_pixel(x, y), Rgb([0, 0, 0]));
+    }
+
+    #[test]
+    fn test_matrix_display() {
+        let mut screen =
+            Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        screen.set_background(Rgb([0, 0, 0]));
+        let map = CoordinateMap::new(&screen).unwrap();
+        let display = MatrixDisplay::new(
+            Matrix::new(vec![vec![1, 2], vec![3, 4]]).unwrap(),
+            (1.0, 1.0),
+        )
+        .with_color(Rgb([255, 0, 0]));
+        let mut img = RgbImage::new(854, 480);
+        display.draw(&mut img, &map).unwrap();
+        let grid = display.grid(&img, &map);
+        // The grid is centered on its point, with the left bracket drawn on its edge
+        let (x, y) = map.to_pixels((1.0, 1.0));
+        assert!(((grid.left + grid.right()) / 2.0 - x).abs() < 1e-3);
+        assert!(((grid.top + grid.bottom()) / 2.0 - y).abs() < 1e-3);
+        assert_eq!(
+            *img.get_pixel(grid.left as u32, (grid.top + 5.0) as u32),
+            Rgb([255, 0, 0])
+        );
+
+        // Large grids are drawn even if their center is far away from the frame
+        let large = MatrixDisplay::new(
+            Matrix::new(vec![vec![1, 2], vec![3, 4]]).unwrap(),
+            (9.0, 0.0),
+        )
+        .with_size(400.0);
+        assert!(is_visible(&large, &map));
+
+        // Halfway through, only the changed entry is faded
+        let target = Matrix::new(vec![vec![1, 2], vec![0, 4]]).unwrap();
+        let animation = display.animate_to(target, 1.0).unwrap();
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(5, &mut img, &screen, &map).unwrap();
+        let brightest = |row: usize, col: usize| {
+            let (x, y) = grid.cell(row, col);
+            let (width, height) = (grid.cell_width() as u32, grid.cell_height() as u32);
+            // Leaves out the brackets on the edges of the grid
+            (2..width - 2)
+                .flat_map(|dx| (0..height).map(move |dy| (x as u32 + dx, y as u32 + dy)))
+                .map(|(x, y)| img.get_pixel(x, y).0[0])
+                .max()
+                .unwrap()
+        };
+        if crate::animation::text::font().is_some() {
+            assert_eq!(brightest(0, 0), 255);
+            assert!(brightest(1, 0) < 255);
+        }
+        // The last frame is the new matrix on its own
+        let mut last = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut last, &screen, &map).unwrap();
+        let mut alone = RgbImage::new(854, 480);
+        MatrixDisplay::new(
+            Matrix::new(vec![vec![1, 2], vec![0, 4]]).unwrap(),
+            (1.0, 1.0),
+        )
+        .with_color(Rgb([255, 0, 0]))
+        .draw(&mut alone, &map)
+        .unwrap();
+        assert_eq!(last, alone);
+    }
+}
@@ -0,0 +1,316 @@
+//! Module containing L-systems, string rewriting systems drawn with turtle graphics into fractal curves and plants.
+#![warn(missing_docs)]
+use std::{collections::HashMap, f64::consts::FRAC_PI_2};
+
+use imageproc::image::Rgb;
+
+use super::{clip::Animation, drawable::Drawable, path::Path2D};
+use crate::{api::angle::Angle, error::MathvisError};
+
+/// Most symbols an L-system can be expanded into, since their length grows exponentially with the iterations.
+pub const MAX_SYMBOLS: usize = 1 << 22;
+
+/// An L-system, made of an axiom rewritten by replacing every symbol with a rule at every iteration,
+/// and drawn by a turtle reading the resulting symbols:
+///
+/// - `F` and `G` move forward one step, drawing a line
+/// - `f` moves forward one step without drawing
+/// - `+` turns left by the angle and `-` turns right by it
+/// - `|` turns around
+/// - `[` saves the position and heading, and `]` goes back to the last one saved
+///
+/// Every other symbol is only used for rewriting. The turtle starts at the origin heading up.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::lsystem::LSystem;
+/// use mathvis::api::angle::Angle;
+///
+/// let koch = LSystem::new("F", Angle::degrees(60.0), Rgb([255, 255, 255])).with_rule('F', "F+F--F+F");
+/// assert_eq!(koch.expand(1).unwrap(), "F+F--F+F");
+/// assert_eq!(koch.expand(2).unwrap().matches('F').count(), 16);
+/// assert!(koch.expand(20).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LSystem {
+    axiom: String,
+    rules: HashMap<char, String>,
+    angle: f64,
+    color: Rgb<u8>,
+}
+
+impl LSystem {
+    /// Creates an L-system with no rules from its axiom and the angle its turns are by, with plain numbers taken as radians (see [Angle]).
+    pub fn new(axiom: &str, angle: impl Into<Angle>, color: Rgb<u8>) -> Self {
+        LSystem {
+            axiom: axiom.to_string(),
+            rules: HashMap::new(),
+            angle: angle.into().to_radians(),
+            color,
+        }
+    }
+
+    /// Adds the rule rewriting a symbol into the replacement, replacing the previous rule of the symbol if there was one.
+    pub fn with_rule(mut self, symbol: char, replacement: &str) -> Self {
+        self.rules.insert(symbol, replacement.to_string());
+        self
+    }
+
+    /// Creates the Koch snowflake, whose every side grows a bump at every iteration.
+    pub fn koch_snowflake(color: Rgb<u8>) -> Self {
+        LSystem::new("F--F--F", Angle::degrees(60.0), color).with_rule('F', "F+F--F+F")
+    }
+
+    /// Creates the Heighway dragon curve, folding a strip of paper in half at every iteration.
+    pub fn dragon_curve(color: Rgb<u8>) -> Self {
+        LSystem::new("F", FRAC_PI_2, color)
+            .with_rule('F', "F+G")
+            .with_rule('G', "F-G")
+    }
+
+    /// Creates a fractal plant, growing branches out of every branch at every iteration.
+    pub fn fractal_plant(color: Rgb<u8>) -> Self {
+        LSystem::new("X", Angle::degrees(25.0), color)
+            .with_rule('X', "F+[[X]-X]-F[-FX]+X")
+            .with_rule('F', "FF")
+    }
+
+    /// Returns the symbols after rewriting the axiom the specified number of times.
+    ///
+    /// Returns an Err if there would be more than [MAX_SYMBOLS] symbols and an Ok with them otherwise.
+    pub fn expand(&self, iterations: u32) -> Result<String, MathvisError> {
+        let mut symbols = self.axiom.clone();
+        for _ in 0..iterations {
+            let length: usize = symbols
+                .chars()
+                .map(|symbol| {
+                    self.rules
+                        .get(&symbol)
+                        .map_or(1, |rule| rule.chars().count())
+                })
+                .sum();
+            if length > MAX_SYMBOLS {
+                return Err(MathvisError::InvalidArgument(format!(
+                    "An L-system can be expanded into at most {} symbols, but {} iterations take more.",
+                    MAX_SYMBOLS, iterations
+                )));
+            }
+            let mut next = String::with_capacity(length);
+            for symbol in symbols.chars() {
+                match self.rules.get(&symbol) {
+                    Some(rule) => next.push_str(rule),
+                    None => next.push(symbol),
+                }
+            }
+            symbols = next;
+        }
+        Ok(symbols)
+    }
+
+    /// Returns the lines the turtle draws after the specified number of iterations, taking steps of one unit from the origin.
+    ///
+    /// Returns an Err if there would be more than [MAX_SYMBOLS] symbols and an Ok with the lines otherwise.
+    pub fn polylines(&self, iterations: u32) -> Result<Vec<Vec<(f64, f64)>>, MathvisError> {
+        let mut polylines = vec![vec![(0.0, 0.0)]];
+        let (mut position, mut heading) = ((0.0, 0.0), FRAC_PI_2);
+        let mut stack = Vec::new();
+        for symbol in self.expand(iterations)?.chars() {
+            match symbol {
+                'F' | 'G' | 'f' => {
+                    position = (position.0 + heading.cos(), position.1 + heading.sin());
+                    if symbol == 'f' {
+                        polylines.push(vec![position]);
+                    } else {
+                        polylines.last_mut().unwrap().push(position);
+                    }
+                }
+                '+' => heading += self.angle,
+                '-' => heading -= self.angle,
+                '|' => heading += std::f64::consts::PI,
+                '[' => stack.push((position, heading)),
+                ']' => {
+                    if let Some(saved) = stack.pop() {
+                        (position, heading) = saved;
+                        polylines.push(vec![position]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        polylines.retain(|polyline| polyline.len() > 1);
+        Ok(polylines)
+    }
+
+    /// Returns the path drawn after the specified number of iterations, scaled and moved to fit centered inside the box
+    /// between `bottom_left` and `top_right` while keeping its proportions.
+    ///
+    /// Returns an Err if there would be more than [MAX_SYMBOLS] symbols and an Ok with the path otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::lsystem::LSystem;
+    ///
+    /// let dragon = LSystem::dragon_curve(Rgb([255, 100, 0]));
+    /// let path = dragon.path(1, (-1.0, -1.0), (1.0, 1.0)).unwrap();
+    /// // Up and then left, filling the box
+    /// assert_eq!(path.subpaths()[0].len(), 3);
+    /// let ((left, bottom), (right, top)) = path.bounds().unwrap();
+    /// for (corner, expected) in [(left, -1.0), (bottom, -1.0), (right, 1.0), (top, 1.0)] {
+    ///     assert!((corner - expected).abs() < 1e-9);
+    /// }
+    /// ```
+    pub fn path(
+        &self,
+        iterations: u32,
+        bottom_left: (f64, f64),
+        top_right: (f64, f64),
+    ) -> Result<Path2D, MathvisError> {
+        let polylines = self.polylines(iterations)?;
+        let Some(((x0, y0), (x1, y1))) = Path2D::new(polylines.clone(), self.color).bounds() else {
+            return Ok(Path2D::new(Vec::new(), self.color));
+        };
+        let (width, height) = (top_right.0 - bottom_left.0, top_right.1 - bottom_left.1);
+        // A straight line has no width or height to fit, so it's only scaled along the other one
+        // Turning by π/2 leaves rounding errors, so tiny extents count as none
+        let scale = match (x1 - x0 > 1e-9, y1 - y0 > 1e-9) {
+            (true, true) => (width / (x1 - x0)).min(height / (y1 - y0)),
+            (true, false) => width / (x1 - x0),
+            (false, true) => height / (y1 - y0),
+            (false, false) => 1.0,
+        };
+        let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+        let (tx, ty) = (
+            (bottom_left.0 + top_right.0) / 2.0,
+            (bottom_left.1 + top_right.1) / 2.0,
+        );
+        let fitted = polylines
+            .into_iter()
+            .map(|polyline| {
+                polyline
+                    .into_iter()
+                    .map(|(x, y)| (tx + (x - cx) * scale, ty + (y - cy) * scale))
+                    .collect()
+            })
+            .collect();
+        Ok(Path2D::new(fitted, self.color))
+    }
+
+    /// Returns an animation of the specified duration drawing every iteration from the axiom up to the specified one,
+    /// each fitted inside the box between `bottom_left` and `top_right` and drawn line by line in an equal part of the animation.
+    ///
+    /// Returns an Err if the last iteration would have more than [MAX_SYMBOLS] symbols and an Ok with the animation otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::lsystem::LSystem;
+    ///
+    /// let plant = LSystem::fractal_plant(Rgb([80, 200, 80]));
+    /// let growth = plant.grow(5, (-3.0, -3.0), (3.0, 3.0), 10.0).unwrap();
+    /// assert_eq!(growth.duration(), 10.0);
+    /// ```
+    pub fn grow(
+        &self,
+        iterations: u32,
+        bottom_left: (f64, f64),
+        top_right: (f64, f64),
+        duration: f32,
+    ) -> Result<Animation, MathvisError> {
+        let paths = (0..=iterations)
+            .map(|iteration| self.path(iteration, bottom_left, top_right))
+            .collect::<Result<Vec<_>, _>>()?;
+        let color = self.color;
+        Ok(Animation::new(duration, move |progress, canvas, _, map| {
+            let position = progress * paths.len() as f64;
+            let index = (position.floor() as usize).min(paths.len() - 1);
+            let drawn = prefix(paths[index].subpaths(), position - index as f64);
+            Path2D::new(drawn, color).draw(canvas, map)
+        }))
+    }
+}
+
+/// Returns the start of the polylines covering the specified fraction of their segments in order,
+/// ending partway through a segment.
+fn prefix(polylines: &[Vec<(f64, f64)>], fraction: f64) -> Vec<Vec<(f64, f64)>> {
+    let total: usize = polylines.iter().map(|polyline| polyline.len() - 1).sum();
+    let mut remaining = fraction.clamp(0.0, 1.0) * total as f64;
+    let mut drawn = Vec::new();
+    for polyline in polylines {
+        if remaining <= 0.0 {
+            break;
+        }
+        let segments = polyline.len() - 1;
+        if remaining >= segments as f64 {
+            drawn.push(polyline.clone());
+            remaining -= segments as f64;
+            continue;
+        }
+        let (whole, part) = (remaining.floor() as usize, remaining.fract());
+        let mut partial = polyline[..=whole].to_vec();
+        let ((x0, y0), (x1, y1)) = (polyline[whole], polyline[whole + 1]);
+        partial.push((x0 + (x1 - x0) * part, y0 + (y1 - y0) * part));
+        drawn.push(partial);
+        break;
+    }
+    drawn
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::{screen::Screen2D, util::CoordinateMap};
+
+    #[test]
+    fn test_polylines() {
+        let color = Rgb([255, 255, 255]);
+        let square = LSystem::new("F+F+F+F", FRAC_PI_2, color);
+        let polylines = square.polylines(0).unwrap();
+        assert_eq!(polylines.len(), 1);
+        let last = polylines[0].last().unwrap();
+        assert!(last.0.abs() < 1e-12 && last.1.abs() < 1e-12);
+
+        // Branches start again from where they were saved, and moving without drawing breaks the line
+        let branches = LSystem::new("F[+F]-FfF", FRAC_PI_2, color);
+        let polylines = branches.polylines(0).unwrap();
+        assert_eq!(polylines.len(), 3);
+        assert_eq!(polylines[1][0], polylines[0][1]);
+        assert_eq!(polylines[2].len(), 2);
+
+        let snowflake = LSystem::koch_snowflake(color);
+        assert_eq!(snowflake.polylines(3).unwrap()[0].len(), 3 * 64 + 1);
+    }
+
+    #[test]
+    fn test_grow() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let color = Rgb([80, 200, 80]);
+        let line = LSystem::new("F", FRAC_PI_2, color).with_rule('F', "F+F-F-F+F");
+        let animation = line.grow(1, (-2.0, -2.0), (2.0, 2.0), 1.0).unwrap();
+        // Partway through drawing the first iteration, a single line going up
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(1, &mut img, &screen, &map).unwrap();
+        let (x, y) = map.to_pixels((0.0, -1.9));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), color);
+        let (x, y) = map.to_pixels((0.0, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([0, 0, 0]));
+
+        assert_eq!(
+            prefix(
+                &[
+                    vec![(0.0, 0.0), (1.0, 0.0)],
+                    vec![(0.0, 1.0), (1.0, 1.0), (2.0, 1.0)]
+                ],
+                0.5
+            ),
+            [vec![(0.0, 0.0), (1.0, 0.0)], vec![(0.0, 1.0), (0.5, 1.0)]]
+        );
+    }
+}
@@ -1,4 +1,36 @@
+pub mod animated;
 pub(crate) mod axis;
 pub(crate) mod background;
+pub mod calculus;
+pub mod cancel;
+pub mod canvas;
+pub mod chart;
+pub mod checkpoint;
+pub mod clip;
+pub mod complex;
+pub mod contour;
+pub mod distribution;
+pub mod drawable;
+pub mod easing;
+pub mod field;
+pub mod fourier;
+pub mod fractal;
+pub mod geometry;
+#[cfg(feature = "wgpu")]
+pub mod gpu;
+pub mod graph;
+pub mod keyframes;
+pub mod linear;
+pub mod lsystem;
+pub mod network;
+pub mod path;
+#[cfg(feature = "plotters")]
+pub mod plot;
+pub mod renderer;
+pub mod replace;
+pub mod scheduler;
 pub mod show;
+pub(crate) mod text;
+pub mod updater;
 pub mod vector;
+pub mod walk;
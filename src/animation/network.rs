@@ -0,0 +1,604 @@
+//! Module containing graphs in the sense of networks, nodes joined by edges, with layouts and animated traversals.
+#![warn(missing_docs)]
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque},
+    f64::consts::TAU,
+};
+
+use imageproc::image::Rgb;
+
+use super::{
+    canvas::Canvas, clip::Animation, drawable::Drawable, field::PointCloud, text::draw_centered,
+    vector::draw_thick_line,
+};
+use crate::{
+    api::{bounds::BoundingBox, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// Radius of the nodes in pixels.
+const NODE_RADIUS: u32 = 14;
+/// Color of the nodes that aren't highlighted.
+const NODE_COLOR: Rgb<u8> = Rgb([220, 220, 220]);
+/// Color of the edges that aren't highlighted.
+const EDGE_COLOR: Rgb<u8> = Rgb([110, 110, 110]);
+/// Color of the labels, drawn on top of the nodes.
+const LABEL_COLOR: Rgb<u8> = Rgb([30, 30, 30]);
+/// Color of the nodes and edges highlighted by traversals.
+const HIGHLIGHT_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+
+/// A step of a traversal, the node reached and the node it was reached from, which is None for the first one.
+pub type Step = (usize, Option<usize>);
+
+/// An undirected graph, made of nodes at positions in screen coordinates joined by weighted edges.
+///
+/// Nodes are drawn as dots with their labels on top of them, and are placed on a circle until they're given
+/// a layout or positions. Traversals from a node are animated by highlighting the nodes in the order they're reached,
+/// growing every edge they're reached through.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::network::Graph2D;
+///
+/// let square = Graph2D::new(4, vec![(0, 1), (1, 2), (2, 3), (3, 0)]).unwrap();
+/// assert_eq!(square.neighbors(0), [1, 3]);
+/// assert_eq!(square.bfs(0).unwrap(), [(0, None), (1, Some(0)), (3, Some(0)), (2, Some(1))]);
+/// assert_eq!(square.shortest_path(1, 3).unwrap(), Some(vec![1, 0, 3]));
+/// assert!(Graph2D::new(2, vec![(0, 2)]).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph2D {
+    positions: Vec<(f64, f64)>,
+    labels: Vec<String>,
+    edges: Vec<(usize, usize)>,
+    weights: Vec<f64>,
+}
+
+impl Graph2D {
+    /// Creates a graph with the specified number of nodes, labeled by their indices, and the edges between the nodes
+    /// at the specified indices, all with a weight of 1. The nodes are placed on a circle of radius 2 around the origin.
+    ///
+    /// Returns an Err if an edge joins a node that doesn't exist and an Ok with the graph otherwise.
+    pub fn new(nodes: usize, edges: Vec<(usize, usize)>) -> Result<Self, MathvisError> {
+        if let Some(&(a, b)) = edges.iter().find(|&&(a, b)| a >= nodes || b >= nodes) {
+            return Err(MathvisError::InvalidArgument(format!(
+                "The edge ({}, {}) joins a node that doesn't exist in a graph of {} nodes.",
+                a, b, nodes
+            )));
+        }
+        Ok(Graph2D {
+            positions: vec![(0.0, 0.0); nodes],
+            labels: (0..nodes).map(|node| node.to_string()).collect(),
+            weights: vec![1.0; edges.len()],
+            edges,
+        }
+        .circular_layout((0.0, 0.0), 2.0))
+    }
+
+    /// Sets the label of every node.
+    ///
+    /// Returns an Err if there isn't one label per node and an Ok with the graph otherwise.
+    pub fn with_labels(mut self, labels: Vec<String>) -> Result<Self, MathvisError> {
+        self.labels = self.check_length(labels, "labels")?;
+        Ok(self)
+    }
+
+    /// Sets the weight of every edge, in the order they were given, which shortest paths minimize the sum of.
+    ///
+    /// Returns an Err if there isn't one weight per edge or any of them is negative and an Ok with the graph otherwise.
+    pub fn with_weights(mut self, weights: Vec<f64>) -> Result<Self, MathvisError> {
+        if weights
+            .iter()
+            .any(|weight| weight.is_nan() || *weight < 0.0)
+        {
+            return Err(MathvisError::InvalidArgument(
+                "The weights of a graph's edges can't be negative.".into(),
+            ));
+        }
+        if weights.len() != self.edges.len() {
+            return Err(MathvisError::DimensionMismatch(format!(
+                "A graph with {} edges needs as many weights, but {} were given.",
+                self.edges.len(),
+                weights.len()
+            )));
+        }
+        self.weights = weights;
+        Ok(self)
+    }
+
+    /// Sets the position of every node in screen coordinates.
+    ///
+    /// Returns an Err if there isn't one position per node and an Ok with the graph otherwise.
+    pub fn with_positions(mut self, positions: Vec<(f64, f64)>) -> Result<Self, MathvisError> {
+        self.positions = self.check_length(positions, "positions")?;
+        Ok(self)
+    }
+
+    /// Returns the values if there's one per node.
+    ///
+    /// Returns an Err if there isn't and an Ok with the values otherwise.
+    fn check_length<T>(&self, values: Vec<T>, name: &str) -> Result<Vec<T>, MathvisError> {
+        if values.len() != self.positions.len() {
+            return Err(MathvisError::DimensionMismatch(format!(
+                "A graph with {} nodes needs as many {}, but {} were given.",
+                self.positions.len(),
+                name,
+                values.len()
+            )));
+        }
+        Ok(values)
+    }
+
+    /// Places the nodes evenly on a circle, counterclockwise from its top.
+    pub fn circular_layout(mut self, center: (f64, f64), radius: f64) -> Self {
+        let count = self.positions.len() as f64;
+        for (node, position) in self.positions.iter_mut().enumerate() {
+            let angle = TAU / 4.0 + TAU * node as f64 / count;
+            *position = (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            );
+        }
+        self
+    }
+
+    /// Places the nodes with the Fruchterman-Reingold force-directed algorithm, in which edges pull the nodes they join
+    /// together and every node pushes every other one away, then fits them inside the box between `bottom_left` and `top_right`.
+    ///
+    /// The nodes start from the circular layout, so the same graph always gets the same layout.
+    pub fn force_layout(
+        self,
+        bottom_left: (f64, f64),
+        top_right: (f64, f64),
+        iterations: u32,
+    ) -> Self {
+        let count = self.positions.len();
+        let mut graph = self.circular_layout((0.0, 0.0), 1.0);
+        // Ideal distance between nodes, for the nodes to spread over an area of about 4
+        let k = 2.0 / (count.max(1) as f64).sqrt();
+        for iteration in 0..iterations {
+            let mut forces = vec![(0.0, 0.0); count];
+            for i in 0..count {
+                for j in i + 1..count {
+                    let (dx, dy, distance) = graph.offset(i, j);
+                    let push = k * k / distance;
+                    forces[i].0 += dx / distance * push;
+                    forces[i].1 += dy / distance * push;
+                    forces[j].0 -= dx / distance * push;
+                    forces[j].1 -= dy / distance * push;
+                }
+            }
+            for &(a, b) in &graph.edges {
+                if a == b {
+                    continue;
+                }
+                let (dx, dy, distance) = graph.offset(a, b);
+                let pull = distance * distance / k;
+                forces[a].0 -= dx / distance * pull;
+                forces[a].1 -= dy / distance * pull;
+                forces[b].0 += dx / distance * pull;
+                forces[b].1 += dy / distance * pull;
+            }
+            // The nodes move less and less, so the layout settles instead of oscillating
+            let temperature = 0.1 * (1.0 - iteration as f64 / iterations as f64);
+            for (position, (fx, fy)) in graph.positions.iter_mut().zip(forces) {
+                let force = fx.hypot(fy);
+                if force > 0.0 {
+                    let step = force.min(temperature);
+                    position.0 += fx / force * step;
+                    position.1 += fy / force * step;
+                }
+            }
+        }
+        graph.fit(bottom_left, top_right)
+    }
+
+    /// Returns the offset from node j to node i and its length, which is never 0 so it can be divided by.
+    fn offset(&self, i: usize, j: usize) -> (f64, f64, f64) {
+        let (dx, dy) = (
+            self.positions[i].0 - self.positions[j].0,
+            self.positions[i].1 - self.positions[j].1,
+        );
+        (dx, dy, dx.hypot(dy).max(1e-6))
+    }
+
+    /// Scales and moves the nodes to fit centered inside the box between `bottom_left` and `top_right`, keeping their proportions.
+    fn fit(mut self, bottom_left: (f64, f64), top_right: (f64, f64)) -> Self {
+        let Some(((x0, y0), (x1, y1))) = self
+            .positions
+            .iter()
+            .map(|&position| (position, position))
+            .reduce(|((x0, y0), (x1, y1)), ((x, y), _)| {
+                ((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y)))
+            })
+        else {
+            return self;
+        };
+        let scale = [
+            (top_right.0 - bottom_left.0) / (x1 - x0),
+            (top_right.1 - bottom_left.1) / (y1 - y0),
+        ]
+        .into_iter()
+        .filter(|scale| scale.is_finite())
+        .reduce(f64::min)
+        .unwrap_or(1.0);
+        let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+        let (tx, ty) = (
+            (bottom_left.0 + top_right.0) / 2.0,
+            (bottom_left.1 + top_right.1) / 2.0,
+        );
+        for position in &mut self.positions {
+            *position = (
+                tx + (position.0 - cx) * scale,
+                ty + (position.1 - cy) * scale,
+            );
+        }
+        self
+    }
+
+    /// Returns the position of every node.
+    pub fn positions(&self) -> &[(f64, f64)] {
+        &self.positions
+    }
+
+    /// Returns the nodes joined to a node by an edge, in the order the edges were given.
+    pub fn neighbors(&self, node: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .filter_map(|&(a, b)| match (a == node, b == node) {
+                (true, _) => Some(b),
+                (_, true) => Some(a),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns an Err if a node doesn't exist and an Ok otherwise.
+    fn check_node(&self, node: usize) -> Result<(), MathvisError> {
+        if node >= self.positions.len() {
+            return Err(MathvisError::InvalidArgument(format!(
+                "Node {} doesn't exist in a graph of {} nodes.",
+                node,
+                self.positions.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the steps of a breadth-first search from a node, visiting the nodes closest to it first.
+    ///
+    /// Returns an Err if the node doesn't exist and an Ok with the steps otherwise.
+    pub fn bfs(&self, start: usize) -> Result<Vec<Step>, MathvisError> {
+        self.check_node(start)?;
+        let mut visited = vec![false; self.positions.len()];
+        visited[start] = true;
+        let mut steps = vec![(start, None)];
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for neighbor in self.neighbors(node) {
+                if !visited[neighbor] {
+                    visited[neighbor] = true;
+                    steps.push((neighbor, Some(node)));
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        Ok(steps)
+    }
+
+    /// Returns the steps of a depth-first search from a node, following every path as far as it goes before backtracking.
+    ///
+    /// Returns an Err if the node doesn't exist and an Ok with the steps otherwise.
+    pub fn dfs(&self, start: usize) -> Result<Vec<Step>, MathvisError> {
+        self.check_node(start)?;
+        let mut visited = vec![false; self.positions.len()];
+        let mut steps = Vec::new();
+        let mut stack = vec![(start, None)];
+        while let Some((node, from)) = stack.pop() {
+            if visited[node] {
+                continue;
+            }
+            visited[node] = true;
+            steps.push((node, from));
+            // Pushed in reverse so the first neighbor is explored first
+            for neighbor in self.neighbors(node).into_iter().rev() {
+                if !visited[neighbor] {
+                    stack.push((neighbor, Some(node)));
+                }
+            }
+        }
+        Ok(steps)
+    }
+
+    /// Returns the path between two nodes with the smallest total weight, found with Dijkstra's algorithm.
+    ///
+    /// Returns an Err if either node doesn't exist, an Ok with None if there's no path between them
+    /// and an Ok with the nodes along the path from `from` to `to` otherwise.
+    pub fn shortest_path(
+        &self,
+        from: usize,
+        to: usize,
+    ) -> Result<Option<Vec<usize>>, MathvisError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+
+        /// A node to visit with its distance from the start, ordered so the closest one comes out of the heap first.
+        struct Visit(f64, usize);
+        impl PartialEq for Visit {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+        impl Eq for Visit {}
+        impl PartialOrd for Visit {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Visit {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+            }
+        }
+
+        let mut distances = vec![f64::INFINITY; self.positions.len()];
+        let mut previous = vec![None; self.positions.len()];
+        distances[from] = 0.0;
+        let mut heap = BinaryHeap::from([Visit(0.0, from)]);
+        while let Some(Visit(distance, node)) = heap.pop() {
+            if node == to {
+                break;
+            }
+            if distance > distances[node] {
+                continue;
+            }
+            for (&(a, b), weight) in self.edges.iter().zip(&self.weights) {
+                let neighbor = match (a == node, b == node) {
+                    (true, _) => b,
+                    (_, true) => a,
+                    _ => continue,
+                };
+                if distance + weight < distances[neighbor] {
+                    distances[neighbor] = distance + weight;
+                    previous[neighbor] = Some(node);
+                    heap.push(Visit(distance + weight, neighbor));
+                }
+            }
+        }
+        if distances[to].is_infinite() {
+            return Ok(None);
+        }
+        let mut path = vec![to];
+        while let Some(node) = previous[*path.last().unwrap()] {
+            path.push(node);
+        }
+        path.reverse();
+        Ok(Some(path))
+    }
+
+    /// Returns an animation of the specified duration highlighting the steps of a traversal, one after the other,
+    /// with every edge a node is reached through growing towards it before it lights up.
+    ///
+    /// Returns an Err if any step reaches or comes from a node that doesn't exist and an Ok with the animation otherwise.
+    pub fn highlight(&self, steps: Vec<Step>, duration: f32) -> Result<Animation, MathvisError> {
+        for &(node, from) in &steps {
+            self.check_node(node)?;
+            if let Some(from) = from {
+                self.check_node(from)?;
+            }
+        }
+        let graph = self.clone();
+        Ok(Animation::new(duration, move |progress, canvas, _, map| {
+            graph.draw(canvas, map)?;
+            let position = progress * steps.len() as f64;
+            let current = position.floor() as usize;
+            let mut lit = Vec::new();
+            for (i, &(node, from)) in steps.iter().enumerate().take(current + 1) {
+                let grown = if i < current { 1.0 } else { position.fract() };
+                if let Some(from) = from {
+                    let (start, end) = (graph.positions[from], graph.positions[node]);
+                    let tip = (
+                        start.0 + (end.0 - start.0) * grown,
+                        start.1 + (end.1 - start.1) * grown,
+                    );
+                    draw_thick_line(
+                        canvas,
+                        map.to_pixels((start.0 as f32, start.1 as f32)),
+                        map.to_pixels((tip.0 as f32, tip.1 as f32)),
+                        4.0,
+                        HIGHLIGHT_COLOR,
+                    );
+                }
+                if i < current || from.is_none() {
+                    lit.push(node);
+                }
+            }
+            // The highlighted edges are drawn over the nodes they start from, which are drawn again on top
+            let lit: Vec<usize> = steps
+                .iter()
+                .map(|&(node, _)| node)
+                .filter(|node| lit.contains(node))
+                .collect();
+            graph.draw_nodes(canvas, map, &lit);
+            Ok(())
+        }))
+    }
+
+    /// Returns an animation of the specified duration highlighting a breadth-first search from a node.
+    ///
+    /// Returns an Err if the node doesn't exist and an Ok with the animation otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::network::Graph2D;
+    ///
+    /// let tree = Graph2D::new(7, vec![(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (2, 6)])
+    ///     .unwrap()
+    ///     .force_layout((-3.0, -3.0), (3.0, 3.0), 100);
+    /// assert_eq!(tree.animate_bfs(0, 7.0).unwrap().duration(), 7.0);
+    /// assert!(tree.animate_dfs(7, 7.0).is_err());
+    /// ```
+    pub fn animate_bfs(&self, start: usize, duration: f32) -> Result<Animation, MathvisError> {
+        self.highlight(self.bfs(start)?, duration)
+    }
+
+    /// Returns an animation of the specified duration highlighting a depth-first search from a node.
+    ///
+    /// Returns an Err if the node doesn't exist and an Ok with the animation otherwise.
+    pub fn animate_dfs(&self, start: usize, duration: f32) -> Result<Animation, MathvisError> {
+        self.highlight(self.dfs(start)?, duration)
+    }
+
+    /// Returns an animation of the specified duration highlighting the shortest path between two nodes, from `from` to `to`.
+    ///
+    /// Returns an Err if either node doesn't exist or there's no path between them and an Ok with the animation otherwise.
+    pub fn animate_shortest_path(
+        &self,
+        from: usize,
+        to: usize,
+        duration: f32,
+    ) -> Result<Animation, MathvisError> {
+        let path = self.shortest_path(from, to)?.ok_or_else(|| {
+            MathvisError::InvalidArgument(format!(
+                "There's no path from node {} to node {}.",
+                from, to
+            ))
+        })?;
+        let steps = path
+            .iter()
+            .enumerate()
+            .map(|(i, &node)| (node, i.checked_sub(1).map(|i| path[i])))
+            .collect();
+        self.highlight(steps, duration)
+    }
+
+    /// Draws the nodes with their labels, in the highlight color for the specified ones.
+    fn draw_nodes(&self, canvas: &mut dyn Canvas, map: &CoordinateMap, highlighted: &[usize]) {
+        let size = NODE_RADIUS as f32 * 1.2;
+        for (node, (&position, label)) in self.positions.iter().zip(&self.labels).enumerate() {
+            let color = if highlighted.contains(&node) {
+                HIGHLIGHT_COLOR
+            } else {
+                NODE_COLOR
+            };
+            // Drawing a point cloud can't fail
+            let _ = PointCloud::new(vec![position], color)
+                .with_radius(NODE_RADIUS)
+                .draw(canvas, map);
+            let (x, y) = map.to_pixels((position.0 as f32, position.1 as f32));
+            draw_centered(canvas, label, (x, y - size * 0.6), size, LABEL_COLOR);
+        }
+    }
+}
+
+impl Drawable for Graph2D {
+    /// Returns the centroid of the nodes, or the origin for a graph without any.
+    fn position(&self) -> (f64, f64) {
+        PointCloud::new(self.positions.clone(), NODE_COLOR).position()
+    }
+
+    /// Returns the box of the centers of the nodes, since the nodes themselves are drawn at a fixed size in pixels.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(self.positions.iter().copied())
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        for &(a, b) in &self.edges {
+            let (start, end) = (self.positions[a], self.positions[b]);
+            draw_thick_line(
+                canvas,
+                map.to_pixels((start.0 as f32, start.1 as f32)),
+                map.to_pixels((end.0 as f32, end.1 as f32)),
+                2.0,
+                EDGE_COLOR,
+            );
+        }
+        self.draw_nodes(canvas, map, &[]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_traversals() {
+        // 0 - 1 - 2
+        // |       |
+        // 3 ----- 4   5
+        let graph = Graph2D::new(6, vec![(0, 1), (1, 2), (0, 3), (3, 4), (2, 4)]).unwrap();
+        assert_eq!(
+            graph.dfs(0).unwrap(),
+            [
+                (0, None),
+                (1, Some(0)),
+                (2, Some(1)),
+                (4, Some(2)),
+                (3, Some(4))
+            ]
+        );
+        assert_eq!(graph.bfs(5).unwrap(), [(5, None)]);
+        assert_eq!(graph.shortest_path(0, 5).unwrap(), None);
+        assert_eq!(graph.shortest_path(1, 4).unwrap(), Some(vec![1, 2, 4]));
+        // A heavy edge is avoided even if the path around it is longer
+        let weighted = graph.with_weights(vec![1.0, 5.0, 1.0, 1.0, 1.0]).unwrap();
+        assert_eq!(
+            weighted.shortest_path(1, 4).unwrap(),
+            Some(vec![1, 0, 3, 4])
+        );
+        assert!(weighted.with_weights(vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn test_layouts() {
+        let graph = Graph2D::new(4, vec![(0, 1), (1, 2), (2, 3)]).unwrap();
+        let top = graph.positions()[0];
+        assert!(top.0.abs() < 1e-12 && (top.1 - 2.0).abs() < 1e-12);
+
+        // A path settles into a line, with its ends furthest apart
+        let laid_out = graph.force_layout((-2.0, -2.0), (2.0, 2.0), 200);
+        let distance = |i: usize, j: usize| {
+            let (a, b) = (laid_out.positions()[i], laid_out.positions()[j]);
+            (a.0 - b.0).hypot(a.1 - b.1)
+        };
+        assert!(distance(0, 3) > distance(0, 2) && distance(0, 2) > distance(0, 1));
+        for &(x, y) in laid_out.positions() {
+            assert!(x.abs() <= 2.0 + 1e-9 && y.abs() <= 2.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_highlight() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let graph = Graph2D::new(2, vec![(0, 1)])
+            .unwrap()
+            .with_positions(vec![(-2.0, 0.0), (2.0, 0.0)])
+            .unwrap()
+            .with_labels(vec![String::new(), String::new()])
+            .unwrap();
+        let animation = graph.animate_bfs(0, 1.0).unwrap();
+        let mut img = RgbImage::new(854, 480);
+        // The start is lit, and the edge is growing towards the other node
+        animation.draw_frame(7, &mut img, &screen, &map).unwrap();
+        for (point, expected) in [
+            ((-2.0, 0.0), HIGHLIGHT_COLOR),
+            ((0.0, 0.0), HIGHLIGHT_COLOR),
+            ((1.5, 0.0), EDGE_COLOR),
+            ((2.0, 0.0), NODE_COLOR),
+        ] {
+            let (x, y) = map.to_pixels(point);
+            assert_eq!(*img.get_pixel(x as u32, y as u32), expected);
+        }
+        assert!(graph.animate_shortest_path(0, 2, 1.0).is_err());
+        assert!(graph.highlight(vec![(0, None), (2, Some(0))], 1.0).is_err());
+        assert!(graph.highlight(vec![(1, Some(5))], 1.0).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Module containing paths made of line segments, which can be imported from SVG path data with the `svg` feature.
+#![warn(missing_docs)]
+use imageproc::image::Rgb;
+#[cfg(feature = "svg")]
+use svgtypes::{SimplePathSegment, SimplifyingPathParser};
+
+use super::{canvas::Canvas, drawable::Drawable, vector::draw_thick_line};
+use crate::{
+    api::{bounds::BoundingBox, util::CoordinateMap},
+    error::MathvisError,
+};
+
+/// Number of line segments every Bézier curve is split into when it's imported.
+#[cfg(feature = "svg")]
+const CURVE_SEGMENTS: u32 = 16;
+
+/// A shape made of polylines in screen coordinates, drawn as its outline.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::path::Path2D;
+///
+/// let square = Path2D::new(
+///     vec![vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)]],
+///     Rgb([255, 255, 0]),
+/// );
+/// assert_eq!(square.bounds(), Some(((0.0, 0.0), (1.0, 1.0))));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path2D {
+    subpaths: Vec<Vec<(f64, f64)>>,
+    color: Rgb<u8>,
+    thickness: f32,
+}
+
+impl Path2D {
+    /// Creates a path from its subpaths, each one a polyline through the specified points.
+    pub fn new(subpaths: Vec<Vec<(f64, f64)>>, color: Rgb<u8>) -> Self {
+        Path2D {
+            subpaths,
+            color,
+            thickness: 1.0,
+        }
+    }
+
+    /// Creates a path from SVG path data (the `d` attribute of a `<path>`), placing the SVG origin at `origin` and scaling every
+    /// SVG unit to `scale` screen units.
+    ///
+    /// The y axis is flipped, since it points down in SVG. Arcs and Bézier curves are split into line segments.
+    ///
+    /// Returns an Err if the path data is invalid and an Ok with the path otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::path::Path2D;
+    ///
+    /// let triangle = Path2D::from_svg("M 0 0 L 20 0 L 10 -20 Z", (-1.0, 0.0), 0.1, Rgb([255, 0, 0])).unwrap();
+    /// assert_eq!(triangle.bounds(), Some(((-1.0, 0.0), (1.0, 2.0))));
+    /// assert!(Path2D::from_svg("M 0 0 L", (0.0, 0.0), 1.0, Rgb([255, 0, 0])).is_err());
+    /// ```
+    #[cfg(feature = "svg")]
+    pub fn from_svg(
+        data: &str,
+        origin: (f64, f64),
+        scale: f64,
+        color: Rgb<u8>,
+    ) -> Result<Self, MathvisError> {
+        let to_screen = |x: f64, y: f64| (origin.0 + x * scale, origin.1 - y * scale);
+        let mut subpaths: Vec<Vec<(f64, f64)>> = Vec::new();
+        // Current position and start of the current subpath, in SVG coordinates
+        let (mut current, mut start) = ((0.0, 0.0), (0.0, 0.0));
+        for segment in SimplifyingPathParser::from(data) {
+            let segment = segment.map_err(|err| {
+                MathvisError::InvalidArgument(format!("Invalid SVG path data: {}", err))
+            })?;
+            let points: Vec<(f64, f64)> = match segment {
+                SimplePathSegment::MoveTo { x, y } => {
+                    subpaths.push(Vec::new());
+                    start = (x, y);
+                    vec![(x, y)]
+                }
+                SimplePathSegment::LineTo { x, y } => vec![(x, y)],
+                SimplePathSegment::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => (1..=CURVE_SEGMENTS)
+                    .map(|i| {
+                        let t = i as f64 / CURVE_SEGMENTS as f64;
+                        let s = 1.0 - t;
+                        let (a, b, c, d) = (s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t);
+                        (
+                            a * current.0 + b * x1 + c * x2 + d * x,
+                            a * current.1 + b * y1 + c * y2 + d * y,
+                        )
+                    })
+                    .collect(),
+                SimplePathSegment::Quadratic { x1, y1, x, y } => (1..=CURVE_SEGMENTS)
+                    .map(|i| {
+                        let t = i as f64 / CURVE_SEGMENTS as f64;
+                        let s = 1.0 - t;
+                        let (a, b, c) = (s * s, 2.0 * s * t, t * t);
+                        (
+                            a * current.0 + b * x1 + c * x,
+                            a * current.1 + b * y1 + c * y,
+                        )
+                    })
+                    .collect(),
+                SimplePathSegment::ClosePath => vec![start],
+            };
+            if let Some(&last) = points.last() {
+                current = last;
+            }
+            // Paths always start with a MoveTo, otherwise the parser fails
+            if let Some(subpath) = subpaths.last_mut() {
+                subpath.extend(points.into_iter().map(|(x, y)| to_screen(x, y)));
+            }
+        }
+        Ok(Path2D::new(subpaths, color))
+    }
+
+    /// Sets the width of the path's lines in pixels, 1 by default.
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Returns the subpaths of the path, each one a polyline in screen coordinates.
+    pub fn subpaths(&self) -> &[Vec<(f64, f64)>] {
+        &self.subpaths
+    }
+
+    /// Returns the smallest box containing every point of the path, as its bottom left and top right corners.
+    ///
+    /// Returns None if the path has no points and Some with the corners otherwise.
+    pub fn bounds(&self) -> Option<((f64, f64), (f64, f64))> {
+        let mut points = self.subpaths.iter().flatten();
+        let &(x, y) = points.next()?;
+        Some(
+            points.fold(((x, y), (x, y)), |((x0, y0), (x1, y1)), &(x, y)| {
+                ((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y)))
+            }),
+        )
+    }
+}
+
+impl Drawable for Path2D {
+    /// Returns the center of the path's bounds, or the origin for an empty path.
+    fn position(&self) -> (f64, f64) {
+        self.bounds().map_or((0.0, 0.0), |((x0, y0), (x1, y1))| {
+            ((x0 + x1) / 2.0, (y0 + y1) / 2.0)
+        })
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        self.bounds().map(|(min, max)| BoundingBox::new(min, max))
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        for subpath in &self.subpaths {
+            let pixels: Vec<(f32, f32)> = subpath
+                .iter()
+                .map(|&(x, y)| map.to_pixels((x as f32, y as f32)))
+                .collect();
+            for segment in pixels.windows(2) {
+                draw_thick_line(canvas, segment[0], segment[1], self.thickness, self.color);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::screen::Screen2D;
+
+    #[test]
+    fn test_draw() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 30, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let path = Path2D::new(vec![vec![(0.0, 0.0), (1.0, 0.0)]], Rgb([255, 0, 0]));
+        let mut img = RgbImage::new(854, 480);
+        path.draw(&mut img, &map).unwrap();
+        let (x, y) = map.to_pixels((0.5, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([255, 0, 0]));
+        assert_eq!(path.position(), (0.5, 0.0));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_from_svg() {
+        let path = Path2D::from_svg(
+            "M 0 0 h 10 q 10 0 10 10 Z m 30 0 c 0 10 10 10 10 0",
+            (0.0, 0.0),
+            1.0,
+            Rgb([255, 255, 255]),
+        )
+        .unwrap();
+        let subpaths = path.subpaths();
+        assert_eq!(subpaths.len(), 2);
+        assert_eq!(subpaths[0][0], (0.0, 0.0));
+        assert_eq!(subpaths[0][1], (10.0, 0.0));
+        assert_eq!(subpaths[0][1 + CURVE_SEGMENTS as usize], (20.0, -10.0));
+        assert_eq!(*subpaths[0].last().unwrap(), (0.0, 0.0));
+        assert_eq!(subpaths[1][0], (30.0, 0.0));
+        assert_eq!(*subpaths[1].last().unwrap(), (40.0, 0.0));
+        let (_, (_, top)) = path.bounds().unwrap();
+        assert_eq!(top, 0.0);
+    }
+}
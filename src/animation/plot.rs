@@ -0,0 +1,254 @@
+//! Module containing a [plotters] drawing backend over canvases, so any chart plotters can make
+//! can be drawn on a frame and animated within a scene.
+#![warn(missing_docs)]
+use std::error::Error;
+
+use imageproc::image::Rgb;
+use plotters::{
+    coord::Shift,
+    prelude::{DrawingArea, DrawingBackend, IntoDrawingArea},
+};
+use plotters_backend::{
+    text_anchor::{HPos, VPos},
+    BackendColor, BackendCoord, BackendTextStyle, DrawingErrorKind,
+};
+
+use super::{
+    canvas::Canvas,
+    clip::Animation,
+    text::{font, text_pixels, text_width},
+};
+use crate::error::MathvisError;
+
+/// A plotters backend drawing on a rectangular region of a canvas, in pixels relative to the region's top left corner.
+///
+/// Pixels outside of the region are clipped, and so are the ones that are more than half transparent,
+/// since canvases can't blend colors.
+pub struct CanvasBackend<'a> {
+    canvas: &'a mut dyn Canvas,
+    offset: (i32, i32),
+    size: (u32, u32),
+}
+
+impl<'a> CanvasBackend<'a> {
+    /// Creates a backend drawing on the whole canvas.
+    pub fn new(canvas: &'a mut dyn Canvas) -> Self {
+        let size = (canvas.width(), canvas.height());
+        CanvasBackend {
+            canvas,
+            offset: (0, 0),
+            size,
+        }
+    }
+
+    /// Creates a backend drawing on the region of the canvas with the specified top left corner and size, in pixels.
+    pub fn region(canvas: &'a mut dyn Canvas, top_left: (i32, i32), size: (u32, u32)) -> Self {
+        CanvasBackend {
+            canvas,
+            offset: top_left,
+            size,
+        }
+    }
+
+    /// Draws a pixel of the region, unless it's outside of it.
+    fn put_pixel(&mut self, (x, y): BackendCoord, color: Rgb<u8>) {
+        if x >= 0 && y >= 0 && (x as u32) < self.size.0 && (y as u32) < self.size.1 {
+            let pixel = ((x + self.offset.0) as f32, (y + self.offset.1) as f32);
+            self.canvas.draw_line(pixel, pixel, color);
+        }
+    }
+}
+
+impl DrawingBackend for CanvasBackend<'_> {
+    type ErrorType = MathvisError;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<MathvisError>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<MathvisError>> {
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<MathvisError>> {
+        if color.alpha >= 0.5 {
+            let (r, g, b) = color.rgb;
+            self.put_pixel(point, Rgb([r, g, b]));
+        }
+        Ok(())
+    }
+
+    /// Draws text with the font of the labels, so nothing is drawn if there's no font available.
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<MathvisError>> {
+        let (Some(font), color) = (font(), style.color()) else {
+            return Ok(());
+        };
+        if color.alpha < 0.5 {
+            return Ok(());
+        }
+        let (r, g, b) = color.rgb;
+        let size = style.size() as f32;
+        let (width, height) = (text_width(font, text, size) as i32, size as i32);
+        let dx = match style.anchor().h_pos {
+            HPos::Left => 0,
+            HPos::Right => -width,
+            HPos::Center => -width / 2,
+        };
+        let dy = match style.anchor().v_pos {
+            VPos::Top => 0,
+            VPos::Center => -height / 2,
+            VPos::Bottom => -height,
+        };
+        let transform = style.transform();
+        text_pixels(font, text, (0.0, 0.0), size, |x, y| {
+            let (x, y) = transform.transform(x + dx, y + dy);
+            self.put_pixel((pos.0 + x, pos.1 + y), Rgb([r, g, b]));
+        });
+        Ok(())
+    }
+
+    fn estimate_text_size<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle,
+    ) -> Result<(u32, u32), DrawingErrorKind<MathvisError>> {
+        match font() {
+            Some(font) => Ok((
+                text_width(font, text, style.size() as f32).ceil() as u32,
+                style.size().ceil() as u32,
+            )),
+            None => {
+                let ((x0, y0), (x1, y1)) = style
+                    .layout_box(text)
+                    .map_err(|err| DrawingErrorKind::FontError(Box::new(err)))?;
+                Ok(((x1 - x0) as u32, (y1 - y0) as u32))
+            }
+        }
+    }
+}
+
+/// Creates an animation of the specified duration drawing a plotters chart on the rectangle of the screen
+/// between `bottom_left` and `top_right`, in screen coordinates.
+///
+/// The draw function is called on every frame with the progress of the animation and the drawing area of the rectangle.
+///
+/// Returns an Err if the corners don't make a rectangle and an Ok with the animation otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::plot::plot;
+/// use plotters::prelude::*;
+///
+/// let growing_parabola = plot(2.0, (-2.0, -2.0), (2.0, 2.0), |progress, area| {
+///     let mut chart = ChartBuilder::on(area)
+///         .x_label_area_size(20)
+///         .y_label_area_size(30)
+///         .build_cartesian_2d(0.0..1.0, 0.0..1.0)?;
+///     chart.configure_mesh().draw()?;
+///     chart.draw_series(LineSeries::new(
+///         (0..=100).map(|i| i as f64 / 100.0 * progress).map(|x| (x, x * x)),
+///         &RED,
+///     ))?;
+///     Ok(())
+/// })
+/// .unwrap();
+/// assert_eq!(growing_parabola.duration(), 2.0);
+/// ```
+pub fn plot<F>(
+    duration: f32,
+    bottom_left: (f64, f64),
+    top_right: (f64, f64),
+    draw: F,
+) -> Result<Animation, MathvisError>
+where
+    F: Fn(f64, &DrawingArea<CanvasBackend<'_>, Shift>) -> Result<(), Box<dyn Error>>
+        + Send
+        + Sync
+        + 'static,
+{
+    if bottom_left.0 >= top_right.0 || bottom_left.1 >= top_right.1 {
+        return Err(MathvisError::InvalidArgument(
+            "The bottom left corner of a plot must be below and to the left of its top right corner."
+                .into(),
+        ));
+    }
+    Ok(Animation::new(duration, move |progress, canvas, _, map| {
+        let (x0, y0) = map.to_pixels((bottom_left.0 as f32, top_right.1 as f32));
+        let (x1, y1) = map.to_pixels((top_right.0 as f32, bottom_left.1 as f32));
+        let size = ((x1 - x0).round() as u32, (y1 - y0).round() as u32);
+        let area = CanvasBackend::region(canvas, (x0.round() as i32, y0.round() as i32), size)
+            .into_drawing_area();
+        draw(progress, &area).map_err(|err| MathvisError::Render(err.to_string()))?;
+        area.present()
+            .map_err(|err| MathvisError::Render(err.to_string()))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+    use plotters::prelude::{ChartBuilder, Color, LineSeries, Rectangle, BLUE};
+
+    use super::*;
+    use crate::api::{screen::Screen2D, util::CoordinateMap};
+
+    #[test]
+    fn test_backend() {
+        let mut img = RgbImage::new(100, 100);
+        let area = CanvasBackend::region(&mut img, (10, 20), (50, 50)).into_drawing_area();
+        area.fill(&BLUE).unwrap();
+        area.draw(&Rectangle::new([(60, 0), (80, 10)], BLUE.filled()))
+            .unwrap();
+        drop(area);
+        assert_eq!(*img.get_pixel(10, 20), Rgb([0, 0, 255]));
+        assert_eq!(*img.get_pixel(59, 69), Rgb([0, 0, 255]));
+        assert_eq!(*img.get_pixel(60, 69), Rgb([0, 0, 0]));
+        assert_eq!(*img.get_pixel(75, 25), Rgb([0, 0, 0]));
+
+        let area = CanvasBackend::new(&mut img).into_drawing_area();
+        let mut chart = ChartBuilder::on(&area)
+            .caption("y = x", ("sans-serif", 12))
+            .x_label_area_size(20)
+            .y_label_area_size(20)
+            .build_cartesian_2d(0.0..1.0, 0.0..1.0)
+            .unwrap();
+        chart.configure_mesh().y_desc("y").draw().unwrap();
+        chart
+            .draw_series(LineSeries::new([(0.0, 0.0), (1.0, 1.0)], &BLUE))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_plot() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let animation = plot(1.0, (0.0, 0.0), (1.0, 1.0), |progress, area| {
+            if progress > 0.5 {
+                area.fill(&BLUE)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        let (x, y) = map.to_pixels((0.5, 0.5));
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(0, &mut img, &screen, &map).unwrap();
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([0, 0, 0]));
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([0, 0, 255]));
+        assert!(plot(1.0, (1.0, 0.0), (0.0, 1.0), |_, _| Ok(())).is_err());
+    }
+}
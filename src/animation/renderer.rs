@@ -0,0 +1,965 @@
+//! Module containing the renderer that turns an animation into numbered frames, saved on disk or drawn in memory.
+#![warn(missing_docs)]
+use std::{
+    fs::{copy, hard_link, remove_file},
+    ops::Range,
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard, TryLockError},
+    time::{Duration, Instant},
+};
+
+use imageproc::image::RgbImage;
+
+use crate::{
+    api::{screen::Screen2D, util::CoordinateMap},
+    error::MathvisError,
+    misc::{frame_buffer::with_frame_buffer, progress::frame_progress_bar, render::render_frames},
+};
+
+#[cfg(feature = "wgpu")]
+use super::gpu::{DrawList, GpuRasterizer};
+use super::{
+    axis::draw_axis, background::remove_background, canvas::Canvas, checkpoint::RenderManifest,
+    clip::Animation, scheduler::LastFrame,
+};
+
+/// Renders the frames of a single animation on a screen.
+///
+/// The renderer owns everything that's shared between animations: the worker pool, reserving and scheduling frame numbers,
+/// the background and axes, partial and resumed renders, the progress bar and saving, so an animation only has to draw its objects.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::canvas::Canvas;
+/// use mathvis::animation::renderer::Renderer;
+/// use mathvis::api::screen::Screen2D;
+///
+/// let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::from("./output"), 30, 854, 480).unwrap();
+/// let renderer = Renderer::new(Arc::new(Mutex::new(screen))).unwrap();
+/// renderer
+///     .render_frames(0..30, |i, canvas| {
+///         canvas.draw_line((0.0, 0.0), (i as f32 * 10.0, 100.0), Rgb([255, 255, 255]));
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+pub struct Renderer {
+    context: Arc<Mutex<Screen2D>>,
+    screen: Arc<Screen2D>,
+    map: CoordinateMap,
+}
+
+impl Renderer {
+    /// Creates a renderer for the next animation on the specified screen, taking a snapshot of its current state.
+    ///
+    /// Returns an Err if the screen can't be locked or has an unsupported resolution and an Ok with the renderer otherwise.
+    pub fn new(context: Arc<Mutex<Screen2D>>) -> Result<Self, MathvisError> {
+        let screen = context
+            .lock()
+            .map_err(|_| MathvisError::Render("Failed to lock context".into()))?
+            .clone();
+        let map = CoordinateMap::new(&screen)
+            .ok_or_else(|| MathvisError::InvalidArgument("Unsupported screen resolution".into()))?;
+        Ok(Renderer {
+            context,
+            screen: Arc::new(screen),
+            map,
+        })
+    }
+
+    /// Returns the snapshot of the screen that every frame is drawn on.
+    ///
+    /// Workers only read the screen, so they share this snapshot instead of locking the context for every frame.
+    pub fn screen(&self) -> Arc<Screen2D> {
+        Arc::clone(&self.screen)
+    }
+
+    /// Returns the map from the screen's coordinates to pixel positions, shared by every frame since the screen doesn't change during an animation.
+    pub fn coordinate_map(&self) -> CoordinateMap {
+        self.map
+    }
+
+    /// Renders every frame of an animation, numbered from the screen's next frame, then schedules them on the screen.
+    ///
+    /// Animations where every frame is the same are held like in [Renderer::hold].
+    ///
+    /// Returns an Err if any frame fails to draw or save and an Ok otherwise.
+    pub fn play(self, animation: &Animation) -> Result<(), MathvisError> {
+        let (screen, map) = (self.screen(), self.map);
+        let frames = animation.frames(screen.fps);
+        let animation = animation.clone();
+        if animation.is_still() {
+            self.hold(frames, move |canvas| {
+                animation.draw_frame(0, canvas, &screen, &map)
+            })
+        } else {
+            self.render_frames(0..frames, move |i, canvas| {
+                animation.draw_frame(i, canvas, &screen, &map)
+            })
+        }
+    }
+
+    /// Returns an iterator drawing the frames of an animation in memory one at a time, as they're asked for,
+    /// with their index counted from the start of the animation.
+    ///
+    /// Frames are drawn like in [Renderer::play], but nothing is saved or scheduled on the screen,
+    /// so they can be sent anywhere, like a window, the network or another encoder.
+    /// On transparent screens the background is drawn like on any other screen.
+    /// Once the screen's cancellation token is cancelled, the iterator returns an Err for the next frame and stops.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{clip::Animation, renderer::Renderer};
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+    /// let renderer = Renderer::new(Arc::new(Mutex::new(screen))).unwrap();
+    /// let animation = Animation::new(1.0, |progress, canvas, _, _| {
+    ///     canvas.draw_line((0.0, 0.0), ((progress * 100.0) as f32, 0.0), Rgb([255, 0, 0]));
+    ///     Ok(())
+    /// });
+    /// let frames = renderer.frames(&animation);
+    /// assert_eq!(frames.len(), 10);
+    /// for frame in frames {
+    ///     let (i, img) = frame.unwrap();
+    ///     assert_eq!(*img.get_pixel(i * 10, 0), Rgb([255, 0, 0]));
+    /// }
+    /// ```
+    pub fn frames(&self, animation: &Animation) -> Frames {
+        Frames {
+            screen: self.screen(),
+            map: self.map,
+            animation: animation.clone(),
+            indices: 0..animation.frames(self.screen.fps),
+        }
+    }
+
+    /// Renders the frames in the range, the first one on the screen's next frame, then schedules them on the screen.
+    ///
+    /// Every frame starts with the background and axes already drawn, and the draw function is called with its index in the range.
+    /// With the `wgpu` feature, frames are rasterized on the GPU whenever one is available.
+    /// Nothing is drawn in a dry run, and frames outside the screen's frame range or already in its checkpoint are kept from a previous render.
+    ///
+    /// Once the screen's cancellation token is cancelled, frames that haven't started drawing are skipped.
+    ///
+    /// Returns an Err if the range is empty, the render is cancelled, any frame fails to draw or save
+    /// or another animation was scheduled on the screen since the renderer was created and an Ok otherwise.
+    pub fn render_frames<F>(self, frames: Range<u32>, draw: F) -> Result<(), MathvisError>
+    where
+        F: Fn(u32, &mut dyn Canvas) -> Result<(), MathvisError> + Send + Sync + 'static,
+    {
+        self.render(frames, false, draw)
+    }
+
+    /// Holds a single image for the specified number of frames, like [Renderer::render_frames] with a draw function that ignores the index.
+    ///
+    /// The image is only drawn once, and every other frame of the hold is a hard link to it (or a copy, if the file system doesn't support links),
+    /// so pauses don't cost a full render per frame.
+    ///
+    /// Returns an Err if the frame fails to draw or any frame fails to save and an Ok otherwise.
+    pub fn hold<F>(self, frames: u32, draw: F) -> Result<(), MathvisError>
+    where
+        F: Fn(&mut dyn Canvas) -> Result<(), MathvisError> + Send + Sync + 'static,
+    {
+        self.render(0..frames, true, move |_, canvas| draw(canvas))
+    }
+
+    /// Repeats the last frame rendered on the screen for the specified number of frames, without drawing anything,
+    /// like a [Renderer::hold] of an image that's already on disk.
+    ///
+    /// Only the frames in the screen's frame range are saved, and nothing is saved in a dry run.
+    /// If the last frame wasn't saved, because it's outside the frame range or in a checkpoint of a render that's gone,
+    /// it's drawn again once with the function that drew it.
+    ///
+    /// Returns an Err if nothing was rendered yet, the last frame isn't on disk and can't be drawn again, the render is cancelled,
+    /// any frame fails to save or another animation was scheduled on the screen in the meantime and an Ok otherwise.
+    pub fn freeze(self, frames: u32) -> Result<(), MathvisError> {
+        let screen = &self.screen;
+        let current_frame = screen.scheduler.next_frame();
+        if current_frame == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "There's no frame to freeze before anything is rendered.".into(),
+            ));
+        }
+        if frames == 0 {
+            return Ok(());
+        }
+        let scheduled = screen.scheduler.reserve(frames)?;
+        check_cancelled(screen)?;
+        if !screen.dry_run {
+            let frame_path = |frame: u32| screen.frame_path(frame);
+            let checkpoint = Checkpoint::load(screen)?;
+            let (start, end) = screen.frame_range;
+            let indices: Vec<u32> = scheduled
+                .clone()
+                .filter(|&frame| frame >= start && end.is_none_or(|end| frame < end))
+                .filter(|&frame| !checkpoint.as_ref().is_some_and(|c| c.contains(frame)))
+                .collect();
+            let mut indices = indices.into_iter();
+            let mut original = frame_path(current_frame - 1);
+            let progress = frame_progress_bar(frames, screen.timeline().len() + 1, screen.quiet);
+            progress.inc((frames as usize - indices.len()) as u64);
+            if indices.len() > 0 && !Path::new(&original).is_file() {
+                let Some(LastFrame(draw)) = screen.scheduler.last_frame() else {
+                    return Err(MathvisError::Render(format!(
+                        "The last frame can't be frozen since it isn't saved at {}.",
+                        original
+                    )));
+                };
+                // The first frame of the freeze is drawn instead, and the others link to it
+                let first = indices.next().unwrap();
+                original = frame_path(first);
+                save_frame(screen, &self.map, &original, |canvas| draw(canvas))?;
+                Checkpoint::record(&checkpoint, first)?;
+                progress.inc(1);
+            }
+            for frame in indices {
+                check_cancelled(screen)?;
+                link_frame(&original, &frame_path(frame))?;
+                Checkpoint::record(&checkpoint, frame)?;
+                progress.inc(1);
+            }
+            Checkpoint::flush(&checkpoint)?;
+            progress.finish();
+        }
+
+        let last_frame = self.screen.scheduler.last_frame().cloned();
+        self.context
+            .lock()
+            .map_err(|_| MathvisError::Render("Failed to lock context".into()))?
+            .scheduler
+            .schedule_drawn(scheduled, last_frame)
+    }
+
+    fn render<F>(self, frames: Range<u32>, hold: bool, draw: F) -> Result<(), MathvisError>
+    where
+        F: Fn(u32, &mut dyn Canvas) -> Result<(), MathvisError> + Send + Sync + 'static,
+    {
+        let (screen, map) = (self.screen, self.map);
+        let scheduled = screen.scheduler.reserve(frames.len() as u32)?;
+        let draw = Arc::new(draw);
+        let last_frame = {
+            let (draw, last) = (Arc::clone(&draw), frames.end - 1);
+            LastFrame(Arc::new(move |canvas| draw(last, canvas)))
+        };
+        // The first frame of the range is the screen's next frame, whatever index it starts at
+        let (current_frame, offset) = (scheduled.start, frames.start);
+        check_cancelled(&screen)?;
+        if !screen.dry_run {
+            let progress = frame_progress_bar(
+                frames.len() as u32,
+                screen.timeline().len() + 1,
+                screen.quiet,
+            );
+
+            let checkpoint = Checkpoint::load(&screen)?;
+            let (start, end) = screen.frame_range;
+            // Frames outside the frame range or already in the manifest are kept from a previous render
+            let (mut indices, skipped): (Vec<u32>, Vec<u32>) = frames.partition(|i| {
+                let frame = current_frame + i - offset;
+                frame >= start
+                    && end.is_none_or(|end| frame < end)
+                    && !checkpoint.as_ref().is_some_and(|c| c.contains(frame))
+            });
+            progress.inc(skipped.len() as u64);
+            let held = if hold && indices.len() > 1 {
+                indices.split_off(1)
+            } else {
+                Vec::new()
+            };
+
+            let jobs = screen.jobs();
+            let frame_path = {
+                let screen = Arc::clone(&screen);
+                move |i: u32| screen.frame_path(current_frame + i - offset)
+            };
+            let path = frame_path.clone();
+            let recorded = checkpoint.clone();
+            let cancelled = Arc::clone(&screen);
+            let render_frame = move |i: u32| {
+                // Frames still waiting for a worker are skipped once the render is cancelled
+                check_cancelled(&screen)?;
+                save_frame(&screen, &map, &path(i), |canvas| draw(i, canvas))?;
+                Checkpoint::record(&recorded, current_frame + i - offset)
+            };
+            let first = indices.first().copied();
+            let rendered = render_frames(indices, jobs, render_frame, &progress);
+            check_cancelled(&cancelled)?;
+            // Failed frames are reported by their number in the whole video, like their files
+            rendered.map_err(|err| match err {
+                MathvisError::Frames(failed) => MathvisError::Frames(
+                    failed
+                        .into_iter()
+                        .map(|(i, err)| (current_frame + i - offset, err))
+                        .collect(),
+                ),
+                err => err,
+            })?;
+            if let Some(first) = first {
+                let original = frame_path(first);
+                for i in held {
+                    check_cancelled(&cancelled)?;
+                    link_frame(&original, &frame_path(i))?;
+                    Checkpoint::record(&checkpoint, current_frame + i - offset)?;
+                    progress.inc(1);
+                }
+            }
+            Checkpoint::flush(&checkpoint)?;
+            progress.finish();
+        }
+
+        self.context
+            .lock()
+            .map_err(|_| MathvisError::Render("Failed to lock context".into()))?
+            .scheduler
+            .schedule_drawn(scheduled, Some(last_frame))
+    }
+}
+
+/// Iterator over the frames of an animation drawn in memory, created by [Renderer::frames].
+pub struct Frames {
+    screen: Arc<Screen2D>,
+    map: CoordinateMap,
+    animation: Animation,
+    indices: Range<u32>,
+}
+
+impl Iterator for Frames {
+    type Item = Result<(u32, RgbImage), MathvisError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.indices.next()?;
+        let (screen, map) = (&self.screen, &self.map);
+        let frame = check_cancelled(screen).and_then(|_| {
+            let mut img = RgbImage::new(screen.width, screen.height);
+            draw_into(&mut img, screen, map, |canvas| {
+                self.animation.draw_frame(i, canvas, screen, map)
+            })?;
+            Ok((i, img))
+        });
+        // There's nothing left to draw once the render is cancelled
+        if frame.is_err() {
+            self.indices.start = self.indices.end;
+        }
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Frames {}
+
+/// Draws a frame into an image, starting with the background and axes, on the GPU with the `wgpu` feature whenever one is available.
+///
+/// Returns an Err if the frame fails to draw or rasterize and an Ok otherwise.
+fn draw_into<F>(
+    img: &mut RgbImage,
+    screen: &Screen2D,
+    map: &CoordinateMap,
+    draw: F,
+) -> Result<(), MathvisError>
+where
+    F: Fn(&mut dyn Canvas) -> Result<(), MathvisError>,
+{
+    let draw_frame = |canvas: &mut dyn Canvas| {
+        draw_base(canvas, screen, map);
+        draw(canvas)
+    };
+    #[cfg(feature = "wgpu")]
+    if let Ok(gpu) = GpuRasterizer::shared() {
+        let mut frame = DrawList::new(screen.width, screen.height);
+        draw_frame(&mut frame)?;
+        return gpu.rasterize(&frame, img);
+    }
+    draw_frame(img)
+}
+
+/// Draws a frame like [draw_into] and saves it, without the background on transparent screens.
+///
+/// Returns an Err if the frame fails to draw or save and an Ok otherwise.
+fn save_frame<F>(
+    screen: &Screen2D,
+    map: &CoordinateMap,
+    path: &str,
+    draw: F,
+) -> Result<(), MathvisError>
+where
+    F: Fn(&mut dyn Canvas) -> Result<(), MathvisError>,
+{
+    with_frame_buffer(screen.width, screen.height, |img| {
+        draw_into(img, screen, map, draw)?;
+        if screen.transparent {
+            screen
+                .frame_format
+                .save(&remove_background(img, screen.background), path)
+        } else {
+            screen.frame_format.save(img, path)
+        }
+    })
+}
+
+/// Saves a frame as a hard link to another one, or as a copy if the file system doesn't support links.
+///
+/// Returns an Err if the frame can't be linked or copied and an Ok otherwise.
+fn link_frame(original: &str, path: &str) -> Result<(), MathvisError> {
+    // Links fail if the frame exists from a previous render
+    let _ = remove_file(path);
+    if hard_link(original, path).is_err() {
+        copy(original, path)?;
+    }
+    Ok(())
+}
+
+/// How many frames can be recorded in a checkpoint before its manifest is saved again.
+const SAVE_EVERY_FRAMES: u32 = 32;
+
+/// How long a frame can stay recorded in a checkpoint before its manifest is saved again.
+const SAVE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The manifest of a screen with a checkpoint, shared by the workers saving its frames.
+///
+/// Frames are saved in batches instead of one by one, so the workers don't all wait on the disk,
+/// and whatever is left is saved when the checkpoint is flushed or dropped, even if the render failed or was cancelled.
+struct Checkpoint {
+    state: Mutex<CheckpointState>,
+    /// Held while the manifest is being saved, so saves never overlap.
+    saving: Mutex<()>,
+    save_directory: String,
+}
+
+/// The manifest of a checkpoint, with the frames recorded in it since it was last saved.
+struct CheckpointState {
+    manifest: RenderManifest,
+    unsaved: u32,
+    saved_at: Instant,
+}
+
+impl Checkpoint {
+    /// Loads the manifest of the scene on the screen, or starts a new one if there's none for it yet.
+    ///
+    /// Returns an Err if the manifest can't be read and an Ok with the checkpoint, or None if the screen has no checkpoint, otherwise.
+    fn load(screen: &Screen2D) -> Result<Option<Arc<Self>>, MathvisError> {
+        let Some(scene_hash) = screen.checkpoint else {
+            return Ok(None);
+        };
+        let manifest = RenderManifest::load(&screen.save_directory, scene_hash)?
+            .unwrap_or_else(|| RenderManifest::new(scene_hash));
+        Ok(Some(Arc::new(Checkpoint {
+            state: Mutex::new(CheckpointState {
+                manifest,
+                unsaved: 0,
+                saved_at: Instant::now(),
+            }),
+            saving: Mutex::new(()),
+            save_directory: screen.save_directory.clone(),
+        })))
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, CheckpointState>, MathvisError> {
+        self.state
+            .lock()
+            .map_err(|_| MathvisError::Render("Failed to lock the render manifest".into()))
+    }
+
+    fn contains(&self, frame: u32) -> bool {
+        self.state()
+            .is_ok_and(|state| state.manifest.contains(frame))
+    }
+
+    /// Records a saved frame in the manifest, if there is one, and saves the manifest once enough frames
+    /// or time have gone by since it was last saved.
+    ///
+    /// Returns an Err if the manifest can't be saved and an Ok otherwise.
+    fn record(checkpoint: &Option<Arc<Self>>, frame: u32) -> Result<(), MathvisError> {
+        let Some(checkpoint) = checkpoint else {
+            return Ok(());
+        };
+        let due = {
+            let mut state = checkpoint.state()?;
+            state.manifest.record(frame..frame + 1);
+            state.unsaved += 1;
+            state.unsaved >= SAVE_EVERY_FRAMES || state.saved_at.elapsed() >= SAVE_INTERVAL
+        };
+        if due {
+            checkpoint.save(false)?;
+        }
+        Ok(())
+    }
+
+    /// Saves the frames recorded in the manifest, if there is one, that weren't saved yet.
+    ///
+    /// Returns an Err if the manifest can't be saved and an Ok otherwise.
+    fn flush(checkpoint: &Option<Arc<Self>>) -> Result<(), MathvisError> {
+        checkpoint
+            .as_ref()
+            .map_or(Ok(()), |checkpoint| checkpoint.save(true))
+    }
+
+    /// Saves the manifest outside the lock on its state, so workers can keep recording frames in the meantime.
+    /// Unless `wait` is set, nothing is saved while another save is in progress, since that one will be followed by the next batch.
+    ///
+    /// Returns an Err if the manifest can't be saved and an Ok otherwise.
+    fn save(&self, wait: bool) -> Result<(), MathvisError> {
+        let locked = if wait {
+            self.saving.lock().map_err(|_| ())
+        } else {
+            match self.saving.try_lock() {
+                Ok(guard) => Ok(guard),
+                Err(TryLockError::WouldBlock) => return Ok(()),
+                Err(TryLockError::Poisoned(_)) => Err(()),
+            }
+        };
+        let _saving = locked
+            .map_err(|_| MathvisError::Render("Failed to lock the render manifest".into()))?;
+        // The manifest is copied after taking the save lock, so an older copy never replaces a newer one
+        let manifest = {
+            let mut state = self.state()?;
+            if state.unsaved == 0 {
+                return Ok(());
+            }
+            state.unsaved = 0;
+            state.saved_at = Instant::now();
+            state.manifest.clone()
+        };
+        manifest.save(&self.save_directory)
+    }
+}
+
+impl Drop for Checkpoint {
+    /// Saves the frames that weren't saved yet, so a render that failed or was cancelled can still be resumed.
+    fn drop(&mut self) {
+        let _ = self.save(true);
+    }
+}
+
+/// Returns an Err if the screen's render was cancelled and an Ok otherwise.
+fn check_cancelled(screen: &Screen2D) -> Result<(), MathvisError> {
+    screen
+        .cancellation
+        .as_ref()
+        .map_or(Ok(()), |token| token.check())
+}
+
+/// Draws the background and axes every frame starts with.
+fn draw_base(canvas: &mut dyn Canvas, screen: &Screen2D, map: &CoordinateMap) {
+    canvas.fill(screen.background);
+    draw_axis(canvas, screen.axis_style, map, screen);
+}
+
+/// Draws a single frame of an animation in memory, counted from its start, with the screen's background and axes under it like every rendered frame.
+///
+/// Unlike a [Renderer], nothing is saved, no worker threads are used and the screen's frames aren't scheduled,
+/// so single frames can be checked pixel by pixel in tests without a file system or ffmpeg.
+///
+/// Returns an Err if the screen has an unsupported resolution or the frame fails to draw and an Ok with the frame otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::{clip::Animation, renderer::render_frame};
+/// use mathvis::api::screen::Screen2D;
+///
+/// let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+/// let red = Rgb([255, 0, 0]);
+/// // A dot moving along the top row of pixels, one pixel a frame
+/// let animation = Animation::new(1.0, move |progress, canvas, _, _| {
+///     let x = (progress * 9.0) as f32;
+///     canvas.draw_line((x, 0.0), (x, 0.0), red);
+///     Ok(())
+/// });
+/// let frame = render_frame(&animation, &screen, 4).unwrap();
+/// assert_eq!(frame.dimensions(), (854, 480));
+/// assert_eq!(*frame.get_pixel(4, 0), red);
+/// assert_ne!(*frame.get_pixel(5, 0), red);
+/// ```
+pub fn render_frame(
+    animation: &Animation,
+    screen: &Screen2D,
+    frame: u32,
+) -> Result<RgbImage, MathvisError> {
+    let map = CoordinateMap::new(screen)
+        .ok_or_else(|| MathvisError::InvalidArgument("Unsupported screen resolution".into()))?;
+    let mut img = RgbImage::new(screen.width, screen.height);
+    draw_into(&mut img, screen, &map, |canvas| {
+        animation.draw_frame(frame, canvas, screen, &map)
+    })?;
+    Ok(img)
+}
+
+/// Draws a single frame of an animation in memory like [render_frame], and returns its pixels as RGBA bytes, row by row.
+///
+/// Frames can be rendered this way where there's no file system or threads, like in the browser on wasm32,
+/// where the bytes can be put in an `ImageData` or a WebCodecs `VideoFrame`.
+/// On transparent screens the background is fully transparent.
+///
+/// Returns an Err if the screen has an unsupported resolution or the frame fails to draw and an Ok with the pixels otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::{renderer::render_frame_to_rgba, show::Show2D, vector::Vector2D};
+/// use mathvis::api::{point::{Point, PointLike}, screen::Screen2D};
+///
+/// let screen = Screen2D::builder().resolution(854, 480).build().unwrap();
+/// let vector = Vector2D::new(1.0, 1.0, Rgb([255, 0, 0]));
+/// let animation = vector.rotate(1.0, 3.14, Point::new(vec![0.0, 0.0]).unwrap());
+/// let pixels = render_frame_to_rgba(&animation, &screen, 15).unwrap();
+/// assert_eq!(pixels.len(), 854 * 480 * 4);
+/// ```
+pub fn render_frame_to_rgba(
+    animation: &Animation,
+    screen: &Screen2D,
+    frame: u32,
+) -> Result<Vec<u8>, MathvisError> {
+    let img = render_frame(animation, screen, frame)?;
+    Ok(if screen.transparent {
+        remove_background(&img, screen.background).into_raw()
+    } else {
+        img.pixels()
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, remove_dir_all};
+
+    use imageproc::image::Rgb;
+
+    use super::*;
+    use crate::animation::cancel::CancellationToken;
+
+    #[test]
+    fn test_render_frames() {
+        let directory = std::env::temp_dir().join("mathvis_renderer_test");
+        create_dir_all(directory.join("tmp")).unwrap();
+        let mut screen = Screen2D::new(
+            (-3.0, 3.0),
+            (-3.0, 3.0),
+            directory.to_str().unwrap().to_string(),
+            30,
+            854,
+            480,
+        )
+        .unwrap();
+        screen.set_quiet(true);
+        screen.set_frame_range(3, None).unwrap();
+        let context = Arc::new(Mutex::new(screen));
+
+        Renderer::new(context.clone())
+            .unwrap()
+            .render_frames(0..5, |i, canvas| {
+                canvas.draw_line((i as f32, 0.0), (i as f32, 0.0), Rgb([255, 0, 0]));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(context.lock().unwrap().frame_count(), 5);
+        assert!(!directory.join("tmp/frame_002.png").exists());
+        let frame = imageproc::image::open(directory.join("tmp/frame_004.png"))
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(*frame.get_pixel(4, 0), Rgb([255, 0, 0]));
+
+        let failed = Renderer::new(context.clone())
+            .unwrap()
+            .render_frames(0..5, |_, _| {
+                Err(MathvisError::OutOfBounds("out of bounds".into()))
+            });
+        let err = failed.unwrap_err();
+        assert!(err.to_string().contains("frame 5: out of bounds"));
+        assert!(matches!(err, MathvisError::Frames(frames) if frames.len() == 5));
+
+        // A range that doesn't start at 0 still starts on the next frame
+        Renderer::new(context.clone())
+            .unwrap()
+            .render_frames(10..13, |i, canvas| {
+                canvas.draw_line((i as f32, 0.0), (i as f32, 0.0), Rgb([255, 0, 0]));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(context.lock().unwrap().frame_count(), 8);
+        let frame = imageproc::image::open(directory.join("tmp/frame_007.png"))
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(*frame.get_pixel(12, 0), Rgb([255, 0, 0]));
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_hold() {
+        let directory = std::env::temp_dir().join("mathvis_renderer_hold_test");
+        create_dir_all(directory.join("tmp")).unwrap();
+        let mut screen = Screen2D::new(
+            (-3.0, 3.0),
+            (-3.0, 3.0),
+            directory.to_str().unwrap().to_string(),
+            30,
+            854,
+            480,
+        )
+        .unwrap();
+        screen.set_quiet(true);
+        let context = Arc::new(Mutex::new(screen));
+
+        let draws = Arc::new(Mutex::new(0));
+        let counter = draws.clone();
+        Renderer::new(context.clone())
+            .unwrap()
+            .hold(4, move |canvas| {
+                *counter.lock().unwrap() += 1;
+                canvas.draw_line((0.0, 0.0), (10.0, 0.0), Rgb([255, 0, 0]));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(*draws.lock().unwrap(), 1);
+        assert_eq!(context.lock().unwrap().frame_count(), 4);
+        let first = std::fs::read(directory.join("tmp/frame_000.png")).unwrap();
+        let last = std::fs::read(directory.join("tmp/frame_003.png")).unwrap();
+        assert_eq!(first, last);
+
+        Renderer::new(context.clone()).unwrap().freeze(3).unwrap();
+        assert_eq!(context.lock().unwrap().frame_count(), 7);
+        assert_eq!(
+            std::fs::read(directory.join("tmp/frame_006.png")).unwrap(),
+            last
+        );
+        remove_dir_all(directory).unwrap();
+
+        let empty = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 30, 854, 480).unwrap();
+        let freeze = Renderer::new(Arc::new(Mutex::new(empty)))
+            .unwrap()
+            .freeze(3);
+        assert!(freeze.is_err());
+    }
+
+    #[test]
+    fn test_freeze_outside_range() {
+        let directory = std::env::temp_dir().join("mathvis_renderer_freeze_test");
+        let _ = remove_dir_all(&directory);
+        create_dir_all(directory.join("tmp")).unwrap();
+        let mut screen = Screen2D::new(
+            (-3.0, 3.0),
+            (-3.0, 3.0),
+            directory.to_str().unwrap().to_string(),
+            30,
+            854,
+            480,
+        )
+        .unwrap();
+        screen.set_quiet(true);
+        screen.set_frame_range(5, None).unwrap();
+        let context = Arc::new(Mutex::new(screen));
+
+        Renderer::new(context.clone())
+            .unwrap()
+            .render_frames(0..3, |i, canvas| {
+                canvas.draw_line((i as f32, 0.0), (i as f32, 0.0), Rgb([255, 0, 0]));
+                Ok(())
+            })
+            .unwrap();
+        assert!(!directory.join("tmp/frame_002.png").exists());
+        // The frozen frame is drawn again, since the range starts in the middle of the freeze
+        Renderer::new(context.clone()).unwrap().freeze(5).unwrap();
+        assert_eq!(context.lock().unwrap().frame_count(), 8);
+        assert!(!directory.join("tmp/frame_004.png").exists());
+        let frame = imageproc::image::open(directory.join("tmp/frame_005.png"))
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(*frame.get_pixel(2, 0), Rgb([255, 0, 0]));
+        assert_eq!(
+            std::fs::read(directory.join("tmp/frame_005.png")).unwrap(),
+            std::fs::read(directory.join("tmp/frame_007.png")).unwrap()
+        );
+        // Freezing again links to the frame the first freeze saved
+        Renderer::new(context.clone()).unwrap().freeze(2).unwrap();
+        assert!(directory.join("tmp/frame_009.png").exists());
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let directory = std::env::temp_dir().join("mathvis_renderer_checkpoint_test");
+        let _ = remove_dir_all(&directory);
+        create_dir_all(directory.join("tmp")).unwrap();
+        let save_directory = directory.to_str().unwrap().to_string();
+        let screen = || {
+            let mut screen = Screen2D::new(
+                (-3.0, 3.0),
+                (-3.0, 3.0),
+                save_directory.clone(),
+                30,
+                854,
+                480,
+            )
+            .unwrap();
+            screen.set_quiet(true);
+            screen.set_checkpoint(Some(7));
+            Arc::new(Mutex::new(screen))
+        };
+
+        // The first render is interrupted after frame 2
+        let failing = |i: u32, _: &mut dyn Canvas| match i {
+            0..=2 => Ok(()),
+            _ => Err(MathvisError::Render("interrupted".into())),
+        };
+        assert!(Renderer::new(screen())
+            .unwrap()
+            .render_frames(0..6, failing)
+            .is_err());
+        let manifest = RenderManifest::load(&save_directory, 7).unwrap().unwrap();
+        assert_eq!(manifest.completed(), &[(0, 3)]);
+
+        // Resuming only draws the missing frames
+        let drawn = Arc::new(Mutex::new(Vec::new()));
+        let log = drawn.clone();
+        let context = screen();
+        Renderer::new(context.clone())
+            .unwrap()
+            .render_frames(0..6, move |i, _| {
+                log.lock().unwrap().push(i);
+                Ok(())
+            })
+            .unwrap();
+        Renderer::new(context.clone()).unwrap().freeze(2).unwrap();
+        let mut drawn = drawn.lock().unwrap().clone();
+        drawn.sort();
+        assert_eq!(drawn, vec![3, 4, 5]);
+        let manifest = RenderManifest::load(&save_directory, 7).unwrap().unwrap();
+        assert_eq!(manifest.completed(), &[(0, 8)]);
+        assert!(directory.join("tmp/frame_007.png").is_file());
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_cancel() {
+        let directory = std::env::temp_dir().join("mathvis_renderer_cancel_test");
+        create_dir_all(directory.join("tmp")).unwrap();
+        let mut screen = Screen2D::new(
+            (-3.0, 3.0),
+            (-3.0, 3.0),
+            directory.to_str().unwrap().to_string(),
+            30,
+            854,
+            480,
+        )
+        .unwrap();
+        screen.set_quiet(true);
+        screen.set_jobs(1).unwrap();
+        let token = CancellationToken::new();
+        screen.set_cancellation(Some(token.clone()));
+        let context = Arc::new(Mutex::new(screen));
+
+        // Cancelled while drawing the third frame, like a Ctrl-C in the middle of a render
+        let drawn = Arc::new(Mutex::new(0));
+        let counter = drawn.clone();
+        let render = Renderer::new(context.clone())
+            .unwrap()
+            .render_frames(0..100, move |i, _| {
+                *counter.lock().unwrap() += 1;
+                if i == 2 {
+                    token.cancel();
+                }
+                Ok(())
+            });
+        assert!(matches!(render, Err(MathvisError::Cancelled)));
+        assert_eq!(*drawn.lock().unwrap(), 3);
+        assert_eq!(context.lock().unwrap().frame_count(), 0);
+        assert!(matches!(
+            Renderer::new(context.clone()).unwrap().hold(3, |_| Ok(())),
+            Err(MathvisError::Cancelled)
+        ));
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut screen =
+            Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let token = CancellationToken::new();
+        screen.set_cancellation(Some(token.clone()));
+        let context = Arc::new(Mutex::new(screen));
+        let renderer = Renderer::new(context.clone()).unwrap();
+        let animation = Animation::new(1.0, move |progress, canvas, _, _| {
+            if progress > 0.5 {
+                token.cancel();
+            }
+            canvas.draw_line((0.0, 0.0), ((progress * 9.0) as f32, 0.0), Rgb([255, 0, 0]));
+            Ok(())
+        });
+
+        // Frames are drawn as they're asked for, so the render stops right after the frame that cancels it
+        let frames: Vec<_> = renderer.frames(&animation).collect();
+        assert_eq!(frames.len(), 7);
+        let (i, img) = frames[4].as_ref().unwrap();
+        assert_eq!(*i, 4);
+        assert_eq!(
+            *img,
+            render_frame(&animation, &renderer.screen(), 4).unwrap()
+        );
+        assert!(matches!(frames[6], Err(MathvisError::Cancelled)));
+        // Nothing is scheduled on the screen
+        assert_eq!(context.lock().unwrap().frame_count(), 0);
+    }
+
+    #[test]
+    fn test_schedule() {
+        let mut screen =
+            Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 30, 854, 480).unwrap();
+        screen.set_dry_run(true);
+        let context = Arc::new(Mutex::new(screen));
+        let draw = |_: u32, _: &mut dyn Canvas| Ok(());
+
+        // Both renderers take their snapshot before either animation is scheduled
+        let first = Renderer::new(context.clone()).unwrap();
+        let stale = Renderer::new(context.clone()).unwrap();
+        first.render_frames(0..5, draw).unwrap();
+        assert!(stale.render_frames(0..5, draw).is_err());
+        assert!(Renderer::new(context.clone())
+            .unwrap()
+            .render_frames(0..0, draw)
+            .is_err());
+        Renderer::new(context.clone())
+            .unwrap()
+            .render_frames(0..3, draw)
+            .unwrap();
+        assert_eq!(context.lock().unwrap().timeline(), &[(0, 5), (5, 3)]);
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let mut screen =
+            Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        screen.set_axis_style(crate::api::screen::AxisStyle {
+            visible: false,
+            ..Default::default()
+        });
+        let animation = Animation::new(1.0, |progress, canvas, _, _| {
+            let x = (progress * 9.0) as f32;
+            canvas.draw_line((x, 0.0), (x, 0.0), Rgb([255, 0, 0]));
+            Ok(())
+        });
+        let img = render_frame(&animation, &screen, 9).unwrap();
+        assert_eq!(*img.get_pixel(9, 0), Rgb([255, 0, 0]));
+        assert_eq!(*img.get_pixel(0, 0), screen.background());
+        let pixels = render_frame_to_rgba(&animation, &screen, 9).unwrap();
+        assert_eq!(pixels.len(), 854 * 480 * 4);
+        assert_eq!(&pixels[9 * 4..10 * 4], &[255, 0, 0, 255]);
+        assert_eq!(pixels[3], 255);
+
+        screen.set_transparent(true);
+        let pixels = render_frame_to_rgba(&animation, &screen, 0).unwrap();
+        assert_eq!(&pixels[..4], &[255, 0, 0, 255]);
+        assert_eq!(pixels[4 * 4 + 3], 0);
+    }
+}
@@ -0,0 +1,189 @@
+//! Module containing replacement transforms, which turn an object into another one of any kind.
+#![warn(missing_docs)]
+use std::sync::Arc;
+
+use imageproc::{image::Rgb, point::Point};
+
+use super::{canvas::Canvas, clip::Animation, drawable::Drawable};
+
+/// An animation turning one object into another, which can be of a different kind, like a vector into its coordinates
+/// or a polygon into a circle.
+///
+/// Both objects slide from the position of the old one to the position of the new one while the old one fades out
+/// and the new one fades in, so the first frame only has the old object and the last one only has the new one.
+/// Canvases can't blend colors, so fading objects are drawn in their colors mixed with the background.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::{geometry::Circle, replace::ReplacementTransform, vector::Vector2D};
+///
+/// let vector = Vector2D::new(1.0, 1.0, Rgb([255, 0, 0]));
+/// let circle = Circle::new((0.0, 0.0), 1.0).unwrap();
+/// let morph = ReplacementTransform::new(Arc::new(vector), Arc::new(circle));
+/// assert_eq!(morph.animate(1.5).duration(), 1.5);
+/// ```
+#[derive(Clone)]
+pub struct ReplacementTransform {
+    from: Arc<dyn Drawable>,
+    to: Arc<dyn Drawable>,
+}
+
+impl ReplacementTransform {
+    /// Creates a transform replacing an object by another one.
+    pub fn new(from: Arc<dyn Drawable>, to: Arc<dyn Drawable>) -> Self {
+        ReplacementTransform { from, to }
+    }
+
+    /// Returns an animation of the specified duration that replaces the old object by the new one.
+    pub fn animate(&self, duration: f32) -> Animation {
+        let (from, to) = (Arc::clone(&self.from), Arc::clone(&self.to));
+        Animation::new(duration, move |progress, canvas, screen, map| {
+            let (start, end) = (from.position(), to.position());
+            let (x0, y0) = map.to_pixels((start.0 as f32, start.1 as f32));
+            let (x1, y1) = map.to_pixels((end.0 as f32, end.1 as f32));
+            let (dx, dy) = ((x1 - x0) as f64, (y1 - y0) as f64);
+            let layers = [
+                (&from, (dx * progress, dy * progress), 1.0 - progress),
+                (
+                    &to,
+                    (dx * (progress - 1.0), dy * (progress - 1.0)),
+                    progress,
+                ),
+            ];
+            // The object that's more visible goes on top
+            let order = if progress < 0.5 { [1, 0] } else { [0, 1] };
+            for (object, offset, opacity) in order.map(|i| layers[i]) {
+                if opacity > 0.0 {
+                    object.draw(
+                        &mut Faded {
+                            inner: canvas,
+                            offset: (offset.0 as f32, offset.1 as f32),
+                            opacity,
+                            background: screen.background,
+                        },
+                        map,
+                    )?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Returns a color mixed with the background, keeping the specified amount of it from 0 for the background to 1 for the color itself.
+///
+/// Canvases can't blend colors, so this is how objects are faded in and out.
+pub(crate) fn fade(color: Rgb<u8>, background: Rgb<u8>, opacity: f64) -> Rgb<u8> {
+    Rgb(std::array::from_fn(|i| {
+        let background = background.0[i] as f64;
+        (background + (color.0[i] as f64 - background) * opacity).round() as u8
+    }))
+}
+
+/// A canvas drawing on another one shifted by an offset in pixels, with every color mixed with the background.
+struct Faded<'a> {
+    inner: &'a mut dyn Canvas,
+    offset: (f32, f32),
+    /// How much of the original colors is kept, from 0 for the background to 1 for the colors themselves.
+    opacity: f64,
+    background: Rgb<u8>,
+}
+
+impl Faded<'_> {
+    fn color(&self, color: Rgb<u8>) -> Rgb<u8> {
+        fade(color, self.background, self.opacity)
+    }
+
+    fn shift(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (x + self.offset.0, y + self.offset.1)
+    }
+}
+
+impl Canvas for Faded<'_> {
+    fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    fn fill(&mut self, color: Rgb<u8>) {
+        let color = self.color(color);
+        self.inner.fill(color);
+    }
+
+    fn draw_line(&mut self, start: (f32, f32), end: (f32, f32), color: Rgb<u8>) {
+        let (start, end, color) = (self.shift(start), self.shift(end), self.color(color));
+        self.inner.draw_line(start, end, color);
+    }
+
+    fn draw_polygon(&mut self, points: &[Point<i32>], color: Rgb<u8>) {
+        let (dx, dy) = (self.offset.0.round() as i32, self.offset.1.round() as i32);
+        let points: Vec<Point<i32>> = points
+            .iter()
+            .map(|point| Point::new(point.x + dx, point.y + dy))
+            .collect();
+        let color = self.color(color);
+        self.inner.draw_polygon(&points, color);
+    }
+
+    fn draw_text(&mut self, text: &str, position: (f32, f32), size: f32, color: Rgb<u8>) {
+        let (position, color) = (self.shift(position), self.color(color));
+        self.inner.draw_text(text, position, size, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::{
+        animation::{geometry::Circle, vector::Vector2D},
+        api::{screen::Screen2D, util::CoordinateMap},
+    };
+
+    #[test]
+    fn test_animate() {
+        let mut screen =
+            Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        screen.set_background(Rgb([0, 0, 0]));
+        let map = CoordinateMap::new(&screen).unwrap();
+        let red = Rgb([255, 0, 0]);
+        let vector = Vector2D::new(2.0, 0.0, red);
+        let circle = Circle::new((-1.0, 0.0), 1.0)
+            .unwrap()
+            .with_color(Rgb([0, 255, 0]));
+        let animation = ReplacementTransform::new(Arc::new(vector), Arc::new(circle)).animate(1.0);
+
+        let pixel = |img: &RgbImage, point: (f32, f32)| {
+            let (x, y) = map.to_pixels(point);
+            *img.get_pixel(x as u32, y as u32)
+        };
+        let frame = |frame| {
+            let mut img = RgbImage::new(854, 480);
+            animation
+                .draw_frame(frame, &mut img, &screen, &map)
+                .unwrap();
+            img
+        };
+        let (first, last) = (frame(0), frame(9));
+        assert_eq!(pixel(&first, (1.0, 0.0)), red);
+        assert!(first.pixels().all(|pixel| pixel.0[1] == 0));
+        // The new object ends exactly where it's drawn on its own
+        let mut alone = RgbImage::new(854, 480);
+        circle.draw(&mut alone, &map).unwrap();
+        assert_eq!(last, alone);
+
+        // Halfway through, both are faded
+        let middle = frame(5);
+        assert!(middle.pixels().any(|pixel| pixel.0[0] > 0));
+        assert!(middle
+            .pixels()
+            .all(|pixel| pixel.0[0] < 255 && pixel.0[1] < 255));
+    }
+}
@@ -0,0 +1,154 @@
+//! Module containing the frame scheduler, which numbers the frames of every animation played on a screen.
+#![warn(missing_docs)]
+use std::{fmt, ops::Range, sync::Arc};
+
+use super::canvas::Canvas;
+use crate::error::MathvisError;
+
+/// Allocates contiguous ranges of frame numbers to the animations played on a screen, in the order they're played.
+///
+/// A renderer reserves the range of an animation before drawing it and schedules it once every frame is saved,
+/// so an animation that was rendered from a stale snapshot of the screen is caught instead of overwriting or skipping frames.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::scheduler::FrameScheduler;
+///
+/// let mut scheduler = FrameScheduler::new();
+/// let first = scheduler.reserve(30).unwrap();
+/// scheduler.schedule(first.clone()).unwrap();
+/// // The same range can't be scheduled twice
+/// assert!(scheduler.schedule(first).is_err());
+/// assert_eq!(scheduler.allocate(15).unwrap(), 30..45);
+/// assert_eq!(scheduler.timeline(), &[(0, 30), (30, 15)]);
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct FrameScheduler {
+    next_frame: u32,
+    timeline: Vec<(u32, u32)>,
+    last_frame: Option<LastFrame>,
+}
+
+type FrameFn = dyn Fn(&mut dyn Canvas) -> Result<(), MathvisError> + Send + Sync;
+
+/// Draws the last frame scheduled again, so it can be frozen even if it wasn't saved, like when it's outside the screen's frame range.
+#[derive(Clone)]
+pub(crate) struct LastFrame(pub(crate) Arc<FrameFn>);
+
+impl fmt::Debug for LastFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LastFrame")
+    }
+}
+
+impl PartialEq for LastFrame {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for LastFrame {}
+
+impl FrameScheduler {
+    /// Creates a scheduler starting on the first frame.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of the next frame to be allocated, which is also the number of frames allocated so far.
+    pub fn next_frame(&self) -> u32 {
+        self.next_frame
+    }
+
+    /// Returns the start frame and frame count of every animation scheduled so far, in order.
+    pub fn timeline(&self) -> &[(u32, u32)] {
+        &self.timeline
+    }
+
+    /// Returns the range of frames the next animation will take if it has the specified number of frames, without scheduling it.
+    ///
+    /// Returns an Err if there are no frames or the range goes past the last possible frame number and an Ok with the range otherwise.
+    pub fn reserve(&self, frames: u32) -> Result<Range<u32>, MathvisError> {
+        if frames == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "An animation must have at least one frame.".into(),
+            ));
+        }
+        let end = self.next_frame.checked_add(frames).ok_or_else(|| {
+            MathvisError::OutOfBounds(format!(
+                "{} more frames don't fit after frame {}.",
+                frames, self.next_frame
+            ))
+        })?;
+        Ok(self.next_frame..end)
+    }
+
+    /// Schedules an animation on a range of frames, which must start right after the last scheduled animation.
+    ///
+    /// Returns an Err if the range is empty or leaves a gap or overlap with the frames already scheduled and an Ok otherwise.
+    pub fn schedule(&mut self, frames: Range<u32>) -> Result<(), MathvisError> {
+        self.schedule_drawn(frames, None)
+    }
+
+    /// Schedules an animation like [FrameScheduler::schedule], remembering how its last frame is drawn.
+    ///
+    /// Returns an Err if the range is empty or leaves a gap or overlap with the frames already scheduled and an Ok otherwise.
+    pub(crate) fn schedule_drawn(
+        &mut self,
+        frames: Range<u32>,
+        last_frame: Option<LastFrame>,
+    ) -> Result<(), MathvisError> {
+        if frames.is_empty() {
+            return Err(MathvisError::InvalidArgument(
+                "An animation must have at least one frame.".into(),
+            ));
+        }
+        if frames.start != self.next_frame {
+            return Err(MathvisError::InvalidArgument(format!(
+                "Frames {} to {} aren't contiguous with the {} frames already scheduled.",
+                frames.start,
+                frames.end - 1,
+                self.next_frame
+            )));
+        }
+        self.timeline.push((frames.start, frames.len() as u32));
+        self.next_frame = frames.end;
+        self.last_frame = last_frame;
+        Ok(())
+    }
+
+    /// Returns how the last frame scheduled is drawn, if it was scheduled with [FrameScheduler::schedule_drawn].
+    pub(crate) fn last_frame(&self) -> Option<&LastFrame> {
+        self.last_frame.as_ref()
+    }
+
+    /// Reserves and schedules the next range of frames with the specified number of frames, like [FrameScheduler::reserve] followed by [FrameScheduler::schedule].
+    ///
+    /// Returns an Err if there are no frames or the range goes past the last possible frame number and an Ok with the range otherwise.
+    pub fn allocate(&mut self, frames: u32) -> Result<Range<u32>, MathvisError> {
+        let range = self.reserve(frames)?;
+        self.schedule(range.clone())?;
+        Ok(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule() {
+        let mut scheduler = FrameScheduler::new();
+        assert!(scheduler.reserve(0).is_err());
+        assert_eq!(scheduler.allocate(10).unwrap(), 0..10);
+        // Gaps, overlaps and empty ranges are rejected and leave the scheduler as it was
+        assert!(scheduler.schedule(11..20).is_err());
+        assert!(scheduler.schedule(5..20).is_err());
+        assert!(scheduler.schedule(10..10).is_err());
+        assert_eq!(scheduler.next_frame(), 10);
+        scheduler.schedule(10..12).unwrap();
+        assert_eq!(scheduler.timeline(), &[(0, 10), (10, 2)]);
+        assert!(scheduler.reserve(u32::MAX).is_err());
+    }
+}
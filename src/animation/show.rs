@@ -1,17 +1,172 @@
 //! Module containing a trait definition for showable objects.
 #![warn(missing_docs)]
 use std::{
-    error::Error,
+    f64::consts::TAU,
     sync::{Arc, Mutex},
 };
 
-use imageproc::image::{Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
 
-use crate::api::{matrix::Matrix, point::Point, screen::Screen2D, util::Number};
+use crate::{
+    api::{
+        angle::Angle,
+        interval::Interval,
+        matrix::Matrix,
+        point::{Point, PointLike},
+        screen::Screen2D,
+        transform::Transform2D,
+        util::{Number, Signed},
+    },
+    error::MathvisError,
+};
+
+use super::{
+    clip::Animation, drawable::Drawable, easing::Easing, geometry::Line, graph::GraphTracker,
+};
+
+/// The direction an object turns in when it's rotated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Turning towards the positive y axis from the positive x axis, the direction of positive angles.
+    #[default]
+    Counterclockwise,
+    /// Turning towards the negative y axis from the positive x axis, the direction of negative angles.
+    Clockwise,
+}
+
+/// A rotation by any angle, including several whole revolutions, with the angular speed shaped by an easing curve.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::{easing::Easing, show::{Direction, Rotation}};
+///
+/// let spin = Rotation::revolutions(2.5)
+///     .with_direction(Direction::Clockwise)
+///     .with_easing(Easing::EaseInOut);
+/// assert_eq!(spin.angle(), -5.0 * std::f64::consts::PI);
+/// // Halfway through, the object has made a turn and a quarter
+/// assert_eq!(spin.angle_at(0.5), -2.5 * std::f64::consts::PI);
+/// let (x, y) = spin.end((1.0, 0.0), (0.0, 0.0));
+/// assert!((x + 1.0).abs() < 1e-12 && y.abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rotation {
+    angle: f64,
+    easing: Easing,
+}
+
+impl Rotation {
+    /// Creates a rotation at a constant angular speed by an angle, counterclockwise if it's positive and clockwise otherwise,
+    /// with plain numbers taken as radians (see [Angle]).
+    /// Angles beyond a whole turn make the object go around several times.
+    pub fn new(angle: impl Into<Angle>) -> Self {
+        Rotation {
+            angle: angle.into().to_radians(),
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Creates a rotation at a constant angular speed by the specified number of whole turns,
+    /// counterclockwise if it's positive and clockwise otherwise.
+    pub fn revolutions(turns: f64) -> Self {
+        Self::new(turns * TAU)
+    }
+
+    /// Sets the direction of the rotation, keeping the size of its angle.
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.angle = match direction {
+            Direction::Counterclockwise => self.angle.abs(),
+            Direction::Clockwise => -self.angle.abs(),
+        };
+        self
+    }
+
+    /// Sets the easing curve of the angular speed, linear by default.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Returns the whole angle of the rotation in radians, negative for clockwise rotations.
+    pub fn angle(&self) -> f64 {
+        self.angle
+    }
+
+    /// Returns the direction of the rotation.
+    pub fn direction(&self) -> Direction {
+        if self.angle < 0.0 {
+            Direction::Clockwise
+        } else {
+            Direction::Counterclockwise
+        }
+    }
+
+    /// Returns the angle turned by the specified progress of the animation, from 0 to 1.
+    pub fn angle_at(&self, progress: f64) -> f64 {
+        self.angle * self.easing.apply(progress)
+    }
+
+    /// Returns where a point is after the specified progress of the rotation around a center, from 0 to 1.
+    pub fn point_at(&self, (x, y): (f64, f64), (cx, cy): (f64, f64), progress: f64) -> (f64, f64) {
+        let (sin, cos) = self.angle_at(progress).sin_cos();
+        (
+            (x - cx) * cos - (y - cy) * sin + cx,
+            (x - cx) * sin + (y - cy) * cos + cy,
+        )
+    }
+
+    /// Returns where a point ends up after the whole rotation around a center.
+    pub fn end(&self, point: (f64, f64), center: (f64, f64)) -> (f64, f64) {
+        self.point_at(point, center, 1.0)
+    }
+}
+
+/// Returns the unit tangent of a parametric function at a parameter, pointing in the direction of travel from the start to the end of its range.
+///
+/// The tangent comes from a central difference, one-sided at the ends of the range so the function is never evaluated outside of it.
+///
+/// Returns None where the function is stationary or not finite and Some with the tangent otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::show::tangent;
+/// use mathvis::api::interval::Interval;
+///
+/// let circle = |t: f64| (t.cos(), t.sin());
+/// let (x, y) = tangent(&circle, 0.0, Interval::closed(0.0, 1.0).unwrap()).unwrap();
+/// assert!(x.abs() < 1e-4 && (y - 1.0).abs() < 1e-4);
+/// // Travelling the other way round flips it
+/// let backwards = |t: f64| circle(-t);
+/// let (_, y) = tangent(&backwards, 0.0, Interval::closed(0.0, 1.0).unwrap()).unwrap();
+/// assert!((y + 1.0).abs() < 1e-4);
+/// assert_eq!(tangent(&|_| (1.0, 1.0), 0.5, Interval::UNIT), None);
+/// ```
+pub fn tangent<F>(parametric: &F, t: f64, range: Interval) -> Option<(f64, f64)>
+where
+    F: Fn(f64) -> (f64, f64) + ?Sized,
+{
+    let h = range.length() * 1e-5;
+    if h == 0.0 || !h.is_finite() {
+        return None;
+    }
+    let (before, after) = (
+        parametric(range.clamp(t - h)),
+        parametric(range.clamp(t + h)),
+    );
+    let (dx, dy) = (after.0 - before.0, after.1 - before.1);
+    let length = dx.hypot(dy);
+    (length > 0.0 && length.is_finite()).then(|| (dx / length, dy / length))
+}
 
 /// Trait representing a showable object.
 /// A Show2D object can be contained by a [Screen2D], and can be shown on the screen and moved around.
-pub trait Show2D<T>
+///
+/// Moving an object doesn't render anything, it returns an [Animation] that's rendered with [Renderer::play](super::renderer::Renderer::play).
+/// Drawing a single frame of the object is done through its [Drawable] implementation.
+pub trait Show2D<T>: Drawable
 where
     T: Number,
 {
@@ -22,40 +177,252 @@ where
     /// Adds a context to the object. Necessary for it to be shown on screen.
     ///
     /// Returns an Err if the object cannot be contained by the [Screen2D] and an Ok otherwise.
-    fn add_context(&mut self, context: Arc<Mutex<Screen2D>>) -> Result<(), Box<dyn Error>>;
-    /// Draws an object on the specified image with the specified color.
+    fn add_context(&mut self, context: Arc<Mutex<Screen2D>>) -> Result<(), MathvisError>;
+    /// Returns an animation that moves an object along a parametric function with one parameter, for the specified duration,
+    /// as the parameter goes steadily through its range from start to end.
+    ///
+    /// Frames where the object leaves the screen fail to draw.
+    fn move_along_parametric<F>(&self, duration: f32, parametric: F, range: Interval) -> Animation
+    where
+        F: Fn(f64) -> (f64, f64) + Send + Sync + 'static;
+    /// Returns an animation that moves an object along a parametric function with one parameter, for the specified duration,
+    /// turning it so it stays tangent to the path and points in the direction of travel, like an arrow.
     ///
-    /// Returns an Err if the object does not have a context and an Ok otherwise.
-    fn draw(&self, color: Rgb<u8>, img: &mut RgbImage) -> Result<(), Box<dyn Error>>;
-    /// Moves an object along a parametric function with one parameter, for the specified duration.    
+    /// The direction comes from the numerical derivative of the function, see [tangent].
+    /// Where the path is stationary, the object keeps the orientation it has at rest.
     ///
-    /// Returns an Err if the object does not have a context or if anything goes wrong with the animation process and an Ok otherwise.
-    fn move_along_parametric<F>(
+    /// Frames where the object leaves the screen fail to draw.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{show::Show2D, vector::Vector2D};
+    /// use mathvis::api::interval::Interval;
+    ///
+    /// // An arrow half a unit long going around the unit circle
+    /// let arrow = Vector2D::new(0.5, 0.0, Rgb([255, 0, 0]));
+    /// let turn = Interval::closed(0.0, std::f64::consts::TAU).unwrap();
+    /// let orbit = arrow.follow_path(4.0, |t| (t.cos(), t.sin()), turn);
+    /// assert_eq!(orbit.duration(), 4.0);
+    /// ```
+    fn follow_path<F>(&self, duration: f32, parametric: F, range: Interval) -> Animation
+    where
+        F: Fn(f64) -> (f64, f64) + Send + Sync + 'static;
+    /// Returns an animation that moves an object along the graph y = f(x) of a function as x goes steadily from `x_start` to `x_end`, for a specified duration.
+    ///
+    /// Frames where the object leaves the screen fail to draw.
+    fn move_along_graph<F>(&self, duration: f32, f: F, x_start: f64, x_end: f64) -> Animation
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        self.move_along_graph_with(duration, f, x_start, x_end, GraphTracker::new())
+    }
+    /// Returns an animation that moves an object along the graph y = f(x) of a function as x goes steadily from `x_start` to `x_end`, for a specified duration,
+    /// with the guides of a [GraphTracker] drawn under it.
+    ///
+    /// Frames where the object leaves the screen fail to draw.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{graph::GraphTracker, show::Show2D, vector::Vector2D};
+    ///
+    /// let point = Vector2D::new(0.0, 0.0, Rgb([255, 0, 0]));
+    /// let tracker = GraphTracker::new().with_drop_line(true).with_labels(true);
+    /// let animation = point.move_along_graph_with(3.0, |x| x * x, -1.5, 1.5, tracker);
+    /// assert_eq!(animation.duration(), 3.0);
+    /// ```
+    fn move_along_graph_with<F>(
         &self,
         duration: f32,
-        parametric: F,
-        t_min: f64,
-        t_max: f64,
-    ) -> Result<(), Box<dyn Error>>
+        f: F,
+        x_start: f64,
+        x_end: f64,
+        tracker: GraphTracker,
+    ) -> Animation
     where
-        F: Fn(f64) -> (f64, f64) + Send + Sync + 'static;
-    /// Rotates an object for a specified duration, by a specified angle, on a specified center of rotation.
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let graph = Arc::clone(&f);
+        tracker
+            .animate(move |x| f(x), x_start, x_end, duration)
+            .with(self.move_along_parametric(
+                duration,
+                move |t| {
+                    let x = x_start + t * (x_end - x_start);
+                    (x, graph(x))
+                },
+                Interval::UNIT,
+            ))
+    }
+    /// Returns an animation that rotates an object for a specified duration, by a specified angle on a specified center of rotation,
+    /// with plain numbers taken as radians (see [Angle]).
+    ///
+    /// Positive angles turn counterclockwise and negative ones clockwise, and angles beyond a whole turn go around several times,
+    /// always at a constant angular speed. The object ends where [Rotation::end] puts it.
+    fn rotate(&self, duration: f32, angle: impl Into<Angle>, center: Point<f64>) -> Animation {
+        self.rotate_with(duration, Rotation::new(angle), center)
+    }
+    /// Returns an animation that rotates an object for a specified duration on a specified center of rotation,
+    /// with the angle, direction and angular easing of a [Rotation].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{easing::Easing, show::{Rotation, Show2D}, vector::Vector2D};
+    /// use mathvis::api::point::{Point, PointLike};
+    ///
+    /// let vector = Vector2D::new(1.0, 0.0, Rgb([255, 0, 0]));
+    /// let spin = Rotation::revolutions(3.0).with_easing(Easing::EaseOut);
+    /// let animation = vector.rotate_with(2.0, spin, Point::new(vec![0.0, 0.0]).unwrap());
+    /// assert_eq!(animation.duration(), 2.0);
+    /// ```
+    fn rotate_with(&self, duration: f32, rotation: Rotation, center: Point<f64>) -> Animation {
+        let (point, center) = (
+            (self.x().to_f64(), self.y().to_f64()),
+            (center.values()[0], center.values()[1]),
+        );
+        self.move_along_parametric(
+            duration,
+            move |t| rotation.point_at(point, center, t),
+            Interval::UNIT,
+        )
+    }
+    /// Returns an animation that scales an object uniformly by a factor around a fixed point, for a specified duration.
+    ///
+    /// The factor grows steadily from 1, so a negative one flips the object through the point halfway through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{show::Show2D, vector::Vector2D};
+    /// use mathvis::api::point::{Point, PointLike};
+    ///
+    /// let vector = Vector2D::new(2.0, 1.0, Rgb([255, 0, 0]));
+    /// // Doubles the distance from (1, 1), ending at (3, 1)
+    /// let animation = vector.scale(1.5, 2.0, Point::new(vec![1.0, 1.0]).unwrap());
+    /// assert_eq!(animation.duration(), 1.5);
+    /// ```
+    fn scale(&self, duration: f32, factor: f64, about: Point<f64>) -> Animation {
+        self.scale_axes(duration, (factor, factor), about)
+    }
+    /// Returns an animation that scales an object by separate factors along the x and y axes around a fixed point, for a specified duration.
+    ///
+    /// Unlike [Show2D::multiply_by_matrix] with a diagonal matrix, the point that stays still doesn't have to be the origin.
+    fn scale_axes(&self, duration: f32, (fx, fy): (f64, f64), about: Point<f64>) -> Animation {
+        let (x, y) = (self.x().to_f64(), self.y().to_f64());
+        let (ax, ay) = (about.values()[0], about.values()[1]);
+        self.move_along_parametric(
+            duration,
+            move |t| {
+                (
+                    ax + (x - ax) * (1.0 + (fx - 1.0) * t),
+                    ay + (y - ay) * (1.0 + (fy - 1.0) * t),
+                )
+            },
+            Interval::UNIT,
+        )
+    }
+    /// Returns an animation that mirrors an object across a line, for a specified duration.
+    ///
+    /// The object moves straight towards its mirror image, crossing the line halfway through,
+    /// so it ends where [Line::reflect] puts it.
     ///
-    /// Returns an Err if the object does not have a context or if anything goes wrong with the animation process and an Ok otherwise.
-    fn rotate(&self, duration: f32, angle: f64, center: Point<f64>) -> Result<(), Box<dyn Error>>;
-    /// Moves an object to a specified point, for a specified duration.
+    /// # Examples
     ///
-    /// Returns an Err if the object does not have a context or if anything goes wrong with the animation process and an Ok otherwise.
-    fn move_to(&self, duration: f32, point: Point<f64>) -> Result<(), Box<dyn Error>>;
-    /// Moves an object to the result of its transformation by multiplication by the specified matrix, for a specified duration.
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{geometry::Line, show::Show2D, vector::Vector2D};
     ///
-    /// Returns an Err if the object does not have a context or if anything goes wrong with the animation process and an Ok otherwise.
-    fn multiply_by_matrix(&self, duration: f32, matrix: Matrix<T>) -> Result<(), Box<dyn Error>>;
-    /// Moves an object to the result of its transformation by multiplication by the specified matrix, for a specified duration,
+    /// let vector = Vector2D::new(2.0, 1.0, Rgb([255, 0, 0]));
+    /// // Flips the vector across the y axis, ending at (-2, 1)
+    /// let axis = Line::through((0.0, 0.0), (0.0, 1.0)).unwrap();
+    /// assert_eq!(vector.reflect(1.0, &axis).duration(), 1.0);
+    /// ```
+    fn reflect(&self, duration: f32, line: &Line) -> Animation {
+        let (x, y) = (self.x().to_f64(), self.y().to_f64());
+        let (mx, my) = line.reflect((x, y));
+        self.move_along_parametric(
+            duration,
+            move |t| (x + (mx - x) * t, y + (my - y) * t),
+            Interval::UNIT,
+        )
+    }
+    /// Returns an animation that moves an object to where an affine transform takes it, for a specified duration.
+    ///
+    /// The object goes through the transforms [Transform2D::lerp] blends from the identity,
+    /// so it turns along the rotation of the transform instead of cutting straight across like [Show2D::multiply_by_matrix].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{show::Show2D, vector::Vector2D};
+    /// use mathvis::api::{angle::Angle, transform::Transform2D};
+    ///
+    /// let vector = Vector2D::new(1.0, 0.0, Rgb([255, 0, 0]));
+    /// // Turns a quarter turn, doubling in length, and then moves up by one, ending at (0, 3)
+    /// let transform = Transform2D::translation(0.0, 1.0)
+    ///     .compose(&Transform2D::rotation(Angle::degrees(90.0)))
+    ///     .compose(&Transform2D::scaling(2.0, 2.0));
+    /// assert_eq!(vector.transform(2.0, transform).duration(), 2.0);
+    /// ```
+    fn transform(&self, duration: f32, transform: Transform2D) -> Animation {
+        let point = (self.x().to_f64(), self.y().to_f64());
+        self.move_along_parametric(
+            duration,
+            move |t| Transform2D::IDENTITY.lerp(&transform, t).apply(point),
+            Interval::UNIT,
+        )
+    }
+    /// Returns an animation that moves an object to a specified point, for a specified duration.
+    fn move_to(&self, duration: f32, point: Point<f64>) -> Animation;
+    /// Returns an animation that keeps an object still for a specified duration.
+    fn wait(&self, duration: f32) -> Animation;
+    /// Returns an animation that moves an object to the result of its transformation by multiplication by the specified matrix, for a specified duration.
+    ///
+    /// Returns an Err if the object can't be multiplied by the matrix and an Ok with the animation otherwise.
+    fn multiply_by_matrix(
+        &self,
+        duration: f32,
+        matrix: Matrix<T>,
+    ) -> Result<Animation, MathvisError>;
+    /// Returns an animation that shears an object for a specified duration, moving it from (x, y) to (x + kx·y, ky·x + y)
+    /// by multiplying it by [Matrix::shear_matrix_2d].
+    ///
+    /// Returns an Err if the object can't be multiplied by the matrix and an Ok with the animation otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imageproc::image::Rgb;
+    /// use mathvis::animation::{show::Show2D, vector::Vector2D};
+    ///
+    /// let vector = Vector2D::new(0.0, 1.0, Rgb([255, 0, 0]));
+    /// // Slants the vector to (1.5, 1)
+    /// assert!(vector.shear(2.0, 1.5, 0.0).is_ok());
+    /// ```
+    fn shear(&self, duration: f32, kx: T, ky: T) -> Result<Animation, MathvisError> {
+        self.multiply_by_matrix(duration, Matrix::shear_matrix_2d(kx, ky))
+    }
+    /// Returns an animation that moves an object to the result of its transformation by multiplication by the specified matrix, for a specified duration,
     /// by separating its rotation and scaling.
     ///
+    /// Only available for signed number types, since the decomposition needs negative values.
+    ///
     /// Warning: Currently not working and should not be used.
     ///
-    /// Returns an Err if the object does not have a context or if anything goes wrong with the animation process and an Ok otherwise.
-    fn rotate_then_scale(&self, duration: f32, matrix: Matrix<T>) -> Result<(), Box<dyn Error>>;
+    /// Returns an Err if the matrix can't be decomposed or the object can't be multiplied by it and an Ok with the animation otherwise.
+    fn rotate_then_scale(
+        &self,
+        duration: f32,
+        matrix: Matrix<T>,
+    ) -> Result<Animation, MathvisError>
+    where
+        T: Signed;
 }
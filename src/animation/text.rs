@@ -0,0 +1,95 @@
+//! Module containing the font that labels are drawn with.
+//!
+//! The font is read from the file in the `MATHVIS_FONT` environment variable, or from one of the usual system font paths,
+//! and labels aren't drawn if there's no font available.
+use std::{env, fs::read, sync::OnceLock};
+
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+use imageproc::image::Rgb;
+
+use super::canvas::Canvas;
+
+/// Font files looked for when `MATHVIS_FONT` isn't set.
+const FONT_PATHS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+/// Returns the font shared by every label, or None if there's no usable font.
+///
+/// The font is only loaded the first time this is called.
+pub(crate) fn font() -> Option<&'static FontVec> {
+    static FONT: OnceLock<Option<FontVec>> = OnceLock::new();
+    FONT.get_or_init(|| {
+        let paths = env::var("MATHVIS_FONT")
+            .map(|path| vec![path])
+            .unwrap_or_else(|_| FONT_PATHS.iter().map(|path| path.to_string()).collect());
+        paths
+            .iter()
+            .find_map(|path| FontVec::try_from_vec(read(path).ok()?).ok())
+    })
+    .as_ref()
+}
+
+/// Calls `plot` with every pixel that's at least half covered by the text, laid out in a single line
+/// with its top left corner at the specified position and the specified height in pixels.
+pub(crate) fn text_pixels<F>(
+    font: &impl Font,
+    text: &str,
+    position: (f32, f32),
+    size: f32,
+    mut plot: F,
+) where
+    F: FnMut(i32, i32),
+{
+    let font = font.as_scaled(PxScale::from(size));
+    let mut x = position.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            x += font.kern(previous, id);
+        }
+        let glyph = id.with_scale_and_position(font.scale(), point(x, position.1 + font.ascent()));
+        x += font.h_advance(id);
+        previous = Some(id);
+        if let Some(outline) = font.outline_glyph(glyph) {
+            let bounds = outline.px_bounds();
+            outline.draw(|gx, gy, coverage| {
+                if coverage >= 0.5 {
+                    plot(
+                        bounds.min.x as i32 + gx as i32,
+                        bounds.min.y as i32 + gy as i32,
+                    );
+                }
+            });
+        }
+    }
+}
+
+/// Returns the width in pixels of the text laid out in a single line with the specified height in pixels.
+pub(crate) fn text_width(font: &impl Font, text: &str, size: f32) -> f32 {
+    let font = font.as_scaled(PxScale::from(size));
+    let mut previous = None;
+    text.chars().fold(0.0, |width, c| {
+        let id = font.glyph_id(c);
+        let kern = previous.map_or(0.0, |previous| font.kern(previous, id));
+        previous = Some(id);
+        width + kern + font.h_advance(id)
+    })
+}
+
+/// Draws a single line of text horizontally centered on a point, with its top at the point's height.
+pub(crate) fn draw_centered(
+    canvas: &mut dyn Canvas,
+    text: &str,
+    (x, y): (f32, f32),
+    size: f32,
+    color: Rgb<u8>,
+) {
+    let width = font().map_or(0.0, |font| text_width(font, text, size));
+    canvas.draw_text(text, (x - width / 2.0, y), size, color);
+}
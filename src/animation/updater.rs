@@ -0,0 +1,138 @@
+//! Module containing updaters, which recompute an object from the time on every frame instead of following a planned animation.
+#![warn(missing_docs)]
+use std::sync::Arc;
+
+use crate::{
+    api::{bounds::BoundingBox, util::CoordinateMap},
+    error::MathvisError,
+};
+
+use super::{canvas::Canvas, clip::Animation, drawable::Drawable};
+
+/// Function updating an object in place from the time in seconds since the start of its animation.
+type UpdateFn<D> = dyn Fn(&mut D, f64) + Send + Sync;
+
+/// Trait for objects that can have updaters attached, implemented by every drawable that can be cloned.
+pub trait Updatable: Drawable + Clone + 'static {
+    /// Attaches an updater to the object, which is called on every frame with a copy of the object
+    /// and the time in seconds since the start of the animation, and changes the copy that's drawn.
+    ///
+    /// Frames are rendered in parallel and in any order, so updaters must only depend on the time,
+    /// which is how they follow other objects: through the same functions of time that move those objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::{geometry::Line, updater::Updatable};
+    ///
+    /// // Two points going around circles, and a line always through both of them
+    /// let p = |t: f64| (t.cos(), t.sin());
+    /// let q = |t: f64| (2.0 * (-t).cos(), 2.0 * (-t).sin() + 0.5);
+    /// let line = Line::through(p(0.0), q(0.0)).unwrap().add_updater(move |line, t| {
+    ///     if let Ok(through) = Line::through(p(t), q(t)) {
+    ///         *line = through;
+    ///     }
+    /// });
+    /// assert_eq!(line.animate(3.0).duration(), 3.0);
+    /// ```
+    fn add_updater<F>(self, updater: F) -> Updated<Self>
+    where
+        F: Fn(&mut Self, f64) + Send + Sync + 'static,
+    {
+        Updated {
+            object: self,
+            updaters: Vec::new(),
+        }
+        .add_updater(updater)
+    }
+}
+
+impl<D: Drawable + Clone + 'static> Updatable for D {}
+
+/// An object with updaters attached, see [Updatable::add_updater].
+///
+/// Drawing it on its own draws the object as it is at the start, before any time has passed.
+#[derive(Clone)]
+pub struct Updated<D> {
+    object: D,
+    updaters: Vec<Arc<UpdateFn<D>>>,
+}
+
+impl<D: Drawable + Clone + 'static> Updated<D> {
+    /// Attaches another updater, which is called after the ones attached before it.
+    pub fn add_updater<F>(mut self, updater: F) -> Self
+    where
+        F: Fn(&mut D, f64) + Send + Sync + 'static,
+    {
+        self.updaters.push(Arc::new(updater));
+        self
+    }
+
+    /// Returns the object before any updater is called.
+    pub fn object(&self) -> &D {
+        &self.object
+    }
+
+    /// Returns a copy of the object with every updater called at the specified time in seconds, in the order they were attached.
+    pub fn at(&self, time: f64) -> D {
+        let mut object = self.object.clone();
+        for updater in &self.updaters {
+            updater(&mut object, time);
+        }
+        object
+    }
+
+    /// Returns an animation of the specified duration drawing the object as its updaters change it on every frame.
+    pub fn animate(&self, duration: f32) -> Animation {
+        let updated = self.clone();
+        Animation::new(duration, move |progress, canvas, _, map| {
+            updated.at(progress * duration as f64).draw(canvas, map)
+        })
+    }
+}
+
+impl<D: Drawable + Clone + 'static> Drawable for Updated<D> {
+    fn position(&self) -> (f64, f64) {
+        self.at(0.0).position()
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        self.at(0.0).bounding_box()
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        self.at(0.0).draw(canvas, map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::{Rgb, RgbImage};
+
+    use super::*;
+    use crate::{animation::vector::Vector2D, api::screen::Screen2D};
+
+    #[test]
+    fn test_updaters() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let red = Rgb([255, 0, 0]);
+        // Grows along the x axis, then the second updater flips it
+        let vector = Vector2D::new(0.0, 1.0, red)
+            .add_updater(move |vector, t| *vector = Vector2D::new(2.0 * t, 0.0, red))
+            .add_updater(move |vector, _| *vector = Vector2D::new(-vector.position().0, 0.0, red));
+        assert_eq!(vector.at(0.5).position(), (-1.0, 0.0));
+        assert_eq!(vector.object().position(), (0.0, 1.0));
+        assert_eq!(vector.position(), (0.0, 0.0));
+
+        let mut img = RgbImage::new(854, 480);
+        vector
+            .animate(1.0)
+            .draw_frame(9, &mut img, &screen, &map)
+            .unwrap();
+        let (x, y) = map.to_pixels((-1.8, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), red);
+        let (x, y) = map.to_pixels((1.0, 0.0));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([0, 0, 0]));
+    }
+}
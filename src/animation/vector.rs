@@ -1,30 +1,31 @@
 //! Module containing a 2D wrapper for a vector that implements animation operations
 #![warn(missing_docs)]
 use std::{
-    error::Error,
     f64::consts::PI,
     ops::{Add, Mul},
     sync::{Arc, Mutex},
 };
 
-use imageproc::{
-    drawing::{draw_line_segment_mut, draw_polygon_mut},
-    image::{Rgb, RgbImage},
-    point::Point,
-};
+use imageproc::{image::Rgb, point::Point};
 
-use crate::{
-    api::{
-        matrix::Matrix,
-        point::{self, PointLike},
-        screen::{Screen2D, ScreenLike},
-        util::{interpolate, Number, Quality},
-        vector::Vector,
-    },
-    misc::thread_pool::ThreadPool,
+use crate::api::{
+    bounds::BoundingBox,
+    interval::Interval,
+    matrix::Matrix,
+    point::{self, PointLike},
+    screen::{Screen2D, ScreenLike},
+    util::{CoordinateMap, Number, Signed},
+    vector::Vector,
+};
+use crate::error::MathvisError;
+
+use super::{
+    canvas::{clip_line, Canvas},
+    clip::Animation,
+    drawable::Drawable,
+    show::{tangent, Show2D},
+    text::{font, text_width},
 };
-
-use super::{axis::draw_axis, background::fill_background, show::Show2D};
 
 /// A 2D vector implementation.
 /// Implements some of the operations of [Vector] and contains one inside for access to more general operations.
@@ -45,7 +46,104 @@ pub struct Vector2D<T: Number> {
     x: T,
     y: T,
     context: Option<Arc<Mutex<Screen2D>>>,
+    style: VectorStyle,
+}
+
+/// How a vector is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VectorStyle {
     color: Rgb<u8>,
+    /// Width of the vector's line in pixels.
+    thickness: f32,
+    /// Text drawn next to the vector's tip.
+    label: Option<String>,
+}
+
+impl VectorStyle {
+    fn new(color: Rgb<u8>) -> Self {
+        VectorStyle {
+            color,
+            thickness: 1.0,
+            label: None,
+        }
+    }
+}
+
+/// Builder for a [Vector2D], for setting any of its style options without a long constructor.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::vector::Vector2D;
+/// use imageproc::image::Rgb;
+///
+/// let vector = Vector2D::builder()
+///     .components(1.0, 2.0)
+///     .color(Rgb([255, 0, 0]))
+///     .thickness(3.0)
+///     .label("v")
+///     .build()
+///     .unwrap();
+/// assert_eq!(vector.thickness(), 3.0);
+/// assert_eq!(vector.label(), Some("v"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Vector2DBuilder<T: Number> {
+    components: Option<(T, T)>,
+    context: Option<Arc<Mutex<Screen2D>>>,
+    style: VectorStyle,
+}
+
+impl<T: Number> Vector2DBuilder<T> {
+    /// Sets the x and y components of the vector, which are required.
+    pub fn components(mut self, x: T, y: T) -> Self {
+        self.components = Some((x, y));
+        self
+    }
+
+    /// Sets the color of the vector, white by default.
+    pub fn color(mut self, color: Rgb<u8>) -> Self {
+        self.style.color = color;
+        self
+    }
+
+    /// Sets the width of the vector's line in pixels, 1 by default.
+    pub fn thickness(mut self, thickness: f32) -> Self {
+        self.style.thickness = thickness;
+        self
+    }
+
+    /// Sets a label that's drawn next to the vector's tip.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.style.label = Some(label.into());
+        self
+    }
+
+    /// Sets the context the vector is shown on, like [Show2D::add_context].
+    pub fn context(mut self, context: Arc<Mutex<Screen2D>>) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Creates the vector.
+    ///
+    /// Returns an Err if the components weren't set, the thickness isn't positive or the vector doesn't fit in its context and an Ok with the vector otherwise.
+    pub fn build(self) -> Result<Vector2D<T>, MathvisError> {
+        let (x, y) = self.components.ok_or_else(|| {
+            MathvisError::InvalidArgument("The vector's components must be set.".into())
+        })?;
+        if self.style.thickness.is_nan() || self.style.thickness <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The vector's thickness must be positive.".into(),
+            ));
+        }
+        let mut vector = Vector2D::new(x, y, self.style.color);
+        vector.style = self.style;
+        if let Some(context) = self.context {
+            vector.add_context(context)?;
+        }
+        Ok(vector)
+    }
 }
 
 impl<T: Number> Show2D<T> for Vector2D<T> {
@@ -57,168 +155,66 @@ impl<T: Number> Show2D<T> for Vector2D<T> {
         return self.y;
     }
 
-    fn draw(&self, color: Rgb<u8>, img: &mut RgbImage) -> Result<(), Box<dyn Error>> {
-        if let Some(context) = self.clone().context {
-            draw_vector(&self.vector, img, color, context.clone());
-            return Ok(());
-        }
-        Err(
-            "This object does not have an associated context. Try using the add_context method."
-                .into(),
-        )
-    }
-
-    fn add_context(&mut self, context: Arc<Mutex<Screen2D>>) -> Result<(), Box<dyn Error>> {
-        let context_lock = context.lock().unwrap();
+    fn add_context(&mut self, context: Arc<Mutex<Screen2D>>) -> Result<(), MathvisError> {
+        let context_lock = context
+            .lock()
+            .map_err(|_| MathvisError::Render("Failed to lock context".into()))?;
         if !context_lock.can_contain(self) {
-            return Err("Vector cannot be contained within the context's bounds.".into());
+            return Err(MathvisError::OutOfBounds(
+                "Vector cannot be contained within the context's bounds.".into(),
+            ));
         }
-        self.context = Some(context.clone());
+        drop(context_lock);
+        self.context = Some(context);
         Ok(())
     }
 
-    fn move_along_parametric<F>(
-        &self,
-        duration: f32,
-        parametric: F,
-        t_min: f64,
-        t_max: f64,
-    ) -> Result<(), Box<dyn Error>>
+    fn move_along_parametric<F>(&self, duration: f32, parametric: F, range: Interval) -> Animation
     where
         F: (Fn(f64) -> (f64, f64)) + Send + Sync + 'static,
     {
-        let context = self
-            .context
-            .clone()
-            .ok_or("This object does not have an associated context")?;
+        let style = self.style.clone();
+        Animation::new(duration, move |progress, canvas, screen, map| {
+            let (x, y) = parametric(range.lerp(progress));
+            let v = Vector2D::new(x, y, style.color);
+            if !screen.can_contain(&v) {
+                return Err(MathvisError::OutOfBounds(
+                    "Vector cannot be contained within the context's bounds.".into(),
+                ));
+            }
+            draw_vector(&v.vector, canvas, &style, map)
+        })
+    }
 
-        let (current_frame, save_directory, fps, img_width, img_height) = {
-            let context_lock = context.lock().map_err(|_| "Failed to lock context")?;
-            (
-                context_lock.current_frame,
-                context_lock.save_directory.clone(),
-                context_lock.fps,
-                context_lock.width,
-                context_lock.height,
-            )
+    /// Moves the tail of the vector along the path instead of its tip, keeping its length.
+    fn follow_path<F>(&self, duration: f32, parametric: F, range: Interval) -> Animation
+    where
+        F: Fn(f64) -> (f64, f64) + Send + Sync + 'static,
+    {
+        let style = self.style.clone();
+        let (x, y) = (self.x.to_f64(), self.y.to_f64());
+        let length = x.hypot(y);
+        let rest = if length > 0.0 {
+            (x / length, y / length)
+        } else {
+            (1.0, 0.0)
         };
-
-        let frames: u32 = (duration * fps as f32) as u32;
-        let completed_frames = Arc::new(Mutex::new(0));
-        let shared_parametric = Arc::new(parametric);
-        let color = Arc::new(self.color);
-        let error_flag = Arc::new(Mutex::new(false));
-
-        {
-            let thread_pool = ThreadPool::new(fps as usize).unwrap();
-
-            for i in 0..frames {
-                let completed_frames = Arc::clone(&completed_frames);
-                let error_flag = Arc::clone(&error_flag);
-                let context = Arc::clone(&context);
-                let save_directory = save_directory.clone();
-                let shared_parametric = Arc::clone(&shared_parametric);
-                let color = Arc::clone(&color);
-                let white = Rgb([255, 255, 255]);
-
-                let frame_generator = move || {
-                    let mut img = RgbImage::new(img_width, img_height);
-
-                    let t = t_min + (i as f64 / (frames - 1) as f64) * (t_max - t_min);
-                    let (x, y) = shared_parametric(t);
-
-                    let context_lock = match context.lock() {
-                        Ok(lock) => lock,
-                        Err(_) => {
-                            let mut error = error_flag.lock().unwrap();
-                            *error = true;
-                            return;
-                        }
-                    };
-
-                    fill_background(&mut img);
-                    draw_axis(&mut img, white, Arc::new(context_lock.clone()));
-
-                    drop(context_lock);
-
-                    let mut v = Vector2D::new(x, y, *color);
-                    if let Err(_) = v.add_context(context.clone()) {
-                        let mut error = error_flag.lock().unwrap();
-                        *error = true;
-                        return;
-                    }
-
-                    if let Err(_) = v.draw(v.color, &mut img) {
-                        let mut error = error_flag.lock().unwrap();
-                        *error = true;
-                        return;
-                    }
-                    match img.save(format!(
-                        "{}/tmp/frame_{:03}.png",
-                        save_directory,
-                        current_frame + i,
-                    )) {
-                        Ok(_) => {
-                            let mut completed = completed_frames.lock().unwrap();
-                            *completed += 1;
-                            println!("Generated frame {}", current_frame + i);
-                        }
-                        Err(_) => {
-                            let mut error = error_flag.lock().unwrap();
-                            *error = true;
-                        }
-                    }
-                };
-
-                thread_pool.execute(frame_generator);
+        Animation::new(duration, move |progress, canvas, screen, map| {
+            let t = range.lerp(progress);
+            let tail = parametric(t);
+            let (dx, dy) = tangent(&parametric, t, range).unwrap_or(rest);
+            let head = (tail.0 + length * dx, tail.1 + length * dy);
+            if !screen.bounds().contains(&BoundingBox::new(tail, head)) {
+                return Err(MathvisError::OutOfBounds(
+                    "Vector cannot be contained within the context's bounds.".into(),
+                ));
             }
-        }
-
-        let completed = *completed_frames.lock().unwrap();
-        let has_error = *error_flag.lock().unwrap();
-
-        if has_error || completed != frames as usize {
-            return Err(format!(
-                "Frame generation failed. Completed: {}, Total: {}",
-                completed, frames
-            )
-            .into());
-        }
-
-        {
-            let mut context_lock = context.lock().unwrap();
-            context_lock
-                .change_current_frame(current_frame + frames)
-                .unwrap();
-        }
-
-        Ok(())
+            draw_arrow(tail, head, canvas, &style, map);
+            Ok(())
+        })
     }
 
-    fn rotate(
-        &self,
-        duration: f32,
-        angle: f64,
-        center: point::Point<f64>,
-    ) -> Result<(), Box<dyn Error>> {
-        let (x, y) = (Arc::new(self.x), Arc::new(self.y));
-        self.move_along_parametric(
-            duration,
-            move |t| {
-                (
-                    (Arc::clone(&x).to_f64() - center.values()[0]) * t.cos()
-                        - (Arc::clone(&y).to_f64() - center.values()[1]) * t.sin()
-                        + center.values()[0],
-                    (Arc::clone(&x).to_f64() - center.values()[0]) * angle.sin()
-                        + (Arc::clone(&y).to_f64() - center.values()[1]) * t.cos()
-                        + center.values()[1],
-                )
-            },
-            0.0,
-            angle,
-        )
-    }
-    fn move_to(&self, duration: f32, point: point::Point<f64>) -> Result<(), Box<dyn Error>> {
+    fn move_to(&self, duration: f32, point: point::Point<f64>) -> Animation {
         let (x, y) = (Arc::new(self.x), Arc::new(self.y));
         self.move_along_parametric(
             duration,
@@ -228,27 +224,55 @@ impl<T: Number> Show2D<T> for Vector2D<T> {
                     (1.0 - t) * y.to_f64() + t * point.values()[1],
                 )
             },
-            0.0,
-            1.0,
+            Interval::UNIT,
         )
     }
 
-    fn multiply_by_matrix(&self, duration: f32, matrix: Matrix<T>) -> Result<(), Box<dyn Error>> {
-        let vector = (matrix * self.clone()).unwrap();
-        self.move_to(
+    fn wait(&self, duration: f32) -> Animation {
+        let vector = Vector2D {
+            context: None,
+            ..self.clone()
+        };
+        Animation::still(duration, move |canvas, _, map| vector.draw(canvas, map))
+    }
+
+    fn multiply_by_matrix(
+        &self,
+        duration: f32,
+        matrix: Matrix<T>,
+    ) -> Result<Animation, MathvisError> {
+        let vector = (&matrix * self)?;
+        Ok(self.move_to(
             duration,
-            point::Point::new(vec![vector.x.to_f64(), vector.y.to_f64()]).unwrap(),
-        )?;
-        Ok(())
+            point::Point::new(vec![vector.x.to_f64(), vector.y.to_f64()])?,
+        ))
     }
 
-    fn rotate_then_scale(&self, duration: f32, matrix: Matrix<T>) -> Result<(), Box<dyn Error>> {
+    fn rotate_then_scale(&self, duration: f32, matrix: Matrix<T>) -> Result<Animation, MathvisError>
+    where
+        T: Signed,
+    {
+        // A = QS = (QSQ^T)Q, so rotating by Q first leaves scaling by QSQ^T
         let (q, s) = matrix.polar_decomposition_2d()?;
-        println!("{:?} {:?}", q.clone(), s.clone());
-        self.multiply_by_matrix(duration / 2.0, q.clone())?;
-        let mid = (q * self.clone())?;
-        mid.multiply_by_matrix(duration / 2.0, s)?;
-        Ok(())
+        let scale = (&(&q * &s)? * &q.transpose())?;
+        let mid = (&q * self)?;
+        let rotation = self.multiply_by_matrix(duration / 2.0, q)?;
+        Ok(rotation.then(mid.multiply_by_matrix(duration / 2.0, scale)?))
+    }
+}
+
+impl<T: Number> Drawable for Vector2D<T> {
+    fn position(&self) -> (f64, f64) {
+        (self.x.to_f64(), self.y.to_f64())
+    }
+
+    /// Returns the box of the arrow, from the origin to the tip.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        Some(BoundingBox::new((0.0, 0.0), self.position()))
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, map: &CoordinateMap) -> Result<(), MathvisError> {
+        draw_vector(&self.vector, canvas, &self.style, map)
     }
 }
 
@@ -272,10 +296,54 @@ impl<T: Number> Vector2D<T> {
             x,
             y,
             context: None,
-            color,
+            style: VectorStyle::new(color),
+        }
+    }
+
+    /// Returns a builder for a vector with any of the style options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::vector::Vector2D;
+    ///
+    /// let vector = Vector2D::builder().components(3, 4).build().unwrap();
+    /// assert_eq!(vector.thickness(), 1.0);
+    /// assert!(Vector2D::<i32>::builder().build().is_err());
+    /// ```
+    pub fn builder() -> Vector2DBuilder<T> {
+        Vector2DBuilder {
+            components: None,
+            context: None,
+            style: VectorStyle::new(Rgb([255, 255, 255])),
         }
     }
 
+    /// Returns the color the vector is drawn with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::vector::Vector2D;
+    /// use imageproc::image::Rgb;
+    ///
+    /// let vector = Vector2D::new(1, 1, Rgb([255, 0, 255]));
+    /// assert_eq!(vector.color(), Rgb([255, 0, 255]));
+    /// ```
+    pub fn color(&self) -> Rgb<u8> {
+        self.style.color
+    }
+
+    /// Returns the width of the vector's line in pixels.
+    pub fn thickness(&self) -> f32 {
+        self.style.thickness
+    }
+
+    /// Returns the label drawn next to the vector's tip, if it has one.
+    pub fn label(&self) -> Option<&str> {
+        self.style.label.as_deref()
+    }
+
     /// Wrapper for the dot product between two vectors.
     /// Unlike its more general counterpart, since both vectors always have the same dimension, it returns the value itself.
     ///
@@ -311,7 +379,7 @@ impl<T: Number> Vector2D<T> {
             x: T::zero(),
             y: T::zero(),
             context: None,
-            color,
+            style: VectorStyle::new(color),
         }
     }
 }
@@ -320,23 +388,26 @@ impl<T> Add for Vector2D<T>
 where
     T: Number,
 {
-    type Output = Result<Vector2D<T>, Box<dyn Error>>;
+    type Output = Result<Vector2D<T>, MathvisError>;
     fn add(self, rhs: Vector2D<T>) -> Self::Output {
-        let vector = (self.vector + rhs.vector).unwrap();
-        if self
-            .context
-            .as_ref()
-            .zip(rhs.context.as_ref())
-            .map_or(false, |(a, b)| *a.lock().unwrap() != *b.lock().unwrap())
-        {
-            return Err("LHS and RHS don't share the same context.".into());
+        let vector = (self.vector + rhs.vector)?;
+        if let Some((a, b)) = self.context.as_ref().zip(rhs.context.as_ref()) {
+            let lock_failed = |_| MathvisError::Render("Failed to lock context".into());
+            // Both sides may share the same context, which can't be locked twice
+            if !Arc::ptr_eq(a, b)
+                && *a.lock().map_err(lock_failed)? != *b.lock().map_err(lock_failed)?
+            {
+                return Err(MathvisError::InvalidArgument(
+                    "LHS and RHS don't share the same context.".into(),
+                ));
+            }
         }
         Ok(Self {
             vector,
             x: self.x + rhs.x,
             y: self.y + rhs.y,
             context: self.context,
-            color: self.color,
+            style: self.style,
         })
     }
 }
@@ -355,54 +426,154 @@ where
             x: scalar * self.x,
             y: scalar * self.y,
             context: self.context,
-            color: self.color,
+            style: self.style,
         };
     }
 }
 
 impl<T: Number> Mul<Vector2D<T>> for Matrix<T> {
-    type Output = Result<Vector2D<T>, Box<dyn Error>>;
+    type Output = Result<Vector2D<T>, MathvisError>;
 
     fn mul(self, rhs: Vector2D<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T: Number> Mul<&Vector2D<T>> for &Matrix<T> {
+    type Output = Result<Vector2D<T>, MathvisError>;
+
+    fn mul(self, rhs: &Vector2D<T>) -> Self::Output {
         if self.get_dimensions() != (2, 2) {
-            return Err("Matrix must be 2x2 to apply to a 2d vector.".into());
+            return Err(MathvisError::DimensionMismatch(
+                "Matrix must be 2x2 to apply to a 2d vector.".into(),
+            ));
         }
-        let vals = self.values;
         let (x, y) = (
-            vals[0][0] * rhs.x + vals[0][1] * rhs.y,
-            vals[1][0] * rhs.x + vals[1][1] * rhs.y,
+            self.at(0, 0) * rhs.x + self.at(0, 1) * rhs.y,
+            self.at(1, 0) * rhs.x + self.at(1, 1) * rhs.y,
         );
         Ok(Vector2D {
             vector: Vector::new(vec![x, y]).unwrap(),
             x,
             y,
-            context: rhs.context,
-            color: rhs.color,
+            context: rhs.context.clone(),
+            style: rhs.style.clone(),
         })
     }
 }
 
-pub(crate) fn draw_vector<T>(
+/// Draws a 2D vector from the origin, with its tip and label.
+///
+/// Returns an Err if the vector isn't 2D and an Ok otherwise.
+pub(crate) fn draw_vector<T, C>(
     vector: &Vector<T>,
-    img: &mut RgbImage,
-    color: Rgb<u8>,
-    screen: Arc<Mutex<Screen2D>>,
-) where
+    img: &mut C,
+    style: &VectorStyle,
+    map: &CoordinateMap,
+) -> Result<(), MathvisError>
+where
     T: Number,
+    C: Canvas + ?Sized,
+{
+    draw_arrow((0.0, 0.0), components(vector)?, img, style, map);
+    Ok(())
+}
+
+/// Draws an arrow from a tail to a head, in screen coordinates, with the label of the style next to its head.
+fn draw_arrow<C>(
+    tail: (f64, f64),
+    head: (f64, f64),
+    img: &mut C,
+    style: &VectorStyle,
+    map: &CoordinateMap,
+) where
+    C: Canvas + ?Sized,
 {
-    let screen = screen.lock().unwrap();
-    let quality = Quality::new(img.width(), img.height()).unwrap();
-    let center = screen.get_center_pixels();
-    let (x, y) = interpolate(
-        quality,
-        Arc::new(screen.clone()),
-        (
-            vector.values()[0].to_f64() as f32,
-            vector.values()[1].to_f64() as f32,
-        ),
+    let (x, y) = map.to_pixels((head.0 as f32, head.1 as f32));
+    draw_thick_line(
+        img,
+        map.to_pixels((tail.0 as f32, tail.1 as f32)),
+        (x, y),
+        style.thickness,
+        style.color,
     );
-    draw_line_segment_mut(img, center, (x, y), color);
-    draw_vector_tip(vector, img, color, Arc::new(screen.clone()), quality);
+    draw_vector_tip(tail, head, img, style.color, map);
+    if let Some(label) = &style.label {
+        let size = map.quality().resolution().values()[1] * 0.04;
+        // The label goes past the tip, on the outside of the arrow's box, so it never covers the arrow
+        let (cx, cy) = BoundingBox::new(tail, head).center();
+        let width = font().map_or(0.0, |font| text_width(font, label, size));
+        let left = match head.0 >= cx {
+            true => x + size / 2.0,
+            false => x - size / 2.0 - width,
+        };
+        let top = match head.1 >= cy {
+            true => y - size * 1.5,
+            false => y + size / 2.0,
+        };
+        // Labels of tips far away from the screen are past what glyphs can be rasterized at
+        let reaches = left + width >= 0.0
+            && left <= img.width() as f32
+            && top + size >= 0.0
+            && top <= img.height() as f32;
+        if reaches {
+            img.draw_text(label, (left, top), size, style.color);
+        }
+    }
+}
+
+/// Returns the x and y components of a vector.
+///
+/// Returns an Err if the vector isn't 2D and an Ok with the components otherwise.
+fn components<T: Number>(vector: &Vector<T>) -> Result<(f64, f64), MathvisError> {
+    match vector.values().as_slice() {
+        [x, y] => Ok((x.to_f64(), y.to_f64())),
+        values => Err(MathvisError::DimensionMismatch(format!(
+            "Only 2D vectors can be drawn, but this one has {} dimensions.",
+            values.len()
+        ))),
+    }
+}
+
+/// Draws a line as a rectangle of the specified width, or as a regular line if it's one pixel wide or less.
+pub(crate) fn draw_thick_line<C: Canvas + ?Sized>(
+    img: &mut C,
+    start: (f32, f32),
+    end: (f32, f32),
+    thickness: f32,
+    color: Rgb<u8>,
+) {
+    // Only the part near the canvas is filled, so the corners of long lines stay within pixel range
+    let Some((start, end)) = clip_line(img, start, end, thickness) else {
+        return;
+    };
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if thickness <= 1.0 || length == 0.0 {
+        img.draw_line(start, end, color);
+        return;
+    }
+    let (nx, ny) = (
+        -dy / length * thickness / 2.0,
+        dx / length * thickness / 2.0,
+    );
+    let mut corners = vec![
+        Point::new((start.0 + nx) as i32, (start.1 + ny) as i32),
+        Point::new((end.0 + nx) as i32, (end.1 + ny) as i32),
+        Point::new((end.0 - nx) as i32, (end.1 - ny) as i32),
+        Point::new((start.0 - nx) as i32, (start.1 - ny) as i32),
+    ];
+    // Thin or short lines can round several corners onto the same pixel, and a polygon
+    // whose first and last corners are the same is rejected by imageproc
+    corners.dedup();
+    while corners.len() > 1 && corners.first() == corners.last() {
+        corners.pop();
+    }
+    if corners.len() < 3 {
+        img.draw_line(start, end, color);
+    } else {
+        img.draw_polygon(&corners, color);
+    }
 }
 
 fn rotate(point: &Point<f64>, angle: f64, rotation_center: &Point<f64>) -> Point<f64> {
@@ -415,49 +586,192 @@ fn rotate(point: &Point<f64>, angle: f64, rotation_center: &Point<f64>) -> Point
     Point::new(new_x, new_y)
 }
 
-fn draw_vector_tip<T>(
-    vector: &Vector<T>,
-    img: &mut RgbImage,
+fn draw_vector_tip<C>(
+    (x0, y0): (f64, f64),
+    (a, b): (f64, f64),
+    img: &mut C,
     color: Rgb<u8>,
-    screen: Arc<Screen2D>,
-    quality: Quality,
+    map: &CoordinateMap,
 ) where
-    T: Number,
+    C: Canvas + ?Sized,
 {
-    let (a, b) = (vector.values()[0].to_f64(), vector.values()[1].to_f64());
-    let (p1, p2): (point::Point<f64>, point::Point<f64>) = (
-        rotate(
-            &Point::new(a, b),
-            2.0 * PI / 3.0,
-            &Point::new(0.95 * a, 0.95 * b),
-        )
-        .into(),
-        rotate(
-            &Point::new(a, b),
-            4.0 * PI / 3.0,
-            &Point::new(0.95 * a, 0.95 * b),
-        )
-        .into(),
-    );
-    let (x, y) = interpolate(quality.clone(), screen.clone(), (a as f32, b as f32));
-    let (x1, y1) = interpolate(
-        quality.clone(),
-        screen.clone(),
-        (p1.values()[0] as f32, p1.values()[1] as f32),
-    );
-    let (x2, y2) = interpolate(
-        quality,
-        screen,
-        (p2.values()[0] as f32, p2.values()[1] as f32),
+    let base = Point::new(x0 + 0.95 * (a - x0), y0 + 0.95 * (b - y0));
+    let (p1, p2) = (
+        rotate(&Point::new(a, b), 2.0 * PI / 3.0, &base),
+        rotate(&Point::new(a, b), 4.0 * PI / 3.0, &base),
     );
+    let (x, y) = map.to_pixels((a as f32, b as f32));
+    let (x1, y1) = map.to_pixels((p1.x as f32, p1.y as f32));
+    let (x2, y2) = map.to_pixels((p2.x as f32, p2.y as f32));
+
+    // Tips far away from the canvas are skipped, before their corners are past the pixels imageproc can fill between
+    let (width, height) = (img.width() as f32, img.height() as f32);
+    let near =
+        |(x, y): (f32, f32)| x >= -width && x <= 2.0 * width && y >= -height && y <= 2.0 * height;
+    if ![(x, y), (x1, y1), (x2, y2)].into_iter().all(near) {
+        return;
+    }
+    let tip = [
+        Point::new(x as i32, y as i32),
+        Point::new(x1 as i32, y1 as i32),
+        Point::new(x2 as i32, y2 as i32),
+    ];
+    // Tips of vectors that are almost zero collapse into a single pixel, which isn't a polygon
+    if tip[0] != tip[2] {
+        img.draw_polygon(&tip, color);
+    }
+}
 
-    draw_polygon_mut(
-        img,
-        &[
-            Point::new(x as i32, y as i32),
-            Point::new(x1 as i32, y1 as i32),
-            Point::new(x2 as i32, y2 as i32),
-        ],
-        color,
-    );
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::animation::{
+        easing::Easing,
+        geometry::Line,
+        show::{Direction, Rotation},
+    };
+
+    #[test]
+    fn test_draw_thick_line() {
+        let mut img = RgbImage::new(20, 20);
+        let white = Rgb([255, 255, 255]);
+        // The corners on either side of these diagonals round onto the same pixels
+        for thickness in [1.1, 1.2, 1.41] {
+            draw_thick_line(&mut img, (5.5, 5.5), (10.5, 10.5), thickness, white);
+            draw_thick_line(&mut img, (5.5, 5.5), (5.5, 5.5), thickness, white);
+        }
+        draw_thick_line(&mut img, (2.0, 2.0), (15.0, 2.0), 4.0, white);
+        assert_eq!(*img.get_pixel(10, 2), white);
+    }
+
+    #[test]
+    fn test_draw_errors() {
+        let screen = Screen2D::builder().build().unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let mut img = RgbImage::new(1920, 1080);
+        let style = VectorStyle::new(Rgb([255, 255, 255]));
+        let flat = Vector::new(vec![1.0, 2.0, 3.0]).unwrap();
+        assert!(matches!(
+            draw_vector(&flat, &mut img, &style, &map),
+            Err(MathvisError::DimensionMismatch(_))
+        ));
+
+        let context = Arc::new(Mutex::new(screen));
+        let poisoned = context.clone();
+        let _ = thread::spawn(move || {
+            let _lock = poisoned.lock().unwrap();
+            panic!("poisons the context");
+        })
+        .join();
+        let mut vector = Vector2D::new(1.0, 1.0, Rgb([255, 255, 255]));
+        assert!(matches!(
+            vector.add_context(context),
+            Err(MathvisError::Render(_))
+        ));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let context = Arc::new(Mutex::new(screen.clone()));
+        let mut vector = Vector2D::new(2.0, 0.0, Rgb([255, 0, 0]));
+        vector.add_context(context).unwrap();
+        let center = point::Point::new(vec![0.0, 0.0]).unwrap();
+        for (rotation, tip) in [
+            // Whole turns end where they started, whatever the direction and easing
+            (
+                Rotation::revolutions(3.0)
+                    .with_direction(Direction::Clockwise)
+                    .with_easing(Easing::EaseInOut),
+                (1.8, 0.0),
+            ),
+            (Rotation::new(-PI / 2.0), (0.0, -1.8)),
+            (Rotation::new(5.0 * PI / 2.0), (0.0, 1.8)),
+        ] {
+            let mut img = RgbImage::new(854, 480);
+            vector
+                .rotate_with(1.0, rotation, center.clone())
+                .draw_frame(9, &mut img, &screen, &map)
+                .unwrap();
+            let (x, y) = map.to_pixels(tip);
+            assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([255, 0, 0]));
+        }
+    }
+
+    #[test]
+    fn test_scale() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let mut vector = Vector2D::new(1.0, 1.0, Rgb([255, 0, 0]));
+        vector
+            .add_context(Arc::new(Mutex::new(screen.clone())))
+            .unwrap();
+        let about = point::Point::new(vec![2.0, 1.0]).unwrap();
+        for (animation, tip) in [
+            // (1, 1) is 1 to the left of (2, 1), so it ends at (0, 1), (3, 1) and (1.5, 1)
+            (vector.scale(1.0, 2.0, about.clone()), (0.0, 0.9)),
+            (vector.scale(1.0, -1.0, about.clone()), (2.7, 0.9)),
+            (vector.scale_axes(1.0, (0.5, 2.0), about), (1.35, 0.9)),
+        ] {
+            let mut img = RgbImage::new(854, 480);
+            animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+            let (x, y) = map.to_pixels(tip);
+            // Diagonal lines are rasterized a pixel away from the exact one at most
+            assert!((-1..=1)
+                .any(|dx| *img.get_pixel((x as i32 + dx) as u32, y as u32) == Rgb([255, 0, 0])));
+        }
+    }
+
+    #[test]
+    fn test_reflect() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let mut vector = Vector2D::new(2.0, 0.0, Rgb([255, 0, 0]));
+        vector
+            .add_context(Arc::new(Mutex::new(screen.clone())))
+            .unwrap();
+        // Across the diagonal, the vector moves up to (0, 2), so just past halfway through it's at (8/9, 10/9)
+        let diagonal = Line::through((0.0, 0.0), (1.0, 1.0)).unwrap();
+        let animation = vector.reflect(1.0, &diagonal);
+        for (frame, tip) in [(9, (0.0, 1.8)), (5, (0.8 * 8.0 / 9.0, 0.8 * 10.0 / 9.0))] {
+            let mut img = RgbImage::new(854, 480);
+            animation
+                .draw_frame(frame, &mut img, &screen, &map)
+                .unwrap();
+            let (x, y) = map.to_pixels(tip);
+            assert!((-1..=1)
+                .any(|dx| *img.get_pixel((x as i32 + dx) as u32, y as u32) == Rgb([255, 0, 0])));
+        }
+    }
+
+    #[test]
+    fn test_follow_path() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let arrow = Vector2D::new(0.0, 1.0, Rgb([255, 0, 0]));
+        // Clockwise around the unit circle, starting from (0, 1) heading right
+        let orbit = arrow.follow_path(
+            1.0,
+            |t| (t.sin(), t.cos()),
+            Interval::closed(0.0, PI).unwrap(),
+        );
+        for (frame, along) in [(0, (0.5, 1.0)), (9, (-0.5, -1.0))] {
+            let mut img = RgbImage::new(854, 480);
+            orbit.draw_frame(frame, &mut img, &screen, &map).unwrap();
+            let (x, y) = map.to_pixels(along);
+            assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([255, 0, 0]));
+            // Nothing is drawn from the origin
+            let (x, y) = map.to_pixels((0.0, 0.5));
+            assert_eq!(*img.get_pixel(x as u32, y as u32), Rgb([0, 0, 0]));
+        }
+        assert!(arrow
+            .follow_path(1.0, |t| (t, 0.0), Interval::closed(0.0, 5.0).unwrap())
+            .draw_frame(9, &mut RgbImage::new(854, 480), &screen, &map)
+            .is_err());
+    }
 }
@@ -0,0 +1,249 @@
+//! Module containing random walks, animated step by step as a discrete picture of Brownian motion.
+#![warn(missing_docs)]
+use std::{collections::BTreeMap, f64::consts::TAU};
+
+use imageproc::image::Rgb;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    chart::BarChart, clip::Animation, drawable::Drawable, field::PointCloud, path::Path2D,
+};
+use crate::{api::random::sample, error::MathvisError};
+
+/// Default color of the walkers and their trails.
+const WALK_COLOR: Rgb<u8> = Rgb([255, 200, 0]);
+/// Default color of the histogram of the walkers' positions.
+const HISTOGRAM_COLOR: Rgb<u8> = Rgb([110, 110, 110]);
+
+/// A set of walkers starting at the same point, each taking steps of a fixed length in uniformly random directions.
+///
+/// The walks are generated from a seed, so the same walk is animated every time it's rendered. The seed is drawn from
+/// the [random](crate::api::random) generator when the walk is created, so it follows [set_seed](crate::api::random::set_seed)
+/// unless it's set explicitly.
+///
+/// With many walkers, a histogram of their x coordinates can be drawn on the x axis. It's normalized to a density,
+/// so it spreads into a bell curve of variance `steps * step_size² / 2` that a [DistributionPlot](super::distribution::DistributionPlot)
+/// can be drawn over.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::walk::RandomWalk2D;
+///
+/// let walk = RandomWalk2D::new(200, 100).with_seed(3).with_histogram(0.25).unwrap();
+/// let paths = walk.paths();
+/// assert_eq!(paths.len(), 200);
+/// assert_eq!(paths[0].len(), 101);
+/// assert_eq!(paths, walk.paths());
+/// assert_eq!(walk.animate(5.0).unwrap().duration(), 5.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomWalk2D {
+    walkers: usize,
+    steps: usize,
+    start: (f64, f64),
+    step_size: f64,
+    seed: u64,
+    trail: bool,
+    bin_width: Option<f64>,
+    walk_color: Rgb<u8>,
+    histogram_color: Rgb<u8>,
+}
+
+impl RandomWalk2D {
+    /// Creates the specified number of walkers taking the specified number of steps of 0.1 units from the origin,
+    /// with their trails drawn and no histogram.
+    pub fn new(walkers: usize, steps: usize) -> Self {
+        RandomWalk2D {
+            walkers,
+            steps,
+            start: (0.0, 0.0),
+            step_size: 0.1,
+            seed: sample::<u64>(1)[0],
+            trail: true,
+            bin_width: None,
+            walk_color: WALK_COLOR,
+            histogram_color: HISTOGRAM_COLOR,
+        }
+    }
+
+    /// Sets the seed the walks are generated from.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the point every walker starts at.
+    pub fn with_start(mut self, start: (f64, f64)) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Sets the length of every step in screen units.
+    ///
+    /// Returns an Err if the length isn't positive and an Ok with the walk otherwise.
+    pub fn with_step_size(mut self, step_size: f64) -> Result<Self, MathvisError> {
+        if step_size.is_nan() || step_size <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The steps of a random walk must have a positive length.".into(),
+            ));
+        }
+        self.step_size = step_size;
+        Ok(self)
+    }
+
+    /// Sets whether the path every walker has taken is drawn behind it.
+    pub fn with_trail(mut self, trail: bool) -> Self {
+        self.trail = trail;
+        self
+    }
+
+    /// Draws a histogram of the walkers' x coordinates, with bins of the specified width centered on its multiples.
+    ///
+    /// Returns an Err if the width isn't positive and an Ok with the walk otherwise.
+    pub fn with_histogram(mut self, bin_width: f64) -> Result<Self, MathvisError> {
+        if bin_width.is_nan() || bin_width <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The bins of a histogram must have a positive width.".into(),
+            ));
+        }
+        self.bin_width = Some(bin_width);
+        Ok(self)
+    }
+
+    /// Sets the colors of the walkers with their trails and of the histogram.
+    pub fn with_colors(mut self, walk: Rgb<u8>, histogram: Rgb<u8>) -> Self {
+        self.walk_color = walk;
+        self.histogram_color = histogram;
+        self
+    }
+
+    /// Returns the path of every walker, from the start through the point reached after every step.
+    pub fn paths(&self) -> Vec<Vec<(f64, f64)>> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        (0..self.walkers)
+            .map(|_| {
+                let mut point = self.start;
+                let mut path = Vec::with_capacity(self.steps + 1);
+                path.push(point);
+                for _ in 0..self.steps {
+                    let angle = rng.random_range(0.0..TAU);
+                    point.0 += self.step_size * angle.cos();
+                    point.1 += self.step_size * angle.sin();
+                    path.push(point);
+                }
+                path
+            })
+            .collect()
+    }
+
+    /// Returns the histogram of the x coordinates of the specified points as bars of a chart, normalized to a density.
+    fn histogram(&self, points: &[(f64, f64)], bin_width: f64) -> BarChart {
+        let mut bins = BTreeMap::new();
+        for &(x, _) in points {
+            *bins.entry((x / bin_width).round() as i64).or_insert(0) += 1;
+        }
+        let total = points.len() as f64 * bin_width;
+        let bars = bins
+            .into_iter()
+            .map(|(bin, count)| (bin as f64 * bin_width, count as f64 / total))
+            .collect();
+        BarChart::new(bars, self.histogram_color)
+            .with_width(bin_width)
+            .expect("The width of the bins was checked to be positive")
+    }
+
+    /// Returns an animation of the specified duration in which every walker takes its steps, one after the other.
+    ///
+    /// Returns an Err if there are no steps to take and an Ok with the animation otherwise.
+    pub fn animate(&self, duration: f32) -> Result<Animation, MathvisError> {
+        if self.steps == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "A random walk must take at least one step.".into(),
+            ));
+        }
+        let paths = self.paths();
+        let walk = self.clone();
+
+        Ok(Animation::new(duration, move |progress, canvas, _, map| {
+            let position = progress * walk.steps as f64;
+            let index = (position.floor() as usize).min(walk.steps);
+            let step = position - index as f64;
+            let mut trails = Vec::new();
+            let mut points = Vec::new();
+            for path in &paths {
+                let (x, y) = path[index];
+                let current = match path.get(index + 1) {
+                    Some(&(nx, ny)) => (x + (nx - x) * step, y + (ny - y) * step),
+                    None => (x, y),
+                };
+                if walk.trail {
+                    let mut trail = path[..=index].to_vec();
+                    trail.push(current);
+                    trails.push(trail);
+                }
+                points.push(current);
+            }
+            if let Some(bin_width) = walk.bin_width {
+                walk.histogram(&points, bin_width).draw(canvas, map)?;
+            }
+            Path2D::new(trails, walk.walk_color).draw(canvas, map)?;
+            PointCloud::new(points, walk.walk_color)
+                .with_radius(3)
+                .draw(canvas, map)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::RgbImage;
+
+    use super::*;
+    use crate::api::{screen::Screen2D, util::CoordinateMap};
+
+    #[test]
+    fn test_paths() {
+        let walk = RandomWalk2D::new(3, 50)
+            .with_seed(1)
+            .with_start((1.0, -1.0))
+            .with_step_size(0.2)
+            .unwrap();
+        let paths = walk.paths();
+        for path in &paths {
+            assert_eq!(path[0], (1.0, -1.0));
+            for pair in path.windows(2) {
+                let (dx, dy) = (pair[1].0 - pair[0].0, pair[1].1 - pair[0].1);
+                assert!((dx.hypot(dy) - 0.2).abs() < 1e-12);
+            }
+        }
+        assert_ne!(paths, walk.clone().with_seed(2).paths());
+        assert!(walk.with_step_size(0.0).is_err());
+    }
+
+    #[test]
+    fn test_histogram() {
+        let walk = RandomWalk2D::new(4, 10);
+        let chart = walk.histogram(&[(0.1, 0.0), (-0.1, 3.0), (0.4, 0.0), (1.1, 0.0)], 0.5);
+        // The density over the bins adds up to 1
+        assert_eq!(chart.bars(), &[(0.0, 1.0), (0.5, 0.5), (1.0, 0.5)]);
+        assert!(walk.with_histogram(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_animate() {
+        let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 10, 854, 480).unwrap();
+        let map = CoordinateMap::new(&screen).unwrap();
+        let walk = RandomWalk2D::new(1, 10)
+            .with_seed(5)
+            .with_step_size(0.5)
+            .unwrap();
+        let animation = walk.animate(1.0).unwrap();
+        let mut img = RgbImage::new(854, 480);
+        animation.draw_frame(9, &mut img, &screen, &map).unwrap();
+        // The trail goes through the points the walker has already reached
+        let (x, y) = map.to_pixels(((walk.paths()[0][3].0) as f32, walk.paths()[0][3].1 as f32));
+        assert_eq!(*img.get_pixel(x as u32, y as u32), WALK_COLOR);
+        assert!(RandomWalk2D::new(1, 0).animate(1.0).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Module containing angles, which keep track of their unit so degrees are never mistaken for radians.
+#![warn(missing_docs)]
+use std::{
+    f64::consts::TAU,
+    fmt::{self, Display},
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// An angle, built from degrees, radians or turns and converted back to any of them.
+///
+/// Everything that takes an angle takes `impl Into<Angle>`, and plain numbers are converted as radians,
+/// so `Angle::degrees(90.0)` and `PI / 2.0` mean the same rotation.
+/// Positive angles turn counterclockwise and negative ones clockwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::angle::Angle;
+/// use std::f64::consts::PI;
+///
+/// let right = Angle::degrees(90.0);
+/// assert_eq!(right, Angle::radians(PI / 2.0));
+/// assert_eq!(right, Angle::turns(0.25));
+/// assert_eq!((right * 3.0).to_degrees(), 270.0);
+/// assert_eq!((-right).normalized().to_degrees(), 270.0);
+/// assert_eq!(right.to_string(), "90°");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Angle {
+    radians: f64,
+}
+
+impl Angle {
+    /// The angle of no rotation.
+    pub const ZERO: Angle = Angle { radians: 0.0 };
+    /// The angle of a whole turn, 360 degrees.
+    pub const FULL_TURN: Angle = Angle { radians: TAU };
+
+    /// Creates an angle in radians.
+    pub fn radians(radians: f64) -> Self {
+        Angle { radians }
+    }
+
+    /// Creates an angle in degrees.
+    pub fn degrees(degrees: f64) -> Self {
+        Angle {
+            radians: degrees.to_radians(),
+        }
+    }
+
+    /// Creates an angle in whole turns, so 1 is 360 degrees.
+    pub fn turns(turns: f64) -> Self {
+        Angle {
+            radians: turns * TAU,
+        }
+    }
+
+    /// Returns the angle in radians.
+    pub fn to_radians(&self) -> f64 {
+        self.radians
+    }
+
+    /// Returns the angle in degrees.
+    pub fn to_degrees(&self) -> f64 {
+        self.radians.to_degrees()
+    }
+
+    /// Returns the angle in whole turns.
+    pub fn to_turns(&self) -> f64 {
+        self.radians / TAU
+    }
+
+    /// Returns the same direction as an angle from 0 (inclusive) to a whole turn (exclusive).
+    pub fn normalized(&self) -> Self {
+        Angle {
+            radians: self.radians.rem_euclid(TAU),
+        }
+    }
+
+    /// Returns the sine and cosine of the angle.
+    pub fn sin_cos(&self) -> (f64, f64) {
+        self.radians.sin_cos()
+    }
+}
+
+impl From<f64> for Angle {
+    /// Converts a number of radians into an angle.
+    fn from(radians: f64) -> Self {
+        Angle::radians(radians)
+    }
+}
+
+impl From<f32> for Angle {
+    /// Converts a number of radians into an angle.
+    fn from(radians: f32) -> Self {
+        Angle::radians(radians as f64)
+    }
+}
+
+impl Display for Angle {
+    /// Formats the angle in degrees, like `90°`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Rounded so angles built from degrees print back as they were written
+        let degrees = (self.to_degrees() * 1e9).round() / 1e9;
+        write!(f, "{}°", degrees)
+    }
+}
+
+impl Add for Angle {
+    type Output = Angle;
+
+    fn add(self, other: Angle) -> Angle {
+        Angle::radians(self.radians + other.radians)
+    }
+}
+
+impl Sub for Angle {
+    type Output = Angle;
+
+    fn sub(self, other: Angle) -> Angle {
+        Angle::radians(self.radians - other.radians)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Angle {
+        Angle::radians(-self.radians)
+    }
+}
+
+impl Mul<f64> for Angle {
+    type Output = Angle;
+
+    fn mul(self, factor: f64) -> Angle {
+        Angle::radians(self.radians * factor)
+    }
+}
+
+impl Div<f64> for Angle {
+    type Output = Angle;
+
+    fn div(self, divisor: f64) -> Angle {
+        Angle::radians(self.radians / divisor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Angle::from(PI), Angle::degrees(180.0));
+        assert_eq!(
+            Angle::from(std::f32::consts::PI).to_radians(),
+            PI as f32 as f64
+        );
+        assert_eq!(Angle::turns(-1.5).normalized(), Angle::radians(PI));
+        assert_eq!(
+            (Angle::FULL_TURN / 4.0 - Angle::degrees(45.0)).to_string(),
+            "45°"
+        );
+        assert!(Angle::degrees(10.0) < Angle::degrees(20.0));
+        assert_eq!(Angle::ZERO + Angle::radians(1.0), Angle::radians(1.0));
+    }
+}
@@ -0,0 +1,109 @@
+//! Module containing conversions between ndarray arrays and the crate's vectors and matrices, only built with the `ndarray` feature.
+//!
+//! Arrays are converted with `try_from`, since an empty array can't be a vector or a matrix, and converted back with `from`.
+#![warn(missing_docs)]
+use ndarray::{Array1, Array2};
+
+use super::{matrix::Matrix, point::PointLike, util::Number, vector::Vector};
+use crate::error::MathvisError;
+
+impl<T> TryFrom<Array1<T>> for Vector<T>
+where
+    T: Number,
+{
+    type Error = MathvisError;
+
+    /// Converts a one-dimensional array into a vector with the same coordinates.
+    ///
+    /// Returns an Err if the array is empty and an Ok with the vector otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::{point::PointLike, vector::Vector};
+    /// use ndarray::array;
+    ///
+    /// let vector = Vector::try_from(array![1.0, 2.0]).unwrap();
+    /// assert_eq!(vector, Vector::new(vec![1.0, 2.0]).unwrap());
+    /// ```
+    fn try_from(array: Array1<T>) -> Result<Self, Self::Error> {
+        Vector::new(array.to_vec())
+    }
+}
+
+impl<T> From<Vector<T>> for Array1<T>
+where
+    T: Number,
+{
+    /// Converts a vector into a one-dimensional array with the same coordinates.
+    fn from(vector: Vector<T>) -> Self {
+        Array1::from(vector.values)
+    }
+}
+
+impl<T> TryFrom<Array2<T>> for Matrix<T>
+where
+    T: Number,
+{
+    type Error = MathvisError;
+
+    /// Converts a two-dimensional array into a matrix with the same rows.
+    ///
+    /// Returns an Err with an InvalidArgument if the array has no rows or columns and an Ok with the matrix otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// use ndarray::{array, Array2};
+    ///
+    /// let matrix = Matrix::try_from(array![[1, 2], [3, 4]]).unwrap();
+    /// assert_eq!(matrix, Matrix::new(vec![vec![1, 2], vec![3, 4]]).unwrap());
+    /// assert!(Matrix::try_from(Array2::<i32>::zeros((0, 2))).is_err());
+    /// ```
+    fn try_from(array: Array2<T>) -> Result<Self, Self::Error> {
+        Matrix::new(array.outer_iter().map(|row| row.to_vec()).collect())
+    }
+}
+
+impl<T> From<Matrix<T>> for Array2<T>
+where
+    T: Number,
+{
+    /// Converts a matrix into a two-dimensional array with the same rows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// use ndarray::{array, Array2};
+    ///
+    /// let array = Array2::from(Matrix::<i32>::identity(2).unwrap());
+    /// assert_eq!(array, array![[1, 0], [0, 1]]);
+    /// ```
+    fn from(matrix: Matrix<T>) -> Self {
+        // Known to work since the values always fill every row and column
+        Array2::from_shape_vec((matrix.rows, matrix.cols), matrix.values).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1};
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let array = array![[1.5, -2.0, 0.0], [3.0, 4.0, 0.25]];
+        let matrix = Matrix::try_from(array.clone()).unwrap();
+        assert_eq!(matrix.get_dimensions(), (2, 3));
+        assert_eq!(matrix.row(1), &[3.0, 4.0, 0.25]);
+        assert_eq!(Array2::from(matrix), array);
+
+        let vector = Vector::try_from(array![1u32, 2, 3]).unwrap();
+        assert_eq!(vector.get_dimensions(), 3);
+        assert_eq!(Array1::from(vector), array![1, 2, 3]);
+        assert!(Vector::try_from(Array1::<f64>::zeros(0)).is_err());
+    }
+}
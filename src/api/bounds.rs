@@ -0,0 +1,161 @@
+//! Module containing axis-aligned bounding boxes, the extents of objects in screen coordinates.
+#![warn(missing_docs)]
+
+/// The smallest axis-aligned rectangle containing an object, in screen coordinates.
+///
+/// A box may have no width or height, like the box of a single point or of a horizontal segment.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::bounds::BoundingBox;
+///
+/// let unit = BoundingBox::new((1.0, 1.0), (0.0, 0.0));
+/// assert_eq!(unit.min(), (0.0, 0.0));
+/// assert_eq!(unit.center(), (0.5, 0.5));
+///
+/// let point = BoundingBox::point((2.0, 0.5));
+/// let both = unit.union(&point);
+/// assert_eq!((both.width(), both.height()), (2.0, 1.0));
+/// assert!(both.contains(&unit) && !unit.intersects(&point));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    min: (f64, f64),
+    max: (f64, f64),
+}
+
+impl BoundingBox {
+    /// Creates the box with two opposite corners, in any order.
+    pub fn new((x0, y0): (f64, f64), (x1, y1): (f64, f64)) -> Self {
+        BoundingBox {
+            min: (x0.min(x1), y0.min(y1)),
+            max: (x0.max(x1), y0.max(y1)),
+        }
+    }
+
+    /// Creates the box of a single point, with no width or height.
+    pub fn point(point: (f64, f64)) -> Self {
+        Self::new(point, point)
+    }
+
+    /// Returns the smallest box containing every point, leaving out the ones that aren't finite.
+    ///
+    /// Returns None if there are no finite points and Some with the box otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::bounds::BoundingBox;
+    ///
+    /// let bounds = BoundingBox::from_points([(0.0, 2.0), (f64::NAN, 0.0), (-1.0, 3.0)]).unwrap();
+    /// assert_eq!(bounds, BoundingBox::new((-1.0, 2.0), (0.0, 3.0)));
+    /// assert_eq!(BoundingBox::from_points([]), None);
+    /// ```
+    pub fn from_points(points: impl IntoIterator<Item = (f64, f64)>) -> Option<Self> {
+        points
+            .into_iter()
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .map(Self::point)
+            .reduce(|bounds, point| bounds.union(&point))
+    }
+
+    /// Returns the smallest box containing every box, or None if there are none.
+    pub fn union_all(boxes: impl IntoIterator<Item = BoundingBox>) -> Option<Self> {
+        boxes.into_iter().reduce(|a, b| a.union(&b))
+    }
+
+    /// Returns the bottom left corner of the box.
+    pub fn min(&self) -> (f64, f64) {
+        self.min
+    }
+
+    /// Returns the top right corner of the box.
+    pub fn max(&self) -> (f64, f64) {
+        self.max
+    }
+
+    /// Returns the width of the box.
+    pub fn width(&self) -> f64 {
+        self.max.0 - self.min.0
+    }
+
+    /// Returns the height of the box.
+    pub fn height(&self) -> f64 {
+        self.max.1 - self.min.1
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> (f64, f64) {
+        (
+            (self.min.0 + self.max.0) / 2.0,
+            (self.min.1 + self.max.1) / 2.0,
+        )
+    }
+
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> Self {
+        BoundingBox {
+            min: (self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: (self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+
+    /// Returns the box grown by a margin on every side, or shrunk down to its center if the margin is negative and larger than the box.
+    pub fn expanded(&self, margin: f64) -> Self {
+        let center = self.center();
+        Self::new(
+            (
+                (self.min.0 - margin).min(center.0),
+                (self.min.1 - margin).min(center.1),
+            ),
+            (
+                (self.max.0 + margin).max(center.0),
+                (self.max.1 + margin).max(center.1),
+            ),
+        )
+    }
+
+    /// Returns whether the point is inside the box or on its edges.
+    pub fn contains_point(&self, (x, y): (f64, f64)) -> bool {
+        self.min.0 <= x && x <= self.max.0 && self.min.1 <= y && y <= self.max.1
+    }
+
+    /// Returns whether the other box is entirely inside this one, edges included.
+    pub fn contains(&self, other: &BoundingBox) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Returns whether the boxes overlap, touching edges included.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.0 <= other.max.0
+            && other.min.0 <= self.max.0
+            && self.min.1 <= other.max.1
+            && other.min.1 <= self.max.1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boxes() {
+        let bounds = BoundingBox::new((-1.0, -2.0), (3.0, 2.0));
+        assert!(bounds.contains_point((3.0, 0.0)) && !bounds.contains_point((3.5, 0.0)));
+        assert!(bounds.intersects(&BoundingBox::point((3.0, 2.0))));
+        assert!(!bounds.contains(&BoundingBox::new((0.0, 0.0), (4.0, 1.0))));
+        assert_eq!(
+            bounds.expanded(1.0),
+            BoundingBox::new((-2.0, -3.0), (4.0, 3.0))
+        );
+        assert_eq!(
+            bounds.expanded(-3.0),
+            BoundingBox::new((1.0, 0.0), (1.0, 0.0))
+        );
+        assert_eq!(
+            BoundingBox::union_all([BoundingBox::point((5.0, 5.0)), bounds]),
+            Some(BoundingBox::new((-1.0, -2.0), (5.0, 5.0)))
+        );
+    }
+}
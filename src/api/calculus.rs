@@ -0,0 +1,110 @@
+//! Module containing numerical derivatives and gradients, computed from central finite differences.
+//!
+//! Every difference uses the step size that balances truncation and rounding errors for its order,
+//! relative to the size of the point, so first derivatives are accurate to about 10 digits on smooth functions.
+#![warn(missing_docs)]
+use crate::{animation::geometry::Line, error::MathvisError};
+
+/// Returns the derivative of a function at x.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::calculus::derivative;
+///
+/// assert!((derivative(f64::sin, 0.0) - 1.0).abs() < 1e-9);
+/// assert!((derivative(|x: f64| x * x, 3.0) - 6.0).abs() < 1e-9);
+/// ```
+pub fn derivative<F: Fn(f64) -> f64>(f: F, x: f64) -> f64 {
+    nth_derivative(f, x, 1)
+}
+
+/// Returns the n-th derivative of a function at x, from the central finite difference of order n, or the value of the function for n = 0.
+///
+/// Higher derivatives lose about a digit of accuracy per order, so past the 15th they're too noisy to be useful.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::calculus::nth_derivative;
+///
+/// // The derivatives of sin go around sin, cos, -sin, -cos
+/// assert!((nth_derivative(f64::sin, 0.0, 3) + 1.0).abs() < 1e-4);
+/// assert_eq!(nth_derivative(f64::sin, 1.0, 0), 1f64.sin());
+/// ```
+pub fn nth_derivative<F: Fn(f64) -> f64>(f: F, x: f64, n: u32) -> f64 {
+    if n == 0 {
+        return f(x);
+    }
+    let h = f64::EPSILON.powf(1.0 / (n as f64 + 2.0)) * x.abs().max(1.0);
+    let mut binomial = 1.0;
+    let mut sum = 0.0;
+    for k in 0..=n {
+        if k > 0 {
+            binomial *= (n - k + 1) as f64 / k as f64;
+        }
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        sum += sign * binomial * f(x + (n as f64 / 2.0 - k as f64) * h);
+    }
+    sum / h.powi(n as i32)
+}
+
+/// Returns the gradient of a function of two variables at a point, its partial derivatives along x and y,
+/// which points in the direction the function grows fastest in.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::calculus::gradient;
+///
+/// let (dx, dy) = gradient(|x, y| x * x * y, (1.0, 2.0));
+/// assert!((dx - 4.0).abs() < 1e-8 && (dy - 1.0).abs() < 1e-8);
+/// ```
+pub fn gradient<F: Fn(f64, f64) -> f64>(f: F, (x, y): (f64, f64)) -> (f64, f64) {
+    (derivative(|x| f(x, y), x), derivative(|y| f(x, y), y))
+}
+
+/// Returns the line tangent to the graph of a function at x, through (x, f(x)) with the slope of its derivative.
+///
+/// Returns an Err if the function or its derivative isn't finite at x and an Ok with the line otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::calculus::tangent_line;
+///
+/// let tangent = tangent_line(|x: f64| x * x, 1.0).unwrap();
+/// // The tangent to x² at 1 is y = 2x - 1, which crosses the x axis at 0.5
+/// assert!(tangent.distance_to((0.5, 0.0)) < 1e-9);
+/// assert!(tangent_line(f64::ln, 0.0).is_err());
+/// ```
+pub fn tangent_line<F: Fn(f64) -> f64>(f: F, x: f64) -> Result<Line, MathvisError> {
+    let (y, slope) = (f(x), derivative(&f, x));
+    if !y.is_finite() || !slope.is_finite() {
+        return Err(MathvisError::InvalidArgument(format!(
+            "The function has no tangent at {}, since it or its derivative isn't finite there.",
+            x
+        )));
+    }
+    Line::through((x, y), (x + 1.0, y + slope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derivatives() {
+        // Steps are relative to the point, so large points don't drown in rounding errors
+        assert!((derivative(f64::ln, 1e6) - 1e-6).abs() < 1e-12);
+        assert!((derivative(f64::exp, 1.0) - 1f64.exp()).abs() < 1e-8);
+        assert!((nth_derivative(f64::exp, 0.0, 2) - 1.0).abs() < 1e-6);
+        assert!(derivative(f64::sqrt, 0.0).is_nan());
+
+        let (dx, dy) = gradient(|x, y| x.sin() * y.cos(), (0.0, 0.0));
+        assert!((dx - 1.0).abs() < 1e-9 && dy.abs() < 1e-9);
+        let tangent = tangent_line(f64::sin, 0.0).unwrap();
+        let (dx, dy) = tangent.direction();
+        assert!((dx - dy).abs() < 1e-9);
+    }
+}
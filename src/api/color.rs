@@ -0,0 +1,444 @@
+//! Module containing named colors, palettes and themes, so scenes look consistent without writing out every color,
+//! and colors that can be built and blended in HSV and HSL.
+#![warn(missing_docs)]
+use imageproc::image::Rgb;
+
+use crate::{animation::background::BACKGROUND_COLOR, error::MathvisError};
+
+/// Pure white.
+pub const WHITE: Rgb<u8> = Rgb([255, 255, 255]);
+/// Pure black.
+pub const BLACK: Rgb<u8> = Rgb([0, 0, 0]);
+/// Pure red.
+pub const RED: Rgb<u8> = Rgb([255, 0, 0]);
+/// Pure green.
+pub const GREEN: Rgb<u8> = Rgb([0, 255, 0]);
+/// Pure blue.
+pub const BLUE: Rgb<u8> = Rgb([0, 0, 255]);
+/// Pure yellow.
+pub const YELLOW: Rgb<u8> = Rgb([255, 255, 0]);
+/// Pure cyan.
+pub const CYAN: Rgb<u8> = Rgb([0, 255, 255]);
+/// Pure magenta.
+pub const MAGENTA: Rgb<u8> = Rgb([255, 0, 255]);
+/// Soft sky blue, the first color of the default palette.
+pub const SKY: Rgb<u8> = Rgb([88, 196, 221]);
+/// Blue green.
+pub const TEAL: Rgb<u8> = Rgb([92, 208, 179]);
+/// Soft green.
+pub const LIME: Rgb<u8> = Rgb([131, 193, 103]);
+/// Warm orange.
+pub const ORANGE: Rgb<u8> = Rgb([255, 134, 47]);
+/// Muted gold.
+pub const GOLD: Rgb<u8> = Rgb([240, 172, 95]);
+/// Muted purple.
+pub const PURPLE: Rgb<u8> = Rgb([154, 114, 172]);
+/// Bright pink.
+pub const PINK: Rgb<u8> = Rgb([209, 71, 189]);
+/// Muted dark red.
+pub const MAROON: Rgb<u8> = Rgb([197, 95, 115]);
+/// Mid gray.
+pub const GRAY: Rgb<u8> = Rgb([136, 136, 136]);
+
+/// Every named color with its name, in lowercase.
+const NAMED: [(&str, Rgb<u8>); 17] = [
+    ("white", WHITE),
+    ("black", BLACK),
+    ("red", RED),
+    ("green", GREEN),
+    ("blue", BLUE),
+    ("yellow", YELLOW),
+    ("cyan", CYAN),
+    ("magenta", MAGENTA),
+    ("sky", SKY),
+    ("teal", TEAL),
+    ("lime", LIME),
+    ("orange", ORANGE),
+    ("gold", GOLD),
+    ("purple", PURPLE),
+    ("pink", PINK),
+    ("maroon", MAROON),
+    ("gray", GRAY),
+];
+
+/// Bright colors that stand out on a dark background.
+pub const BRIGHT: [Rgb<u8>; 6] = [SKY, ORANGE, LIME, PINK, GOLD, TEAL];
+/// Light colors that are easy on the eyes on a dark background.
+pub const PASTEL: [Rgb<u8>; 6] = [
+    Rgb([174, 198, 232]),
+    Rgb([255, 187, 120]),
+    Rgb([152, 223, 138]),
+    Rgb([255, 152, 150]),
+    Rgb([197, 176, 213]),
+    Rgb([196, 156, 148]),
+];
+/// Deep colors that stand out on a light background.
+pub const INK: [Rgb<u8>; 6] = [
+    Rgb([31, 119, 180]),
+    Rgb([255, 127, 14]),
+    Rgb([44, 160, 44]),
+    Rgb([214, 39, 40]),
+    Rgb([148, 103, 189]),
+    Rgb([140, 86, 75]),
+];
+
+/// Returns the named color with the specified name, like `teal`, or None if there's no such color.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::color::{by_name, TEAL};
+///
+/// assert_eq!(by_name("teal"), Some(TEAL));
+/// assert_eq!(by_name("chartreuse"), None);
+/// ```
+pub fn by_name(name: &str) -> Option<Rgb<u8>> {
+    NAMED
+        .iter()
+        .find(|(named, _)| *named == name)
+        .map(|(_, color)| *color)
+}
+
+/// A set of colors that go well together, which objects without a color of their own take in turn.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::color::{Palette, BLUE, RED};
+///
+/// let palette = Palette::new(vec![RED, BLUE]).unwrap();
+/// // Colors repeat once every one was taken
+/// assert_eq!(palette.color(3), BLUE);
+/// assert!(Palette::new(vec![]).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<Rgb<u8>>,
+}
+
+impl Palette {
+    /// Creates a palette with the specified colors, in the order objects take them.
+    ///
+    /// Returns an Err if there are no colors and an Ok with the palette otherwise.
+    pub fn new(colors: impl Into<Vec<Rgb<u8>>>) -> Result<Self, MathvisError> {
+        let colors = colors.into();
+        if colors.is_empty() {
+            return Err(MathvisError::InvalidArgument(
+                "A palette must have at least one color.".into(),
+            ));
+        }
+        Ok(Palette { colors })
+    }
+
+    /// Returns the colors of the palette, in order.
+    pub fn colors(&self) -> &[Rgb<u8>] {
+        &self.colors
+    }
+
+    /// Returns the color of the object with the specified index, starting over from the first color after the last one.
+    pub fn color(&self, index: usize) -> Rgb<u8> {
+        self.colors[index % self.colors.len()]
+    }
+}
+
+impl Default for Palette {
+    /// Returns the [BRIGHT] palette.
+    fn default() -> Self {
+        Palette {
+            colors: BRIGHT.to_vec(),
+        }
+    }
+}
+
+/// The colors of everything drawn on a screen that isn't given a color of its own.
+///
+/// See [Screen2D::set_theme](crate::api::screen::Screen2D::set_theme) for applying a theme to a screen.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::color::{Theme, BLACK, INK};
+///
+/// let theme = Theme::light();
+/// assert_eq!(theme.palette.colors(), &INK);
+/// let night = Theme { background: BLACK, ..Theme::dark() };
+/// assert_eq!(night.axis_color, Theme::dark().axis_color);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Color used to fill the background of every frame.
+    pub background: Rgb<u8>,
+    /// Color of the axes and their ticks.
+    pub axis_color: Rgb<u8>,
+    /// Colors objects take in turn.
+    pub palette: Palette,
+}
+
+impl Theme {
+    /// Returns the default theme, with white axes and [BRIGHT] objects on a dark background.
+    pub fn dark() -> Self {
+        Theme {
+            background: BACKGROUND_COLOR,
+            axis_color: WHITE,
+            palette: Palette::default(),
+        }
+    }
+
+    /// Returns a theme with dark axes and [INK] objects on a white background, for printed figures and slides.
+    pub fn light() -> Self {
+        Theme {
+            background: WHITE,
+            axis_color: Rgb([60, 60, 60]),
+            palette: Palette {
+                colors: INK.to_vec(),
+            },
+        }
+    }
+
+    /// Returns the theme with the specified name (`dark` or `light`), or None if there's no such theme.
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Theme {
+    /// Returns the [Theme::dark] theme.
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// A color with components from 0 to 1, which can be built from and converted to RGB, HSV, HSL and hex codes.
+///
+/// Hues are in degrees, wrapping around so 360 is red again, and every other component goes from 0 to 1.
+/// Blending in HSV with [Color::lerp_hsv] goes around the color wheel instead of through gray like blending in RGB,
+/// which makes for smoother color animations and gradients.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::api::color::Color;
+///
+/// let orange = Color::hsv(30.0, 1.0, 1.0);
+/// assert_eq!(orange.to_rgb(), Rgb([255, 128, 0]));
+/// assert_eq!(Color::hex("#ff8000").unwrap().to_rgb(), orange.to_rgb());
+/// assert_eq!(Color::hsl(30.0, 1.0, 0.5).to_hex(), "#ff8000");
+/// // Halfway between red and blue around the wheel is magenta, not a dark purple
+/// let magenta = Color::hsv(0.0, 1.0, 1.0).lerp_hsv(Color::hsv(240.0, 1.0, 1.0), 0.5);
+/// assert_eq!(magenta.to_rgb(), Rgb([255, 0, 255]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    r: f32,
+    g: f32,
+    b: f32,
+}
+
+impl Color {
+    /// Creates a color from its red, green and blue components.
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+        }
+    }
+
+    /// Creates a color from its hue, saturation and value, clamping the saturation and value between 0 and 1.
+    pub fn hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let (s, v) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+        let chroma = v * s;
+        Self::from_hue(hue, chroma, v - chroma)
+    }
+
+    /// Creates a color from its hue, saturation and lightness, clamping the saturation and lightness between 0 and 1.
+    pub fn hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let (s, l) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_hue(hue, chroma, l - chroma / 2.0)
+    }
+
+    /// Parses a hex code like `#ff8800` or `#f80`, with or without the `#`.
+    ///
+    /// Returns an Err if the code isn't 3 or 6 hex digits and an Ok with the color otherwise.
+    pub fn hex(code: &str) -> Result<Self, MathvisError> {
+        let digits = code.strip_prefix('#').unwrap_or(code);
+        let invalid = || MathvisError::InvalidArgument(format!("Invalid hex color {}.", code));
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+        match digits.len() {
+            3 => {
+                // Every digit is repeated, so #f80 is #ff8800
+                let short = |i: usize| Ok::<_, MathvisError>(channel(&digits[i..=i])? * 17);
+                Ok(Self::rgb(short(0)?, short(1)?, short(2)?))
+            }
+            6 => Ok(Self::rgb(
+                channel(&digits[0..2])?,
+                channel(&digits[2..4])?,
+                channel(&digits[4..6])?,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Returns the color whose hue is the specified angle in radians, fully saturated and bright,
+    /// for coloring things by their direction like the arrows of a vector field.
+    pub fn from_angle(angle: f64) -> Self {
+        Self::hsv(angle.to_degrees() as f32, 1.0, 1.0)
+    }
+
+    /// Creates a color from a hue and its chroma, adding the same amount to every component.
+    fn from_hue(hue: f32, chroma: f32, offset: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Color {
+            r: r + offset,
+            g: g + offset,
+            b: b + offset,
+        }
+    }
+
+    /// Returns the hue of the color and its largest and smallest components, with a hue of 0 for grays.
+    fn hue(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+        let hue = if chroma == 0.0 {
+            0.0
+        } else if max == self.r {
+            60.0 * ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / chroma + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / chroma + 4.0)
+        };
+        (hue, max, min)
+    }
+
+    /// Returns the hue, saturation and value of the color.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue();
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (hue, saturation, max)
+    }
+
+    /// Returns the hue, saturation and lightness of the color.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        (hue, saturation, lightness)
+    }
+
+    /// Returns the color as an RGB pixel, rounding every component.
+    pub fn to_rgb(&self) -> Rgb<u8> {
+        let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Rgb([channel(self.r), channel(self.g), channel(self.b)])
+    }
+
+    /// Returns the hex code of the color, like `#ff8800`.
+    pub fn to_hex(&self) -> String {
+        let Rgb([r, g, b]) = self.to_rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    /// Blends the color with another one component by component, from the color at 0 to the other at 1.
+    pub fn lerp(&self, other: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Color {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+        }
+    }
+
+    /// Blends the color with another one in HSV, from the color at 0 to the other at 1,
+    /// going around the color wheel the shortest way between their hues.
+    ///
+    /// The hue of a gray is meaningless, so blending from or to a gray only changes the saturation and value.
+    pub fn lerp_hsv(&self, other: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let (h1, s1, v1) = self.to_hsv();
+        let (h2, s2, v2) = other.to_hsv();
+        let (h1, h2) = match (s1 == 0.0, s2 == 0.0) {
+            (true, _) => (h2, h2),
+            (_, true) => (h1, h1),
+            _ => (h1, h2),
+        };
+        let turn = (h2 - h1 + 540.0).rem_euclid(360.0) - 180.0;
+        Self::hsv(h1 + turn * t, s1 + (s2 - s1) * t, v1 + (v2 - v1) * t)
+    }
+}
+
+impl From<Rgb<u8>> for Color {
+    fn from(Rgb([r, g, b]): Rgb<u8>) -> Self {
+        Color::rgb(r, g, b)
+    }
+}
+
+impl From<Color> for Rgb<u8> {
+    fn from(color: Color) -> Self {
+        color.to_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        for (name, color) in NAMED {
+            assert_eq!(by_name(name), Some(color));
+        }
+        assert_eq!(by_name("Teal"), None);
+        assert_eq!(Theme::by_name("light"), Some(Theme::light()));
+        assert_eq!(Theme::default().palette.color(BRIGHT.len()), SKY);
+    }
+
+    #[test]
+    fn test_conversions() {
+        for (_, rgb) in NAMED {
+            let color = Color::from(rgb);
+            let (h, s, v) = color.to_hsv();
+            assert_eq!(Color::hsv(h, s, v).to_rgb(), rgb);
+            let (h, s, l) = color.to_hsl();
+            assert_eq!(Color::hsl(h, s, l).to_rgb(), rgb);
+            assert_eq!(Color::hex(&color.to_hex()).unwrap(), color);
+        }
+        assert_eq!(Color::hex("f80").unwrap().to_rgb(), Rgb([255, 136, 0]));
+        assert!(Color::hex("#ff88").is_err() && Color::hex("#gg8800").is_err());
+        assert_eq!(Color::hsv(-120.0, 1.0, 1.0).to_rgb(), BLUE);
+        assert_eq!(
+            Color::from_angle(std::f64::consts::FRAC_PI_2).to_hsv().0,
+            90.0
+        );
+        // Blending with a gray keeps the hue of the other color
+        let faded_red = Color::from(BLACK).lerp_hsv(RED.into(), 0.5);
+        assert_eq!(faded_red.to_rgb(), Rgb([128, 64, 64]));
+        assert_eq!(
+            Color::from(RED).lerp(BLUE.into(), 0.5).to_rgb(),
+            Rgb([128, 0, 128])
+        );
+    }
+}
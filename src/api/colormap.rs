@@ -0,0 +1,194 @@
+//! Module containing colormaps, which color scalar data like the values of a heatmap or the lengths of a vector field's arrows.
+#![warn(missing_docs)]
+use std::{fmt, sync::Arc};
+
+use imageproc::image::Rgb;
+
+use crate::error::MathvisError;
+
+/// Colors of the heat colormap, from dark blue through purple and orange to yellow.
+const HEAT: [Rgb<u8>; 4] = [
+    Rgb([20, 30, 90]),
+    Rgb([120, 40, 130]),
+    Rgb([230, 110, 50]),
+    Rgb([250, 230, 90]),
+];
+/// Colors of matplotlib's viridis, from dark purple through blue and green to yellow.
+const VIRIDIS: [Rgb<u8>; 8] = [
+    Rgb([68, 1, 84]),
+    Rgb([70, 50, 126]),
+    Rgb([54, 92, 141]),
+    Rgb([39, 127, 142]),
+    Rgb([31, 161, 135]),
+    Rgb([74, 193, 109]),
+    Rgb([160, 218, 57]),
+    Rgb([253, 231, 37]),
+];
+/// Colors of matplotlib's magma, from black through purple and red to pale yellow.
+const MAGMA: [Rgb<u8>; 9] = [
+    Rgb([0, 0, 4]),
+    Rgb([28, 16, 68]),
+    Rgb([79, 18, 123]),
+    Rgb([129, 37, 129]),
+    Rgb([181, 54, 122]),
+    Rgb([229, 89, 100]),
+    Rgb([251, 135, 97]),
+    Rgb([254, 194, 135]),
+    Rgb([252, 253, 191]),
+];
+/// Colors of the diverging coolwarm, from blue through light gray to red.
+const COOLWARM: [Rgb<u8>; 9] = [
+    Rgb([59, 76, 192]),
+    Rgb([98, 130, 234]),
+    Rgb([141, 176, 254]),
+    Rgb([184, 208, 249]),
+    Rgb([221, 221, 221]),
+    Rgb([245, 196, 172]),
+    Rgb([244, 152, 122]),
+    Rgb([222, 96, 77]),
+    Rgb([180, 4, 38]),
+];
+
+/// A colormap, giving the color of a value from 0 to 1.
+///
+/// Values outside of 0 to 1 take the color of the closest end, and values that aren't numbers the color of 0.
+/// Besides the standard colormaps, any function from values to colors or list of evenly spread colors can be a colormap.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::api::colormap::Colormap;
+///
+/// let viridis = Colormap::viridis();
+/// assert_eq!(viridis.color(0.0), Rgb([68, 1, 84]));
+/// assert_eq!(viridis.color(2.0), viridis.color(1.0));
+/// // Values are mapped from a range onto the colormap
+/// assert_eq!(viridis.map(15.0, 10.0, 20.0), viridis.color(0.5));
+/// assert_eq!(viridis.reversed().color(1.0), Rgb([68, 1, 84]));
+///
+/// let grays = Colormap::from_stops(vec![Rgb([0, 0, 0]), Rgb([255, 255, 255])]).unwrap();
+/// assert_eq!(grays.color(0.5), Rgb([128, 128, 128]));
+/// ```
+#[derive(Clone)]
+pub struct Colormap {
+    map: Arc<dyn Fn(f64) -> Rgb<u8> + Send + Sync>,
+}
+
+impl Colormap {
+    /// Creates a colormap from a function, which is only given values from 0 to 1.
+    pub fn from_fn<F>(map: F) -> Self
+    where
+        F: Fn(f64) -> Rgb<u8> + Send + Sync + 'static,
+    {
+        Colormap { map: Arc::new(map) }
+    }
+
+    /// Creates a colormap with colors evenly spread from 0 to 1, and the values in between blended.
+    ///
+    /// Returns an Err if there are less than 2 colors and an Ok with the colormap otherwise.
+    pub fn from_stops(stops: Vec<Rgb<u8>>) -> Result<Self, MathvisError> {
+        if stops.len() < 2 {
+            return Err(MathvisError::InvalidArgument(
+                "A colormap needs at least 2 colors.".into(),
+            ));
+        }
+        Ok(Self::from_fn(move |t| blend_stops(&stops, t)))
+    }
+
+    /// Returns the default colormap of heatmaps, contour plots and fractals, from dark blue through purple and orange to yellow.
+    pub fn heat() -> Self {
+        Self::from_fn(|t| blend_stops(&HEAT, t))
+    }
+
+    /// Returns matplotlib's viridis, which is perceptually uniform and readable by colorblind viewers.
+    pub fn viridis() -> Self {
+        Self::from_fn(|t| blend_stops(&VIRIDIS, t))
+    }
+
+    /// Returns matplotlib's magma, which is perceptually uniform and goes from black to pale yellow.
+    pub fn magma() -> Self {
+        Self::from_fn(|t| blend_stops(&MAGMA, t))
+    }
+
+    /// Returns coolwarm, a diverging colormap from blue to red through gray in the middle,
+    /// for data where the values around 0.5 are neutral, like signed values centered on 0.
+    pub fn coolwarm() -> Self {
+        Self::from_fn(|t| blend_stops(&COOLWARM, t))
+    }
+
+    /// Returns the standard colormap with the specified name (`heat`, `viridis`, `magma` or `coolwarm`), or None if there's no such colormap.
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "heat" => Some(Self::heat()),
+            "viridis" => Some(Self::viridis()),
+            "magma" => Some(Self::magma()),
+            "coolwarm" => Some(Self::coolwarm()),
+            _ => None,
+        }
+    }
+
+    /// Returns the color of a value from 0 to 1.
+    pub fn color(&self, t: f64) -> Rgb<u8> {
+        (self.map)(if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) })
+    }
+
+    /// Returns the color of a value in the range from `low` to `high`, mapping `low` to 0 and `high` to 1.
+    /// An empty range takes the color of 0.
+    pub fn map(&self, value: f64, low: f64, high: f64) -> Rgb<u8> {
+        match high > low {
+            true => self.color((value - low) / (high - low)),
+            false => self.color(0.0),
+        }
+    }
+
+    /// Returns the colormap going the other way, from the color of 1 to the color of 0.
+    pub fn reversed(&self) -> Self {
+        let map = Arc::clone(&self.map);
+        Self::from_fn(move |t| map(1.0 - t))
+    }
+}
+
+impl Default for Colormap {
+    /// Returns the [Colormap::heat] colormap.
+    fn default() -> Self {
+        Self::heat()
+    }
+}
+
+impl fmt::Debug for Colormap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Colormap").finish_non_exhaustive()
+    }
+}
+
+/// Returns the color of a value from 0 to 1 between colors evenly spread from 0 to 1, blending the two closest ones.
+fn blend_stops(stops: &[Rgb<u8>], t: f64) -> Rgb<u8> {
+    let position = t * (stops.len() - 1) as f64;
+    let index = (position.floor() as usize).min(stops.len() - 2);
+    let s = position - index as f64;
+    let (Rgb(a), Rgb(b)) = (stops[index], stops[index + 1]);
+    Rgb([0, 1, 2].map(|i| (a[i] as f64 + (b[i] as f64 - a[i] as f64) * s).round() as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colormaps() {
+        let heat = Colormap::heat();
+        assert_eq!(heat.color(0.0), HEAT[0]);
+        assert_eq!(heat.color(1.0), HEAT[3]);
+        assert_eq!(heat.color(2.0), HEAT[3]);
+        assert_eq!(heat.color(f64::NAN), HEAT[0]);
+        assert_eq!(heat.color(1.0 / 3.0), HEAT[1]);
+        assert_eq!(Colormap::coolwarm().color(0.5), Rgb([221, 221, 221]));
+        assert_eq!(Colormap::magma().map(3.0, 3.0, 3.0), MAGMA[0]);
+        assert!(Colormap::by_name("viridis").is_some() && Colormap::by_name("jet").is_none());
+        assert!(Colormap::from_stops(vec![HEAT[0]]).is_err());
+        // Functions are clamped like the standard colormaps
+        let red = Colormap::from_fn(|t| Rgb([(t * 255.0) as u8, 0, 0]));
+        assert_eq!(red.color(-1.0), Rgb([0, 0, 0]));
+    }
+}
@@ -0,0 +1,271 @@
+//! Module containing a complex number structure and its respective operations.
+#![warn(missing_docs)]
+use std::{
+    f64::consts::TAU,
+    fmt::{self, Display},
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A complex number with a real and an imaginary part.
+///
+/// Drawn on a [ComplexPlane](crate::api::screen::ScreenMode::ComplexPlane) screen, the real part is the x coordinate and the imaginary part is the y coordinate.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::complex::Complex;
+///
+/// let z = Complex::new(1.0, 2.0);
+/// let w = Complex::new(3.0, -1.0);
+/// assert_eq!(z * w, Complex::new(5.0, 5.0));
+/// assert_eq!(z.to_string(), "1 + 2i");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Complex {
+    /// The real part.
+    pub re: f64,
+    /// The imaginary part.
+    pub im: f64,
+}
+
+impl Complex {
+    /// The imaginary unit.
+    pub const I: Complex = Complex { re: 0.0, im: 1.0 };
+    /// The multiplicative identity.
+    pub const ONE: Complex = Complex { re: 1.0, im: 0.0 };
+
+    /// Creates a complex number with the specified real and imaginary parts.
+    pub fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    /// Creates a complex number from its modulus and argument, in radians.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::complex::Complex;
+    /// use std::f64::consts::FRAC_PI_2;
+    ///
+    /// let z = Complex::from_polar(2.0, FRAC_PI_2);
+    /// assert!((z - Complex::new(0.0, 2.0)).norm() < 1e-12);
+    /// ```
+    pub fn from_polar(norm: f64, arg: f64) -> Self {
+        Complex::new(norm * arg.cos(), norm * arg.sin())
+    }
+
+    /// Returns the modulus of the number, its distance to the origin.
+    pub fn norm(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Returns the argument of the number, the angle from the positive real axis in radians, between -π and π.
+    pub fn arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    /// Returns the complex conjugate, the reflection of the number over the real axis.
+    pub fn conj(&self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    /// Returns the number raised to an integer power, which is 1 for the power 0.
+    ///
+    /// Negative powers of 0 aren't finite, like dividing by 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::complex::Complex;
+    ///
+    /// assert_eq!(Complex::I.powi(2), Complex::new(-1.0, 0.0));
+    /// assert_eq!(Complex::new(2.0, 0.0).powi(-1), Complex::new(0.5, 0.0));
+    /// ```
+    pub fn powi(&self, exponent: i32) -> Self {
+        let mut result = Complex::ONE;
+        let mut base = if exponent < 0 {
+            Complex::ONE / *self
+        } else {
+            *self
+        };
+        // Exponentiation by squaring keeps integer powers exact when the parts are integers
+        let mut exponent = exponent.unsigned_abs();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// Returns the n n-th roots of the number, in counterclockwise order starting from the principal root.
+    ///
+    /// Returns an empty vector for n = 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::complex::Complex;
+    ///
+    /// let roots = Complex::new(-4.0, 0.0).roots(2);
+    /// assert!((roots[0] - Complex::new(0.0, 2.0)).norm() < 1e-12);
+    /// assert!((roots[1] - Complex::new(0.0, -2.0)).norm() < 1e-12);
+    /// ```
+    pub fn roots(&self, n: u32) -> Vec<Self> {
+        let norm = self.norm().powf(1.0 / n as f64);
+        let arg = self.arg() / n as f64;
+        (0..n)
+            .map(|k| Complex::from_polar(norm, arg + TAU * k as f64 / n as f64))
+            .collect()
+    }
+
+    /// Returns the n n-th roots of unity, the vertices of a regular polygon on the unit circle starting from 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::complex::Complex;
+    ///
+    /// let roots = Complex::roots_of_unity(4);
+    /// assert_eq!(roots.len(), 4);
+    /// assert!((roots[1] - Complex::I).norm() < 1e-12);
+    /// ```
+    pub fn roots_of_unity(n: u32) -> Vec<Self> {
+        Complex::ONE.roots(n)
+    }
+}
+
+impl From<f64> for Complex {
+    /// Returns the real number as a complex number with no imaginary part.
+    fn from(re: f64) -> Self {
+        Complex::new(re, 0.0)
+    }
+}
+
+impl From<(f64, f64)> for Complex {
+    /// Returns the point of the plane as a complex number, with the x coordinate as the real part and the y coordinate as the imaginary part.
+    fn from((re, im): (f64, f64)) -> Self {
+        Complex::new(re, im)
+    }
+}
+
+impl From<Complex> for (f64, f64) {
+    /// Returns the complex number as a point of the plane.
+    fn from(z: Complex) -> Self {
+        (z.re, z.im)
+    }
+}
+
+impl Display for Complex {
+    /// Formats the number as `a + bi`, leaving out parts that are 0, with the precision applied to both parts.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let part = |value: f64| match f.precision() {
+            Some(precision) => format!("{:.*}", precision, value),
+            None => value.to_string(),
+        };
+        match (self.re, self.im) {
+            (re, 0.0) => write!(f, "{}", part(re)),
+            (0.0, im) => write!(f, "{}i", part(im)),
+            (re, im) if im < 0.0 => write!(f, "{} - {}i", part(re), part(-im)),
+            (re, im) => write!(f, "{} + {}i", part(re), part(im)),
+        }
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, rhs: Complex) -> Self::Output {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, rhs: Complex) -> Self::Output {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    /// Multiplies two complex numbers, multiplying their moduli and adding their arguments.
+    fn mul(self, rhs: Complex) -> Self::Output {
+        Complex::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+
+    fn mul(self, scalar: f64) -> Self::Output {
+        Complex::new(self.re * scalar, self.im * scalar)
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+
+    /// Divides two complex numbers, which gives infinite or NaN parts when dividing by 0 like floats do.
+    fn div(self, rhs: Complex) -> Self::Output {
+        let denominator = rhs.re * rhs.re + rhs.im * rhs.im;
+        let numerator = self * rhs.conj();
+        Complex::new(numerator.re / denominator, numerator.im / denominator)
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Self::Output {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operations() {
+        let z = Complex::new(3.0, 4.0);
+        assert_eq!(z.norm(), 5.0);
+        assert_eq!(z.conj(), Complex::new(3.0, -4.0));
+        assert_eq!(z * z.conj(), Complex::from(25.0));
+        assert_eq!(z / z, Complex::ONE);
+        assert_eq!(z - z, Complex::default());
+        assert_eq!(-z + z, Complex::default());
+        assert_eq!(z.powi(3), z * z * z);
+        assert_eq!(z.powi(0), Complex::ONE);
+        assert!((z.powi(-2) * z.powi(2) - Complex::ONE).norm() < 1e-12);
+        assert_eq!(Complex::new(-2.0, 0.0).to_string(), "-2");
+        assert_eq!(Complex::new(1.5, -0.5).to_string(), "1.5 - 0.5i");
+        assert_eq!(format!("{:.1}", Complex::new(0.0, 0.26)), "0.3i");
+    }
+
+    #[test]
+    fn test_roots() {
+        let z = Complex::new(1.0, 1.0);
+        for n in 1..6 {
+            let roots = z.roots(n);
+            assert_eq!(roots.len(), n as usize);
+            for root in roots {
+                assert!((root.powi(n as i32) - z).norm() < 1e-12);
+            }
+        }
+        assert!(Complex::roots_of_unity(0).is_empty());
+        let sum = Complex::roots_of_unity(5)
+            .into_iter()
+            .fold(Complex::default(), |sum, root| sum + root);
+        assert!(sum.norm() < 1e-12);
+    }
+}
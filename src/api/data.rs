@@ -0,0 +1,246 @@
+//! Module containing functions to load datasets from files, so they can be plotted and animated.
+#![warn(missing_docs)]
+use std::{fs::File, io::Read, path::Path};
+
+use csv::{ReaderBuilder, StringRecord};
+
+use super::point::{Point, PointLike};
+use crate::error::MathvisError;
+
+/// A column of a CSV file, selected either by its position or by its name in the header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// The column at the specified position, starting at 0.
+    Index(usize),
+    /// The column with the specified name in the header.
+    Name(String),
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.into())
+    }
+}
+
+/// Loader for the points of a CSV or TSV file, with the columns used for each coordinate and how the header is handled.
+///
+/// By default, the first two columns are used as the x and y coordinates, the header is detected by
+/// checking whether the first row is numeric, and files with a `.tsv` extension are separated by tabs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use mathvis::api::data::CsvLoader;
+///
+/// let points = CsvLoader::new()
+///     .columns("year", "temperature")
+///     .load("./data/temperatures.csv")
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvLoader {
+    x_column: Column,
+    y_column: Column,
+    header: Option<bool>,
+    delimiter: Option<u8>,
+}
+
+impl Default for CsvLoader {
+    fn default() -> Self {
+        CsvLoader {
+            x_column: Column::Index(0),
+            y_column: Column::Index(1),
+            header: None,
+            delimiter: None,
+        }
+    }
+}
+
+impl CsvLoader {
+    /// Creates a loader with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the columns of the x and y coordinates, by position or by name.
+    pub fn columns(mut self, x: impl Into<Column>, y: impl Into<Column>) -> Self {
+        self.x_column = x.into();
+        self.y_column = y.into();
+        self
+    }
+
+    /// Sets whether the first row is a header, instead of detecting it.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Sets the byte separating the columns, instead of picking it from the file extension.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Loads a point for every row of the file at the specified path.
+    ///
+    /// Returns an Err if the file can't be read, a column doesn't exist or a value isn't a number and an Ok with the points otherwise.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<Vec<Point<f64>>, MathvisError> {
+        let path = path.as_ref();
+        let delimiter = self.delimiter.unwrap_or(
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("tsv") | Some("tab") => b'\t',
+                _ => b',',
+            },
+        );
+        self.read(File::open(path)?, delimiter)
+    }
+
+    /// Loads a point for every row read from a reader, separated by the specified delimiter.
+    fn read<R: Read>(&self, reader: R, delimiter: u8) -> Result<Vec<Point<f64>>, MathvisError> {
+        let mut records = ReaderBuilder::new()
+            .has_headers(false)
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(reader)
+            .into_records()
+            .enumerate()
+            .peekable();
+
+        let first = match records.peek() {
+            Some((_, Ok(record))) => Some(record.clone()),
+            _ => None,
+        };
+        let header = match (self.header, &first) {
+            (Some(header), _) => header,
+            (None, Some(record)) => record.iter().any(|field| field.parse::<f64>().is_err()),
+            (None, None) => false,
+        };
+        let header_record = if header {
+            records.next();
+            first
+        } else {
+            None
+        };
+        let x = column_index(&self.x_column, header_record.as_ref())?;
+        let y = column_index(&self.y_column, header_record.as_ref())?;
+
+        records
+            .map(|(row, record)| {
+                let record =
+                    record.map_err(|err| MathvisError::InvalidArgument(err.to_string()))?;
+                Point::new(vec![
+                    parse_field(&record, x, row)?,
+                    parse_field(&record, y, row)?,
+                ])
+            })
+            .collect()
+    }
+}
+
+/// Loads the points of a CSV or TSV file with the default options of a [CsvLoader].
+///
+/// Returns an Err if the file can't be read, it has less than two columns or a value isn't a number and an Ok with the points otherwise.
+///
+/// # Examples
+///
+/// ```no_run
+/// use mathvis::api::data::load_csv;
+///
+/// let points = load_csv("./data/measurements.csv").unwrap();
+/// ```
+pub fn load_csv(path: impl AsRef<Path>) -> Result<Vec<Point<f64>>, MathvisError> {
+    CsvLoader::new().load(path)
+}
+
+/// Returns the position of a column, looking its name up in the header.
+fn column_index(column: &Column, header: Option<&StringRecord>) -> Result<usize, MathvisError> {
+    match column {
+        Column::Index(index) => Ok(*index),
+        Column::Name(name) => header
+            .ok_or_else(|| {
+                MathvisError::InvalidArgument(format!(
+                    "The column {} can't be found without a header.",
+                    name
+                ))
+            })?
+            .iter()
+            .position(|field| field == name)
+            .ok_or_else(|| {
+                MathvisError::InvalidArgument(format!("There's no column named {}.", name))
+            }),
+    }
+}
+
+/// Parses the value of a column of a row as a number.
+fn parse_field(record: &StringRecord, column: usize, row: usize) -> Result<f64, MathvisError> {
+    let field = record.get(column).ok_or_else(|| {
+        MathvisError::OutOfBounds(format!("Row {} has no column {}.", row + 1, column))
+    })?;
+    field.parse().map_err(|_| {
+        MathvisError::InvalidArgument(format!(
+            "The value {:?} in row {} isn't a number.",
+            field,
+            row + 1
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let source = "time, height, speed\n0, 1.5, 3\n1, 2.5, -4\n";
+        let points = CsvLoader::new()
+            .columns("time", 2)
+            .read(source.as_bytes(), b',')
+            .unwrap();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(vec![0.0, 3.0]).unwrap(),
+                Point::new(vec![1.0, -4.0]).unwrap()
+            ]
+        );
+
+        let points = CsvLoader::new()
+            .read("1\t2\n3\t4".as_bytes(), b'\t')
+            .unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].values(), &vec![3.0, 4.0]);
+
+        let loader = CsvLoader::new().header(false);
+        assert!(matches!(
+            loader.read(source.as_bytes(), b','),
+            Err(MathvisError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            CsvLoader::new().read("1,2\n3".as_bytes(), b','),
+            Err(MathvisError::OutOfBounds(_))
+        ));
+        assert!(CsvLoader::new()
+            .columns("x", "y")
+            .read("1,2".as_bytes(), b',')
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_csv() {
+        let path = std::env::temp_dir().join("mathvis_load_csv_test.tsv");
+        std::fs::write(&path, "x\ty\n-1\t0.5\n").unwrap();
+        assert_eq!(
+            load_csv(&path).unwrap(),
+            vec![Point::new(vec![-1.0, 0.5]).unwrap()]
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(load_csv(&path), Err(MathvisError::Io(_))));
+    }
+}
@@ -0,0 +1,559 @@
+//! Module containing a parser for mathematical expressions written as text, like `sin(x) / x` or `x^2 - 3*x`,
+//! so functions can be described in scene files instead of Rust closures.
+//!
+//! Expressions are made of:
+//! - numbers, like `2`, `0.5` or `1e-3`, and the constants `pi`, `tau` and `e`
+//! - the variables they're parsed with, like `x` and `y`
+//! - `+`, `-`, `*`, `/` and `^` (powers, from right to left), with the usual precedence, and parentheses
+//! - the functions `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `sinh`, `cosh`, `tanh`, `exp`, `ln` (or `log`), `log10`, `log2`,
+//!   `sqrt`, `abs`, `floor`, `ceil`, `round` and `sign` of one argument, and `min`, `max`, `pow` and `atan2` of two
+//!
+//! Multiplication is always written out, so `2*x` instead of `2x`.
+//! Expressions can't be nested more than 256 levels deep, counting parentheses, function calls, signs and every operation in a chain,
+//! so no expression is too deep to parse and evaluate.
+#![warn(missing_docs)]
+use std::{
+    f64::consts::{E, PI, TAU},
+    fmt::Display,
+};
+
+use crate::error::MathvisError;
+
+/// A function of one argument.
+type Unary = fn(f64) -> f64;
+/// A function of two arguments, or a binary operation.
+type Binary = fn(f64, f64) -> f64;
+
+/// Functions of one argument that can be called in expressions.
+const FUNCTIONS: [(&str, Unary); 20] = [
+    ("sin", f64::sin),
+    ("cos", f64::cos),
+    ("tan", f64::tan),
+    ("asin", f64::asin),
+    ("acos", f64::acos),
+    ("atan", f64::atan),
+    ("sinh", f64::sinh),
+    ("cosh", f64::cosh),
+    ("tanh", f64::tanh),
+    ("exp", f64::exp),
+    ("ln", f64::ln),
+    ("log", f64::ln),
+    ("log10", f64::log10),
+    ("log2", f64::log2),
+    ("sqrt", f64::sqrt),
+    ("abs", f64::abs),
+    ("floor", f64::floor),
+    ("ceil", f64::ceil),
+    ("round", f64::round),
+    ("sign", sign),
+];
+
+/// Functions of two arguments that can be called in expressions.
+const BINARY_FUNCTIONS: [(&str, Binary); 4] = [
+    ("min", f64::min),
+    ("max", f64::max),
+    ("pow", f64::powf),
+    ("atan2", f64::atan2),
+];
+
+/// Named constants that can be used in expressions.
+const CONSTANTS: [(&str, f64); 3] = [("pi", PI), ("tau", TAU), ("e", E)];
+
+/// How many levels deep expressions can be nested, which keeps parsing and evaluating them from overflowing the stack.
+const MAX_DEPTH: usize = 256;
+
+/// A parsed expression, which can be evaluated for any values of its variables.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::expression::Expression;
+///
+/// let expression = Expression::parse("x^2 - 3*x + y", &["x", "y"]).unwrap();
+/// assert_eq!(expression.evaluate(&[4.0, 1.0]).unwrap(), 5.0);
+/// assert_eq!(expression.to_string(), "x^2 - 3*x + y");
+/// // Powers go from right to left and come before negation
+/// assert_eq!(Expression::parse("-2^3^2", &[]).unwrap().evaluate(&[]).unwrap(), -512.0);
+/// assert!(Expression::parse("2*z", &["x"]).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    variables: Vec<String>,
+    root: Node,
+}
+
+/// A node of the syntax tree of an expression.
+#[derive(Debug, Clone)]
+enum Node {
+    Number(f64),
+    Variable(usize),
+    Unary(Unary, Box<Node>),
+    Binary(Binary, Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn evaluate(&self, values: &[f64]) -> f64 {
+        match self {
+            Node::Number(value) => *value,
+            Node::Variable(i) => values[*i],
+            Node::Unary(function, argument) => function(argument.evaluate(values)),
+            Node::Binary(function, left, right) => {
+                function(left.evaluate(values), right.evaluate(values))
+            }
+        }
+    }
+}
+
+/// A token of an expression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Number(f64),
+    Name(&'a str),
+    Symbol(char),
+}
+
+impl Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "{}", value),
+            Token::Name(name) => write!(f, "{}", name),
+            Token::Symbol(symbol) => write!(f, "{}", symbol),
+        }
+    }
+}
+
+/// Splits an expression into tokens, each with the position of its first character, counted from 1.
+///
+/// Returns an Err if there's a character that can't start a token and an Ok with the tokens otherwise.
+fn tokenize(source: &str) -> Result<Vec<(Token<'_>, usize)>, MathvisError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while let Some(c) = source[start..].chars().next() {
+        let end = if c.is_whitespace() {
+            start + c.len_utf8()
+        } else if c.is_ascii_digit() || c == '.' {
+            let digits = |from: usize| {
+                (from..bytes.len())
+                    .find(|&i| !bytes[i].is_ascii_digit() && bytes[i] != b'.')
+                    .unwrap_or(bytes.len())
+            };
+            let mut end = digits(start);
+            // Exponents need digits after them, so `2e` is a 2 followed by the constant e
+            if matches!(bytes.get(end), Some(b'e' | b'E')) {
+                let exponent =
+                    end + 1 + usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+                if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+                    end = digits(exponent);
+                }
+            }
+            let value = source[start..end].parse().map_err(|_| {
+                MathvisError::InvalidArgument(format!(
+                    "\"{}\" at position {} of \"{}\" isn't a number.",
+                    &source[start..end],
+                    start + 1,
+                    source
+                ))
+            })?;
+            tokens.push((Token::Number(value), start + 1));
+            end
+        } else if c.is_alphabetic() || c == '_' {
+            let end = source[start..]
+                .char_indices()
+                .find(|&(_, c)| !c.is_alphanumeric() && c != '_')
+                .map_or(source.len(), |(i, _)| start + i);
+            tokens.push((Token::Name(&source[start..end]), start + 1));
+            end
+        } else if "+-*/^(),".contains(c) {
+            tokens.push((Token::Symbol(c), start + 1));
+            start + 1
+        } else {
+            return Err(MathvisError::InvalidArgument(format!(
+                "Unexpected \"{}\" at position {} of \"{}\".",
+                c,
+                start + 1,
+                source
+            )));
+        };
+        start = end;
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser, with one function for every level of precedence.
+struct Parser<'a> {
+    source: &'a str,
+    variables: &'a [&'a str],
+    tokens: Vec<(Token<'a>, usize)>,
+    next: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.next).map(|(token, _)| *token)
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(Token::Symbol(symbol));
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    /// Returns the error for the next token, or for the end of the expression if there are no tokens left.
+    fn unexpected(&self) -> MathvisError {
+        MathvisError::InvalidArgument(match self.tokens.get(self.next) {
+            Some((token, position)) => format!(
+                "Unexpected \"{}\" at position {} of \"{}\".",
+                token, position, self.source
+            ),
+            None => format!("\"{}\" ends too early.", self.source),
+        })
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), MathvisError> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    /// Goes one level deeper into the expression.
+    ///
+    /// Returns an Err if the expression is nested more than [MAX_DEPTH] levels deep and an Ok otherwise.
+    fn descend(&mut self) -> Result<(), MathvisError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(MathvisError::InvalidArgument(format!(
+                "\"{}\" is nested more than {} levels deep.",
+                self.source, MAX_DEPTH
+            )));
+        }
+        Ok(())
+    }
+
+    /// Parses sums and differences.
+    fn sum(&mut self) -> Result<Node, MathvisError> {
+        let (mut node, depth) = (self.product()?, self.depth);
+        loop {
+            let operation: Binary = if self.eat('+') {
+                |a, b| a + b
+            } else if self.eat('-') {
+                |a, b| a - b
+            } else {
+                self.depth = depth;
+                return Ok(node);
+            };
+            // Every operation nests the ones before it one level deeper
+            self.descend()?;
+            node = Node::Binary(operation, Box::new(node), Box::new(self.product()?));
+        }
+    }
+
+    /// Parses products and quotients.
+    fn product(&mut self) -> Result<Node, MathvisError> {
+        let (mut node, depth) = (self.unary()?, self.depth);
+        loop {
+            let operation: Binary = if self.eat('*') {
+                |a, b| a * b
+            } else if self.eat('/') {
+                |a, b| a / b
+            } else {
+                self.depth = depth;
+                return Ok(node);
+            };
+            self.descend()?;
+            node = Node::Binary(operation, Box::new(node), Box::new(self.unary()?));
+        }
+    }
+
+    /// Parses signs, which apply after powers, so `-x^2` is `-(x^2)`.
+    /// Everything nested, like parentheses, function arguments and exponents, goes through here, one level deeper.
+    fn unary(&mut self) -> Result<Node, MathvisError> {
+        self.descend()?;
+        let node = if self.eat('-') {
+            Node::Unary(|a| -a, Box::new(self.unary()?))
+        } else if self.eat('+') {
+            self.unary()?
+        } else {
+            self.power()?
+        };
+        self.depth -= 1;
+        Ok(node)
+    }
+
+    /// Parses powers, which group from right to left and can have signed exponents, like `2^-x`.
+    fn power(&mut self) -> Result<Node, MathvisError> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            Ok(Node::Binary(
+                f64::powf,
+                Box::new(base),
+                Box::new(self.unary()?),
+            ))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// Parses numbers, variables, constants, function calls and parentheses.
+    fn atom(&mut self) -> Result<Node, MathvisError> {
+        let Some(token) = self.peek() else {
+            return Err(self.unexpected());
+        };
+        match token {
+            Token::Number(value) => {
+                self.next += 1;
+                Ok(Node::Number(value))
+            }
+            Token::Symbol('(') => {
+                self.next += 1;
+                let node = self.sum()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Token::Name(name) => {
+                let position = self.tokens[self.next].1;
+                self.next += 1;
+                if let Some(i) = self.variables.iter().position(|variable| *variable == name) {
+                    return Ok(Node::Variable(i));
+                }
+                if let Some((_, value)) = CONSTANTS.iter().find(|(constant, _)| *constant == name) {
+                    return Ok(Node::Number(*value));
+                }
+                if let Some((_, function)) =
+                    FUNCTIONS.iter().find(|(function, _)| *function == name)
+                {
+                    return Ok(Node::Unary(
+                        *function,
+                        Box::new(self.arguments(1)?.remove(0)),
+                    ));
+                }
+                if let Some((_, function)) = BINARY_FUNCTIONS
+                    .iter()
+                    .find(|(function, _)| *function == name)
+                {
+                    let mut arguments = self.arguments(2)?;
+                    let (right, left) = (arguments.remove(1), arguments.remove(0));
+                    return Ok(Node::Binary(*function, Box::new(left), Box::new(right)));
+                }
+                Err(MathvisError::InvalidArgument(format!(
+                    "Unknown name \"{}\" at position {} of \"{}\".",
+                    name, position, self.source
+                )))
+            }
+            Token::Symbol(_) => Err(self.unexpected()),
+        }
+    }
+
+    /// Parses the parenthesized arguments of a function, which must take exactly `count` of them.
+    fn arguments(&mut self, count: usize) -> Result<Vec<Node>, MathvisError> {
+        self.expect('(')?;
+        let mut arguments = vec![self.sum()?];
+        while arguments.len() < count {
+            self.expect(',')?;
+            arguments.push(self.sum()?);
+        }
+        self.expect(')')?;
+        Ok(arguments)
+    }
+}
+
+/// Returns -1 for negative numbers, 1 for positive ones and 0 for zero, unlike [f64::signum].
+fn sign(x: f64) -> f64 {
+    if x == 0.0 {
+        0.0
+    } else {
+        x.signum()
+    }
+}
+
+impl Expression {
+    /// Parses an expression in the specified variables, which can be any names that aren't constants or functions.
+    ///
+    /// Returns an Err with the position of the problem if the expression is invalid or uses a name that isn't
+    /// one of the variables, a constant or a function and an Ok with the expression otherwise.
+    pub fn parse(source: &str, variables: &[&str]) -> Result<Self, MathvisError> {
+        let mut parser = Parser {
+            source,
+            variables,
+            tokens: tokenize(source)?,
+            next: 0,
+            depth: 0,
+        };
+        let root = parser.sum()?;
+        if parser.peek().is_some() {
+            return Err(parser.unexpected());
+        }
+        Ok(Expression {
+            source: source.into(),
+            variables: variables
+                .iter()
+                .map(|variable| variable.to_string())
+                .collect(),
+            root,
+        })
+    }
+
+    /// Returns the variables of the expression, in the order their values are given in.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// Returns the value of the expression for the values of its variables, in order.
+    /// Operations that aren't defined, like dividing by 0, give infinite or NaN values like they do on floats.
+    ///
+    /// Returns an Err if there isn't exactly one value for every variable and an Ok with the value otherwise.
+    pub fn evaluate(&self, values: &[f64]) -> Result<f64, MathvisError> {
+        if values.len() != self.variables.len() {
+            return Err(MathvisError::DimensionMismatch(format!(
+                "\"{}\" has {} variables, but it was given {} values.",
+                self.source,
+                self.variables.len(),
+                values.len()
+            )));
+        }
+        Ok(self.root.evaluate(values))
+    }
+}
+
+impl Display for Expression {
+    /// Writes the expression as it was parsed.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// Parses a function of `x`, like the ones drawn by a [FunctionGraph](crate::animation::graph::FunctionGraph).
+///
+/// Returns an Err if the expression is invalid and an Ok with the function otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::graph::FunctionGraph;
+/// use mathvis::api::expression::parse_function;
+///
+/// let sinc = parse_function("sin(x) / x").unwrap();
+/// assert_eq!(sinc(0.5), 0.5f64.sin() / 0.5);
+/// assert!(sinc(0.0).is_nan());
+/// let graph = FunctionGraph::new(sinc, Rgb([255, 255, 0]));
+/// assert!(parse_function("sin x").is_err());
+/// ```
+pub fn parse_function(
+    source: &str,
+) -> Result<impl Fn(f64) -> f64 + Clone + Send + Sync + 'static, MathvisError> {
+    let expression = Expression::parse(source, &["x"])?;
+    Ok(move |x| expression.root.evaluate(&[x]))
+}
+
+/// Parses a vector field from the expressions of its components in `x` and `y`, like the ones drawn by a
+/// [VectorField](crate::animation::field::VectorField).
+///
+/// Returns an Err if either expression is invalid and an Ok with the field otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::expression::parse_field;
+///
+/// let rotation = parse_field("-y", "x").unwrap();
+/// assert_eq!(rotation(1.0, 2.0), (-2.0, 1.0));
+/// ```
+pub fn parse_field(
+    x: &str,
+    y: &str,
+) -> Result<impl Fn(f64, f64) -> (f64, f64) + Clone + Send + Sync + 'static, MathvisError> {
+    let (dx, dy) = (
+        Expression::parse(x, &["x", "y"])?,
+        Expression::parse(y, &["x", "y"])?,
+    );
+    Ok(move |x, y| (dx.root.evaluate(&[x, y]), dy.root.evaluate(&[x, y])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(source: &str, x: f64) -> f64 {
+        Expression::parse(source, &["x"])
+            .unwrap()
+            .evaluate(&[x])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_expressions() {
+        assert_eq!(evaluate("1 + 2 * 3 - 4 / 2", 0.0), 5.0);
+        assert_eq!(evaluate("(1 + 2) * 3", 0.0), 9.0);
+        assert_eq!(evaluate("2^-x", 1.0), 0.5);
+        assert_eq!(evaluate("-x^2", 3.0), -9.0);
+        assert_eq!(evaluate("--x", 3.0), 3.0);
+        assert_eq!(evaluate("1.5e2 + 25E-1 + .5", 0.0), 153.0);
+        assert_eq!(evaluate("max(x, 2) + min(pow(2, 3), 7)", 1.0), 9.0);
+        assert_eq!(evaluate("sign(x) + sign(0) + abs(-x)", -2.0), 1.0);
+        assert_eq!(
+            evaluate("exp(0) + cos(pi) + sqrt(16) + log10(100)", 0.0),
+            6.0
+        );
+        assert_eq!(evaluate("atan2(1, 1) * 4", 0.0), PI);
+        assert!(evaluate("1 / x", 0.0).is_infinite());
+
+        for invalid in [
+            "",
+            "2x",
+            "x +",
+            "(x",
+            "x)",
+            "sin(x, 2)",
+            "max(x)",
+            "2e",
+            "y",
+            "x $ 2",
+            "1..2",
+            "sin",
+        ] {
+            assert!(
+                matches!(
+                    Expression::parse(invalid, &["x"]),
+                    Err(MathvisError::InvalidArgument(_))
+                ),
+                "{} should be invalid",
+                invalid
+            );
+        }
+        let Err(err) = Expression::parse("x + * 2", &["x"]) else {
+            panic!("x + * 2 should be invalid");
+        };
+        assert_eq!(
+            err.to_string(),
+            "Unexpected \"*\" at position 5 of \"x + * 2\"."
+        );
+        assert!(Expression::parse("x", &["x"])
+            .unwrap()
+            .evaluate(&[])
+            .is_err());
+
+        // Expressions nested too deeply are rejected instead of overflowing the stack
+        let nested = |depth: usize| format!("{}x{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(evaluate(&nested(200), 2.0), 2.0);
+        for deep in [
+            nested(100_000),
+            format!("{}x", "-".repeat(100_000)),
+            format!("x{}", "^x".repeat(100_000)),
+            vec!["x"; 100_000].join("+"),
+        ] {
+            let Err(err) = Expression::parse(&deep, &["x"]) else {
+                panic!("expressions nested too deeply should be invalid");
+            };
+            assert!(err
+                .to_string()
+                .ends_with("is nested more than 256 levels deep."));
+        }
+    }
+}
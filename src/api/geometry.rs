@@ -0,0 +1,201 @@
+//! Module containing the intersections of lines, circles, segments and polygons.
+//!
+//! Intersections are computed in floating point, so shapes that only just touch count as meeting in a single point.
+#![warn(missing_docs)]
+use crate::{
+    animation::geometry::{circle_intersections, Circle, Line},
+    error::MathvisError,
+};
+
+use super::point::{Point, PointLike};
+
+/// How close two results may be, relative to the size of the shapes, to be taken as the same point.
+const EPSILON: f64 = 1e-9;
+
+/// Returns the 2D point with the specified coordinates.
+fn point((x, y): (f64, f64)) -> Point<f64> {
+    // Known to work since x and y always exist
+    Point::new(vec![x, y]).unwrap()
+}
+
+/// Returns the cross product of two 2D vectors, positive if the second turns counterclockwise from the first.
+fn cross(a: (f64, f64), b: (f64, f64)) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+/// Returns the point where two lines cross, or None if they're parallel, which includes being the same line.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::Line;
+/// use mathvis::api::{geometry::line_line, point::{Point, PointLike}};
+///
+/// let diagonal = Line::through((0.0, 0.0), (1.0, 1.0)).unwrap();
+/// let vertical = Line::through((2.0, -1.0), (2.0, 1.0)).unwrap();
+/// assert_eq!(line_line(&diagonal, &vertical), Some(Point::new(vec![2.0, 2.0]).unwrap()));
+///
+/// let parallel = Line::through((0.0, 1.0), (1.0, 2.0)).unwrap();
+/// assert_eq!(line_line(&diagonal, &parallel), None);
+/// ```
+pub fn line_line(a: &Line, b: &Line) -> Option<Point<f64>> {
+    let denominator = cross(a.direction(), b.direction());
+    if denominator.abs() < EPSILON {
+        return None;
+    }
+    let (p, q) = (a.point(), b.point());
+    let t = cross((q.0 - p.0, q.1 - p.1), b.direction()) / denominator;
+    let (dx, dy) = a.direction();
+    Some(point((p.0 + t * dx, p.1 + t * dy)))
+}
+
+/// Returns the points where a line meets a circle, in the order they come along the line's direction:
+/// none if it misses, one if it's tangent and two if it crosses.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::{Circle, Line};
+/// use mathvis::api::{geometry::line_circle, point::PointLike};
+///
+/// let unit = Circle::new((0.0, 0.0), 1.0).unwrap();
+/// let x_axis = Line::through((0.0, 0.0), (1.0, 0.0)).unwrap();
+/// let crossings = line_circle(&x_axis, &unit);
+/// assert_eq!(crossings[0].values(), &vec![-1.0, 0.0]);
+/// assert_eq!(crossings[1].values(), &vec![1.0, 0.0]);
+///
+/// let tangent = Line::through((-1.0, 1.0), (1.0, 1.0)).unwrap();
+/// assert_eq!(line_circle(&tangent, &unit).len(), 1);
+/// ```
+pub fn line_circle(line: &Line, circle: &Circle) -> Vec<Point<f64>> {
+    let foot = line.project(circle.center());
+    let (distance, radius) = (line.distance_to(circle.center()), circle.radius());
+    if distance > radius * (1.0 + EPSILON) {
+        return Vec::new();
+    }
+    let half = (radius * radius - distance * distance).max(0.0).sqrt();
+    if half <= radius * EPSILON {
+        return vec![point(foot)];
+    }
+    let (dx, dy) = line.direction();
+    vec![
+        point((foot.0 - half * dx, foot.1 - half * dy)),
+        point((foot.0 + half * dx, foot.1 + half * dy)),
+    ]
+}
+
+/// Returns the points where two circles meet: none if they're apart, inside one another or the same circle,
+/// one if they touch and two if they cross.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::animation::geometry::Circle;
+/// use mathvis::api::geometry::circle_circle;
+///
+/// let a = Circle::new((0.0, 0.0), 1.0).unwrap();
+/// assert_eq!(circle_circle(&a, &Circle::new((1.0, 0.0), 1.0).unwrap()).len(), 2);
+/// assert_eq!(circle_circle(&a, &Circle::new((2.0, 0.0), 1.0).unwrap()).len(), 1);
+/// assert!(circle_circle(&a, &Circle::new((0.0, 0.0), 0.5).unwrap()).is_empty());
+/// ```
+pub fn circle_circle(a: &Circle, b: &Circle) -> Vec<Point<f64>> {
+    let mut meets = circle_intersections(a.center(), a.radius(), b.center(), b.radius());
+    let scale = a.radius().max(b.radius());
+    if let [(x0, y0), (x1, y1)] = meets[..] {
+        if (x1 - x0).hypot(y1 - y0) <= scale * EPSILON {
+            meets.pop();
+        }
+    }
+    meets.into_iter().map(point).collect()
+}
+
+/// Returns the point where the segments from `a` to `b` and from `c` to `d` cross, or None if they don't,
+/// counting their ends. Segments along the same line don't cross, even if they overlap.
+fn segment_segment(
+    a: (f64, f64),
+    b: (f64, f64),
+    c: (f64, f64),
+    d: (f64, f64),
+) -> Option<(f64, (f64, f64))> {
+    let (r, s) = ((b.0 - a.0, b.1 - a.1), (d.0 - c.0, d.1 - c.1));
+    let denominator = cross(r, s);
+    if denominator.abs() <= EPSILON * r.0.hypot(r.1) * s.0.hypot(s.1) {
+        return None;
+    }
+    let offset = (c.0 - a.0, c.1 - a.1);
+    let (t, u) = (
+        cross(offset, s) / denominator,
+        cross(offset, r) / denominator,
+    );
+    let inside = |v: f64| (-EPSILON..=1.0 + EPSILON).contains(&v);
+    (inside(t) && inside(u)).then_some((t, (a.0 + t * r.0, a.1 + t * r.1)))
+}
+
+/// Returns the points where the segment from `a` to `b` crosses the edges of a closed polygon, given by its vertices,
+/// in order from `a` to `b`. A segment through a vertex meets the polygon there once.
+///
+/// Returns an Err if the polygon has less than 3 vertices and an Ok with the points otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::{geometry::segment_polygon, point::PointLike};
+///
+/// let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+/// let crossings = segment_polygon((-1.0, 1.0), (3.0, 1.0), &square).unwrap();
+/// assert_eq!(crossings[0].values(), &vec![0.0, 1.0]);
+/// assert_eq!(crossings[1].values(), &vec![2.0, 1.0]);
+/// // Through opposite corners, each one only counts once
+/// assert_eq!(segment_polygon((-1.0, -1.0), (3.0, 3.0), &square).unwrap().len(), 2);
+/// assert!(segment_polygon((0.0, 0.0), (1.0, 1.0), &square[..2]).is_err());
+/// ```
+pub fn segment_polygon(
+    a: (f64, f64),
+    b: (f64, f64),
+    polygon: &[(f64, f64)],
+) -> Result<Vec<Point<f64>>, MathvisError> {
+    if polygon.len() < 3 {
+        return Err(MathvisError::InvalidArgument(format!(
+            "A polygon needs at least 3 vertices, but it was given {}.",
+            polygon.len()
+        )));
+    }
+    let mut meets: Vec<(f64, (f64, f64))> = (0..polygon.len())
+        .filter_map(|i| segment_segment(a, b, polygon[i], polygon[(i + 1) % polygon.len()]))
+        .collect();
+    meets.sort_by(|(t, _), (u, _)| t.total_cmp(u));
+    // Crossing through a vertex meets both edges sharing it at the same point
+    meets.dedup_by(|(t, _), (u, _)| (*t - *u).abs() <= EPSILON);
+    Ok(meets.into_iter().map(|(_, meet)| point(meet)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(point: &Point<f64>, (x, y): (f64, f64)) -> bool {
+        (point.values()[0] - x).abs() < 1e-9 && (point.values()[1] - y).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_intersections() {
+        let circle = Circle::new((1.0, 1.0), 2.0).unwrap();
+        let line = Line::through((1.0, -5.0), (1.0, 5.0)).unwrap();
+        let meets = line_circle(&line, &circle);
+        assert!(close(&meets[0], (1.0, -1.0)) && close(&meets[1], (1.0, 3.0)));
+        assert!(line_circle(&Line::through((4.0, 0.0), (4.0, 1.0)).unwrap(), &circle).is_empty());
+        assert!(circle_circle(&circle, &circle).is_empty());
+
+        // Segments stopping short of an edge don't meet it
+        let triangle = [(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)];
+        let meets = segment_polygon((1.0, 1.0), (3.0, 3.0), &triangle).unwrap();
+        assert_eq!(meets.len(), 1);
+        assert!(close(&meets[0], (2.0, 2.0)));
+        assert!(segment_polygon((1.0, 1.0), (1.5, 1.0), &triangle)
+            .unwrap()
+            .is_empty());
+        assert!(segment_polygon((5.0, 5.0), (5.0, 5.0), &triangle)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+//! Module containing intervals of real numbers, used for axis ranges, function domains and parameter ranges.
+#![warn(missing_docs)]
+use std::fmt::{self, Display};
+
+use crate::error::MathvisError;
+
+/// An interval of real numbers from a start to an end, each of which may be open or closed.
+///
+/// Intervals always start before they end, so an interval can never be empty or backwards,
+/// and whatever is built from one (an axis, a domain, a parameter range) doesn't have to check it again.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::interval::Interval;
+///
+/// let unit = Interval::closed(0.0, 1.0).unwrap();
+/// assert!(unit.contains(0.0) && unit.contains(1.0));
+/// assert_eq!(unit.lerp(0.25), 0.25);
+///
+/// let half_open = unit.with_open_end();
+/// assert!(!half_open.contains(1.0));
+/// assert_eq!(half_open.to_string(), "[0, 1)");
+///
+/// assert!(Interval::closed(1.0, -1.0).is_err());
+/// assert!(Interval::open(2.0, 2.0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    start: f64,
+    end: f64,
+    start_open: bool,
+    end_open: bool,
+}
+
+impl Interval {
+    /// The closed interval from 0 to 1, which animations go through from start to end.
+    pub const UNIT: Interval = Interval {
+        start: 0.0,
+        end: 1.0,
+        start_open: false,
+        end_open: false,
+    };
+
+    /// Creates the closed interval from `start` to `end`, which contains both of them.
+    ///
+    /// Returns an Err if the interval doesn't start before it ends and an Ok with the interval otherwise.
+    pub fn closed(start: f64, end: f64) -> Result<Self, MathvisError> {
+        Self::named("interval", start, end)
+    }
+
+    /// Creates the open interval from `start` to `end`, which contains neither of them.
+    ///
+    /// Returns an Err if the interval doesn't start before it ends and an Ok with the interval otherwise.
+    pub fn open(start: f64, end: f64) -> Result<Self, MathvisError> {
+        Ok(Self::closed(start, end)?.with_open_start().with_open_end())
+    }
+
+    /// Creates a closed interval, naming what it is in the error if it doesn't start before it ends,
+    /// like "The x axis must start before it ends, but it goes from 1 to 0.".
+    pub(crate) fn named(what: &str, start: f64, end: f64) -> Result<Self, MathvisError> {
+        if start.partial_cmp(&end) != Some(std::cmp::Ordering::Less) {
+            return Err(MathvisError::InvalidArgument(format!(
+                "The {} must start before it ends, but it goes from {} to {}.",
+                what, start, end
+            )));
+        }
+        Ok(Interval {
+            start,
+            end,
+            start_open: false,
+            end_open: false,
+        })
+    }
+
+    /// Changes the interval so it doesn't contain its start.
+    pub fn with_open_start(mut self) -> Self {
+        self.start_open = true;
+        self
+    }
+
+    /// Changes the interval so it doesn't contain its end.
+    pub fn with_open_end(mut self) -> Self {
+        self.end_open = true;
+        self
+    }
+
+    /// Returns the start of the interval.
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    /// Returns the end of the interval.
+    pub fn end(&self) -> f64 {
+        self.end
+    }
+
+    /// Returns whether the interval leaves out its start.
+    pub fn is_start_open(&self) -> bool {
+        self.start_open
+    }
+
+    /// Returns whether the interval leaves out its end.
+    pub fn is_end_open(&self) -> bool {
+        self.end_open
+    }
+
+    /// Returns the length of the interval, which is always positive.
+    pub fn length(&self) -> f64 {
+        self.end - self.start
+    }
+
+    /// Returns the point in the middle of the interval.
+    pub fn midpoint(&self) -> f64 {
+        (self.start + self.end) / 2.0
+    }
+
+    /// Returns whether the value is in the interval, leaving out the ends that are open.
+    pub fn contains(&self, value: f64) -> bool {
+        let after_start = match self.start_open {
+            true => value > self.start,
+            false => value >= self.start,
+        };
+        let before_end = match self.end_open {
+            true => value < self.end,
+            false => value <= self.end,
+        };
+        after_start && before_end
+    }
+
+    /// Returns the value moved into the interval, as the closest of its ends if it's outside, whether they're open or not.
+    pub fn clamp(&self, value: f64) -> f64 {
+        value.clamp(self.start, self.end)
+    }
+
+    /// Returns the point a fraction `t` of the way through the interval, so 0 is its start and 1 its end.
+    pub fn lerp(&self, t: f64) -> f64 {
+        self.start + t * self.length()
+    }
+
+    /// Returns how far through the interval the value is, the inverse of [Interval::lerp].
+    pub fn fraction(&self, value: f64) -> f64 {
+        (value - self.start) / self.length()
+    }
+
+    /// Returns the overlap of two intervals, or None if they don't overlap in more than a point.
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let (start, start_open) = match self.start.partial_cmp(&other.start)? {
+            std::cmp::Ordering::Less => (other.start, other.start_open),
+            std::cmp::Ordering::Greater => (self.start, self.start_open),
+            std::cmp::Ordering::Equal => (self.start, self.start_open || other.start_open),
+        };
+        let (end, end_open) = match self.end.partial_cmp(&other.end)? {
+            std::cmp::Ordering::Less => (self.end, self.end_open),
+            std::cmp::Ordering::Greater => (other.end, other.end_open),
+            std::cmp::Ordering::Equal => (self.end, self.end_open || other.end_open),
+        };
+        let interval = Interval::closed(start, end).ok()?;
+        Some(Interval {
+            start_open,
+            end_open,
+            ..interval
+        })
+    }
+}
+
+impl TryFrom<(f64, f64)> for Interval {
+    type Error = MathvisError;
+
+    /// Converts a pair of a start and an end into a closed interval, failing if it doesn't start before it ends.
+    fn try_from((start, end): (f64, f64)) -> Result<Self, Self::Error> {
+        Interval::closed(start, end)
+    }
+}
+
+impl TryFrom<(f32, f32)> for Interval {
+    type Error = MathvisError;
+
+    /// Converts a pair of a start and an end into a closed interval, failing if it doesn't start before it ends.
+    fn try_from((start, end): (f32, f32)) -> Result<Self, Self::Error> {
+        Interval::closed(start as f64, end as f64)
+    }
+}
+
+impl From<Interval> for (f64, f64) {
+    /// Converts an interval into the pair of its start and end.
+    fn from(interval: Interval) -> Self {
+        (interval.start, interval.end)
+    }
+}
+
+impl Display for Interval {
+    /// Formats the interval like `[0, 1)`, with a square bracket on the closed ends and a parenthesis on the open ones.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}, {}{}",
+            if self.start_open { '(' } else { '[' },
+            self.start,
+            self.end,
+            if self.end_open { ')' } else { ']' }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intervals() {
+        let interval = Interval::open(-2.0, 2.0).unwrap();
+        assert!(!interval.contains(-2.0) && interval.contains(0.0) && !interval.contains(2.0));
+        assert_eq!(interval.length(), 4.0);
+        assert_eq!(interval.midpoint(), 0.0);
+        assert_eq!(interval.clamp(5.0), 2.0);
+        assert_eq!(interval.fraction(interval.lerp(0.75)), 0.75);
+        assert!(Interval::closed(f64::NAN, 1.0).is_err());
+        assert!(Interval::try_from((1.0f32, 0.0f32)).is_err());
+
+        let other = Interval::closed(0.0, 5.0).unwrap();
+        let overlap = interval.intersection(&other).unwrap();
+        assert_eq!(overlap.to_string(), "[0, 2)");
+        assert!(overlap
+            .intersection(&Interval::closed(2.0, 3.0).unwrap())
+            .is_none());
+    }
+}
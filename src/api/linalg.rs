@@ -0,0 +1,233 @@
+//! Module containing matrix decompositions delegated to nalgebra, only built with the `nalgebra` feature.
+//!
+//! Matrices are converted into f64 nalgebra matrices for the computation and back into their own type,
+//! so nalgebra never shows up in the API.
+#![warn(missing_docs)]
+use nalgebra::DMatrix;
+
+use super::{
+    matrix::Matrix,
+    util::{Number, Signed},
+    vector::Vector,
+};
+use crate::error::MathvisError;
+
+/// The parts a matrix is decomposed into by [Matrix::svd]: U, the singular values and V^T.
+pub type Svd<T> = (Matrix<T>, Vector<T>, Matrix<T>);
+
+/// Maximum number of iterations of the SVD before it's considered to not converge.
+const SVD_MAX_ITERATIONS: usize = 1000;
+
+impl<T> Matrix<T>
+where
+    T: Number,
+{
+    /// Converts the matrix into an nalgebra matrix of f64 values.
+    pub(crate) fn to_nalgebra(&self) -> DMatrix<f64> {
+        DMatrix::from_row_iterator(
+            self.rows,
+            self.cols,
+            self.values.iter().map(|val| val.to_f64()),
+        )
+    }
+
+    /// Converts an nalgebra matrix of f64 values into a matrix of this type.
+    pub(crate) fn from_nalgebra(matrix: &DMatrix<f64>) -> Matrix<T> {
+        let (rows, cols) = matrix.shape();
+        Matrix {
+            values: (0..rows)
+                .flat_map(|row| (0..cols).map(move |col| T::from_f64(matrix[(row, col)])))
+                .collect(),
+            rows,
+            cols,
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Signed,
+{
+    /// Performs Singular Value Decomposition on a matrix of any size, using nalgebra.
+    ///
+    /// The matrix is decomposed into A = U Sigma V^T, where Sigma is the diagonal matrix of the singular values.
+    /// The singular values are sorted in descending order, and for integer types every value is truncated.
+    ///
+    /// Returns an Err if the decomposition doesn't converge and an Ok with U, the singular values and V^T otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// use mathvis::api::point::PointLike;
+    ///
+    /// let matrix = Matrix::new(vec![vec![3.0, 0.0], vec![0.0, -2.0]]).unwrap();
+    /// let (_, sigma, _) = matrix.svd().unwrap();
+    /// assert_eq!(sigma.values(), &vec![3.0, 2.0]);
+    /// ```
+    pub fn svd(&self) -> Result<Svd<T>, MathvisError> {
+        let svd = self
+            .to_nalgebra()
+            .try_svd(true, true, f64::EPSILON, SVD_MAX_ITERATIONS)
+            .ok_or_else(|| MathvisError::InvalidArgument("SVD didn't converge".into()))?;
+        // Both are always computed, since they were requested
+        let (u, v_t) = (svd.u.unwrap(), svd.v_t.unwrap());
+        Ok((
+            Matrix::from_nalgebra(&u),
+            Vector {
+                values: svd
+                    .singular_values
+                    .iter()
+                    .map(|&val| T::from_f64(val))
+                    .collect(),
+            },
+            Matrix::from_nalgebra(&v_t),
+        ))
+    }
+
+    /// Performs QR decomposition on a matrix of any size, using nalgebra.
+    ///
+    /// The matrix is decomposed into A = QR, where Q has orthonormal columns and R is upper triangular.
+    /// For integer types every value is truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new(vec![vec![1.0, 2.0], vec![0.0, 3.0]]).unwrap();
+    /// let (q, r) = matrix.qr();
+    /// assert_eq!((&q * &r).unwrap(), matrix);
+    /// ```
+    pub fn qr(&self) -> (Matrix<T>, Matrix<T>) {
+        let qr = self.to_nalgebra().qr();
+        (
+            Matrix::from_nalgebra(&qr.q()),
+            Matrix::from_nalgebra(&qr.r()),
+        )
+    }
+
+    /// Calculates the eigenvalues and eigenvectors of a symmetric matrix, using nalgebra.
+    ///
+    /// Returns an Err if the matrix is not square or not symmetric and an Ok with the eigenvalues
+    /// and a matrix with the respective eigenvectors as its columns otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// use mathvis::api::point::PointLike;
+    ///
+    /// let matrix = Matrix::new(vec![vec![2.0, 0.0], vec![0.0, 5.0]]).unwrap();
+    /// let (values, vectors) = matrix.symmetric_eigen().unwrap();
+    /// assert_eq!(values.values(), &vec![2.0, 5.0]);
+    /// assert_eq!(vectors, Matrix::identity(2).unwrap());
+    /// assert!(Matrix::new(vec![vec![1.0, 2.0], vec![0.0, 1.0]]).unwrap().symmetric_eigen().is_err());
+    /// ```
+    pub fn symmetric_eigen(&self) -> Result<(Vector<T>, Matrix<T>), MathvisError> {
+        if self.rows != self.cols {
+            return Err(MathvisError::DimensionMismatch(
+                "must be a square matrix".into(),
+            ));
+        }
+        if self.transpose() != *self {
+            return Err(MathvisError::InvalidArgument(
+                "must be a symmetric matrix".into(),
+            ));
+        }
+        let eigen = self.to_nalgebra().symmetric_eigen();
+        Ok((
+            Vector {
+                values: eigen
+                    .eigenvalues
+                    .iter()
+                    .map(|&val| T::from_f64(val))
+                    .collect(),
+            },
+            Matrix::from_nalgebra(&eigen.eigenvectors),
+        ))
+    }
+
+    /// Calculates the real eigenvalues of a square matrix, using nalgebra.
+    ///
+    /// Returns an Err if the matrix is not square or has complex eigenvalues and an Ok with the eigenvalues otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let rotation = Matrix::<f32>::rotation_matrix_2d(std::f32::consts::FRAC_PI_2);
+    /// assert!(rotation.eigenvalues().is_err());
+    /// ```
+    pub fn eigenvalues(&self) -> Result<Vector<T>, MathvisError> {
+        if self.rows != self.cols {
+            return Err(MathvisError::DimensionMismatch(
+                "must be a square matrix".into(),
+            ));
+        }
+        let values = self
+            .to_nalgebra()
+            .eigenvalues()
+            .ok_or_else(|| MathvisError::InvalidArgument("eigenvalues are complex".into()))?;
+        Ok(Vector {
+            values: values.iter().map(|&val| T::from_f64(val)).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::point::PointLike;
+
+    use super::*;
+
+    fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>) {
+        assert_eq!(a.get_dimensions(), b.get_dimensions());
+        for (x, y) in a.values.iter().zip(b.values.iter()) {
+            assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_svd() {
+        let matrix = Matrix::new(vec![vec![1.0, 2.0, 0.0], vec![0.0, 1.0, 3.0]]).unwrap();
+        let (u, sigma, v_t) = matrix.svd().unwrap();
+        let diagonal = Matrix::new(vec![
+            vec![sigma.values()[0], 0.0],
+            vec![0.0, sigma.values()[1]],
+        ])
+        .unwrap();
+        let v_t = Matrix::new(v_t.to_rows()[..2].to_vec()).unwrap();
+        assert_close(&(&(&u * &diagonal).unwrap() * &v_t).unwrap(), &matrix);
+    }
+
+    #[test]
+    fn test_polar_decomposition() {
+        let matrix = Matrix::new(vec![vec![1.0, 2.0], vec![-1.0, 3.0]]).unwrap();
+        let (q, s) = matrix.polar_decomposition_2d().unwrap();
+        assert_close(&(&q * &s).unwrap(), &matrix);
+        assert_close(
+            &(&q * &q.transpose()).unwrap(),
+            &Matrix::identity(2).unwrap(),
+        );
+        assert_close(&s, &s.transpose());
+    }
+
+    #[test]
+    fn test_eigen() {
+        let (values, vectors) = Matrix::new(vec![vec![2.0, 1.0], vec![1.0, 2.0]])
+            .unwrap()
+            .symmetric_eigen()
+            .unwrap();
+        let mut sorted = values.values().clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!((sorted[0] - 1.0).abs() < 1e-9 && (sorted[1] - 3.0).abs() < 1e-9);
+        assert_eq!(vectors.get_dimensions(), (2, 2));
+
+        assert!(Matrix::new(vec![vec![1.0, 2.0, 3.0]])
+            .unwrap()
+            .eigenvalues()
+            .is_err());
+    }
+}
@@ -1,27 +1,30 @@
 //! Module containing a simple implementation of a matrix and its respective operations
 #![warn(missing_docs)]
 use std::{
-    error::Error,
     iter::Sum,
     ops::{AddAssign, Mul},
 };
 
-use rand::{
-    distr::{Distribution, StandardUniform},
-    rng, Rng,
-};
+use rand::distr::{Distribution, StandardUniform};
+use serde::{Deserialize, Serialize};
 
 use super::{
+    angle::Angle,
     point::PointLike,
-    util::{quadsolve, Number},
+    random::sample,
+    util::{quadsolve, Number, Signed},
     vector::Vector,
 };
+use crate::error::MathvisError;
+
+/// The matrices a 2x2 matrix is decomposed into by [Matrix::svd_2d]: U, Sigma and V, with A = U Sigma V^T.
+pub type Svd2D<T> = (Matrix<T>, Matrix<T>, Matrix<T>);
 
-/// A matrix with any width or length implemented using a vector of vectors
+/// A matrix with any width or length implemented using a single vector with its values row by row
 ///
-/// This matrix implementation is generic over any type of number (for simplicity's sake,
-/// unsigned number types must be converted into signed types, might be changed soon) which implements
-/// the traits defined in [Number].
+/// This matrix implementation is generic over any type of number which implements
+/// the traits defined in [Number], signed or unsigned. Operations that need negative values,
+/// like the determinant or the inverse, are only available for the types that implement [Signed].
 ///
 /// A matrix implements PartialEq and Eq which means they can be compared for equality and
 /// the reflexive property holds.
@@ -51,9 +54,37 @@ use super::{
 /// }
 ///
 /// ```
-#[derive(Debug, PartialEq, Clone, Eq)]
+#[derive(Debug, PartialEq, Clone, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Vec<T>>", into = "Vec<Vec<T>>")]
 pub struct Matrix<T: Number> {
-    pub(crate) values: Vec<Vec<T>>,
+    /// Values stored row by row in a single vector, so rows are contiguous in memory.
+    pub(crate) values: Vec<T>,
+    pub(crate) rows: usize,
+    pub(crate) cols: usize,
+}
+
+impl<T> TryFrom<Vec<Vec<T>>> for Matrix<T>
+where
+    T: Number,
+{
+    type Error = MathvisError;
+
+    /// Converts a vector of rows into a matrix, like [Matrix::new].
+    ///
+    /// Returns an Err if the rows are empty or not all the same length and an Ok with the matrix otherwise.
+    fn try_from(rows: Vec<Vec<T>>) -> Result<Self, Self::Error> {
+        Matrix::new(rows)
+    }
+}
+
+impl<T> From<Matrix<T>> for Vec<Vec<T>>
+where
+    T: Number,
+{
+    /// Converts a matrix into its rows, like [Matrix::to_rows].
+    fn from(matrix: Matrix<T>) -> Self {
+        matrix.to_rows()
+    }
 }
 
 impl<T> Matrix<T>
@@ -62,27 +93,62 @@ where
 {
     /// Creates a new Matrix with the specified values.
     ///
-    /// Returns a None if the vector is empty or if all rows are not the same length.
-    /// Returns a Some with the matrix otherwise.
+    /// Returns an Err with an InvalidArgument if there are no rows or the rows are empty,
+    /// an Err with a DimensionMismatch if the rows are not all the same length, and an Ok with the matrix otherwise.
     ///
     /// # Examples
     ///
     /// ```
     /// use mathvis::api::matrix::Matrix;
+    /// use mathvis::error::MathvisError;
+    ///
     /// let m1 = Matrix::<i32>::new(Vec::new());
-    /// let m2 = Matrix::new(vec![vec![1, 1], vec![1, 1]]);
+    /// let m2 = Matrix::new(vec![vec![1, 1], vec![1]]);
+    /// let m3 = Matrix::new(vec![vec![1, 1], vec![1, 1]]);
     ///
-    /// assert!(m1 == None && m2 == Some(Matrix::new(vec![vec![1, 1], vec![1, 1]]).unwrap()));
+    /// assert!(matches!(m1, Err(MathvisError::InvalidArgument(_))));
+    /// assert!(matches!(m2, Err(MathvisError::DimensionMismatch(_))));
+    /// assert!(m3.is_ok());
     /// ```
-    pub fn new(values: Vec<Vec<T>>) -> Option<Self> {
+    pub fn new(values: Vec<Vec<T>>) -> Result<Self, MathvisError> {
         let first_length = values.first().map_or(0, |row| row.len());
-        if values.len() == 0
-            || first_length == 0
-            || !values.iter().all(|row| row.len() == first_length)
+        if first_length == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "A matrix must have at least one row and one column.".into(),
+            ));
+        }
+        if let Some((i, row)) = values
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != first_length)
         {
-            return None;
+            return Err(MathvisError::DimensionMismatch(format!(
+                "Row {} has {} values but the first row has {}.",
+                i,
+                row.len(),
+                first_length
+            )));
         }
-        Some(Matrix { values })
+        Ok(Matrix {
+            rows: values.len(),
+            cols: first_length,
+            values: values.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Creates a new Matrix with the specified values, like [Matrix::new], discarding the reason it's invalid.
+    ///
+    /// Returns a None if the matrix is invalid and a Some with the matrix otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// assert!(Matrix::<i32>::try_new(Vec::new()).is_none());
+    /// ```
+    pub fn try_new(values: Vec<Vec<T>>) -> Option<Self> {
+        Matrix::new(values).ok()
     }
 
     /// Creates an identity matrix with the specified dimensions. By definition,
@@ -102,19 +168,38 @@ where
         if dimensions == 0 {
             return None;
         }
+        let mut values = vec![T::zero(); dimensions * dimensions];
+        for i in 0..dimensions {
+            values[i * dimensions + i] = T::one();
+        }
         Some(Matrix {
-            values: (0..dimensions)
-                .map(|i| {
-                    let mut row = vec![T::zero(); dimensions];
-                    row[i] = T::one();
-                    row
-                })
-                .collect(),
+            values,
+            rows: dimensions,
+            cols: dimensions,
         })
     }
 
-    /// Creates a 2d rotation matrix that when applied to a vector (see [Vector2D](crate::animation::vector::Vector2D)), rotates it by the specified angle in radians.
-    /// around the origin.
+    /// Creates a 2d shear matrix that when applied to a vector (see [Vector2D](crate::animation::vector::Vector2D)), moves (x, y) to (x + kx·y, ky·x + y),
+    /// sliding points horizontally in proportion to their height and vertically in proportion to their distance from the y axis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// let m = Matrix::shear_matrix_2d(2, 0);
+    ///
+    /// assert_eq!(m.to_rows(), vec![vec![1, 2], vec![0, 1]]);
+    /// ```
+    pub fn shear_matrix_2d(kx: T, ky: T) -> Self {
+        Matrix {
+            values: vec![T::one(), kx, ky, T::one()],
+            rows: 2,
+            cols: 2,
+        }
+    }
+
+    /// Creates a 2d rotation matrix that when applied to a vector (see [Vector2D](crate::animation::vector::Vector2D)), rotates it by the specified angle
+    /// around the origin, with plain numbers taken as radians (see [Angle]).
     ///
     /// Since there is no way this method would not work, it always returns a matrix, and for
     /// simplicity's sake, the matrix always contains f32 values, since it works by calculating
@@ -125,19 +210,22 @@ where
     /// ```
     /// use mathvis::api::matrix::Matrix;
     /// use std::f32::consts::PI;
+    /// use mathvis::api::angle::Angle;
     /// let m = Matrix::<f32>::rotation_matrix_2d(PI); // creates a matrix that rotates a vector by PI radians
+    /// assert_eq!(m, Matrix::<f32>::rotation_matrix_2d(Angle::degrees(180.0)));
     /// ```
-    pub fn rotation_matrix_2d(angle: f32) -> Matrix<f32> {
+    pub fn rotation_matrix_2d(angle: impl Into<Angle>) -> Matrix<f32> {
+        let angle = angle.into().to_radians() as f32;
         Matrix {
-            values: vec![
-                vec![angle.cos(), -angle.sin()],
-                vec![angle.sin(), angle.cos()],
-            ],
+            values: vec![angle.cos(), -angle.sin(), angle.sin(), angle.cos()],
+            rows: 2,
+            cols: 2,
         }
     }
 
     /// Creates a random matrix of the specified dimensions.
     /// Not meant to be used for anything other than testing purposes.
+    /// The values are reproducible after calling [set_seed](crate::api::random::set_seed).
     ///
     /// Returns None if the number of rows or columns is 0 and Some with the matrix otherwise.
     fn random((rows, cols): (usize, usize)) -> Option<Self>
@@ -147,11 +235,11 @@ where
         if rows == 0 || cols == 0 {
             return None;
         }
-        let mut rng = rng();
-        let vals: Vec<Vec<T>> = (0..rows)
-            .map(|_| (0..cols).map(|_| rng.random()).collect())
-            .collect();
-        Some(Matrix { values: vals })
+        Some(Matrix {
+            values: sample(rows * cols),
+            rows,
+            cols,
+        })
     }
 
     /// Returns the dimensions of this matrix.
@@ -165,9 +253,70 @@ where
     /// assert_eq!(matrix.get_dimensions(), (2, 2));
     /// ```
     pub fn get_dimensions(&self) -> (usize, usize) {
-        (self.values.len(), self.values[0].len())
+        (self.rows, self.cols)
+    }
+
+    /// Returns the value at the specified row and column, which must be inside the matrix.
+    pub(crate) fn at(&self, row: usize, col: usize) -> T {
+        self.values[row * self.cols + col]
+    }
+
+    /// Returns a slice with the values of the specified row.
+    ///
+    /// Panics if the row is outside the matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new(vec![vec![1, 2], vec![3, 4]]).unwrap();
+    /// assert_eq!(matrix.row(1), &[3, 4]);
+    /// ```
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.values[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Returns the values of the matrix as a vector of rows, the same layout it's created from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let rows = vec![vec![1, 2], vec![3, 4]];
+    /// assert_eq!(Matrix::new(rows.clone()).unwrap().to_rows(), rows);
+    /// ```
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        self.values
+            .chunks(self.cols)
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    /// Applies a function to every value of the matrix, returning a matrix of the results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::<f32>::identity(2).unwrap().map(|val| val as f64 * 3.0);
+    /// assert_eq!(matrix, Matrix::new(vec![vec![3.0, 0.0], vec![0.0, 3.0]]).unwrap());
+    /// ```
+    pub fn map<U: Number>(&self, f: impl Fn(T) -> U) -> Matrix<U> {
+        Matrix {
+            values: self.values.iter().map(|val| f(*val)).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
     }
+}
 
+impl<T> Matrix<T>
+where
+    T: Signed,
+{
     /// Calculates the determinant of the matrix using the definition.
     ///
     /// Returns a Result, returning an Err if the matrix is not square and an Ok otherwise.
@@ -181,24 +330,26 @@ where
     ///
     /// assert!(m.determinant().unwrap() == 1.0);
     /// ```
-    pub fn determinant(&self) -> Result<T, Box<dyn Error>> {
+    pub fn determinant(&self) -> Result<T, MathvisError> {
         if self.get_dimensions().0 != self.get_dimensions().1 {
-            return Err("must be a square matrix".into());
+            return Err(MathvisError::DimensionMismatch(
+                "must be a square matrix".into(),
+            ));
         }
         let size = self.get_dimensions().0;
         if size == 1 {
-            return Ok(self.values[0][0]);
+            return Ok(self.at(0, 0));
         }
 
         let mut curr_determinant = T::zero();
         for col in 0..size {
-            let value = self.values[0][col];
+            let value = self.at(0, col);
             let mut sub_values: Vec<Vec<T>> = Vec::new();
             for row in 1..size {
                 let mut sub_row_values: Vec<T> = Vec::new();
                 for collumn in 0..size {
                     if collumn != col {
-                        sub_row_values.push(self.values[row][collumn]);
+                        sub_row_values.push(self.at(row, collumn));
                     }
                 }
                 sub_values.push(sub_row_values);
@@ -227,15 +378,13 @@ where
     /// assert!(m.transpose() == Matrix::new(vec![vec![1, 1], vec![0, 1]]).unwrap());
     /// ```
     pub fn transpose(&self) -> Matrix<T> {
-        let values: Vec<Vec<T>> = (0..self.get_dimensions().1)
-            .map(|i| {
-                self.values
-                    .iter()
-                    .map(|inner| inner[i].clone())
-                    .collect::<Vec<T>>()
-            })
-            .collect();
-        Matrix { values }
+        Matrix {
+            values: (0..self.cols)
+                .flat_map(|col| (0..self.rows).map(move |row| self.at(row, col)))
+                .collect(),
+            rows: self.cols,
+            cols: self.rows,
+        }
     }
 
     /// Calculates and returns the eigenvalues of a 2x2 matrix.
@@ -251,16 +400,11 @@ where
     /// let matrix = Matrix::<f32>::identity(2).unwrap();
     /// assert_eq!(matrix.eigenvalues_2d().unwrap(), (1.0, 1.0));
     /// ```
-    pub fn eigenvalues_2d(self) -> Result<(T, T), Box<dyn Error>> {
+    pub fn eigenvalues_2d(&self) -> Result<(T, T), MathvisError> {
         if self.get_dimensions() != (2, 2) {
-            return Err("Matrix is not 2x2".into());
+            return Err(MathvisError::DimensionMismatch("Matrix is not 2x2".into()));
         }
-        let (a, b, c, d) = (
-            self.values[0][0],
-            self.values[0][1],
-            self.values[1][0],
-            self.values[1][1],
-        );
+        let (a, b, c, d) = (self.at(0, 0), self.at(0, 1), self.at(1, 0), self.at(1, 1));
         Ok(quadsolve(T::one(), -a - d, -(b * c) + a * d))
     }
 
@@ -268,7 +412,8 @@ where
     ///
     /// Returns an Err if the matrix is not 2x2 and an Ok with the vectors otherwise.
     ///
-    /// Warning: Doesn't work with matrices that have only one distinct eigenvalue currently
+    /// Matrices with a single eigenvalue have the same eigenvector returned twice, unless they're a multiple of the identity,
+    /// where every vector is an eigenvector and the standard basis is returned.
     ///
     /// # Examples
     ///
@@ -280,20 +425,19 @@ where
     /// let matrix = Matrix::new(vec![vec![2, 1], vec![1, 2]]).unwrap();
     /// assert_eq!(matrix.eigenvectors_2d().unwrap(), (Vector::new(vec![1, 1]).unwrap(), Vector::new(vec![-1, 1]).unwrap()));
     /// ```
-    pub fn eigenvectors_2d(self) -> Result<(Vector<T>, Vector<T>), Box<dyn Error>> {
-        let (a, b, c, d) = (
-            self.values[0][0],
-            self.values[0][1],
-            self.values[1][0],
-            self.values[1][1],
-        );
-        if let Ok((l1, l2)) = self.eigenvalues_2d() {
-            return Ok((
-                Vector::new(vec![l1 - a, b]).unwrap().normalize().unwrap(),
-                Vector::new(vec![l2 - d, c]).unwrap().normalize().unwrap(),
-            ));
-        }
-        Err("Matrix is not 2x2".into())
+    pub fn eigenvectors_2d(&self) -> Result<(Vector<T>, Vector<T>), MathvisError> {
+        let (l1, l2) = self.eigenvalues_2d()?;
+        let (a, b, c, d) = (self.at(0, 0), self.at(0, 1), self.at(1, 0), self.at(1, 1));
+        // Each eigenvector is orthogonal to the rows of A - λI, so it's found from the first row that isn't zero
+        let solve = |rows: [(T, T); 2], basis: (T, T)| {
+            rows.into_iter()
+                .find_map(|(x, y)| Vector::new(vec![x, y]).unwrap().normalize().ok())
+                .unwrap_or_else(|| Vector::new(vec![basis.0, basis.1]).unwrap())
+        };
+        Ok((
+            solve([(b, l1 - a), (l1 - d, c)], (T::one(), T::zero())),
+            solve([(l2 - d, c), (b, l2 - a)], (T::zero(), T::one())),
+        ))
     }
 
     /// Calculates and returns the inverse of a 2x2 matrix.
@@ -309,57 +453,127 @@ where
     /// let matrix = Matrix::<i32>::identity(2).unwrap();
     /// assert_eq!(matrix.invert_2d().unwrap(), Matrix::<i32>::identity(2).unwrap());
     /// ```
-    pub fn invert_2d(self) -> Result<Matrix<T>, Box<dyn Error>> {
+    pub fn invert_2d(&self) -> Result<Matrix<T>, MathvisError> {
         if self.get_dimensions() != (2, 2) {
-            return Err("Matrix is not 2x2".into());
+            return Err(MathvisError::DimensionMismatch("Matrix is not 2x2".into()));
         }
-        let (a, b, c, d) = (
-            self.values[0][0],
-            self.values[0][1],
-            self.values[1][0],
-            self.values[1][1],
-        );
-        Ok(Matrix::new(vec![vec![d, -b], vec![-c, a]]).unwrap()
-            * (T::one() / self.determinant()?))
+        let determinant = self.determinant()?;
+        if determinant.is_zero() {
+            return Err(MathvisError::SingularMatrix);
+        }
+        let (a, b, c, d) = (self.at(0, 0), self.at(0, 1), self.at(1, 0), self.at(1, 1));
+        Ok(Matrix::new(vec![vec![d, -b], vec![-c, a]]).unwrap() * (T::one() / determinant))
     }
 
     /// Performs Singular Value Decomposition on a 2x2 matrix.
     ///
-    /// SVD is a similar process to diagonalization, but it's performed on A^T A, and the diagonal matrix
-    /// contains the singular values, which are the square root of the eigenvalues.
+    /// The matrix is decomposed into A = U Sigma V^T, where Sigma is the diagonal matrix of the singular values,
+    /// sorted in descending order, and U and V have orthonormal columns.
+    /// SVD is a similar process to diagonalization, but it's performed on A^T A: its eigenvectors are the columns of V
+    /// and the singular values are the square roots of its eigenvalues.
     ///
     /// Returns an Err if the matrix is not 2x2 and an Ok with the matrices U, Sigma and V inside otherwise.
-    pub fn svd_2d(self) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Box<dyn Error>> {
-        let transpose_a_by_a = (self.transpose() * self.clone())?;
-        if let Ok((l1, l2)) = transpose_a_by_a.clone().eigenvalues_2d() {
-            let sigma =
-                Matrix::new(vec![vec![l1.sqrt(), T::zero()], vec![T::zero(), l2.sqrt()]]).unwrap();
-            let (v1, v2) = transpose_a_by_a.eigenvectors_2d()?;
-            let u = Matrix::new(vec![
-                vec![v1.values()[0], v2.values()[0]],
-                vec![v1.values()[1], v2.values()[1]],
-            ])
-            .unwrap();
-            let v = u.clone().invert_2d()?;
-            return Ok((u, sigma, v));
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new(vec![vec![0.0, -2.0], vec![3.0, 0.0]]).unwrap();
+    /// let (u, sigma, v) = matrix.svd_2d().unwrap();
+    /// assert_eq!(sigma, Matrix::new(vec![vec![3.0, 0.0], vec![0.0, 2.0]]).unwrap());
+    /// assert_eq!((&(&u * &sigma).unwrap() * &v.transpose()).unwrap().map(f64::round), matrix);
+    /// ```
+    #[cfg(not(feature = "nalgebra"))]
+    pub fn svd_2d(&self) -> Result<Svd2D<T>, MathvisError> {
+        if self.get_dimensions() != (2, 2) {
+            return Err(MathvisError::DimensionMismatch("Matrix is not 2x2".into()));
+        }
+        let gram = (&self.transpose() * self)?;
+        let (l1, l2) = gram.eigenvalues_2d()?;
+        let (v1, v2) = gram.eigenvectors_2d()?;
+        // Sorted in descending order like nalgebra's, and rounding errors can't make them negative
+        let ((l1, v1), (l2, v2)) = if l1 >= l2 {
+            ((l1, v1), (l2, v2))
         } else {
-            Err("Matrix is not 2x2".into())
+            ((l2, v2), (l1, v1))
+        };
+        let singular = |l: T| if l > T::zero() { l.sqrt() } else { T::zero() };
+        let (s1, s2) = (singular(l1), singular(l2));
+        let (v1, v2) = (
+            (v1.values()[0], v1.values()[1]),
+            (v2.values()[0], v2.values()[1]),
+        );
+        // Each column of U is A v / sigma, and where sigma is 0 it's completed into an orthonormal basis
+        let (a, b, c, d) = (self.at(0, 0), self.at(0, 1), self.at(1, 0), self.at(1, 1));
+        let column = |(x, y): (T, T), s: T| ((a * x + b * y) / s, (c * x + d * y) / s);
+        let u1 = match s1 > T::zero() {
+            true => column(v1, s1),
+            false => (T::one(), T::zero()),
+        };
+        let u2 = match s2 > T::zero() {
+            true => column(v2, s2),
+            false => (T::zero() - u1.1, u1.0),
+        };
+        Ok((
+            Matrix::new(vec![vec![u1.0, u2.0], vec![u1.1, u2.1]]).unwrap(),
+            Matrix::new(vec![vec![s1, T::zero()], vec![T::zero(), s2]]).unwrap(),
+            Matrix::new(vec![vec![v1.0, v2.0], vec![v1.1, v2.1]]).unwrap(),
+        ))
+    }
+
+    /// Performs Singular Value Decomposition on a 2x2 matrix, using nalgebra.
+    ///
+    /// The matrix is decomposed into A = U Sigma V^T, where Sigma is the diagonal matrix of the singular values,
+    /// sorted in descending order, and U and V have orthonormal columns. See [Matrix::svd] for matrices of any size.
+    ///
+    /// Returns an Err if the matrix is not 2x2 or the decomposition doesn't converge and an Ok with the matrices U, Sigma and V inside otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new(vec![vec![0.0, -2.0], vec![3.0, 0.0]]).unwrap();
+    /// let (u, sigma, v) = matrix.svd_2d().unwrap();
+    /// assert_eq!(sigma, Matrix::new(vec![vec![3.0, 0.0], vec![0.0, 2.0]]).unwrap());
+    /// assert_eq!((&(&u * &sigma).unwrap() * &v.transpose()).unwrap().map(f64::round), matrix);
+    /// ```
+    #[cfg(feature = "nalgebra")]
+    pub fn svd_2d(&self) -> Result<Svd2D<T>, MathvisError> {
+        if self.get_dimensions() != (2, 2) {
+            return Err(MathvisError::DimensionMismatch("Matrix is not 2x2".into()));
         }
+        let (u, sigma, v_t) = self.svd()?;
+        let (s1, s2) = (sigma.values()[0], sigma.values()[1]);
+        let sigma = Matrix::new(vec![vec![s1, T::zero()], vec![T::zero(), s2]]).unwrap();
+        Ok((u, sigma, v_t.transpose()))
     }
 
-    /// Performs polar decomposition of a 2x2 matrix.
+    /// Performs polar decomposition of a 2x2 matrix, from its SVD.
     ///
-    /// This process consists in the separation of a matrix in a rotation and scaling matrix, using SVD.
-    /// Warning: Currently doesn't work properly.
+    /// This process consists in the separation of a matrix in a rotation (or reflection) Q and a symmetric scaling matrix S.
+    /// The matrix is decomposed into A = QS, so transforming by S and then by Q is the same as transforming by A.
     ///
     /// Returns an Err if the matrix is not 2x2 and an Ok with both the rotation and scaling matrices otherwise.
-    pub fn polar_decomposition_2d(self) -> Result<(Matrix<T>, Matrix<T>), Box<dyn Error>> {
-        if let Ok((u, sigma, v)) = self.clone().svd_2d() {
-            let s = ((u * sigma)? * v)?;
-            let q = (self * s.clone().invert_2d()?)?;
-            return Ok((q, s));
-        }
-        Err("Matrix is not 2x2".into())
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    ///
+    /// let matrix = Matrix::new(vec![vec![0.0, -2.0], vec![2.0, 0.0]]).unwrap();
+    /// let (q, s) = matrix.polar_decomposition_2d().unwrap();
+    /// // Rounded, since the decomposition is only exact up to floating point errors
+    /// assert_eq!(s.map(f64::round), Matrix::new(vec![vec![2.0, 0.0], vec![0.0, 2.0]]).unwrap());
+    /// assert_eq!((&q * &s).unwrap().map(f64::round), matrix);
+    /// ```
+    pub fn polar_decomposition_2d(&self) -> Result<(Matrix<T>, Matrix<T>), MathvisError> {
+        let (u, sigma, v) = self.svd_2d()?;
+        let v_t = v.transpose();
+        let q = (&u * &v_t)?;
+        let s = (&(&v * &sigma)? * &v_t)?;
+        Ok((q, s))
     }
 }
 
@@ -368,7 +582,7 @@ where
     T: Number + AddAssign<T> + Mul<U, Output = T>,
     U: Number + Mul<T, Output = U>,
 {
-    type Output = Result<Matrix<T>, Box<dyn Error>>;
+    type Output = Result<Matrix<T>, MathvisError>;
 
     /// Multiplies two matrices together.
     ///
@@ -384,23 +598,107 @@ where
     /// assert_eq!((m1.clone() * i).unwrap(), m1);
     /// ```
     fn mul(self, rhs: Matrix<U>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T, U> Mul<&Matrix<U>> for &Matrix<T>
+where
+    T: Number + AddAssign<T> + Mul<U, Output = T>,
+    U: Number + Mul<T, Output = U>,
+{
+    type Output = Result<Matrix<T>, MathvisError>;
+
+    /// Multiplies two borrowed matrices together, without consuming either of them.
+    ///
+    /// Returns an Err if the dimensions aren't fit for matrix multiplication and an Ok with the result otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// let m1 = Matrix::new(vec![vec![1, 1], vec![1, 1]]).unwrap();
+    /// let i = Matrix::<i32>::identity(2).unwrap();
+    ///
+    /// assert_eq!((&m1 * &i).unwrap(), m1);
+    /// ```
+    fn mul(self, rhs: &Matrix<U>) -> Self::Output {
         if self.get_dimensions().1 != rhs.get_dimensions().0 {
-            return Err("Wrong dimensions.".into());
+            return Err(MathvisError::DimensionMismatch("Wrong dimensions.".into()));
         }
-        let (a, b) = (&self.values, &rhs.values);
-        let (n, p) = (self.get_dimensions().0, rhs.get_dimensions().1);
-        let mut c = vec![vec![T::zero(); p]; n];
-        for i in 0..n {
-            for j in 0..p {
-                let mut sum = T::zero();
-                for k in 0..self.get_dimensions().1 {
-                    sum += a[i][k] * b[k][j]
+        let (n, m, p) = (
+            self.get_dimensions().0,
+            self.get_dimensions().1,
+            rhs.get_dimensions().1,
+        );
+        let values = if n * m * p < BLOCKED_MULTIPLICATION_THRESHOLD {
+            multiply_naive(self, rhs)
+        } else {
+            multiply_blocked(self, rhs)
+        };
+        Ok(Matrix {
+            values,
+            rows: n,
+            cols: p,
+        })
+    }
+}
+
+/// Number of multiplications (rows * shared dimension * columns) from which the blocked algorithm is used.
+/// Below it, every matrix fits in the cache anyway and the simple loop is faster.
+const BLOCKED_MULTIPLICATION_THRESHOLD: usize = 64 * 64 * 64;
+
+/// Side of the square blocks the matrices are split into, chosen so three blocks of f64 fit in a typical L1 cache.
+const BLOCK_SIZE: usize = 32;
+
+/// Multiplies two matrices with the textbook triple loop, the dimensions must already be checked.
+fn multiply_naive<T, U>(a: &Matrix<T>, b: &Matrix<U>) -> Vec<T>
+where
+    T: Number + AddAssign<T> + Mul<U, Output = T>,
+    U: Number,
+{
+    let (n, m, p) = (a.rows, a.cols, b.cols);
+    let mut c = vec![T::zero(); n * p];
+    for i in 0..n {
+        for j in 0..p {
+            let mut sum = T::zero();
+            for k in 0..m {
+                sum += a.at(i, k) * b.at(k, j)
+            }
+            c[i * p + j] = sum;
+        }
+    }
+    c
+}
+
+/// Multiplies two matrices one block at a time, the dimensions must already be checked.
+///
+/// Each block of the result is accumulated from blocks of both matrices that stay in the cache,
+/// and the innermost loop walks rows of the right matrix sequentially instead of striding down its columns.
+fn multiply_blocked<T, U>(a: &Matrix<T>, b: &Matrix<U>) -> Vec<T>
+where
+    T: Number + AddAssign<T> + Mul<U, Output = T>,
+    U: Number,
+{
+    let (n, m, p) = (a.rows, a.cols, b.cols);
+    let mut c = vec![T::zero(); n * p];
+    for i_block in (0..n).step_by(BLOCK_SIZE) {
+        for k_block in (0..m).step_by(BLOCK_SIZE) {
+            for j_block in (0..p).step_by(BLOCK_SIZE) {
+                let j_end = (j_block + BLOCK_SIZE).min(p);
+                for i in i_block..(i_block + BLOCK_SIZE).min(n) {
+                    let c_row = &mut c[i * p + j_block..i * p + j_end];
+                    for k in k_block..(k_block + BLOCK_SIZE).min(m) {
+                        let a_ik = a.at(i, k);
+                        for (c_ij, b_kj) in c_row.iter_mut().zip(&b.row(k)[j_block..j_end]) {
+                            *c_ij += a_ik * *b_kj;
+                        }
+                    }
                 }
-                c[i][j] = sum;
             }
         }
-        Ok(Matrix { values: c })
     }
+    c
 }
 
 impl<T, U> Mul<U> for Matrix<T>
@@ -422,13 +720,28 @@ where
     /// assert!(m1 * 2.0 == Matrix::new(vec![vec![2.0, 0.0], vec![0.0, 2.0]]).unwrap());
     /// ```
     fn mul(self, scalar: U) -> Self::Output {
-        Matrix {
-            values: self
-                .values
-                .iter()
-                .map(|row| row.iter().map(|val| val.clone() * scalar).collect())
-                .collect(),
-        }
+        &self * scalar
+    }
+}
+
+impl<T, U> Mul<U> for &Matrix<T>
+where
+    T: Number + Mul<U, Output = U>,
+    U: Number,
+{
+    type Output = Matrix<U>;
+
+    /// Scales a borrowed matrix by a scalar value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// let m1 = Matrix::<f32>::identity(2).unwrap();
+    /// assert!(&m1 * 2.0 == Matrix::new(vec![vec![2.0, 0.0], vec![0.0, 2.0]]).unwrap());
+    /// ```
+    fn mul(self, scalar: U) -> Self::Output {
+        self.map(|val| val * scalar)
     }
 }
 
@@ -436,7 +749,7 @@ impl<T> Mul<Vector<T>> for Matrix<T>
 where
     T: Number + Sum,
 {
-    type Output = Result<Vector<T>, Box<dyn Error>>;
+    type Output = Result<Vector<T>, MathvisError>;
 
     /// Multiplies a vector by a matrix.
     ///
@@ -455,13 +768,42 @@ where
     /// assert!((m1 * v.clone()).unwrap() == v);
     /// ```
     fn mul(self, rhs: Vector<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T> Mul<&Vector<T>> for &Matrix<T>
+where
+    T: Number + Sum,
+{
+    type Output = Result<Vector<T>, MathvisError>;
+
+    /// Multiplies a borrowed vector by a borrowed matrix.
+    ///
+    /// Returns an Err if the matrix height is not the same as the vector's dimension and an Ok with the result otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// use mathvis::api::vector::Vector;
+    /// use mathvis::api::point::PointLike;
+    ///
+    /// let m1 = Matrix::<i32>::identity(2).unwrap();
+    /// let v = Vector::random(2).unwrap();
+    ///
+    /// assert!((&m1 * &v).unwrap() == v);
+    /// ```
+    fn mul(self, rhs: &Vector<T>) -> Self::Output {
         if self.get_dimensions().1 != rhs.get_dimensions() {
-            return Err("Matrix must be mxn to multiply by vector of size n.".into());
+            return Err(MathvisError::DimensionMismatch(
+                "Matrix must be mxn to multiply by vector of size n.".into(),
+            ));
         }
         Ok(Vector {
             values: self
                 .values
-                .iter()
+                .chunks(self.cols)
                 .map(|row| {
                     row.iter()
                         .zip(rhs.values().iter())
@@ -477,16 +819,57 @@ where
 mod tests {
     use super::*;
 
+    fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>) {
+        assert_eq!(a.get_dimensions(), b.get_dimensions());
+        for (x, y) in a.values.iter().zip(b.values.iter()) {
+            assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_decompositions_2d() {
+        // The same conventions with and without nalgebra, including for singular and reflecting matrices
+        let identity = Matrix::identity(2).unwrap();
+        for rows in [
+            vec![vec![1.0, 2.0], vec![-1.0, 3.0]],
+            vec![vec![2.0, 1.0], vec![1.0, 2.0]],
+            vec![vec![1.0, 1.0], vec![1.0, 1.0]],
+            vec![vec![0.0, 1.0], vec![1.0, 0.0]],
+        ] {
+            let matrix = Matrix::new(rows).unwrap();
+            let (u, sigma, v) = matrix.svd_2d().unwrap();
+            assert_close(
+                &(&(&u * &sigma).unwrap() * &v.transpose()).unwrap(),
+                &matrix,
+            );
+            assert_close(&(&u.transpose() * &u).unwrap(), &identity);
+            assert_close(&(&v.transpose() * &v).unwrap(), &identity);
+            assert!(sigma.at(0, 0) >= sigma.at(1, 1) && sigma.at(1, 1) >= 0.0);
+
+            let (q, s) = matrix.polar_decomposition_2d().unwrap();
+            assert_close(&(&q * &s).unwrap(), &matrix);
+            assert_close(&(&q.transpose() * &q).unwrap(), &identity);
+            assert_close(&s, &s.transpose());
+        }
+        assert!(Matrix::<f64>::identity(3).unwrap().svd_2d().is_err());
+    }
+
     #[test]
     fn test_empty() {
         let vals: Vec<Vec<f32>> = Vec::new();
-        assert!(Matrix::new(vals) == None);
+        assert!(matches!(
+            Matrix::new(vals),
+            Err(MathvisError::InvalidArgument(_))
+        ));
     }
 
     #[test]
     fn test_different_lengths() {
         let vals = vec![vec![1.0, 2.0, 3.0], vec![1.0, 2.0]];
-        assert!(Matrix::new(vals) == None);
+        assert!(matches!(
+            Matrix::new(vals),
+            Err(MathvisError::DimensionMismatch(_))
+        ));
     }
 
     #[test]
@@ -504,6 +887,31 @@ mod tests {
         assert!((a * b).unwrap() == c);
     }
 
+    #[test]
+    fn test_multiply_borrowed() {
+        let a = Matrix::new(vec![vec![1.0, 1.0], vec![1.0, 1.0]]).unwrap();
+        let b = Matrix::new(vec![vec![1.0, 1.0], vec![0.0, 1.0]]).unwrap();
+        let v = Vector::new(vec![1.0, 2.0]).unwrap();
+        assert!((&a * &b).unwrap() == (a.clone() * b.clone()).unwrap());
+        assert!((&a * &v).unwrap() == (a.clone() * v.clone()).unwrap());
+        assert!(&b * 2.0 == b * 2.0);
+    }
+
+    #[test]
+    fn test_multiply_blocked() {
+        // Integers keep both algorithms exact, so their results can be compared directly
+        let a: Vec<Vec<i64>> = (0..70)
+            .map(|i| (0..90).map(|j| (i * 7 + j * 3) % 11 - 5).collect())
+            .collect();
+        let b: Vec<Vec<i64>> = (0..90)
+            .map(|i| (0..50).map(|j| (i * 5 + j) % 13 - 6).collect())
+            .collect();
+        let (a, b) = (Matrix::new(a).unwrap(), Matrix::new(b).unwrap());
+        assert_eq!(multiply_blocked(&a, &b), multiply_naive(&a, &b));
+        let product = (a * b).unwrap();
+        assert_eq!(product.get_dimensions(), (70, 50));
+    }
+
     #[test]
     fn test_determinant() {
         let a = Matrix::new(vec![
@@ -522,6 +930,40 @@ mod tests {
         assert!((a * v).unwrap() == Vector::new(vec![1, -3]).unwrap());
     }
 
+    #[test]
+    fn test_flat_layout() {
+        let a = Matrix::new(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!(a.values, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(a.row(0), &[1, 2, 3]);
+        assert_eq!(
+            a.transpose().to_rows(),
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]]
+        );
+        assert_eq!((a * 2).at(1, 2), 12);
+    }
+
+    #[test]
+    fn test_eigenvectors_2d() {
+        let matrix = Matrix::new(vec![vec![2.0, 0.0], vec![1.0, 3.0]]).unwrap();
+        let (v1, v2) = matrix.eigenvectors_2d().unwrap();
+        assert_eq!(v1, Vector::new(vec![0.0, 1.0]).unwrap());
+        let scaled = (&matrix * &v2).unwrap();
+        assert!(scaled
+            .values()
+            .iter()
+            .zip(v2.values())
+            .all(|(s, v)| (s - 2.0 * v).abs() < 1e-12));
+
+        let scaling = Matrix::new(vec![vec![2.0, 0.0], vec![0.0, 2.0]]).unwrap();
+        assert_eq!(
+            scaling.eigenvectors_2d().unwrap(),
+            (
+                Vector::new(vec![1.0, 0.0]).unwrap(),
+                Vector::new(vec![0.0, 1.0]).unwrap()
+            )
+        );
+    }
+
     #[test]
     fn test_transpose() {
         let a = Matrix::new(vec![vec![1, 0], vec![1, 1]]).unwrap();
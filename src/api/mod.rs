@@ -1,6 +1,24 @@
+pub mod angle;
+#[cfg(feature = "ndarray")]
+pub mod arrays;
+pub mod bounds;
+pub mod calculus;
+pub mod color;
+pub mod colormap;
+pub mod complex;
+pub mod data;
+pub mod expression;
+pub mod geometry;
+pub mod interval;
+#[cfg(feature = "nalgebra")]
+pub mod linalg;
 pub mod matrix;
+pub mod ode;
 pub mod point;
+pub mod quaternion;
+pub mod random;
 pub mod screen;
 pub mod simple;
+pub mod transform;
 pub mod util;
 pub mod vector;
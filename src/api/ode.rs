@@ -0,0 +1,153 @@
+//! Module containing a numerical solver for systems of ordinary differential equations, using the classic Runge-Kutta method (RK4).
+#![warn(missing_docs)]
+use crate::error::MathvisError;
+
+/// Advances the solution of y' = f(t, y) by one RK4 step of size h from the state y at time t.
+///
+/// Returns an Err if the derivative doesn't have as many components as the state and an Ok with the next state otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::ode::rk4_step;
+///
+/// // y' = y, so one step from y(0) = 1 is close to e^0.1
+/// let next = rk4_step(&|_, y: &[f64]| vec![y[0]], 0.0, &[1.0], 0.1).unwrap();
+/// assert!((next[0] - 0.1f64.exp()).abs() < 1e-6);
+/// ```
+pub fn rk4_step<F>(f: &F, t: f64, y: &[f64], h: f64) -> Result<Vec<f64>, MathvisError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    let derivative = |t: f64, y: &[f64]| {
+        let dy = f(t, y);
+        if dy.len() != y.len() {
+            return Err(MathvisError::DimensionMismatch(format!(
+                "The derivative has {} components, but the state has {}.",
+                dy.len(),
+                y.len()
+            )));
+        }
+        Ok(dy)
+    };
+    let shifted = |k: &[f64], scale: f64| -> Vec<f64> {
+        y.iter().zip(k).map(|(y, k)| y + scale * k).collect()
+    };
+    let k1 = derivative(t, y)?;
+    let k2 = derivative(t + h / 2.0, &shifted(&k1, h / 2.0))?;
+    let k3 = derivative(t + h / 2.0, &shifted(&k2, h / 2.0))?;
+    let k4 = derivative(t + h, &shifted(&k3, h))?;
+    Ok((0..y.len())
+        .map(|i| y[i] + h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+        .collect())
+}
+
+/// Solves y' = f(t, y) with y(t0) = y0 from t0 to t1 in the specified number of equal RK4 steps.
+///
+/// Returns an Err if there are no steps or the derivative doesn't have as many components as the state,
+/// and an Ok with the time and state at every step otherwise, starting with (t0, y0) and ending at t1.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::ode::solve;
+///
+/// // A harmonic oscillator goes around a full circle in 2π
+/// let oscillator = |_, y: &[f64]| vec![y[1], -y[0]];
+/// let solution = solve(oscillator, 0.0, vec![1.0, 0.0], std::f64::consts::TAU, 200).unwrap();
+/// assert_eq!(solution.len(), 201);
+/// let (t, y) = solution.last().unwrap();
+/// assert!((t - std::f64::consts::TAU).abs() < 1e-12);
+/// assert!((y[0] - 1.0).abs() < 1e-6 && y[1].abs() < 1e-6);
+/// ```
+pub fn solve<F>(
+    f: F,
+    t0: f64,
+    y0: Vec<f64>,
+    t1: f64,
+    steps: u32,
+) -> Result<Vec<(f64, Vec<f64>)>, MathvisError>
+where
+    F: Fn(f64, &[f64]) -> Vec<f64>,
+{
+    if steps == 0 {
+        return Err(MathvisError::InvalidArgument(
+            "At least one step is needed to solve an ODE.".into(),
+        ));
+    }
+    let h = (t1 - t0) / steps as f64;
+    let mut solution = Vec::with_capacity(steps as usize + 1);
+    let mut y = y0;
+    for i in 0..steps {
+        let t = t0 + h * i as f64;
+        let next = rk4_step(&f, t, &y, h)?;
+        solution.push((t, y));
+        y = next;
+    }
+    solution.push((t1, y));
+    Ok(solution)
+}
+
+/// Solves the autonomous planar system (x', y') = f(x, y) from a starting point, for the specified time and number of steps.
+///
+/// Returns an Err if there are no steps and an Ok with the point at every step otherwise, starting with the starting point.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::ode::solve_2d;
+///
+/// // Every point decays towards the origin
+/// let trajectory = solve_2d(|x, y| (-x, -y), (2.0, 1.0), 10.0, 100).unwrap();
+/// let (x, y) = trajectory.last().unwrap();
+/// assert!(x.abs() < 1e-3 && y.abs() < 1e-3);
+/// ```
+pub fn solve_2d<F>(
+    f: F,
+    start: (f64, f64),
+    time: f64,
+    steps: u32,
+) -> Result<Vec<(f64, f64)>, MathvisError>
+where
+    F: Fn(f64, f64) -> (f64, f64),
+{
+    let system = |_, y: &[f64]| {
+        let (dx, dy) = f(y[0], y[1]);
+        vec![dx, dy]
+    };
+    Ok(solve(system, 0.0, vec![start.0, start.1], time, steps)?
+        .into_iter()
+        .map(|(_, y)| (y[0], y[1]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        // y' = t, so y = t² / 2, which RK4 integrates exactly
+        let solution = solve(|t, _| vec![t], 0.0, vec![0.0], 2.0, 4).unwrap();
+        assert_eq!(solution.len(), 5);
+        for (t, y) in solution {
+            assert!((y[0] - t * t / 2.0).abs() < 1e-12);
+        }
+        assert!(solve(|_, _| vec![1.0], 0.0, vec![0.0], 1.0, 0).is_err());
+        assert!(matches!(
+            solve(|_, _| vec![1.0, 2.0], 0.0, vec![0.0], 1.0, 10),
+            Err(MathvisError::DimensionMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_accuracy() {
+        // The error of RK4 shrinks with the fourth power of the step size
+        let error = |steps| {
+            let solution = solve(|_, y| vec![y[0]], 0.0, vec![1.0], 1.0, steps).unwrap();
+            (solution.last().unwrap().1[0] - 1f64.exp()).abs()
+        };
+        let ratio = error(10) / error(20);
+        assert!(ratio > 14.0 && ratio < 18.0);
+    }
+}
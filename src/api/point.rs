@@ -1,23 +1,32 @@
 //! Module containing a point implementation and its operations
 #![warn(missing_docs)]
-use std::error::Error;
 use std::ops::{Add, Sub};
 
 use rand::distr::{Distribution, StandardUniform};
-use rand::rng;
+use serde::{Deserialize, Serialize};
 
+use super::random::sample;
 use super::util::Number;
 use super::vector::Vector;
+use crate::error::MathvisError;
 
 /// Trait that defines behavior similar to a point.
 ///
 /// A PointLike is anything that can be represented on an n-dimensional coordinate system, and has a position.
 pub trait PointLike<T: Number> {
     /// Creates a new PointLike with the specified coordinates.
-    /// Returns an Option because the arguments are not always expected to be valid.
-    fn new(values: Vec<T>) -> Option<Self>
+    ///
+    /// Returns an Err if there are no coordinates and an Ok with the PointLike otherwise.
+    fn new(values: Vec<T>) -> Result<Self, MathvisError>
     where
         Self: Sized;
+    /// Creates a new PointLike with the specified coordinates, like [PointLike::new], discarding the reason they're invalid.
+    fn try_new(values: Vec<T>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::new(values).ok()
+    }
     /// Creates a new PointLike with coordinates at the origin, on a coordinate system of the specified dimension.
     /// Returns an Option since the dimension has to be greater than 0.
     fn origin(dimensions: u32) -> Option<Self>
@@ -53,11 +62,36 @@ pub trait PointLike<T: Number> {
 /// let p = Point::new(vec![1, 1]).unwrap();
 /// p.get_dimensions();
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<T>", into = "Vec<T>")]
 pub struct Point<T: Number> {
     values: Vec<T>,
 }
 
+impl<T> TryFrom<Vec<T>> for Point<T>
+where
+    T: Number,
+{
+    type Error = MathvisError;
+
+    /// Converts a vector of coordinates into a point, like [PointLike::new].
+    ///
+    /// Returns an Err if there are no coordinates and an Ok with the point otherwise.
+    fn try_from(values: Vec<T>) -> Result<Self, Self::Error> {
+        Point::new(values)
+    }
+}
+
+impl<T> From<Point<T>> for Vec<T>
+where
+    T: Number,
+{
+    /// Converts a point into its coordinates.
+    fn from(point: Point<T>) -> Self {
+        point.values
+    }
+}
+
 impl<T> From<imageproc::point::Point<T>> for Point<T>
 where
     T: Number,
@@ -89,16 +123,18 @@ where
     /// let other = Point::new(vec![1, 0]).unwrap();
     /// assert_eq!(point.distance_to(&other).unwrap(), 1);
     /// ```
-    pub fn distance_to(&self, other: &Point<T>) -> Result<T, Box<dyn Error>> {
+    pub fn distance_to(&self, other: &Point<T>) -> Result<T, MathvisError> {
         if self.get_dimensions() != other.get_dimensions() {
-            return Err("Wrong dimensions.".into());
+            return Err(MathvisError::DimensionMismatch("Wrong dimensions.".into()));
         }
         Ok(self
             .values
             .iter()
             .zip(other.values.iter())
-            .fold(T::zero(), |acc, (a, b)| {
-                acc + (a.clone() - b.clone()).pow(2)
+            .fold(T::zero(), |acc, (&a, &b)| {
+                // Subtracting the smaller value keeps unsigned types from underflowing
+                let difference = if a > b { a - b } else { b - a };
+                acc + difference.pow(2)
             })
             .sqrt())
     }
@@ -111,7 +147,7 @@ where
     T: Number + Add<U, Output = U>,
     U: Number,
 {
-    type Output = Result<Point<U>, Box<dyn Error>>;
+    type Output = Result<Point<U>, MathvisError>;
 
     /// Adds a vector and a point.
     ///
@@ -129,15 +165,42 @@ where
     /// assert_eq!((point + vector).unwrap(), Point::new(vec![1, 0]).unwrap());
     /// ```
     fn add(self, vec: Vector<U>) -> Self::Output {
+        &self + &vec
+    }
+}
+
+impl<T, U> Add<&Vector<U>> for &Point<T>
+where
+    T: Number + Add<U, Output = U>,
+    U: Number,
+{
+    type Output = Result<Point<U>, MathvisError>;
+
+    /// Adds a borrowed vector and a borrowed point.
+    ///
+    /// Returns an Err if the dimensions of the point and the vector are different and an Ok with the resulting point otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::point::Point;
+    /// use mathvis::api::point::PointLike;
+    /// use mathvis::api::vector::Vector;
+    ///
+    /// let point = Point::<i32>::origin(2).unwrap();
+    /// let vector = Vector::new(vec![1, 0]).unwrap();
+    /// assert_eq!((&point + &vector).unwrap(), Point::new(vec![1, 0]).unwrap());
+    /// ```
+    fn add(self, vec: &Vector<U>) -> Self::Output {
         if self.get_dimensions() != vec.get_dimensions() {
-            return Err("Wrong dimensions.".into());
+            return Err(MathvisError::DimensionMismatch("Wrong dimensions.".into()));
         }
         Ok(Point {
             values: self
                 .values
                 .iter()
                 .zip(vec.values().iter())
-                .map(|(a, b)| a.clone() + b.clone())
+                .map(|(&a, &b)| a + b)
                 .collect(),
         })
     }
@@ -149,7 +212,7 @@ where
 {
     /// Creates a new Point with the specified coordinates.
     ///
-    /// Returns a None if the coordinates vector is empty and a Some with the Point otherwise.
+    /// Returns an Err if the coordinates vector is empty and an Ok with the Point otherwise.
     ///
     /// # Examples
     ///
@@ -159,16 +222,19 @@ where
     /// let p1 = Point::<i32>::new(Vec::new());
     /// let p2 = Point::new(vec![1, 1]);
     ///
-    /// assert!(p1 == None && p2 == Some(Point::new(vec![1, 1]).unwrap()));
+    /// assert!(p1.is_err() && p2.unwrap().values() == &vec![1, 1]);
+    /// assert!(Point::<i32>::try_new(Vec::new()).is_none());
     /// ```
-    fn new(values: Vec<T>) -> Option<Self>
+    fn new(values: Vec<T>) -> Result<Self, MathvisError>
     where
         Self: Sized,
     {
         if values.is_empty() {
-            return None;
+            return Err(MathvisError::InvalidArgument(
+                "A point must have at least one coordinate.".into(),
+            ));
         }
-        Some(Point { values })
+        Ok(Point { values })
     }
 
     /// Creates a new Point on the origin with the specified dimensions.
@@ -225,6 +291,7 @@ where
 
     /// Creates a point with the specified dimensions and random coordinates.
     /// Not meant to be used for anything other than testing purposes.
+    /// The coordinates are reproducible after calling [set_seed](crate::api::random::set_seed).
     ///
     /// Returns a None if the dimension is 0 and a Some with the point otherwise.
     ///
@@ -243,11 +310,8 @@ where
             return None;
         }
 
-        let mut rng = rng();
         Some(Point {
-            values: (0..dimensions)
-                .map(|_| StandardUniform.sample(&mut rng))
-                .collect(),
+            values: sample(dimensions as usize),
         })
     }
 }
@@ -0,0 +1,359 @@
+//! Module containing quaternions, which represent 3D orientations and interpolate between them without gimbal lock.
+#![warn(missing_docs)]
+use std::ops::{Add, Mul, Neg};
+
+use super::{angle::Angle, matrix::Matrix};
+use crate::error::MathvisError;
+
+/// How far a matrix may be from a rotation, in any entry of its product with its transpose, before it's rejected.
+const ROTATION_TOLERANCE: f64 = 1e-6;
+
+/// A quaternion w + xi + yj + zk, where the unit quaternions are the rotations of 3D space.
+///
+/// Rotations are composed by multiplying them, with `a * b` rotating by `b` first and then by `a` like matrices do,
+/// and interpolated with [Quaternion::slerp], which turns at a steady speed along the shortest way between two orientations.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::angle::Angle;
+/// use mathvis::api::quaternion::Quaternion;
+///
+/// let quarter = Quaternion::from_axis_angle((0.0, 0.0, 1.0), Angle::degrees(90.0)).unwrap();
+/// let (x, y, z) = quarter.rotate((1.0, 0.0, 0.0));
+/// assert!(x.abs() < 1e-12 && (y - 1.0).abs() < 1e-12 && z == 0.0);
+///
+/// // Halfway to a quarter turn is an eighth of a turn
+/// let eighth = Quaternion::IDENTITY.slerp(&quarter, 0.5);
+/// let (_, angle) = eighth.to_axis_angle();
+/// assert!((angle.to_degrees() - 45.0).abs() < 1e-9);
+///
+/// // Rotations round trip through matrices
+/// let matrix = quarter.to_rotation_matrix();
+/// let back = Quaternion::from_rotation_matrix(&matrix).unwrap();
+/// assert!((back.dot(&quarter) - 1.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    /// The real part.
+    pub w: f64,
+    /// The coefficient of i.
+    pub x: f64,
+    /// The coefficient of j.
+    pub y: f64,
+    /// The coefficient of k.
+    pub z: f64,
+}
+
+impl Quaternion {
+    /// The quaternion 1, which is the rotation that leaves everything in place.
+    pub const IDENTITY: Quaternion = Quaternion {
+        w: 1.0,
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+
+    /// Creates the quaternion w + xi + yj + zk.
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Quaternion { w, x, y, z }
+    }
+
+    /// Creates the rotation by an angle around an axis, counterclockwise when looking from the tip of the axis towards the origin.
+    ///
+    /// Returns an Err if the axis has no length and an Ok with the rotation otherwise.
+    pub fn from_axis_angle(
+        (x, y, z): (f64, f64, f64),
+        angle: impl Into<Angle>,
+    ) -> Result<Self, MathvisError> {
+        let length = (x * x + y * y + z * z).sqrt();
+        if length == 0.0 || !length.is_finite() {
+            return Err(MathvisError::InvalidArgument(format!(
+                "The axis of a rotation must have a finite, nonzero length, but it's ({}, {}, {}).",
+                x, y, z
+            )));
+        }
+        let (sin, cos) = (angle.into() / 2.0).sin_cos();
+        let scale = sin / length;
+        Ok(Quaternion::new(cos, x * scale, y * scale, z * scale))
+    }
+
+    /// Returns the axis and angle of the rotation, with an angle from 0 to a whole turn.
+    /// The identity has no axis, so it's returned with the z axis.
+    ///
+    /// The quaternion is assumed to be a unit quaternion.
+    pub fn to_axis_angle(&self) -> ((f64, f64, f64), Angle) {
+        let sin = (self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+        if sin == 0.0 {
+            return ((0.0, 0.0, 1.0), Angle::ZERO);
+        }
+        let angle = Angle::radians(2.0 * sin.atan2(self.w));
+        ((self.x / sin, self.y / sin, self.z / sin), angle)
+    }
+
+    /// Returns the length of the quaternion.
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns the dot product of both quaternions as 4D vectors, which is the cosine of half the angle between two rotations.
+    pub fn dot(&self, other: &Quaternion) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Returns the quaternion scaled to length 1, which makes it a rotation.
+    ///
+    /// Returns an Err if the quaternion has no length and an Ok with the unit quaternion otherwise.
+    pub fn normalize(&self) -> Result<Self, MathvisError> {
+        let norm = self.norm();
+        if norm == 0.0 || !norm.is_finite() {
+            return Err(MathvisError::InvalidArgument(
+                "Can't normalize a quaternion of length 0.".into(),
+            ));
+        }
+        Ok(self.scale(1.0 / norm))
+    }
+
+    /// Returns the conjugate w - xi - yj - zk, which for rotations is the rotation that undoes this one.
+    pub fn conjugate(&self) -> Self {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    /// Returns the multiplicative inverse of the quaternion.
+    ///
+    /// Returns an Err if the quaternion is 0 and an Ok with the inverse otherwise.
+    pub fn inverse(&self) -> Result<Self, MathvisError> {
+        let norm_squared = self.dot(self);
+        if norm_squared == 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The quaternion 0 has no inverse.".into(),
+            ));
+        }
+        Ok(self.conjugate().scale(1.0 / norm_squared))
+    }
+
+    /// Returns the point rotated by the quaternion, which is assumed to be a unit quaternion.
+    pub fn rotate(&self, (x, y, z): (f64, f64, f64)) -> (f64, f64, f64) {
+        let rotated = *self * Quaternion::new(0.0, x, y, z) * self.conjugate();
+        (rotated.x, rotated.y, rotated.z)
+    }
+
+    /// Returns the spherical linear interpolation a fraction `t` of the way from this rotation to the other,
+    /// turning at a steady speed along the shortest way between them.
+    ///
+    /// Both quaternions are assumed to be unit quaternions.
+    pub fn slerp(&self, other: &Quaternion, t: f64) -> Self {
+        // q and -q are the same rotation, and picking the closer one takes the shorter way around
+        let (other, cos) = match self.dot(other) {
+            cos if cos < 0.0 => (-*other, -cos),
+            cos => (*other, cos),
+        };
+        // Nearly equal rotations are interpolated linearly, since the sine below would be close to 0
+        if cos > 1.0 - 1e-9 {
+            let blended = self.scale(1.0 - t) + other.scale(t);
+            return blended.normalize().unwrap_or(*self);
+        }
+        let theta = cos.min(1.0).acos();
+        let sin = theta.sin();
+        self.scale(((1.0 - t) * theta).sin() / sin) + other.scale((t * theta).sin() / sin)
+    }
+
+    /// Returns the 3x3 matrix of the rotation, which is assumed to be a unit quaternion.
+    pub fn to_rotation_matrix(&self) -> Matrix<f64> {
+        let Quaternion { w, x, y, z } = *self;
+        Matrix {
+            values: vec![
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+            rows: 3,
+            cols: 3,
+        }
+    }
+
+    /// Creates the unit quaternion of a 3x3 rotation matrix.
+    ///
+    /// Returns an Err with a DimensionMismatch if the matrix isn't 3x3,
+    /// an Err with an InvalidArgument if it isn't a rotation (orthogonal with determinant 1) and an Ok with the quaternion otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::matrix::Matrix;
+    /// use mathvis::api::quaternion::Quaternion;
+    ///
+    /// let identity = Matrix::<f64>::identity(3).unwrap();
+    /// assert_eq!(Quaternion::from_rotation_matrix(&identity).unwrap(), Quaternion::IDENTITY);
+    ///
+    /// let mirror = Matrix::new(vec![vec![-1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0]]).unwrap();
+    /// assert!(Quaternion::from_rotation_matrix(&mirror).is_err());
+    /// ```
+    pub fn from_rotation_matrix(matrix: &Matrix<f64>) -> Result<Self, MathvisError> {
+        if matrix.get_dimensions() != (3, 3) {
+            return Err(MathvisError::DimensionMismatch(
+                "A rotation matrix must be 3x3.".into(),
+            ));
+        }
+        let m = |row: usize, col: usize| matrix.row(row)[col];
+        let orthogonal = (0..3).all(|i| {
+            (0..3).all(|j| {
+                let product: f64 = (0..3).map(|k| m(k, i) * m(k, j)).sum();
+                let expected = if i == j { 1.0 } else { 0.0 };
+                (product - expected).abs() <= ROTATION_TOLERANCE
+            })
+        });
+        if !orthogonal || matrix.determinant()? <= 0.0 {
+            return Err(MathvisError::InvalidArgument(
+                "The matrix isn't a rotation, since it isn't orthogonal with determinant 1.".into(),
+            ));
+        }
+        // Taken from the largest of w, x, y and z, so the division is never by something close to 0
+        let trace = m(0, 0) + m(1, 1) + m(2, 2);
+        let quaternion = if trace > 0.0 {
+            let s = 2.0 * (trace + 1.0).sqrt();
+            Quaternion::new(
+                s / 4.0,
+                (m(2, 1) - m(1, 2)) / s,
+                (m(0, 2) - m(2, 0)) / s,
+                (m(1, 0) - m(0, 1)) / s,
+            )
+        } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+            let s = 2.0 * (1.0 + m(0, 0) - m(1, 1) - m(2, 2)).sqrt();
+            Quaternion::new(
+                (m(2, 1) - m(1, 2)) / s,
+                s / 4.0,
+                (m(0, 1) + m(1, 0)) / s,
+                (m(0, 2) + m(2, 0)) / s,
+            )
+        } else if m(1, 1) > m(2, 2) {
+            let s = 2.0 * (1.0 + m(1, 1) - m(0, 0) - m(2, 2)).sqrt();
+            Quaternion::new(
+                (m(0, 2) - m(2, 0)) / s,
+                (m(0, 1) + m(1, 0)) / s,
+                s / 4.0,
+                (m(1, 2) + m(2, 1)) / s,
+            )
+        } else {
+            let s = 2.0 * (1.0 + m(2, 2) - m(0, 0) - m(1, 1)).sqrt();
+            Quaternion::new(
+                (m(1, 0) - m(0, 1)) / s,
+                (m(0, 2) + m(2, 0)) / s,
+                (m(1, 2) + m(2, 1)) / s,
+                s / 4.0,
+            )
+        };
+        quaternion.normalize()
+    }
+
+    /// Returns the quaternion with every component multiplied by a number.
+    fn scale(&self, factor: f64) -> Self {
+        Quaternion::new(
+            self.w * factor,
+            self.x * factor,
+            self.y * factor,
+            self.z * factor,
+        )
+    }
+}
+
+impl Default for Quaternion {
+    /// Returns the [Quaternion::IDENTITY] rotation.
+    fn default() -> Self {
+        Quaternion::IDENTITY
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    /// Multiplies two quaternions, which for rotations is rotating by `rhs` and then by `self`.
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+impl Add for Quaternion {
+    type Output = Quaternion;
+
+    fn add(self, rhs: Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w + rhs.w,
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+        )
+    }
+}
+
+impl Neg for Quaternion {
+    type Output = Quaternion;
+
+    fn neg(self) -> Quaternion {
+        self.scale(-1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn close(a: (f64, f64, f64), b: (f64, f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9 && (a.2 - b.2).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_rotations() {
+        let about_x = Quaternion::from_axis_angle((2.0, 0.0, 0.0), PI / 2.0).unwrap();
+        let about_z = Quaternion::from_axis_angle((0.0, 0.0, 1.0), PI / 2.0).unwrap();
+        // Rotating about x and then z takes y to z and then stays there
+        assert!(close(
+            (about_z * about_x).rotate((0.0, 1.0, 0.0)),
+            (0.0, 0.0, 1.0)
+        ));
+        // Composing matches multiplying the matrices
+        let product = about_z.to_rotation_matrix() * about_x.to_rotation_matrix();
+        let composed = Quaternion::from_rotation_matrix(&product.unwrap()).unwrap();
+        assert!((composed.dot(&(about_z * about_x)).abs() - 1.0).abs() < 1e-12);
+        assert!(close(
+            (about_x * about_x.inverse().unwrap()).rotate((1.0, 2.0, 3.0)),
+            (1.0, 2.0, 3.0)
+        ));
+        assert!(Quaternion::from_axis_angle((0.0, 0.0, 0.0), 1.0).is_err());
+        assert!(Quaternion::new(0.0, 0.0, 0.0, 0.0).normalize().is_err());
+
+        // A half turn about each axis goes through the other branch of the matrix conversion
+        for axis in [(1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, 1.0)] {
+            let half = Quaternion::from_axis_angle(axis, PI).unwrap();
+            let back = Quaternion::from_rotation_matrix(&half.to_rotation_matrix()).unwrap();
+            assert!((back.dot(&half).abs() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_slerp() {
+        let start = Quaternion::from_axis_angle((0.0, 1.0, 0.0), 0.2).unwrap();
+        let end = Quaternion::from_axis_angle((0.0, 1.0, 0.0), 1.0).unwrap();
+        assert!((start.slerp(&end, 0.0).dot(&start) - 1.0).abs() < 1e-12);
+        assert!((start.slerp(&end, 1.0).dot(&end) - 1.0).abs() < 1e-12);
+        let (axis, angle) = start.slerp(&end, 0.5).to_axis_angle();
+        assert!(close(axis, (0.0, 1.0, 0.0)) && (angle.to_radians() - 0.6).abs() < 1e-12);
+        // The negated end is the same rotation, and still takes the short way
+        let (_, angle) = start.slerp(&-end, 0.5).to_axis_angle();
+        assert!((angle.normalized().to_radians() - 0.6).abs() < 1e-12);
+        assert!((start.slerp(&start, 0.3).norm() - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,68 @@
+//! Module containing the random number generator behind every `random` constructor, which can be seeded so renders are reproducible.
+#![warn(missing_docs)]
+use std::cell::RefCell;
+
+use rand::{
+    distr::{Distribution, StandardUniform},
+    rng,
+    rngs::StdRng,
+    Rng, SeedableRng,
+};
+
+thread_local! {
+    /// Seeded generator of the current thread, the thread's entropy-seeded generator is used while it's None.
+    static SEEDED_RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Seeds the random number generator of the current thread, so every random value generated on it afterwards is reproducible.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::point::PointLike;
+/// use mathvis::api::random::set_seed;
+/// use mathvis::api::vector::Vector;
+/// set_seed(42);
+/// let a = Vector::<f64>::random(3).unwrap();
+/// set_seed(42);
+/// let b = Vector::<f64>::random(3).unwrap();
+/// assert!(a == b);
+/// ```
+pub fn set_seed(seed: u64) {
+    SEEDED_RNG.with(|cell| *cell.borrow_mut() = Some(StdRng::seed_from_u64(seed)));
+}
+
+/// Removes the seed of the current thread, going back to non-reproducible random values.
+pub fn clear_seed() {
+    SEEDED_RNG.with(|cell| *cell.borrow_mut() = None);
+}
+
+/// Generates the specified number of random values, using the seeded generator if there is one.
+pub(crate) fn sample<T>(count: usize) -> Vec<T>
+where
+    StandardUniform: Distribution<T>,
+{
+    SEEDED_RNG.with(|cell| match cell.borrow_mut().as_mut() {
+        Some(seeded) => (0..count).map(|_| seeded.random()).collect(),
+        None => {
+            let mut rng = rng();
+            (0..count).map(|_| rng.random()).collect()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed() {
+        set_seed(7);
+        let a: Vec<u32> = sample(5);
+        set_seed(7);
+        assert_eq!(a, sample::<u32>(5));
+        set_seed(8);
+        assert_ne!(a, sample::<u32>(5));
+        clear_seed();
+    }
+}
@@ -1,12 +1,24 @@
 //! A module containing a 2D and later on, a 3D screen that holds global properties of the program.
 #![warn(missing_docs)]
-use std::{error::Error, f32};
+use std::{f32, thread::available_parallelism};
 
-use crate::animation::show::Show2D;
+use imageproc::image::Rgb;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animation::{
+        background::BACKGROUND_COLOR, cancel::CancellationToken, scheduler::FrameScheduler,
+        show::Show2D,
+    },
+    error::MathvisError,
+};
 
 use super::{
+    bounds::BoundingBox,
+    color::Theme,
+    interval::Interval,
     point::{Point, PointLike},
-    util::{in_axis_range, Number},
+    util::{FrameFormat, Number, Quality},
 };
 
 /// Trait that defines behavior belonging to a screen.
@@ -22,6 +34,55 @@ pub trait ScreenLike<V: Number> {
     fn y_axis(&self) -> (f32, f32);
 }
 
+/// Style of the axes drawn on every frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AxisStyle {
+    /// Whether the axes are drawn at all.
+    pub visible: bool,
+    /// Whether tick markers are drawn on the integer coordinates.
+    pub ticks: bool,
+    /// Color of the axes and their ticks.
+    #[serde(with = "crate::scene::loader::rgb")]
+    pub color: Rgb<u8>,
+    /// Where the ticks of the x axis are placed and how they're labelled.
+    pub tick_mode: TickMode,
+}
+
+impl Default for AxisStyle {
+    fn default() -> Self {
+        AxisStyle {
+            visible: true,
+            ticks: true,
+            color: Rgb([255, 255, 255]),
+            tick_mode: TickMode::default(),
+        }
+    }
+}
+
+/// Where the ticks of the x axis are placed and how they're labelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TickMode {
+    /// Unlabelled ticks on the integers.
+    #[default]
+    Integer,
+    /// Ticks on the multiples of π/2, labelled like `π/2`, `π` and `3π/2`, for plots of trigonometric functions.
+    /// The y axis keeps its ticks on the integers.
+    Pi,
+}
+
+/// How the plane of a screen is presented, which changes how its axes are labelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenMode {
+    /// The real plane, with unlabelled x and y axes.
+    #[default]
+    Cartesian,
+    /// The complex plane, with a real and an imaginary axis whose ticks are labelled, and the unit circle drawn around the origin.
+    ComplexPlane,
+}
+
 /// A 2D screen, with several global properties.
 ///
 /// This implementation implements [PartialEq], meaning the common equality properties hold, except for the reflexive property (there's no big reason why it shouldn't have this, but having it would require using integers for the axis limits).
@@ -34,21 +95,38 @@ pub trait ScreenLike<V: Number> {
 ///
 /// <Screen2D as ScreenLike<f32>>::x_axis(&s);
 /// ```
-#[derive(Debug, PartialEq, Clone)]
+///
+/// With serde, a screen is saved and loaded as the [Screen2DBuilder] options it was created with,
+/// so loading a screen checks them like [Screen2DBuilder::build] and starts it on the first frame.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(into = "Screen2DBuilder", try_from = "Screen2DBuilder")]
 pub struct Screen2D {
     x_axis: (f32, f32),
     y_axis: (f32, f32),
     pub(crate) save_directory: String,
-    pub(crate) current_frame: u32,
+    pub(crate) scheduler: FrameScheduler,
     pub(crate) fps: u32,
     pub(crate) width: u32,
     pub(crate) height: u32,
+    pub(crate) transparent: bool,
+    pub(crate) background: Rgb<u8>,
+    pub(crate) axis_style: AxisStyle,
+    pub(crate) theme: Theme,
+    pub(crate) mode: ScreenMode,
+    pub(crate) frame_range: (u32, Option<u32>),
+    pub(crate) dry_run: bool,
+    pub(crate) checkpoint: Option<u64>,
+    pub(crate) cancellation: Option<CancellationToken>,
+    pub(crate) quiet: bool,
+    pub(crate) jobs: Option<usize>,
+    pub(crate) frame_format: FrameFormat,
+    pub(crate) frame_digits: usize,
 }
 
 impl Screen2D {
     /// Creates a new screen with the specified axes, save directory, fps, width and height.
     ///
-    /// Returns a None if the axes limits are not valid (end > start) and a Some with the Screen otherwise.
+    /// Returns an Err if the axes limits are not valid (end > start) and an Ok with the Screen otherwise.
     /// Warning this function is not meant to be used directly as the Screen2D is created automatically, and only its axes may be changed through `change_dimensions`
     ///
     /// # Examples
@@ -58,7 +136,7 @@ impl Screen2D {
     ///
     /// let s1 = Screen2D::new((10.0, 5.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080);
     /// let s2 = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080);
-    /// assert!(s1 == None && s2 == Some(Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap()));
+    /// assert!(s1.is_err() && s2.is_ok());
     /// ```
     pub fn new(
         (xstart, xend): (f32, f32),
@@ -67,19 +145,74 @@ impl Screen2D {
         fps: u32,
         width: u32,
         height: u32,
+    ) -> Result<Self, MathvisError> {
+        check_axes((xstart, xend), (ystart, yend))?;
+        Ok(Screen2D {
+            x_axis: (xstart, xend),
+            y_axis: (ystart, yend),
+            save_directory,
+            scheduler: FrameScheduler::new(),
+            fps,
+            width,
+            height,
+            transparent: false,
+            background: BACKGROUND_COLOR,
+            axis_style: AxisStyle::default(),
+            theme: Theme::default(),
+            mode: ScreenMode::default(),
+            frame_range: (0, None),
+            dry_run: false,
+            checkpoint: None,
+            cancellation: None,
+            quiet: false,
+            jobs: None,
+            frame_format: FrameFormat::default(),
+            frame_digits: 3,
+        })
+    }
+
+    /// Creates a new screen like [Screen2D::new], discarding the reason it's invalid.
+    ///
+    /// Returns a None if the axes limits are not valid and a Some with the Screen otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// assert!(Screen2D::try_new((10.0, 5.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).is_none());
+    /// ```
+    pub fn try_new(
+        x_axis: (f32, f32),
+        y_axis: (f32, f32),
+        save_directory: String,
+        fps: u32,
+        width: u32,
+        height: u32,
     ) -> Option<Self> {
-        if xstart < xend && ystart < yend {
-            return Some(Screen2D {
-                x_axis: (xstart, xend),
-                y_axis: (ystart, yend),
-                save_directory,
-                current_frame: 0,
-                fps,
-                width,
-                height,
-            });
-        }
-        None
+        Screen2D::new(x_axis, y_axis, save_directory, fps, width, height).ok()
+    }
+
+    /// Returns a builder for a screen, with every option set to its default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::{Screen2D, ScreenLike};
+    /// use mathvis::api::util::Quality;
+    ///
+    /// let screen = Screen2D::builder()
+    ///     .x_axis(-5.0, 5.0)
+    ///     .quality(Quality::HIGH)
+    ///     .fps(60)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(<Screen2D as ScreenLike<f32>>::x_axis(&screen), (-5.0, 5.0));
+    /// assert_eq!(<Screen2D as ScreenLike<f32>>::y_axis(&screen), (-3.0, 3.0));
+    /// assert!(Screen2D::builder().resolution(100, 100).build().is_err());
+    /// ```
+    pub fn builder() -> Screen2DBuilder {
+        Screen2DBuilder::default()
     }
 
     /// Changes the axes' limits to the specified ones.
@@ -99,13 +232,73 @@ impl Screen2D {
         &mut self,
         (xstart, xend): (f32, f32),
         (ystart, yend): (f32, f32),
-    ) -> Result<(), Box<dyn Error>> {
-        if xstart < xend && ystart < yend {
-            self.x_axis = (xstart, xend);
-            self.y_axis = (ystart, yend);
-            return Ok(());
-        }
-        Err("Invalid axes' dimensions.".into())
+    ) -> Result<(), MathvisError> {
+        check_axes((xstart, xend), (ystart, yend))?;
+        self.x_axis = (xstart, xend);
+        self.y_axis = (ystart, yend);
+        Ok(())
+    }
+
+    /// Returns the range of the x axis as an interval.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let screen = Screen2D::new((-4.0, 6.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// assert_eq!(screen.x_interval().length(), 10.0);
+    /// assert!(screen.x_interval().contains(6.0));
+    /// ```
+    pub fn x_interval(&self) -> Interval {
+        Interval::named("x axis", self.x_axis.0 as f64, self.x_axis.1 as f64)
+            .expect("the axes are checked whenever they change")
+    }
+
+    /// Returns the range of the y axis as an interval.
+    pub fn y_interval(&self) -> Interval {
+        Interval::named("y axis", self.y_axis.0 as f64, self.y_axis.1 as f64)
+            .expect("the axes are checked whenever they change")
+    }
+
+    /// Returns the rectangle covered by the axes.
+    pub fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            (self.x_axis.0 as f64, self.y_axis.0 as f64),
+            (self.x_axis.1 as f64, self.y_axis.1 as f64),
+        )
+    }
+
+    /// Changes the axes' limits so they show the whole box with a margin around it, centered,
+    /// keeping the ratio between units on both axes the same as between the width and height of the frame so circles stay round.
+    ///
+    /// Returns an Err if the box has no width or height even with the margin and an Ok otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::{bounds::BoundingBox, screen::{Screen2D, ScreenLike}};
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.fit_to(&BoundingBox::new((0.0, 0.0), (4.0, 4.0)), 0.5).unwrap();
+    /// assert_eq!(<Screen2D as ScreenLike<f32>>::y_axis(&screen), (-0.5, 4.5));
+    /// // The x axis is widened to keep the 16:9 ratio of the frame
+    /// let (left, right) = <Screen2D as ScreenLike<f32>>::x_axis(&screen);
+    /// assert!((right - left - 5.0 * 16.0 / 9.0).abs() < 1e-4 && (left + right - 4.0).abs() < 1e-4);
+    /// assert!(screen.fit_to(&BoundingBox::point((1.0, 1.0)), 0.0).is_err());
+    /// ```
+    pub fn fit_to(&mut self, bounds: &BoundingBox, margin: f64) -> Result<(), MathvisError> {
+        let bounds = bounds.expanded(margin);
+        let (cx, cy) = bounds.center();
+        let aspect = self.width as f64 / self.height as f64;
+        let (width, height) = (
+            bounds.width().max(bounds.height() * aspect),
+            bounds.height().max(bounds.width() / aspect),
+        );
+        self.change_dimensions(
+            ((cx - width / 2.0) as f32, (cx + width / 2.0) as f32),
+            ((cy - height / 2.0) as f32, (cy + height / 2.0) as f32),
+        )
     }
 
     /// Returns the position of the origin in pixels.
@@ -125,16 +318,503 @@ impl Screen2D {
         (self.width as f32 * ratio_x, self.height as f32 * ratio_y)
     }
 
-    /// Updates the current frame value to a specified value.
-    /// Not meant to be used outside of internal API
+    /// Sets whether frames are rendered with a transparent background.
+    ///
+    /// Transparent frames can only be encoded into formats that keep the alpha channel (.webm or .mov).
     ///
-    /// Returns an Err if the specified frame value is not greater than the current one and an Ok otherwise.
-    pub(crate) fn change_current_frame(&mut self, val: u32) -> Result<(), Box<dyn Error>> {
-        if val > self.current_frame {
-            self.current_frame = val;
-            return Ok(());
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_transparent(true);
+    /// ```
+    pub fn set_transparent(&mut self, transparent: bool) {
+        self.transparent = transparent;
+    }
+
+    /// Sets the color used to fill the background of every frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    /// use imageproc::image::Rgb;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_background(Rgb([0, 0, 0]));
+    /// ```
+    pub fn set_background(&mut self, color: Rgb<u8>) {
+        self.background = color;
+    }
+
+    /// Returns the color used to fill the background of every frame.
+    pub fn background(&self) -> Rgb<u8> {
+        self.background
+    }
+
+    /// Sets the style the axes are drawn with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::{AxisStyle, Screen2D};
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_axis_style(AxisStyle { ticks: false, ..AxisStyle::default() });
+    /// ```
+    pub fn set_axis_style(&mut self, style: AxisStyle) {
+        self.axis_style = style;
+    }
+
+    /// Sets the theme of the screen, which sets the background and axis colors and the palette objects take their colors from.
+    ///
+    /// The background and axes can still be changed afterwards, like with [Screen2D::set_background].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::color::{Theme, INK, WHITE};
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_theme(Theme::light());
+    /// assert_eq!(screen.background(), WHITE);
+    /// assert_eq!(screen.object_color(1), INK[1]);
+    /// ```
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.background = theme.background;
+        self.axis_style.color = theme.axis_color;
+        self.theme = theme;
+    }
+
+    /// Returns the theme of the screen.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Returns the color of the object with the specified index from the palette of the screen's theme, for objects without a color of their own.
+    pub fn object_color(&self, index: usize) -> Rgb<u8> {
+        self.theme.palette.color(index)
+    }
+
+    /// Sets how the plane is presented, as the real plane by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::{Screen2D, ScreenMode};
+    ///
+    /// let mut screen = Screen2D::new((-2.0, 2.0), (-2.0, 2.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_mode(ScreenMode::ComplexPlane);
+    /// assert_eq!(screen.mode(), ScreenMode::ComplexPlane);
+    /// ```
+    pub fn set_mode(&mut self, mode: ScreenMode) {
+        self.mode = mode;
+    }
+
+    /// Returns how the plane is presented.
+    pub fn mode(&self) -> ScreenMode {
+        self.mode
+    }
+
+    /// Restricts rendering to the frames from `start` (inclusive) to `end` (exclusive), or until the last frame if `end` is None.
+    ///
+    /// Frames outside the range are skipped, so they must already exist from a previous render for the video to be complete.
+    ///
+    /// Returns an Err if the range is empty and an Ok otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// assert!(screen.set_frame_range(60, Some(90)).is_ok());
+    /// assert!(screen.set_frame_range(60, Some(60)).is_err());
+    /// ```
+    pub fn set_frame_range(&mut self, start: u32, end: Option<u32>) -> Result<(), MathvisError> {
+        if end.is_some_and(|end| end <= start) {
+            return Err(MathvisError::InvalidArgument(
+                "The end frame must come after the start frame.".into(),
+            ));
+        }
+        self.frame_range = (start, end);
+        Ok(())
+    }
+
+    /// Sets whether animations only advance the frame count and timeline, without rendering or saving any frames.
+    ///
+    /// Useful for checking how long an animation is before rendering it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::{renderer::Renderer, show::Show2D, vector::Vector2D};
+    /// use mathvis::api::{point::{Point, PointLike}, screen::Screen2D};
+    /// use imageproc::image::Rgb;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_dry_run(true);
+    /// let screen = Arc::new(Mutex::new(screen));
+    ///
+    /// let mut vector = Vector2D::new(1.0, 0.0, Rgb([255, 255, 255]));
+    /// vector.add_context(screen.clone()).unwrap();
+    /// let animation = vector.move_to(2.0, Point::new(vec![0.0, 1.0]).unwrap());
+    /// Renderer::new(screen.clone()).unwrap().play(&animation).unwrap();
+    /// assert_eq!(screen.lock().unwrap().timeline(), &[(0, 60)]);
+    /// ```
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// Sets the hash of the scene being rendered (see [hash_scene](crate::animation::checkpoint::hash_scene)) to record
+    /// every saved frame in a [RenderManifest](crate::animation::checkpoint::RenderManifest) in the output directory, or None to stop recording them.
+    ///
+    /// Frames the manifest already has for the same scene are skipped, so an interrupted render resumes where it stopped.
+    /// A manifest for a different scene is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::animation::checkpoint::hash_scene;
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_checkpoint(Some(hash_scene(&[b"scene source"])));
+    /// assert!(screen.checkpoint().is_some());
+    /// ```
+    pub fn set_checkpoint(&mut self, scene_hash: Option<u64>) {
+        self.checkpoint = scene_hash;
+    }
+
+    /// Returns the hash of the scene whose saved frames are recorded in a manifest, if they are.
+    pub fn checkpoint(&self) -> Option<u64> {
+        self.checkpoint
+    }
+
+    /// Sets the token that cancels every render on the screen, or None to make them impossible to cancel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use mathvis::animation::{cancel::CancellationToken, renderer::Renderer};
+    /// use mathvis::api::screen::Screen2D;
+    /// use mathvis::error::MathvisError;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// let token = CancellationToken::new();
+    /// screen.set_cancellation(Some(token.clone()));
+    /// token.cancel();
+    /// let render = Renderer::new(Arc::new(Mutex::new(screen))).unwrap().render_frames(0..30, |_, _| Ok(()));
+    /// assert!(matches!(render, Err(MathvisError::Cancelled)));
+    /// ```
+    pub fn set_cancellation(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Returns the token that cancels every render on the screen, if there's one.
+    pub fn cancellation(&self) -> Option<&CancellationToken> {
+        self.cancellation.as_ref()
+    }
+
+    /// Sets whether the rendering progress of each animation is hidden.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_quiet(true);
+    /// ```
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// Sets the number of worker threads used to render frames.
+    /// By default, one thread is used per available CPU core.
+    ///
+    /// Returns an Err if the number of threads is 0 and an Ok otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 60, 1920, 1080).unwrap();
+    /// assert!(screen.set_jobs(4).is_ok());
+    /// assert_eq!(screen.jobs(), 4);
+    /// assert!(screen.set_jobs(0).is_err());
+    /// ```
+    pub fn set_jobs(&mut self, jobs: usize) -> Result<(), MathvisError> {
+        if jobs == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "At least one worker thread is needed.".into(),
+            ));
+        }
+        self.jobs = Some(jobs);
+        Ok(())
+    }
+
+    /// Returns the number of worker threads used to render frames.
+    pub fn jobs(&self) -> usize {
+        // Frames are rendered on the CPU, so more threads than cores only add contention
+        self.jobs
+            .unwrap_or_else(|| available_parallelism().map_or(1, |cores| cores.get()))
+    }
+
+    /// Sets the image format of the frames saved in the output directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    /// use mathvis::api::util::FrameFormat;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_frame_format(FrameFormat::PngFast);
+    /// ```
+    pub fn set_frame_format(&mut self, frame_format: FrameFormat) {
+        self.frame_format = frame_format;
+    }
+
+    /// Returns the image format of the frames saved in the output directory.
+    pub fn frame_format(&self) -> FrameFormat {
+        self.frame_format
+    }
+
+    /// Sets the number of digits frame numbers are padded to with zeros in the names of the saved frames,
+    /// 3 by default like `frame_007.png`.
+    ///
+    /// Longer numbers are never cut, but names only sort in frame order when every number has the same length,
+    /// so renders with more than 1000 frames should use more digits, see [Screen2D::digits_for].
+    ///
+    /// Returns an Err if the number of digits is 0 or more than 10, the length of the largest frame number, and an Ok otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    ///
+    /// let mut screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::from("./save"), 30, 1920, 1080).unwrap();
+    /// screen.set_frame_digits(Screen2D::digits_for(108_000)).unwrap();
+    /// assert_eq!(screen.frame_path(42), "./save/tmp/frame_000042.png");
+    /// assert_eq!(screen.frame_pattern(), "./save/tmp/frame_%06d.png");
+    /// assert!(screen.set_frame_digits(0).is_err());
+    /// ```
+    pub fn set_frame_digits(&mut self, digits: usize) -> Result<(), MathvisError> {
+        if !(1..=10).contains(&digits) {
+            return Err(MathvisError::InvalidArgument(format!(
+                "Frame numbers must be padded to between 1 and 10 digits, not {}.",
+                digits
+            )));
+        }
+        self.frame_digits = digits;
+        Ok(())
+    }
+
+    /// Returns the number of digits frame numbers are padded to in the names of the saved frames.
+    pub fn frame_digits(&self) -> usize {
+        self.frame_digits
+    }
+
+    /// Returns the number of digits frame numbers have to be padded to for a video with the specified number of frames,
+    /// which is never less than the default of 3.
+    pub fn digits_for(frames: u32) -> usize {
+        frames.saturating_sub(1).to_string().len().max(3)
+    }
+
+    /// Returns the path a frame is saved at, in the `tmp` directory of the save directory.
+    pub fn frame_path(&self, frame: u32) -> String {
+        format!(
+            "{}/tmp/frame_{:0digits$}.{}",
+            self.save_directory,
+            frame,
+            self.frame_format.extension(),
+            digits = self.frame_digits
+        )
+    }
+
+    /// Returns the pattern of the paths frames are saved at, with a printf-style placeholder for the frame number like ffmpeg's image sequences.
+    pub fn frame_pattern(&self) -> String {
+        format!(
+            "{}/tmp/frame_%0{}d.{}",
+            self.save_directory,
+            self.frame_digits,
+            self.frame_format.extension()
+        )
+    }
+
+    /// Returns the width of the frames in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the frames in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the start frame and frame count of every animation played so far, in order.
+    pub fn timeline(&self) -> &[(u32, u32)] {
+        self.scheduler.timeline()
+    }
+
+    /// Returns the number of frames rendered so far.
+    pub fn frame_count(&self) -> u32 {
+        self.scheduler.next_frame()
+    }
+
+    /// Returns the scheduler numbering the frames of every animation played on the screen.
+    pub fn scheduler(&self) -> &FrameScheduler {
+        &self.scheduler
+    }
+}
+
+/// Builder for a [Screen2D], with the same defaults as scene files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Screen2DBuilder {
+    x_axis: (f32, f32),
+    y_axis: (f32, f32),
+    save_directory: String,
+    fps: u32,
+    resolution: (u32, u32),
+    #[serde(with = "crate::scene::loader::rgb")]
+    background: Rgb<u8>,
+    axis_style: AxisStyle,
+    mode: ScreenMode,
+}
+
+impl From<Screen2D> for Screen2DBuilder {
+    /// Returns a builder with the options of an existing screen.
+    fn from(screen: Screen2D) -> Self {
+        Screen2DBuilder {
+            x_axis: screen.x_axis,
+            y_axis: screen.y_axis,
+            save_directory: screen.save_directory,
+            fps: screen.fps,
+            resolution: (screen.width, screen.height),
+            background: screen.background,
+            axis_style: screen.axis_style,
+            mode: screen.mode,
+        }
+    }
+}
+
+impl TryFrom<Screen2DBuilder> for Screen2D {
+    type Error = MathvisError;
+
+    /// Creates a screen with the options of a builder, like [Screen2DBuilder::build].
+    fn try_from(builder: Screen2DBuilder) -> Result<Self, Self::Error> {
+        builder.build()
+    }
+}
+
+impl Default for Screen2DBuilder {
+    fn default() -> Self {
+        let resolution = Quality::HIGH.resolution();
+        Screen2DBuilder {
+            x_axis: (-3.0, 3.0),
+            y_axis: (-3.0, 3.0),
+            save_directory: String::from("."),
+            fps: 30,
+            resolution: (resolution.values()[0] as u32, resolution.values()[1] as u32),
+            background: BACKGROUND_COLOR,
+            axis_style: AxisStyle::default(),
+            mode: ScreenMode::default(),
         }
-        Err("You can't change the frame to an earlier one.".into())
+    }
+}
+
+impl Screen2DBuilder {
+    /// Sets the limits of the x axis, (-3, 3) by default.
+    pub fn x_axis(mut self, start: f32, end: f32) -> Self {
+        self.x_axis = (start, end);
+        self
+    }
+
+    /// Sets the limits of the y axis, (-3, 3) by default.
+    pub fn y_axis(mut self, start: f32, end: f32) -> Self {
+        self.y_axis = (start, end);
+        self
+    }
+
+    /// Sets the directory frames are saved in (inside its `tmp` directory), the working directory by default.
+    pub fn save_directory(mut self, save_directory: impl Into<String>) -> Self {
+        self.save_directory = save_directory.into();
+        self
+    }
+
+    /// Sets the frames per second of the animations, 30 by default.
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Sets the resolution of the frames to one of the supported qualities, [Quality::HIGH] by default.
+    pub fn quality(mut self, quality: Quality) -> Self {
+        let resolution = quality.resolution();
+        self.resolution = (resolution.values()[0] as u32, resolution.values()[1] as u32);
+        self
+    }
+
+    /// Sets the resolution of the frames in pixels, which must be one of the supported qualities.
+    pub fn resolution(mut self, width: u32, height: u32) -> Self {
+        self.resolution = (width, height);
+        self
+    }
+
+    /// Sets the color used to fill the background of every frame.
+    pub fn background(mut self, color: Rgb<u8>) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// Sets the style of the axes drawn on every frame.
+    pub fn axis_style(mut self, style: AxisStyle) -> Self {
+        self.axis_style = style;
+        self
+    }
+
+    /// Sets how the plane is presented, as the real plane by default.
+    pub fn mode(mut self, mode: ScreenMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Creates the screen.
+    ///
+    /// Returns an Err if the axes' limits aren't increasing, the fps is 0 or the resolution isn't supported and an Ok with the screen otherwise.
+    pub fn build(self) -> Result<Screen2D, MathvisError> {
+        let (width, height) = self.resolution;
+        if Quality::new(width, height).is_none() {
+            return Err(MathvisError::InvalidArgument(format!(
+                "Unsupported resolution {}x{}.",
+                width, height
+            )));
+        }
+        if self.fps == 0 {
+            return Err(MathvisError::InvalidArgument(
+                "The fps must be positive.".into(),
+            ));
+        }
+        let mut screen = Screen2D::new(
+            self.x_axis,
+            self.y_axis,
+            self.save_directory,
+            self.fps,
+            width,
+            height,
+        )?;
+        screen.set_background(self.background);
+        screen.set_axis_style(self.axis_style);
+        screen.set_mode(self.mode);
+        Ok(screen)
     }
 }
 
@@ -149,15 +829,25 @@ impl<T: Number> ScreenLike<T> for Screen2D {
         self.y_axis
     }
 
-    /// Returns true if the specified object can be contained by the screen, that is, if the object's coordinates are in the axes' range.
+    /// Returns true if the specified object can be contained by the screen, that is, if its whole bounding box is in the axes' range.
+    /// Objects without a bounding box, which span the whole plane, can always be contained.
     fn can_contain<V>(&self, object: &V) -> bool
     where
         V: Show2D<T>,
     {
-        in_axis_range(object.x(), self.x_axis) && in_axis_range(object.y(), self.y_axis)
+        object
+            .bounding_box()
+            .is_none_or(|bounds| self.bounds().contains(&bounds))
     }
 }
 
+/// Returns an Err naming the axis that doesn't start before it ends, if there's one, and an Ok otherwise.
+fn check_axes(x_axis: (f32, f32), y_axis: (f32, f32)) -> Result<(), MathvisError> {
+    Interval::named("x axis", x_axis.0 as f64, x_axis.1 as f64)?;
+    Interval::named("y axis", y_axis.0 as f64, y_axis.1 as f64)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,4 +858,67 @@ mod tests {
             Screen2D::new((-10.0, 10.0), (-10.0, 15.0), String::new(), 30, 1920, 1080).unwrap();
         assert!(screen.get_center_pixels() == (960.0, 648.0));
     }
+
+    #[test]
+    fn test_builder() {
+        let screen = Screen2D::builder()
+            .y_axis(-10.0, 15.0)
+            .x_axis(-10.0, 10.0)
+            .background(Rgb([0, 0, 0]))
+            .build()
+            .unwrap();
+        assert_eq!(screen.get_center_pixels(), (960.0, 648.0));
+        assert_eq!(screen.background, Rgb([0, 0, 0]));
+        assert!(Screen2D::builder().x_axis(1.0, -1.0).build().is_err());
+        assert!(Screen2D::builder().fps(0).build().is_err());
+    }
+
+    #[test]
+    fn test_can_contain() {
+        use crate::animation::{drawable::is_visible, geometry::Circle, vector::Vector2D};
+        use crate::api::util::CoordinateMap;
+
+        let screen = Screen2D::new((1.0, 10.0), (-5.0, 5.0), String::new(), 30, 854, 480).unwrap();
+        // The tip is on the screen, but the arrow starts at the origin, which isn't
+        assert!(!screen.can_contain(&Vector2D::new(5.0, 1.0, Rgb([255, 0, 0]))));
+        let screen = Screen2D::new((-1.0, 10.0), (-5.0, 5.0), String::new(), 30, 854, 480).unwrap();
+        assert!(screen.can_contain(&Vector2D::new(5.0, 1.0, Rgb([255, 0, 0]))));
+
+        let map = CoordinateMap::new(&screen).unwrap();
+        let circle = |x| Circle::new((x, 0.0), 1.0).unwrap();
+        // Circles hanging over the edge are drawn, and the ones well past it aren't
+        assert!(is_visible(&circle(10.5), &map));
+        assert!(!is_visible(&circle(20.0), &map));
+    }
+
+    #[test]
+    fn test_serde() {
+        use crate::api::{matrix::Matrix, vector::Vector};
+
+        let screen = Screen2D::builder()
+            .x_axis(-5.0, 5.0)
+            .quality(Quality::LOW)
+            .background(Rgb([10, 20, 30]))
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&screen).unwrap();
+        assert_eq!(serde_json::from_str::<Screen2D>(&json).unwrap(), screen);
+        assert!(serde_json::from_str::<Screen2D>(r#"{"x_axis": [1.0, -1.0]}"#).is_err());
+        let complex: Screen2D = serde_json::from_str(r#"{"mode": "complex_plane"}"#).unwrap();
+        assert_eq!(complex.mode(), ScreenMode::ComplexPlane);
+        let style: AxisStyle = serde_json::from_str(r#"{"color": "red"}"#).unwrap();
+        assert_eq!(style.color, Rgb([255, 0, 0]));
+        assert!(style.visible);
+
+        let matrix = Matrix::new(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(serde_json::to_string(&matrix).unwrap(), "[[1,2],[3,4]]");
+        assert_eq!(
+            serde_json::from_str::<Matrix<i32>>("[[1,2],[3,4]]").unwrap(),
+            matrix
+        );
+        assert!(serde_json::from_str::<Matrix<i32>>("[[1,2],[3]]").is_err());
+        let vector: Vector<f64> = serde_json::from_str("[1.5, 2.0]").unwrap();
+        assert_eq!(vector, Vector::new(vec![1.5, 2.0]).unwrap());
+        assert!(serde_json::from_str::<Point<f64>>("[]").is_err());
+    }
 }
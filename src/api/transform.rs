@@ -0,0 +1,285 @@
+//! Module containing affine transforms of the plane, a linear map followed by a translation.
+#![warn(missing_docs)]
+use std::f64::consts::{PI, TAU};
+
+use super::{angle::Angle, matrix::Matrix};
+use crate::error::MathvisError;
+
+/// An affine transform of the plane, taking (x, y) to (a·x + b·y + tx, c·x + d·y + ty).
+///
+/// Transforms are built from rotations, scalings, shears and translations and combined with [Transform2D::compose].
+/// Interpolating between two of them with [Transform2D::lerp] blends their rotation, scale, shear and translation separately,
+/// so halfway through a rotation is a smaller rotation instead of a squashed image like blending the matrices would give.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::angle::Angle;
+/// use mathvis::api::transform::Transform2D;
+///
+/// let turn = Transform2D::rotation(Angle::degrees(90.0));
+/// let shift = Transform2D::translation(1.0, 0.0);
+/// // Turns first and then shifts
+/// let (x, y) = shift.compose(&turn).apply((1.0, 0.0));
+/// assert!((x - 1.0).abs() < 1e-12 && (y - 1.0).abs() < 1e-12);
+///
+/// // Halfway through a half turn is a quarter turn, not a collapse to the origin
+/// let half_turn = Transform2D::rotation(Angle::degrees(180.0));
+/// let (x, y) = Transform2D::IDENTITY.lerp(&half_turn, 0.5).apply((1.0, 0.0));
+/// assert!(x.abs() < 1e-12 && (y.abs() - 1.0).abs() < 1e-12);
+///
+/// let inverse = shift.compose(&turn).inverse().unwrap();
+/// let (x, y) = inverse.apply((1.0, 1.0));
+/// assert!((x - 1.0).abs() < 1e-12 && y.abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2D {
+    linear: [[f64; 2]; 2],
+    translation: (f64, f64),
+}
+
+/// The parts of a transform that are interpolated separately, in the order they're applied in:
+/// a shear along x and a scaling (the upper triangular part), a rotation and a translation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Decomposition {
+    scale: (f64, f64),
+    shear: f64,
+    angle: f64,
+    translation: (f64, f64),
+}
+
+impl Transform2D {
+    /// The transform that leaves every point in place.
+    pub const IDENTITY: Transform2D = Transform2D {
+        linear: [[1.0, 0.0], [0.0, 1.0]],
+        translation: (0.0, 0.0),
+    };
+
+    /// Creates the transform multiplying points by a 2x2 matrix, given by rows, and then translating them.
+    pub fn new(linear: [[f64; 2]; 2], translation: (f64, f64)) -> Self {
+        Transform2D {
+            linear,
+            translation,
+        }
+    }
+
+    /// Creates the transform multiplying points by a 2x2 matrix.
+    ///
+    /// Returns an Err if the matrix isn't 2x2 and an Ok with the transform otherwise.
+    pub fn from_matrix(matrix: &Matrix<f64>) -> Result<Self, MathvisError> {
+        if matrix.get_dimensions() != (2, 2) {
+            return Err(MathvisError::DimensionMismatch(
+                "The linear part of a 2D transform must be a 2x2 matrix.".into(),
+            ));
+        }
+        let (top, bottom) = (matrix.row(0), matrix.row(1));
+        Ok(Self::new(
+            [[top[0], top[1]], [bottom[0], bottom[1]]],
+            (0.0, 0.0),
+        ))
+    }
+
+    /// Creates the transform moving every point by (dx, dy).
+    pub fn translation(dx: f64, dy: f64) -> Self {
+        Self::new(Self::IDENTITY.linear, (dx, dy))
+    }
+
+    /// Creates the rotation around the origin by an angle, with plain numbers taken as radians (see [Angle]).
+    pub fn rotation(angle: impl Into<Angle>) -> Self {
+        let (sin, cos) = angle.into().sin_cos();
+        Self::new([[cos, -sin], [sin, cos]], (0.0, 0.0))
+    }
+
+    /// Creates the scaling from the origin by separate factors along the x and y axes.
+    pub fn scaling(fx: f64, fy: f64) -> Self {
+        Self::new([[fx, 0.0], [0.0, fy]], (0.0, 0.0))
+    }
+
+    /// Creates the shear taking (x, y) to (x + kx·y, ky·x + y), like [Matrix::shear_matrix_2d].
+    pub fn shear(kx: f64, ky: f64) -> Self {
+        Self::new([[1.0, kx], [ky, 1.0]], (0.0, 0.0))
+    }
+
+    /// Returns the linear part of the transform as a 2x2 matrix.
+    pub fn linear_matrix(&self) -> Matrix<f64> {
+        Matrix {
+            values: self.linear.concat(),
+            rows: 2,
+            cols: 2,
+        }
+    }
+
+    /// Returns how far the transform moves the origin.
+    pub fn translation_part(&self) -> (f64, f64) {
+        self.translation
+    }
+
+    /// Returns the determinant of the linear part, the factor areas are scaled by, negative if the transform mirrors the plane.
+    pub fn determinant(&self) -> f64 {
+        let [[a, b], [c, d]] = self.linear;
+        a * d - b * c
+    }
+
+    /// Returns where the transform takes a point.
+    pub fn apply(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let [[a, b], [c, d]] = self.linear;
+        (
+            a * x + b * y + self.translation.0,
+            c * x + d * y + self.translation.1,
+        )
+    }
+
+    /// Returns the transform applying `other` first and then this one, like multiplying their matrices.
+    pub fn compose(&self, other: &Transform2D) -> Self {
+        let [[a, b], [c, d]] = self.linear;
+        let [[e, f], [g, h]] = other.linear;
+        Self::new(
+            [
+                [a * e + b * g, a * f + b * h],
+                [c * e + d * g, c * f + d * h],
+            ],
+            self.apply(other.translation),
+        )
+    }
+
+    /// Returns the transform that undoes this one.
+    ///
+    /// Returns an Err if the transform collapses the plane onto a line or a point and an Ok with the inverse otherwise.
+    pub fn inverse(&self) -> Result<Self, MathvisError> {
+        let determinant = self.determinant();
+        if determinant == 0.0 || !determinant.is_finite() {
+            return Err(MathvisError::SingularMatrix);
+        }
+        let [[a, b], [c, d]] = self.linear;
+        let linear = Self::new(
+            [
+                [d / determinant, -b / determinant],
+                [-c / determinant, a / determinant],
+            ],
+            (0.0, 0.0),
+        );
+        let (tx, ty) = linear.apply(self.translation);
+        Ok(Self::new(linear.linear, (-tx, -ty)))
+    }
+
+    /// Returns the transform a fraction `t` of the way from this one to the other,
+    /// blending their rotations, scales, shears and translations separately.
+    ///
+    /// Rotations take the shorter way around, so they never turn by more than half a turn.
+    pub fn lerp(&self, other: &Transform2D, t: f64) -> Self {
+        let (from, to) = (self.decompose(), other.decompose());
+        let blend = |a: f64, b: f64| a + (b - a) * t;
+        // The difference between the angles wrapped to half a turn either way
+        let turn = (to.angle - from.angle + PI).rem_euclid(TAU) - PI;
+        Decomposition {
+            scale: (
+                blend(from.scale.0, to.scale.0),
+                blend(from.scale.1, to.scale.1),
+            ),
+            shear: blend(from.shear, to.shear),
+            angle: from.angle + turn * t,
+            translation: (
+                blend(from.translation.0, to.translation.0),
+                blend(from.translation.1, to.translation.1),
+            ),
+        }
+        .recompose()
+    }
+
+    /// Splits the linear part into a rotation times an upper triangular matrix, which holds the scaling and the shear.
+    fn decompose(&self) -> Decomposition {
+        let [[a, b], [c, d]] = self.linear;
+        let fx = a.hypot(c);
+        let angle = if fx == 0.0 { 0.0 } else { c.atan2(a) };
+        let (sin, cos) = angle.sin_cos();
+        Decomposition {
+            scale: (fx, cos * d - sin * b),
+            shear: cos * b + sin * d,
+            angle,
+            translation: self.translation,
+        }
+    }
+}
+
+impl Decomposition {
+    /// Puts the parts back together into a transform.
+    fn recompose(&self) -> Transform2D {
+        let (sin, cos) = self.angle.sin_cos();
+        let (fx, fy) = self.scale;
+        Transform2D::new(
+            [
+                [cos * fx, cos * self.shear - sin * fy],
+                [sin * fx, sin * self.shear + cos * fy],
+            ],
+            self.translation,
+        )
+    }
+}
+
+impl Default for Transform2D {
+    /// Returns the [Transform2D::IDENTITY] transform.
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_transforms() {
+        let transform = Transform2D::translation(2.0, -1.0)
+            .compose(&Transform2D::rotation(0.7))
+            .compose(&Transform2D::shear(0.5, 0.0))
+            .compose(&Transform2D::scaling(2.0, -3.0));
+        // Decomposing and recomposing gives the same transform back
+        let recomposed = transform.decompose().recompose();
+        for point in [(1.0, 0.0), (0.0, 1.0), (-2.0, 3.0)] {
+            assert!(close(transform.apply(point), recomposed.apply(point)));
+            let back = transform.inverse().unwrap().apply(transform.apply(point));
+            assert!(close(back, point));
+        }
+        assert!(close(
+            transform.lerp(&transform, 0.3).apply((1.0, 1.0)),
+            transform.apply((1.0, 1.0))
+        ));
+        assert!(close(
+            Transform2D::IDENTITY
+                .lerp(&transform, 1.0)
+                .apply((3.0, 2.0)),
+            transform.apply((3.0, 2.0))
+        ));
+        assert!(matches!(
+            Transform2D::scaling(1.0, 0.0).inverse(),
+            Err(MathvisError::SingularMatrix)
+        ));
+        let shear = Matrix::shear_matrix_2d(1.5, 0.0);
+        assert_eq!(
+            Transform2D::from_matrix(&shear).unwrap(),
+            Transform2D::shear(1.5, 0.0)
+        );
+        assert_eq!(Transform2D::shear(1.5, 0.0).linear_matrix(), shear);
+    }
+
+    #[test]
+    fn test_lerp_angles() {
+        // From just below a half turn to just above it goes the short way, through the half turn
+        let start = Transform2D::rotation(Angle::degrees(170.0));
+        let end = Transform2D::rotation(Angle::degrees(-170.0));
+        let halfway = start.lerp(&end, 0.5);
+        assert!(close(halfway.apply((1.0, 0.0)), (-1.0, 0.0)));
+        // Collapsed transforms have no rotation, and still blend
+        let collapsed = Transform2D::scaling(0.0, 0.0);
+        assert!(close(
+            collapsed
+                .lerp(&Transform2D::IDENTITY, 0.5)
+                .apply((2.0, 0.0)),
+            (1.0, 0.0)
+        ));
+    }
+}
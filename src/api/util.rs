@@ -2,28 +2,39 @@
 #![warn(missing_docs)]
 use std::{
     fmt::Display,
+    fs::File,
+    io::BufWriter,
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
-    path::PathBuf,
-    sync::Arc,
 };
 
-use clap::{command, Parser, ValueEnum};
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+use imageproc::image::{
+    codecs::png::{CompressionType, FilterType, PngEncoder},
+    EncodableLayout, ImageBuffer, ImageFormat, PixelWithColorType,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::MathvisError;
 
 use super::{
+    bounds::BoundingBox,
     point::{Point, PointLike},
     screen::{Screen2D, ScreenLike},
 };
 
-/// Returns whether or not a value is inside an axis' range.
-pub(crate) fn in_axis_range<T: Number>(val: T, (start, end): (f32, f32)) -> bool {
-    start <= val.to_f64() as f32 && val.to_f64() as f32 <= end
-}
-
-#[derive(ValueEnum, Clone, Debug, PartialEq, Eq, Copy)]
-pub(crate) enum Quality {
+/// Output resolutions supported by the renderer.
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Quality {
+    /// 854x480
     LOW,
+    /// 1280x720
     MEDIUM,
+    /// 1920x1080
     HIGH,
+    /// 3840x2160
     ULTRA,
 }
 
@@ -42,7 +53,7 @@ impl Quality {
     }
 
     /// Returns a [Point] with the quality's resolution values.
-    pub(crate) fn resolution(&self) -> Point<f32> {
+    pub fn resolution(&self) -> Point<f32> {
         match self {
             Quality::LOW => Point::new(vec![854.0, 480.0]).unwrap(),
             Quality::MEDIUM => Point::new(vec![1280.0, 720.0]).unwrap(),
@@ -69,36 +80,155 @@ impl ToString for Quality {
     }
 }
 
-/// Converts an (x, y) coordinate into a pixel position.
-pub(crate) fn interpolate(
+/// Image formats of the intermediate frames that are joined into the video.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum FrameFormat {
+    /// PNG with the default compression, the smallest frames
+    #[default]
+    Png,
+    /// PNG with the fastest compression, larger frames that are written several times faster
+    PngFast,
+    /// Uncompressed BMP, the fastest to write but a few megabytes per frame, can't be transparent
+    Bmp,
+}
+
+impl FrameFormat {
+    /// Returns the file extension of frames in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FrameFormat::Png | FrameFormat::PngFast => "png",
+            FrameFormat::Bmp => "bmp",
+        }
+    }
+
+    /// Saves a frame to the specified path in this format.
+    ///
+    /// Returns an Err if the frame can't be encoded or written and an Ok otherwise.
+    pub(crate) fn save<P>(
+        &self,
+        img: &ImageBuffer<P, Vec<P::Subpixel>>,
+        path: &str,
+    ) -> Result<(), MathvisError>
+    where
+        P: PixelWithColorType,
+        [P::Subpixel]: EncodableLayout,
+    {
+        match self {
+            FrameFormat::Png => img.save_with_format(path, ImageFormat::Png),
+            FrameFormat::PngFast => {
+                let file = BufWriter::new(File::create(path)?);
+                let encoder =
+                    PngEncoder::new_with_quality(file, CompressionType::Fast, FilterType::NoFilter);
+                img.write_with_encoder(encoder)
+            }
+            FrameFormat::Bmp => img.save_with_format(path, ImageFormat::Bmp),
+        }
+        .map_err(|err| MathvisError::Encode(format!("Failed to save frame {}: {}", path, err)))
+    }
+}
+
+/// Converts (x, y) coordinates of a screen into pixel positions.
+///
+/// Holds everything the conversion needs (usable resolution, screen center and scaling factors),
+/// so it's computed once per frame, or once per animation when the screen doesn't change, instead of once per coordinate.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::screen::Screen2D;
+/// use mathvis::api::util::CoordinateMap;
+///
+/// let screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::new(), 30, 1920, 1080).unwrap();
+/// let map = CoordinateMap::new(&screen).unwrap();
+/// assert_eq!(map.to_pixels((0.0, 0.0)), (960.0, 540.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoordinateMap {
     quality: Quality,
-    screen: Arc<Screen2D>,
-    (x, y): (f32, f32),
-) -> (f32, f32) {
-    let usable_res = quality.usable();
-    let center = screen.get_center_pixels();
-    let scaling_factor = (
-        usable_res.values()[0]
-            / (ScreenLike::<f32>::x_axis(&*screen).0.abs()
-                + ScreenLike::<f32>::x_axis(&*screen).1.abs()),
-        usable_res.values()[1]
-            / (ScreenLike::<f32>::y_axis(&*screen).0.abs()
-                + ScreenLike::<f32>::x_axis(&*screen).1.abs()),
-    );
-    (
-        x * scaling_factor.0 + center.0,
-        -y * scaling_factor.1 + center.1,
-    )
+    center: (f32, f32),
+    scaling_factor: (f32, f32),
 }
 
-/// Trait that represents a generic signed number type.
+impl CoordinateMap {
+    /// Creates the coordinate map of a screen.
+    ///
+    /// Returns None if the screen's resolution isn't one of the supported qualities and Some with the map otherwise.
+    pub fn new(screen: &Screen2D) -> Option<CoordinateMap> {
+        let quality = Quality::new(screen.width, screen.height)?;
+        let usable_res = quality.usable();
+        let (x_axis, y_axis) = (
+            ScreenLike::<f32>::x_axis(screen),
+            ScreenLike::<f32>::y_axis(screen),
+        );
+        Some(CoordinateMap {
+            quality,
+            center: screen.get_center_pixels(),
+            scaling_factor: (
+                usable_res.values()[0] / (x_axis.0.abs() + x_axis.1.abs()),
+                usable_res.values()[1] / (y_axis.0.abs() + x_axis.1.abs()),
+            ),
+        })
+    }
+
+    /// Converts an (x, y) coordinate into a pixel position.
+    pub fn to_pixels(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (
+            x * self.scaling_factor.0 + self.center.0,
+            -y * self.scaling_factor.1 + self.center.1,
+        )
+    }
+
+    /// Converts a pixel position into an (x, y) coordinate, the inverse of [CoordinateMap::to_pixels].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::screen::Screen2D;
+    /// use mathvis::api::util::CoordinateMap;
+    ///
+    /// let screen = Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::new(), 30, 1920, 1080).unwrap();
+    /// let map = CoordinateMap::new(&screen).unwrap();
+    /// assert_eq!(map.to_coordinates((960.0, 540.0)), (0.0, 0.0));
+    /// let (x, y) = map.to_coordinates(map.to_pixels((2.0, -3.0)));
+    /// assert!((x - 2.0).abs() < 1e-4 && (y + 3.0).abs() < 1e-4);
+    /// ```
+    pub fn to_coordinates(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (
+            (x - self.center.0) / self.scaling_factor.0,
+            -(y - self.center.1) / self.scaling_factor.1,
+        )
+    }
+
+    /// Returns the part of the plane shown in the frame, in screen coordinates.
+    pub fn visible_bounds(&self) -> BoundingBox {
+        let resolution = self.quality.resolution();
+        let (x0, y0) = self.to_coordinates((0.0, 0.0));
+        let (x1, y1) = self.to_coordinates((resolution.values()[0], resolution.values()[1]));
+        BoundingBox::new((x0 as f64, y0 as f64), (x1 as f64, y1 as f64))
+    }
+
+    /// Returns the quality of the frames this map draws on.
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    /// Returns the pixel position of the origin.
+    pub fn center(&self) -> (f32, f32) {
+        self.center
+    }
+}
+
+/// Trait that represents a generic number type, signed or unsigned.
 /// Number implements all basic operations, partial ordering and equality, Send and Sync for safe passing between threads, Display and Debug for testing purposes, and Sized because all numbers must have a compile-time size
+///
+/// Negation isn't required, see [Signed] for the number types that can be negated.
 pub trait Number:
     Add<Output = Self>
     + Sub<Output = Self>
     + Mul<Output = Self>
     + Div<Output = Self>
-    + Neg<Output = Self>
     + SubAssign
     + MulAssign
     + AddAssign
@@ -121,6 +251,7 @@ pub trait Number:
     /// Checks if a value is 0.
     fn is_zero(self) -> bool;
     /// Returns the absolute value of that number.
+    /// For unsigned types, this is the number itself.
     fn abs(self) -> Self;
     /// Returns the square root of that number in that type.
     /// For integer types, the result is truncated to only the integer part.
@@ -128,6 +259,7 @@ pub trait Number:
     /// Returns the result of raising a value to a specified integer.
     fn pow(self, exponent: i32) -> Self;
     /// Converts an f64 into this type.
+    /// For unsigned types, negative values become 0.
     fn from_f64(value: f64) -> Self;
     /// Converts an f32 into this type.
     fn from_f32(value: f32) -> Self;
@@ -383,30 +515,187 @@ impl Number for i64 {
     }
 }
 
-/// Struct containing the command line arguments for the CLI interface
-#[derive(Parser, Debug, Clone)]
-#[command(author, version, about)]
-pub(crate) struct Args {
-    pub(crate) source: String,
+impl Number for u32 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn is_zero(self) -> bool {
+        self == 0
+    }
+
+    fn abs(self) -> Self {
+        self
+    }
+
+    fn sqrt(self) -> Self {
+        (self as f64).sqrt() as u32
+    }
+
+    fn pow(self, exponent: i32) -> Self {
+        if exponent < 0 {
+            return Self::from_f64((self as f64).powi(exponent));
+        }
+        self.pow(exponent as u32)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as u32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value as u32
+    }
+
+    fn from_i64(value: i64) -> Self {
+        value.max(0) as u32
+    }
+
+    fn from_i32(value: i32) -> Self {
+        value.max(0) as u32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn to_i64(self) -> i64 {
+        self as i64
+    }
+
+    fn is_positive(&self) -> bool {
+        *self > 0
+    }
+
+    fn is_negative(&self) -> bool {
+        false
+    }
+}
+
+impl Number for usize {
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn is_zero(self) -> bool {
+        self == 0
+    }
+
+    fn abs(self) -> Self {
+        self
+    }
+
+    fn sqrt(self) -> Self {
+        (self as f64).sqrt() as usize
+    }
+
+    fn pow(self, exponent: i32) -> Self {
+        if exponent < 0 {
+            return Self::from_f64((self as f64).powi(exponent));
+        }
+        self.pow(exponent as u32)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as usize
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value as usize
+    }
+
+    fn from_i64(value: i64) -> Self {
+        value.max(0) as usize
+    }
 
-    #[arg(long, default_value_t = 30)]
-    pub(crate) fps: u32,
+    fn from_i32(value: i32) -> Self {
+        value.max(0) as usize
+    }
 
-    #[arg(short, long, default_value_os = "../output/output.mp4")]
-    pub(crate) output: PathBuf,
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn to_i64(self) -> i64 {
+        self as i64
+    }
 
-    #[arg(long, default_value_t = false)]
-    pub(crate) gif: bool,
+    fn is_positive(&self) -> bool {
+        *self > 0
+    }
 
-    #[arg(short, long, default_value_t = Quality::HIGH)]
-    pub(crate) quality: Quality,
+    fn is_negative(&self) -> bool {
+        false
+    }
 }
 
+/// Trait that represents a generic signed number type, a [Number] that can also be negated.
+/// Anything that needs negative values, like determinants, eigenvalues and inverses, requires it.
+pub trait Signed: Number + Neg<Output = Self> {}
+
+impl Signed for f64 {}
+
+impl Signed for f32 {}
+
+impl Signed for i32 {}
+
+impl Signed for i64 {}
+
 /// Returns the solution of a quadratic equation with the specified coefficients.
-pub(crate) fn quadsolve<T: Number>(a: T, b: T, c: T) -> (T, T) {
+pub(crate) fn quadsolve<T: Signed>(a: T, b: T, c: T) -> (T, T) {
     let delta = b * b - a * T::from_f64(4.0) * c;
     (
         (-b + delta.sqrt()) / (a * T::from_f64(2.0)),
         (-b - delta.sqrt()) / (a * T::from_f64(2.0)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::{open, Rgb, RgbImage};
+
+    use super::*;
+    use crate::api::{matrix::Matrix, point::PointLike, vector::Vector};
+
+    #[test]
+    fn test_frame_formats_are_lossless() {
+        let img = RgbImage::from_fn(16, 9, |x, y| Rgb([x as u8 * 10, y as u8 * 20, 43]));
+        for format in [FrameFormat::Png, FrameFormat::PngFast, FrameFormat::Bmp] {
+            let path = std::env::temp_dir()
+                .join(format!("mathvis_frame_format_test.{}", format.extension()));
+            let path = path.to_str().unwrap();
+            format.save(&img, path).unwrap();
+            assert_eq!(open(path).unwrap().to_rgb8(), img);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_unsigned_numbers() {
+        let matrix = Matrix::<u32>::new(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        let vector = Vector::<u32>::new(vec![1, 1]).unwrap();
+        assert_eq!(
+            (&matrix * &vector).unwrap(),
+            Vector::new(vec![3, 7]).unwrap()
+        );
+        assert_eq!(
+            (&matrix * &Matrix::<u32>::identity(2).unwrap()).unwrap(),
+            matrix
+        );
+
+        let point = Point::<usize>::new(vec![3, 0]).unwrap();
+        let other = Point::<usize>::new(vec![0, 4]).unwrap();
+        assert_eq!(point.distance_to(&other).unwrap(), 5);
+        assert_eq!(other.distance_to(&point).unwrap(), 5);
+        assert_eq!(u32::from_i32(-4), 0);
+        assert!(!5u32.is_negative());
+    }
+}
@@ -1,15 +1,15 @@
 //! Module containing an n-dimensional vector structure and its respective operations.
 #![warn(missing_docs)]
-use rand::{
-    distr::{Distribution, StandardUniform},
-    rng, Rng,
-};
+use rand::distr::{Distribution, StandardUniform};
+use serde::{Deserialize, Serialize};
 
-use super::{point::PointLike, util::Number};
-use std::{
-    error::Error,
-    ops::{Add, Mul},
+use super::{
+    point::PointLike,
+    random::sample,
+    util::{Number, Signed},
 };
+use crate::error::MathvisError;
+use std::ops::{Add, Mul};
 
 /// An n-dimensional vector that allows for different vector operations.
 ///
@@ -25,11 +25,36 @@ use std::{
 /// let v = Vector::new(vec![1, 0]).unwrap();
 /// v.norm();
 /// ```
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Vec<T>", into = "Vec<T>")]
 pub struct Vector<T: Number> {
     pub(crate) values: Vec<T>,
 }
 
+impl<T> TryFrom<Vec<T>> for Vector<T>
+where
+    T: Number,
+{
+    type Error = MathvisError;
+
+    /// Converts a vector of coordinates into a vector, like [PointLike::new].
+    ///
+    /// Returns an Err if there are no coordinates and an Ok with the vector otherwise.
+    fn try_from(values: Vec<T>) -> Result<Self, Self::Error> {
+        Vector::new(values)
+    }
+}
+
+impl<T> From<Vector<T>> for Vec<T>
+where
+    T: Number,
+{
+    /// Converts a vector into its coordinates.
+    fn from(vector: Vector<T>) -> Self {
+        vector.values
+    }
+}
+
 impl<T> Vector<T>
 where
     T: Number,
@@ -50,9 +75,9 @@ where
     /// let v2 = Vector::new(vec![1, 1]).unwrap();
     /// assert_eq!(v1.dot(v2).unwrap(), 2);
     /// ```
-    pub fn dot(&self, rhs: Vector<T>) -> Result<T, Box<dyn Error>> {
+    pub fn dot(&self, rhs: Vector<T>) -> Result<T, MathvisError> {
         if self.get_dimensions() != rhs.get_dimensions() {
-            return Err("wrong dimensions".into());
+            return Err(MathvisError::DimensionMismatch("wrong dimensions".into()));
         }
         Ok(self
             .values
@@ -94,9 +119,11 @@ where
     /// let vector = Vector::new(vec![2, 0]).unwrap();
     /// assert_eq!(vector.normalize().unwrap(), Vector::new(vec![1, 0]).unwrap());
     /// ```
-    pub fn normalize(&self) -> Result<Vector<T>, Box<dyn Error>> {
+    pub fn normalize(&self) -> Result<Vector<T>, MathvisError> {
         if self.norm() == T::zero() {
-            return Err("Cannot normalize vector of norm 0".into());
+            return Err(MathvisError::InvalidArgument(
+                "Cannot normalize vector of norm 0".into(),
+            ));
         }
         Ok(Vector {
             values: self.values.iter().map(|val| *val / self.norm()).collect(),
@@ -111,7 +138,7 @@ where
     Vector<T>: PointLike<T>,
     Vector<U>: PointLike<U>,
 {
-    type Output = Result<Vector<U>, String>;
+    type Output = Result<Vector<U>, MathvisError>;
 
     /// Adds two vectors together according to regular vector addition.
     /// Both vectors can be of different types but the resulting vector will always be of the second one's type, and addition between floats and integers is not allowed.
@@ -127,15 +154,41 @@ where
     /// assert!((v1.clone() + v1).unwrap() == Vector::new(vec![2, 2]).unwrap());
     /// ```
     fn add(self, rhs: Vector<U>) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl<T, U> Add<&Vector<U>> for &Vector<T>
+where
+    T: Number + Add<U, Output = U>,
+    U: Number,
+    Vector<T>: PointLike<T>,
+    Vector<U>: PointLike<U>,
+{
+    type Output = Result<Vector<U>, MathvisError>;
+
+    /// Adds two borrowed vectors together, without consuming either of them.
+    ///
+    /// Returns an Err if the dimensions are different and an Ok with the resulting vector otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::vector::Vector;
+    /// use mathvis::api::point::PointLike;
+    /// let v1 = Vector::new(vec![1, 1]).unwrap();
+    /// assert!((&v1 + &v1).unwrap() == Vector::new(vec![2, 2]).unwrap());
+    /// ```
+    fn add(self, rhs: &Vector<U>) -> Self::Output {
         if self.get_dimensions() != rhs.get_dimensions() {
-            return Err(String::from("wrong dimensions"));
+            return Err(MathvisError::DimensionMismatch("wrong dimensions".into()));
         }
         Ok(Vector {
             values: self
                 .values
                 .iter()
                 .zip(rhs.values.iter())
-                .map(|(a, b): (&T, &U)| a.clone() + b.clone())
+                .map(|(&a, &b)| a + b)
                 .collect(),
         })
     }
@@ -144,11 +197,11 @@ where
 impl<T, U> Mul<Vector<U>> for Vector<T>
 where
     T: Number + Mul<U, Output = U>,
-    U: Number,
+    U: Signed,
     Vector<T>: PointLike<T>,
     Vector<U>: PointLike<U>,
 {
-    type Output = Result<Vector<U>, String>;
+    type Output = Result<Vector<U>, MathvisError>;
 
     /// Performs a cross product between two 3D vectors.
     ///
@@ -163,8 +216,34 @@ where
     /// assert!((v1.clone() * v1).unwrap() == Vector::new(vec![0, 0, 0]).unwrap());
     /// ```
     fn mul(self, rhs: Vector<U>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<T, U> Mul<&Vector<U>> for &Vector<T>
+where
+    T: Number + Mul<U, Output = U>,
+    U: Signed,
+    Vector<T>: PointLike<T>,
+    Vector<U>: PointLike<U>,
+{
+    type Output = Result<Vector<U>, MathvisError>;
+
+    /// Performs a cross product between two borrowed 3D vectors.
+    ///
+    /// Returns Err if both vectors' dimensions are not 3 and an Ok with the result otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::{point::PointLike, vector::Vector};
+    ///
+    /// let v1 = Vector::new(vec![1, 0, 1]).unwrap();
+    /// assert!((&v1 * &v1).unwrap() == Vector::new(vec![0, 0, 0]).unwrap());
+    /// ```
+    fn mul(self, rhs: &Vector<U>) -> Self::Output {
         if self.get_dimensions() != rhs.get_dimensions() || self.get_dimensions() != 3 {
-            return Err(String::from("wrong dimensions"));
+            return Err(MathvisError::DimensionMismatch("wrong dimensions".into()));
         }
         let (l1, l2, l3) = (
             self.values()[1] * rhs.values()[2] - self.values()[2] * rhs.values()[1],
@@ -196,9 +275,32 @@ where
     ///
     /// assert!(v1.clone() * 2 == Vector::new(vec![2, 2]).unwrap());
     /// ```
+    fn mul(self, scalar: U) -> Self::Output {
+        &self * scalar
+    }
+}
+
+impl<T, U> Mul<U> for &Vector<T>
+where
+    T: Number,
+    U: Number + Mul<T, Output = U>,
+{
+    type Output = Vector<U>;
+
+    /// Multiplies a borrowed vector by a scalar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mathvis::api::{point::PointLike, vector::Vector};
+    ///
+    /// let v1 = Vector::new(vec![1, 1]).unwrap();
+    ///
+    /// assert!(&v1 * 2 == Vector::new(vec![2, 2]).unwrap());
+    /// ```
     fn mul(self, scalar: U) -> Self::Output {
         Vector {
-            values: self.values.iter().map(|val| scalar * val.clone()).collect(),
+            values: self.values.iter().map(|&val| scalar * val).collect(),
         }
     }
 }
@@ -209,7 +311,7 @@ where
 {
     /// Creates a new Vector with the specified values.
     ///
-    /// Returns None if the coordinates vector is empty and a Some otherwise.
+    /// Returns an Err if the coordinates vector is empty and an Ok otherwise.
     ///
     /// # Examples
     /// ```
@@ -218,16 +320,18 @@ where
     /// let v1 = Vector::new(vec![1, 1]);
     /// let v2 = Vector::<f32>::new(Vec::new());
     ///
-    /// assert!(v1 == Some(Vector::new(vec![1, 1]).unwrap()) && v2 == None);
+    /// assert!(v1.unwrap().values() == &vec![1, 1] && v2.is_err());
     /// ```
-    fn new(values: Vec<T>) -> Option<Self>
+    fn new(values: Vec<T>) -> Result<Self, MathvisError>
     where
         Self: Sized,
     {
         if values.is_empty() {
-            return None;
+            return Err(MathvisError::InvalidArgument(
+                "A vector must have at least one coordinate.".into(),
+            ));
         }
-        Some(Vector { values })
+        Ok(Vector { values })
     }
 
     /// Creates a new Vector on the origin with the specified dimensions.
@@ -290,6 +394,7 @@ where
 
     /// Creates a vector with the specified dimensions and random coordinates.
     /// Not meant to be used for anything other than testing purposes.
+    /// The coordinates are reproducible after calling [set_seed](crate::api::random::set_seed).
     ///
     /// Returns a None if the dimension is 0 and a Some with the vector otherwise.
     ///
@@ -309,9 +414,8 @@ where
             return None;
         }
 
-        let mut rng = rng();
         Some(Vector {
-            values: (0..dimensions).map(|_| rng.random()).collect(),
+            values: sample(dimensions as usize),
         })
     }
 }
@@ -331,7 +435,7 @@ mod tests {
             values: vec![1.0, 3.0, 3.0],
         };
 
-        assert!(a + b == Ok(c));
+        assert!((a + b).unwrap() == c);
     }
 
     #[test]
@@ -340,7 +444,7 @@ mod tests {
             values: vec![1.0, 2.0, 3.0],
         };
         let b = Vector::<f32>::random(2).unwrap();
-        assert!(a + b == Err(String::from("wrong dimensions")));
+        assert!(matches!(a + b, Err(MathvisError::DimensionMismatch(_))));
     }
 
     #[test]
@@ -355,6 +459,6 @@ mod tests {
         let a: Vector<f32> = Vector::new(vec![1.0, 2.0, 3.0]).unwrap();
         let b: Vector<f32> = Vector::new(vec![3.0, 2.0, 1.0]).unwrap();
         let c: Vector<f32> = Vector::new(vec![-4.0, 8.0, -4.0]).unwrap();
-        assert!(a * b == Ok(c));
+        assert!((a * b).unwrap() == c);
     }
 }
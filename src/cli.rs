@@ -0,0 +1,240 @@
+//! Module containing the command line arguments of the CLI, only built with the `cli` feature.
+use std::{fmt::Display, path::PathBuf};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use mathvis::api::util::{FrameFormat, Quality};
+
+/// Encoder speed presets, named after the x264 ones.
+///
+/// Slower presets produce smaller files for the same quality.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq, Copy)]
+pub(crate) enum Preset {
+    Ultrafast,
+    Superfast,
+    Veryfast,
+    Faster,
+    Fast,
+    Medium,
+    Slow,
+    Slower,
+    Veryslow,
+}
+
+impl Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Preset::Ultrafast => "ultrafast",
+            Preset::Superfast => "superfast",
+            Preset::Veryfast => "veryfast",
+            Preset::Faster => "faster",
+            Preset::Fast => "fast",
+            Preset::Medium => "medium",
+            Preset::Slow => "slow",
+            Preset::Slower => "slower",
+            Preset::Veryslow => "veryslow",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Struct containing the command line arguments for the CLI interface
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, about)]
+pub(crate) struct Args {
+    #[command(subcommand)]
+    pub(crate) command: Command,
+}
+
+/// The CLI's subcommands.
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum Command {
+    /// Renders a scene into a video
+    Render(RenderArgs),
+    /// Quickly renders a low quality video of a scene, optionally every time it changes
+    Preview(PreviewArgs),
+    /// Quickly renders a low quality video of a scene again every time it changes, like `preview --watch`
+    Watch(WatchArgs),
+    /// Renders a single frame of a scene into an image
+    Still(StillArgs),
+    /// Prints the timing of every animation in a scene and estimates the render's size, without rendering
+    Info(SceneArgs),
+    /// Opens a scene in a window, to scrub through it and tune its objects and animations before rendering it
+    #[cfg(feature = "inspector")]
+    Inspect(InspectArgs),
+}
+
+/// Arguments shared by every subcommand, describing the scene and how its frames are rendered.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct SceneArgs {
+    /// Scene file (.mvs, .json, .yaml or .rhai)
+    pub(crate) source: String,
+
+    /// Frames per second
+    #[arg(long, default_value_t = 30)]
+    pub(crate) fps: u32,
+
+    /// Resolution of the frames
+    #[arg(short, long)]
+    pub(crate) quality: Option<Quality>,
+
+    /// Number of worker threads used to render frames, one per CPU core by default
+    #[arg(short, long)]
+    pub(crate) jobs: Option<usize>,
+
+    /// Renders frames with a transparent background
+    #[arg(long, default_value_t = false)]
+    pub(crate) transparent: bool,
+
+    /// Hides the progress bars
+    #[arg(long, default_value_t = false)]
+    pub(crate) quiet: bool,
+
+    /// Image format of the intermediate frames, faster formats take more disk space
+    #[arg(long)]
+    pub(crate) frame_format: Option<FrameFormat>,
+
+    /// Seed of the random number generator, so renders involving random values are reproducible
+    #[arg(long)]
+    pub(crate) seed: Option<u64>,
+}
+
+/// Arguments of the encoder, used for every subcommand that outputs a video.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct EncodeArgs {
+    /// Output video file, .webm files are encoded with VP9 and everything else with x264
+    #[arg(short, long, default_value_os = "../output/output.mp4")]
+    pub(crate) output: PathBuf,
+
+    /// Encodes the output as a gif
+    #[arg(long, default_value_t = false)]
+    pub(crate) gif: bool,
+
+    /// Audio track muxed into the video, padded or trimmed to its duration
+    #[arg(long)]
+    pub(crate) audio: Option<PathBuf>,
+
+    /// Constant rate factor (0-51 for x264, 0-63 for VP9), lower values mean better quality
+    #[arg(long)]
+    pub(crate) crf: Option<u8>,
+
+    /// Encoder speed preset, slower presets produce smaller files
+    #[arg(long)]
+    pub(crate) preset: Option<Preset>,
+
+    /// Target bitrate, like 2500k or 5M
+    #[arg(long)]
+    pub(crate) bitrate: Option<String>,
+}
+
+/// Arguments of the render subcommand.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct RenderArgs {
+    #[command(flatten)]
+    pub(crate) scene: SceneArgs,
+
+    #[command(flatten)]
+    pub(crate) encode: EncodeArgs,
+
+    /// First frame to render again, the others are reused from a previous render
+    #[arg(long, conflicts_with = "start_time")]
+    pub(crate) start_frame: Option<u32>,
+
+    /// Frame after the last one to render again
+    #[arg(long, conflicts_with = "end_time")]
+    pub(crate) end_frame: Option<u32>,
+
+    /// Time in seconds to start rendering again from
+    #[arg(long)]
+    pub(crate) start_time: Option<f32>,
+
+    /// Time in seconds to stop rendering again at
+    #[arg(long)]
+    pub(crate) end_time: Option<f32>,
+
+    /// Keeps the rendered frames, so parts of the video can be rendered again later
+    #[arg(long, default_value_t = false)]
+    pub(crate) keep_frames: bool,
+
+    /// Same as the info subcommand
+    #[arg(long, default_value_t = false)]
+    pub(crate) dry_run: bool,
+
+    /// Writes the render statistics as JSON next to the output video
+    #[arg(long, default_value_t = false)]
+    pub(crate) stats: bool,
+
+    /// Resumes an interrupted render of the same scene, only rendering the frames that weren't saved
+    #[arg(long, default_value_t = false, conflicts_with_all = ["start_frame", "end_frame", "start_time", "end_time"])]
+    pub(crate) resume: bool,
+
+    /// Renders and encodes the video in chunks of this many frames, removing each chunk's frames once it's encoded,
+    /// so long videos never need every frame on disk at once
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["start_frame", "end_frame", "start_time", "end_time", "keep_frames", "resume", "gif"])]
+    pub(crate) chunk_frames: Option<u32>,
+}
+
+/// Arguments of the preview subcommand.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct PreviewArgs {
+    #[command(flatten)]
+    pub(crate) scene: SceneArgs,
+
+    /// Output video file
+    #[arg(short, long, default_value_os = "../output/preview.mp4")]
+    pub(crate) output: PathBuf,
+
+    /// Renders the preview again every time the scene file changes
+    #[arg(long, default_value_t = false)]
+    pub(crate) watch: bool,
+}
+
+/// Arguments of the watch subcommand, the same as the preview subcommand's without `--watch`, which it always does.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct WatchArgs {
+    #[command(flatten)]
+    pub(crate) scene: SceneArgs,
+
+    /// Output video file
+    #[arg(short, long, default_value_os = "../output/preview.mp4")]
+    pub(crate) output: PathBuf,
+}
+
+impl From<WatchArgs> for PreviewArgs {
+    fn from(args: WatchArgs) -> Self {
+        PreviewArgs {
+            scene: args.scene,
+            output: args.output,
+            watch: true,
+        }
+    }
+}
+
+/// Arguments of the still subcommand.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct StillArgs {
+    #[command(flatten)]
+    pub(crate) scene: SceneArgs,
+
+    /// Output image file, the format is picked from its extension
+    #[arg(short, long, default_value_os = "../output/still.png")]
+    pub(crate) output: PathBuf,
+
+    /// Frame to render, the last one by default
+    #[arg(long, conflicts_with = "time")]
+    pub(crate) frame: Option<u32>,
+
+    /// Time in seconds of the frame to render
+    #[arg(long)]
+    pub(crate) time: Option<f32>,
+}
+
+/// Arguments of the inspect subcommand.
+#[cfg(feature = "inspector")]
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct InspectArgs {
+    #[command(flatten)]
+    pub(crate) scene: SceneArgs,
+
+    #[command(flatten)]
+    pub(crate) encode: EncodeArgs,
+}
@@ -0,0 +1,221 @@
+//! Module containing the project configuration file (`mathvis.toml`), which supplies defaults for the CLI.
+//!
+//! Every key is optional, and options given on the command line always take precedence over the file.
+//!
+//! ```toml
+//! fps = 60
+//! jobs = 8
+//! seed = 42
+//! frame_format = "png-fast"
+//! quality = "medium"
+//! output_dir = "renders"
+//! theme = "light"
+//! background = "#1e1e2e"
+//!
+//! [axes]
+//! visible = true
+//! ticks = false
+//! pi_ticks = true
+//! color = "white"
+//! ```
+use std::{
+    error::Error,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use clap::{parser::ValueSource, ArgMatches};
+use imageproc::image::Rgb;
+use serde::Deserialize;
+
+use mathvis::{
+    api::{
+        color::Theme,
+        screen::{AxisStyle, TickMode},
+        util::{FrameFormat, Quality},
+    },
+    scene::script::parse_color,
+};
+
+use crate::cli::{Args, Command};
+
+/// Name of the configuration file looked up in the working directory.
+pub(crate) const CONFIG_FILE: &str = "mathvis.toml";
+
+/// Defaults loaded from a `mathvis.toml` file.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    pub(crate) fps: Option<u32>,
+    pub(crate) jobs: Option<usize>,
+    pub(crate) seed: Option<u64>,
+    pub(crate) frame_format: Option<FrameFormat>,
+    pub(crate) quality: Option<Quality>,
+    pub(crate) output_dir: Option<PathBuf>,
+    pub(crate) theme: Option<String>,
+    pub(crate) background: Option<String>,
+    pub(crate) axes: AxesConfig,
+}
+
+/// Axis style section of the configuration file.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AxesConfig {
+    pub(crate) visible: Option<bool>,
+    pub(crate) ticks: Option<bool>,
+    pub(crate) pi_ticks: Option<bool>,
+    pub(crate) color: Option<String>,
+}
+
+impl Config {
+    /// Loads the configuration file from the specified directory.
+    ///
+    /// Returns the default (empty) configuration if there's no file, an Err if the file is invalid and an Ok with the configuration otherwise.
+    pub(crate) fn load(directory: &Path) -> Result<Config, Box<dyn Error>> {
+        let path = directory.join(CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(Config::default());
+        }
+        Config::parse(&read_to_string(&path)?)
+            .map_err(|err| format!("Invalid {}: {}", path.display(), err).into())
+    }
+
+    /// Parses the contents of a configuration file.
+    pub(crate) fn parse(source: &str) -> Result<Config, Box<dyn Error>> {
+        let config: Config = toml::from_str(source)?;
+        if let Some(theme) = &config.theme {
+            Theme::by_name(theme).ok_or_else(|| format!("unknown theme \"{}\"", theme))?;
+        }
+        if let Some(background) = &config.background {
+            parse_color(background)?;
+        }
+        if let Some(color) = &config.axes.color {
+            parse_color(color)?;
+        }
+        Ok(config)
+    }
+
+    /// Fills in every argument that wasn't explicitly given on the command line with the value from the configuration.
+    pub(crate) fn apply(&self, args: &mut Args, matches: &ArgMatches) {
+        let Some((_, matches)) = matches.subcommand() else {
+            return;
+        };
+        let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        let (scene, output) = match &mut args.command {
+            Command::Render(args) => (&mut args.scene, Some(&mut args.encode.output)),
+            Command::Preview(args) => (&mut args.scene, Some(&mut args.output)),
+            Command::Watch(args) => (&mut args.scene, Some(&mut args.output)),
+            Command::Still(args) => (&mut args.scene, Some(&mut args.output)),
+            Command::Info(scene) => (scene, None),
+            #[cfg(feature = "inspector")]
+            Command::Inspect(args) => (&mut args.scene, Some(&mut args.encode.output)),
+        };
+        if let Some(fps) = self.fps.filter(|_| !from_cli("fps")) {
+            scene.fps = fps;
+        }
+        if scene.quality.is_none() {
+            scene.quality = self.quality;
+        }
+        if scene.jobs.is_none() {
+            scene.jobs = self.jobs;
+        }
+        if scene.seed.is_none() {
+            scene.seed = self.seed;
+        }
+        if scene.frame_format.is_none() {
+            scene.frame_format = self.frame_format;
+        }
+        if let (Some(output_dir), Some(output)) = (&self.output_dir, output) {
+            if !from_cli("output") {
+                if let Some(file_name) = output.file_name() {
+                    *output = output_dir.join(file_name);
+                }
+            }
+        }
+    }
+
+    /// Returns the theme of every screen, the default one if it isn't configured.
+    pub(crate) fn theme(&self) -> Theme {
+        // Already validated when parsing
+        self.theme
+            .as_ref()
+            .map_or_else(Theme::default, |theme| Theme::by_name(theme).unwrap())
+    }
+
+    /// Returns the background color of every frame, the theme's if it isn't configured.
+    pub(crate) fn background(&self) -> Rgb<u8> {
+        self.background
+            .as_ref()
+            .map_or(self.theme().background, |color| {
+                Rgb(parse_color(color).unwrap())
+            })
+    }
+
+    /// Returns the style of the axes, using the defaults and the theme's color for anything that isn't configured.
+    pub(crate) fn axis_style(&self) -> AxisStyle {
+        let default = AxisStyle::default();
+        AxisStyle {
+            visible: self.axes.visible.unwrap_or(default.visible),
+            ticks: self.axes.ticks.unwrap_or(default.ticks),
+            color: self
+                .axes
+                .color
+                .as_ref()
+                .map_or(self.theme().axis_color, |color| {
+                    Rgb(parse_color(color).unwrap())
+                }),
+            tick_mode: match self.axes.pi_ticks {
+                Some(true) => TickMode::Pi,
+                Some(false) => TickMode::Integer,
+                None => default.tick_mode,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, FromArgMatches};
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            "fps = 60\nquality = \"low\"\nbackground = \"black\"\n[axes]\nticks = false\n",
+        )
+        .unwrap();
+        assert_eq!(config.fps, Some(60));
+        assert_eq!(config.quality, Some(Quality::LOW));
+        assert_eq!(config.background(), Rgb([0, 0, 0]));
+        assert!(!config.axis_style().ticks && config.axis_style().visible);
+        assert_eq!(config.axis_style().tick_mode, TickMode::Integer);
+        let pi = Config::parse("[axes]\npi_ticks = true\n").unwrap();
+        assert_eq!(pi.axis_style().tick_mode, TickMode::Pi);
+        assert!(Config::parse("background = \"chartreuse\"").is_err());
+
+        let light = Config::parse("theme = \"light\"\n[axes]\ncolor = \"red\"\n").unwrap();
+        assert_eq!(light.background(), Theme::light().background);
+        assert_eq!(light.axis_style().color, Rgb([255, 0, 0]));
+        assert!(Config::parse("theme = \"sepia\"").is_err());
+        assert!(Config::parse("frames = 3").is_err());
+    }
+
+    #[test]
+    fn test_cli_takes_precedence() {
+        let config =
+            Config::parse("fps = 60\njobs = 2\nquality = \"low\"\noutput_dir = \"renders\"")
+                .unwrap();
+        let matches =
+            Args::command().get_matches_from(["mathvis", "render", "scene.mvs", "--fps", "24"]);
+        let mut args = Args::from_arg_matches(&matches).unwrap();
+        config.apply(&mut args, &matches);
+        let Command::Render(args) = args.command else {
+            panic!("expected the render subcommand");
+        };
+        assert_eq!(args.scene.fps, 24);
+        assert_eq!(args.scene.quality, Some(Quality::LOW));
+        assert_eq!(args.scene.jobs, Some(2));
+        assert_eq!(args.encode.output, PathBuf::from("renders/output.mp4"));
+    }
+}
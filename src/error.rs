@@ -0,0 +1,57 @@
+//! Module containing the error type returned by the vector, matrix, screen and animation APIs.
+#![warn(missing_docs)]
+use thiserror::Error;
+
+/// Every way an operation of the library can fail, so callers can match on the kind of failure instead of its message.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::api::matrix::Matrix;
+/// use mathvis::error::MathvisError;
+///
+/// let singular = Matrix::new(vec![vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+/// assert!(matches!(singular.invert_2d(), Err(MathvisError::SingularMatrix)));
+/// ```
+#[derive(Debug, Error)]
+pub enum MathvisError {
+    /// The operands don't have compatible dimensions.
+    #[error("{0}")]
+    DimensionMismatch(String),
+    /// The matrix can't be inverted because its determinant is zero.
+    #[error("Matrix is singular")]
+    SingularMatrix,
+    /// The object was drawn or animated without a screen.
+    #[error("This object does not have an associated context. Try using the add_context method.")]
+    NoContext,
+    /// The object doesn't fit within the screen's bounds.
+    #[error("{0}")]
+    OutOfBounds(String),
+    /// An argument isn't valid for the operation, like decreasing axis limits.
+    #[error("{0}")]
+    InvalidArgument(String),
+    /// A frame couldn't be drawn or rendered.
+    #[error("{0}")]
+    Render(String),
+    /// Some frames failed to render, with the number of each frame and the reason it failed, in order.
+    #[error("{} frame(s) failed to render: {}", .0.len(), describe_frames(.0))]
+    Frames(Vec<(u32, MathvisError)>),
+    /// The render was cancelled through its screen's cancellation token before it finished.
+    #[error("The render was cancelled")]
+    Cancelled,
+    /// A frame couldn't be encoded.
+    #[error("{0}")]
+    Encode(String),
+    /// Reading or writing a file failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Lists every failed frame with its reason, one after the other.
+fn describe_frames(frames: &[(u32, MathvisError)]) -> String {
+    frames
+        .iter()
+        .map(|(frame, err)| format!("frame {}: {}", frame, err))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
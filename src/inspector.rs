@@ -0,0 +1,340 @@
+//! Module containing the interactive scene inspector, only built with the `inspector` feature.
+//!
+//! The scene is drawn in a window with a slider to scrub through time and sliders for the parameters of its objects and animations,
+//! so animations can be tuned before committing to a render. Frames are drawn in memory without saving anything,
+//! and the render button exports the tuned scene with FFmpeg like the render subcommand.
+use std::{
+    error::Error,
+    f64::consts::TAU,
+    sync::{
+        mpsc::{channel, Receiver, TryRecvError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use eframe::egui::{
+    self, Button, CollapsingHeader, ColorImage, DragValue, Image, ScrollArea, Slider,
+    TextureHandle, TextureOptions,
+};
+use mathvis::{
+    animation::{clip::Animation, renderer::render_frame_to_rgba},
+    api::{point::PointLike, random::set_seed, screen::Screen2D, util::Quality},
+    scene::{loader::load, AnimationKind, ObjectKind, SceneDescription},
+};
+
+use crate::{
+    cli::{InspectArgs, RenderArgs},
+    config::Config,
+    configure_screen, play_scene, render_with, scene_hash,
+};
+
+/// State of the inspector window: the scene being tuned and the frame currently shown.
+struct Inspector {
+    scene: SceneDescription,
+    args: InspectArgs,
+    config: Config,
+    /// Screen the preview frames are drawn on.
+    screen: Screen2D,
+    /// The scene's animations played one after the other, or the reason they can't be played.
+    animation: Result<Animation, String>,
+    /// Time of the frame shown, in seconds.
+    time: f32,
+    playing: bool,
+    texture: Option<TextureHandle>,
+    /// Whether the frame shown is out of date.
+    dirty: bool,
+    /// Result of the render running in the background, if there's one.
+    render: Option<Receiver<Result<(), String>>>,
+    status: Option<String>,
+}
+
+impl Inspector {
+    /// Plays the scene again after its parameters change, showing the first problem instead if it's invalid.
+    fn rebuild(&mut self) {
+        let context = Arc::new(Mutex::new(self.screen.clone()));
+        self.animation = self
+            .scene
+            .validate()
+            .and_then(|_| self.scene.animation(context))
+            .map_err(|err| err.to_string());
+        if let Ok(animation) = &self.animation {
+            self.time = self.time.min(animation.duration());
+        }
+        self.dirty = true;
+    }
+
+    /// Returns the number of frames of the scene and the index of the frame shown.
+    fn frames(&self) -> (u32, u32) {
+        let fps = self.args.scene.fps;
+        let frames = self
+            .animation
+            .as_ref()
+            .map_or(0, |animation| animation.frames(fps));
+        let frame = (self.time * fps as f32).round() as u32;
+        (frames, frame.min(frames.saturating_sub(1)))
+    }
+
+    /// Draws the frame shown into the window's texture.
+    fn redraw(&mut self, ctx: &egui::Context) {
+        let Ok(animation) = &self.animation else {
+            return;
+        };
+        let (_, frame) = self.frames();
+        match render_frame_to_rgba(animation, &self.screen, frame) {
+            Ok(pixels) => {
+                let size = [self.screen.width() as usize, self.screen.height() as usize];
+                let image = ColorImage::from_rgba_unmultiplied(size, &pixels);
+                match &mut self.texture {
+                    Some(texture) => texture.set(image, TextureOptions::LINEAR),
+                    None => {
+                        self.texture =
+                            Some(ctx.load_texture("frame", image, TextureOptions::LINEAR))
+                    }
+                }
+            }
+            Err(err) => self.animation = Err(err.to_string()),
+        }
+    }
+
+    /// Renders the scene as it's currently tuned in a background thread, encoding it into the output video.
+    fn start_render(&mut self) {
+        let args = RenderArgs {
+            scene: self.args.scene.clone(),
+            encode: self.args.encode.clone(),
+            start_frame: None,
+            end_frame: None,
+            start_time: None,
+            end_time: None,
+            keep_frames: false,
+            dry_run: false,
+            stats: false,
+            resume: false,
+            chunk_frames: None,
+        };
+        let (scene, config) = (self.scene.clone(), self.config.clone());
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            // The tuned scene only exists in memory, so it's hashed instead of its file
+            let source = serde_json::to_vec(&scene).unwrap_or_default();
+            let hash = scene_hash(&source, &args.scene, &config);
+            let result = render_with(&args, hash, |directory, setup| {
+                play_scene(&scene, &args.scene, &config, directory, setup)
+            });
+            // The window may have been closed while rendering
+            let _ = sender.send(result.map_err(|err| err.to_string()));
+        });
+        self.render = Some(receiver);
+        self.status = Some("Rendering...".into());
+    }
+
+    /// Checks whether the background render is done, showing how it went.
+    fn poll_render(&mut self, ctx: &egui::Context) {
+        let Some(receiver) = &self.render else {
+            return;
+        };
+        let status = match receiver.try_recv() {
+            Ok(Ok(())) => format!("Video saved as {}", self.args.encode.output.display()),
+            Ok(Err(err)) => format!("Render failed: {}", err),
+            Err(TryRecvError::Empty) => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+                return;
+            }
+            Err(TryRecvError::Disconnected) => "Render failed".into(),
+        };
+        self.status = Some(status);
+        self.render = None;
+    }
+
+    /// Shows the time slider and the play button, returning whether the time changed.
+    fn timeline(&mut self, ui: &mut egui::Ui) -> bool {
+        let duration = self
+            .animation
+            .as_ref()
+            .map_or(0.0, |animation| animation.duration());
+        let mut changed = ui
+            .add(Slider::new(&mut self.time, 0.0..=duration).suffix(" s"))
+            .changed();
+        ui.horizontal(|ui| {
+            if ui
+                .button(if self.playing { "Pause" } else { "Play" })
+                .clicked()
+            {
+                self.playing = !self.playing;
+            }
+            let (frames, frame) = self.frames();
+            ui.label(format!("Frame {} of {}", frame + 1, frames));
+        });
+        if self.playing {
+            self.time += ui.input(|input| input.stable_dt);
+            if self.time > duration {
+                self.time = 0.0;
+            }
+            changed = true;
+            ui.ctx().request_repaint();
+        }
+        changed
+    }
+
+    /// Shows the parameters of every object and animation, returning whether any of them changed.
+    fn parameters(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let (x_axis, y_axis) = (self.scene.x_axis, self.scene.y_axis);
+        let x_range = x_axis.0 as f64..=x_axis.1 as f64;
+        let y_range = y_axis.0 as f64..=y_axis.1 as f64;
+
+        ui.heading("Objects");
+        for object in &mut self.scene.objects {
+            CollapsingHeader::new(&object.name)
+                .default_open(true)
+                .show(ui, |ui| match &mut object.kind {
+                    ObjectKind::Vector { x, y } => {
+                        changed |= ui.add(Slider::new(x, x_range.clone()).text("x")).changed();
+                        changed |= ui.add(Slider::new(y, y_range.clone()).text("y")).changed();
+                    }
+                    ObjectKind::Function { function, .. } => {
+                        ui.label(format!("y = {}", function));
+                    }
+                    ObjectKind::Field { x, y } => {
+                        ui.label(format!("({}, {})", x, y));
+                    }
+                });
+        }
+
+        ui.heading("Animations");
+        for (i, animation) in self.scene.animations.iter_mut().enumerate() {
+            let name = match animation.kind {
+                AnimationKind::MoveTo { .. } => "move_to",
+                AnimationKind::Rotate { .. } => "rotate",
+                AnimationKind::Transform { .. } => "transform",
+                AnimationKind::Wait => "wait",
+            };
+            CollapsingHeader::new(format!("{}. {} {}", i + 1, name, animation.target))
+                .id_salt(i)
+                .show(ui, |ui| {
+                    changed |= ui
+                        .add(
+                            Slider::new(&mut animation.duration, 0.1..=10.0)
+                                .logarithmic(true)
+                                .suffix(" s"),
+                        )
+                        .changed();
+                    match &mut animation.kind {
+                        AnimationKind::MoveTo { x, y } => {
+                            changed |= ui.add(Slider::new(x, x_range.clone()).text("x")).changed();
+                            changed |= ui.add(Slider::new(y, y_range.clone()).text("y")).changed();
+                        }
+                        AnimationKind::Rotate { angle, center } => {
+                            changed |= ui
+                                .add(Slider::new(angle, -TAU..=TAU).text("angle"))
+                                .changed();
+                            changed |= ui
+                                .add(Slider::new(&mut center.0, x_range.clone()).text("center x"))
+                                .changed();
+                            changed |= ui
+                                .add(Slider::new(&mut center.1, y_range.clone()).text("center y"))
+                                .changed();
+                        }
+                        AnimationKind::Transform { matrix } => {
+                            for row in matrix {
+                                ui.horizontal(|ui| {
+                                    for value in row {
+                                        changed |=
+                                            ui.add(DragValue::new(value).speed(0.01)).changed();
+                                    }
+                                });
+                            }
+                        }
+                        AnimationKind::Wait => {}
+                    }
+                });
+        }
+        changed
+    }
+}
+
+impl eframe::App for Inspector {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_render(ctx);
+        egui::SidePanel::left("parameters").show(ctx, |ui| {
+            ScrollArea::vertical().show(ui, |ui| {
+                self.dirty |= self.timeline(ui);
+                ui.separator();
+                if self.parameters(ui) {
+                    self.rebuild();
+                }
+                ui.separator();
+                let rendering = self.render.is_some();
+                let render =
+                    ui.add_enabled(!rendering && self.animation.is_ok(), Button::new("Render"));
+                if render.clicked() {
+                    self.start_render();
+                }
+                if let Some(status) = &self.status {
+                    ui.label(status);
+                }
+            });
+        });
+        if self.dirty {
+            self.redraw(ctx);
+            self.dirty = false;
+        }
+        egui::CentralPanel::default().show(ctx, |ui| match (&self.animation, &self.texture) {
+            (Err(err), _) => {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+            (Ok(_), Some(texture)) => {
+                ui.add(Image::from_texture(texture).shrink_to_fit());
+            }
+            (Ok(_), None) => {}
+        });
+    }
+}
+
+/// Opens the scene in the source file in an inspector window, previewing it in low quality unless a quality is given.
+///
+/// Returns an Err if the scene can't be loaded or the window can't be opened and an Ok once it's closed.
+pub(crate) fn run(args: &InspectArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    if let Some(seed) = args.scene.seed {
+        set_seed(seed);
+    }
+    let scene = load(args.scene.source.as_ref())?;
+    let resolution = args
+        .scene
+        .quality
+        .or(scene.quality)
+        .unwrap_or(Quality::LOW)
+        .resolution();
+    let mut screen = scene.screen(
+        String::new(),
+        args.scene.fps,
+        resolution.values()[0] as u32,
+        resolution.values()[1] as u32,
+    )?;
+    configure_screen(&mut screen, &args.scene, config)?;
+    screen.set_dry_run(true);
+
+    let mut inspector = Inspector {
+        scene,
+        args: args.clone(),
+        config: config.clone(),
+        screen,
+        animation: Err(String::new()),
+        time: 0.0,
+        playing: false,
+        texture: None,
+        dirty: true,
+        render: None,
+        status: None,
+    };
+    inspector.rebuild();
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title(format!("mathvis - {}", args.scene.source))
+            .with_inner_size([1280.0, 720.0]),
+        ..Default::default()
+    };
+    eframe::run_native("mathvis", options, Box::new(|_| Ok(Box::new(inspector))))
+        .map_err(|err| err.to_string().into())
+}
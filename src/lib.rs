@@ -1,4 +1,9 @@
 pub mod animation;
 pub mod api;
+pub mod error;
 mod frb_generated;
 mod misc;
+pub mod scene;
+pub mod testing;
+
+pub use scene::quick_scene;
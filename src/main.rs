@@ -1,89 +1,889 @@
-pub mod animation;
-pub mod api;
-mod misc;
+mod cli;
+mod config;
+#[cfg(feature = "inspector")]
+mod inspector;
+mod render_dir;
+mod stats;
 use std::{
     error::Error,
-    fs::{create_dir_all, remove_dir_all},
+    fs::{remove_dir_all, remove_file},
+    ops::Range,
+    path::{Path, PathBuf},
     process::Command,
-    sync::{Arc, Mutex},
+    sync::OnceLock,
+    thread::sleep,
+    time::{Duration, Instant},
 };
 
-use animation::show::Show2D;
-use animation::vector::Vector2D;
-use api::{matrix::Matrix, point::PointLike, screen::Screen2D, util::Args};
-use clap::Parser;
-use imageproc::image::Rgb;
+use clap::{CommandFactory, FromArgMatches};
+use cli::{
+    Args, Command as Subcommand, EncodeArgs, Preset, PreviewArgs, RenderArgs, SceneArgs, StillArgs,
+};
+use config::Config;
+use mathvis::{
+    animation::{cancel::CancellationToken, checkpoint::hash_scene},
+    api::{
+        point::PointLike,
+        random::set_seed,
+        screen::Screen2D,
+        util::{FrameFormat, Quality},
+    },
+    error::MathvisError,
+    scene::{loader::load, Scene, SceneDescription},
+};
+use render_dir::RenderDirectory;
+use stats::RenderStats;
+
+/// Returns whether the output should be encoded with VP9 instead of x264, based on its extension.
+fn is_webm(args: &EncodeArgs) -> bool {
+    args.output.extension().is_some_and(|ext| ext == "webm")
+}
+
+/// Returns whether the output is a QuickTime file, used for ProRes encoding of transparent videos.
+fn is_mov(args: &EncodeArgs) -> bool {
+    args.output.extension().is_some_and(|ext| ext == "mov")
+}
+
+/// Returns the VP9 deadline and cpu-used values that roughly match a preset's speed.
+fn vp9_speed(preset: Preset) -> (&'static str, u8) {
+    match preset {
+        Preset::Ultrafast => ("realtime", 8),
+        Preset::Superfast => ("realtime", 7),
+        Preset::Veryfast => ("realtime", 6),
+        Preset::Faster => ("good", 5),
+        Preset::Fast => ("good", 4),
+        Preset::Medium => ("good", 2),
+        Preset::Slow => ("good", 1),
+        Preset::Slower => ("good", 0),
+        Preset::Veryslow => ("best", 0),
+    }
+}
+
+/// Builds the ffmpeg codec arguments for the output, applying the CRF, preset and bitrate options.
+///
+/// Returns an Err if any of those options are used with a gif or if the CRF is out of range for the codec.
+fn codec_args(args: &EncodeArgs, transparent: bool) -> Result<Vec<String>, Box<dyn Error>> {
+    if args.gif {
+        if args.crf.is_some() || args.preset.is_some() || args.bitrate.is_some() {
+            return Err("--crf, --preset and --bitrate can't be used with gif output".into());
+        }
+        if transparent {
+            return Err("Transparent output is only supported for .webm and .mov files".into());
+        }
+        return Ok(vec![String::from("-f"), String::from("gif")]);
+    }
 
-pub(crate) fn join_frames(args: &Args, directory: String) -> Result<(), Box<dyn Error>> {
-    let codec = if args.gif {
-        vec!["-f", "gif"]
+    let webm = is_webm(args);
+    if transparent && !webm {
+        if !is_mov(args) {
+            return Err("Transparent output is only supported for .webm and .mov files".into());
+        }
+        if args.crf.is_some() || args.preset.is_some() || args.bitrate.is_some() {
+            return Err("--crf, --preset and --bitrate can't be used with ProRes output".into());
+        }
+        return Ok([
+            "-c:v",
+            "prores_ks",
+            "-profile:v",
+            "4444",
+            "-pix_fmt",
+            "yuva444p10le",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect());
+    }
+    let pix_fmt = if transparent { "yuva420p" } else { "yuv420p" };
+    let (codec, max_crf) = if webm {
+        ("libvpx-vp9", 63)
     } else {
-        vec!["-c:v", "libx264", "-pix_fmt", "yuv420p"]
+        ("libx264", 51)
     };
-    let ffmpeg_cmd = Command::new("ffmpeg")
-        .args([
-            "-framerate",
-            &args.fps.to_string(),
-            "-i",
-            &format!("{}/tmp/frame_%03d.png", directory),
-            "-nostats",
-            "-loglevel",
-            "0",
-            "-y",
-        ])
-        .args(&codec)
-        .arg(args.output.to_str().ok_or("Invalid output path")?)
-        .status()?;
+    let mut codec_args: Vec<String> = ["-c:v", codec, "-pix_fmt", pix_fmt]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+
+    if let Some(crf) = args.crf {
+        if crf > max_crf {
+            return Err(format!("CRF must be between 0 and {} for {}", max_crf, codec).into());
+        }
+        codec_args.extend([String::from("-crf"), crf.to_string()]);
+        // VP9 only runs in constant quality mode when the bitrate is explicitly set to 0
+        if webm && args.bitrate.is_none() {
+            codec_args.extend([String::from("-b:v"), String::from("0")]);
+        }
+    }
+    if let Some(bitrate) = &args.bitrate {
+        codec_args.extend([String::from("-b:v"), bitrate.clone()]);
+    }
+    if let Some(preset) = args.preset {
+        if webm {
+            let (deadline, cpu_used) = vp9_speed(preset);
+            codec_args.extend([
+                String::from("-deadline"),
+                deadline.to_string(),
+                String::from("-cpu-used"),
+                cpu_used.to_string(),
+            ]);
+        } else {
+            codec_args.extend([String::from("-preset"), preset.to_string()]);
+        }
+    }
+    Ok(codec_args)
+}
+
+/// Encodes the frames saved on the screen into the output video, adding the audio track if there's one.
+///
+/// Returns an Err if the options are invalid for the output, the audio file doesn't exist or ffmpeg fails and an Ok otherwise.
+pub(crate) fn join_frames(
+    args: &EncodeArgs,
+    scene: &SceneArgs,
+    screen: &Screen2D,
+) -> Result<(), Box<dyn Error>> {
+    let codec = codec_args(args, scene.transparent)?;
+    let mut ffmpeg = Command::new("ffmpeg");
+    ffmpeg.args([
+        "-framerate",
+        &scene.fps.to_string(),
+        "-i",
+        &screen.frame_pattern(),
+        "-nostats",
+        "-loglevel",
+        "0",
+        "-y",
+    ]);
+    add_audio(&mut ffmpeg, args)?;
+    ffmpeg.args(&codec);
+    run_ffmpeg(ffmpeg, &args.output)?;
+    if !scene.quiet {
+        println!("Video saved as {}", args.output.display());
+    }
+    Ok(())
+}
+
+/// Encodes a chunk of the frames saved on the screen into a segment of the video, without audio,
+/// which [concat_segments] joins with the others once every chunk is encoded.
+///
+/// Returns an Err if the options are invalid for the output or ffmpeg fails and an Ok otherwise.
+fn encode_chunk(
+    args: &EncodeArgs,
+    scene: &SceneArgs,
+    screen: &Screen2D,
+    frames: Range<u32>,
+    segment: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let codec = codec_args(args, scene.transparent)?;
+    let mut ffmpeg = Command::new("ffmpeg");
+    ffmpeg.args([
+        "-framerate",
+        &scene.fps.to_string(),
+        "-start_number",
+        &frames.start.to_string(),
+        "-i",
+        &screen.frame_pattern(),
+        "-frames:v",
+        &frames.len().to_string(),
+        "-nostats",
+        "-loglevel",
+        "0",
+        "-y",
+    ]);
+    ffmpeg.args(&codec);
+    run_ffmpeg(ffmpeg, segment)
+}
+
+/// Joins the segments of a chunked render into the output video without encoding them again, adding the audio track if there's one.
+///
+/// Returns an Err if the list of segments can't be written, the audio file doesn't exist or ffmpeg fails and an Ok otherwise.
+fn concat_segments(
+    args: &EncodeArgs,
+    scene: &SceneArgs,
+    segments: &[PathBuf],
+    list: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut files = String::new();
+    for segment in segments {
+        let path = segment.canonicalize()?;
+        // Quotes in the concat list are escaped by closing the string around them
+        let path = path
+            .to_str()
+            .ok_or("Invalid segment path")?
+            .replace('\'', "'\\''");
+        files.push_str(&format!("file '{}'\n", path));
+    }
+    std::fs::write(list, files)?;
 
-    if ffmpeg_cmd.success() {
+    let mut ffmpeg = Command::new("ffmpeg");
+    ffmpeg
+        .args(["-f", "concat", "-safe", "0", "-i"])
+        .arg(list)
+        .args(["-nostats", "-loglevel", "0", "-y"]);
+    add_audio(&mut ffmpeg, args)?;
+    ffmpeg.args(["-c:v", "copy"]);
+    run_ffmpeg(ffmpeg, &args.output)?;
+    if !scene.quiet {
         println!("Video saved as {}", args.output.display());
-        Ok({})
+    }
+    Ok(())
+}
+
+/// Adds the audio track to an ffmpeg command whose first input is the video, if there's one.
+///
+/// Returns an Err if the output is a gif or the audio file doesn't exist and an Ok otherwise.
+fn add_audio(ffmpeg: &mut Command, args: &EncodeArgs) -> Result<(), Box<dyn Error>> {
+    let Some(audio) = &args.audio else {
+        return Ok(());
+    };
+    if args.gif {
+        return Err("Audio tracks can't be added to a gif".into());
+    }
+    if !audio.is_file() {
+        return Err(format!("Audio file {} not found", audio.display()).into());
+    }
+    // apad pads the track with silence and -shortest cuts it at the end of the video,
+    // so the audio always ends up with the same duration as the animation
+    ffmpeg.arg("-i").arg(audio).args([
+        "-map",
+        "0:v",
+        "-map",
+        "1:a",
+        "-af",
+        "apad",
+        "-c:a",
+        if is_webm(args) { "libopus" } else { "aac" },
+        "-shortest",
+    ]);
+    Ok(())
+}
+
+/// Runs an ffmpeg command writing the specified output, killing it and removing the partial output if the render is cancelled.
+///
+/// Returns an Err if ffmpeg can't be run, fails or is cancelled and an Ok otherwise.
+fn run_ffmpeg(mut ffmpeg: Command, output: &Path) -> Result<(), Box<dyn Error>> {
+    let mut child = ffmpeg
+        .arg(output.to_str().ok_or("Invalid output path")?)
+        .spawn()
+        .map_err(|err| format!("Couldn't run ffmpeg: {}", err))?;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if cancellation().is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            // The video is only partially written
+            let _ = remove_file(output);
+            return Err(MathvisError::Cancelled.into());
+        }
+        sleep(Duration::from_millis(50));
+    };
+    if status.success() {
+        Ok(())
     } else {
         Err("FFmpeg error".into())
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
-    let directory = args
-        .output
+/// Returns the token cancelled by Ctrl-C, which stops the render in progress and the encoder
+/// so their frames and partial video are removed instead of being left behind.
+fn cancellation() -> &'static CancellationToken {
+    static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+    TOKEN.get_or_init(CancellationToken::new)
+}
+
+/// Setup of the screen before a scene is played, like restricting the rendered frames.
+type Setup<'a> = &'a dyn Fn(&mut Screen2D) -> Result<(), Box<dyn Error>>;
+
+/// Applies the rendering options from the command line and configuration file to a screen.
+fn configure_screen(
+    screen: &mut Screen2D,
+    args: &SceneArgs,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    screen.set_transparent(args.transparent);
+    screen.set_quiet(args.quiet);
+    screen.set_cancellation(Some(cancellation().clone()));
+    screen.set_theme(config.theme());
+    screen.set_background(config.background());
+    screen.set_axis_style(config.axis_style());
+    if let Some(jobs) = args.jobs {
+        screen.set_jobs(jobs)?;
+    }
+    let frame_format = args.frame_format.unwrap_or_default();
+    if args.transparent && frame_format == FrameFormat::Bmp {
+        return Err("Transparent frames can't be saved as BMP, use a PNG frame format".into());
+    }
+    screen.set_frame_format(frame_format);
+    Ok(())
+}
+
+/// Plays the animation in the source file, saving its frames in the output directory.
+///
+/// Rhai scripts are run directly when the `rhai` feature is enabled, every other file is loaded as a scene.
+/// The setup closure is called on the configured screen before playing, to restrict the rendered frames.
+/// The frames directory is created unless the setup makes it a dry run.
+///
+/// Returns an Err if anything goes wrong while playing and an Ok with the screen's final state otherwise.
+fn play_source(
+    args: &SceneArgs,
+    config: &Config,
+    directory: &str,
+    setup: impl FnOnce(&mut Screen2D) -> Result<(), Box<dyn Error>>,
+) -> Result<Screen2D, Box<dyn Error>> {
+    // Seeded on every play, so the dry run of a still and every watched render see the same values
+    if let Some(seed) = args.seed {
+        set_seed(seed);
+    }
+    let source = Path::new(&args.source);
+
+    #[cfg(feature = "rhai")]
+    if source.extension().is_some_and(|ext| ext == "rhai") {
+        let script = std::fs::read_to_string(source)
+            .map_err(|err| format!("Couldn't read {}: {}", source.display(), err))?;
+        let mut screen = Screen2D::builder()
+            .save_directory(directory)
+            .fps(args.fps)
+            .quality(args.quality.unwrap_or(Quality::HIGH))
+            .build()?;
+        configure_screen(&mut screen, args, config)?;
+        setup(&mut screen)?;
+        let handle = Scene::new(screen)?;
+        mathvis::scene::scripting::run(&script, handle.context())?;
+        return Ok(handle.screen()?);
+    }
+
+    play_scene(&load(source)?, args, config, directory, setup)
+}
+
+/// Plays a scene on a screen configured like in [play_source], saving its frames in the output directory.
+///
+/// Returns an Err if anything goes wrong while playing and an Ok with the screen's final state otherwise.
+fn play_scene(
+    scene: &SceneDescription,
+    args: &SceneArgs,
+    config: &Config,
+    directory: &str,
+    setup: impl FnOnce(&mut Screen2D) -> Result<(), Box<dyn Error>>,
+) -> Result<Screen2D, Box<dyn Error>> {
+    let resolution = args
+        .quality
+        .or(scene.quality)
+        .unwrap_or(Quality::HIGH)
+        .resolution();
+    let mut screen = scene.screen(
+        directory.to_string(),
+        args.fps,
+        resolution.values()[0] as u32,
+        resolution.values()[1] as u32,
+    )?;
+    configure_screen(&mut screen, args, config)?;
+    setup(&mut screen)?;
+    let handle = Scene::new(screen)?;
+    scene.play(handle.context())?;
+    Ok(handle.screen()?)
+}
+
+/// Checks that every frame of the animation was saved before it's encoded, and removes leftover frames past its end.
+///
+/// Frames that are missing or empty, like after a save that silently failed, would otherwise make ffmpeg fail
+/// or leave the video with skipped frames.
+/// Partial renders reuse the other frames from a previous render, so they're also listed if they're missing.
+///
+/// Returns an Err listing the missing and empty frames if there are any and an Ok otherwise.
+fn check_frames(
+    screen: &Screen2D,
+    frames: Range<u32>,
+    partial: bool,
+) -> Result<(), Box<dyn Error>> {
+    let (mut missing, mut empty) = (Vec::new(), Vec::new());
+    for i in frames.clone() {
+        match Path::new(&screen.frame_path(i)).metadata() {
+            Ok(meta) if meta.is_file() && meta.len() > 0 => {}
+            Ok(meta) if meta.is_file() => empty.push(i),
+            _ => missing.push(i),
+        }
+    }
+    if !missing.is_empty() || !empty.is_empty() {
+        let mut problems = Vec::new();
+        if !missing.is_empty() {
+            problems.push(format!("missing {}", describe_indices(&missing)));
+        }
+        if !empty.is_empty() {
+            problems.push(format!("empty {}", describe_indices(&empty)));
+        }
+        let hint = if partial {
+            "render the whole animation with --keep-frames first"
+        } else {
+            "render the animation again"
+        };
+        return Err(format!(
+            "Some frames weren't saved ({}), {}",
+            problems.join("; "),
+            hint
+        )
+        .into());
+    }
+    let mut leftover = frames.end;
+    while Path::new(&screen.frame_path(leftover)).is_file() {
+        remove_file(screen.frame_path(leftover))?;
+        leftover += 1;
+    }
+    Ok(())
+}
+
+/// Lists sorted frame indices, with runs of consecutive ones shortened to their first and last, like `3-7, 12`.
+fn describe_indices(indices: &[u32]) -> String {
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for &i in indices {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == i => *end = i,
+            _ => runs.push((i, i)),
+        }
+    }
+    runs.iter()
+        .map(|&(start, end)| match start == end {
+            true => start.to_string(),
+            false => format!("{}-{}", start, end),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the rough size of a frame in bytes per pixel.
+///
+/// Most of a frame is flat background, which compresses very well even with the fastest PNG compression.
+fn frame_bytes_per_pixel(frame_format: FrameFormat) -> f64 {
+    match frame_format {
+        FrameFormat::Png => 0.02,
+        FrameFormat::PngFast => 0.1,
+        FrameFormat::Bmp => 3.0,
+    }
+}
+/// Rough bitrate of the encoded video in bits per pixel per frame, for the default encoder settings.
+const VIDEO_BITS_PER_PIXEL: f64 = 0.02;
+
+/// Plays the source file without rendering anything, and prints the timing of every animation
+/// along with estimates of the disk space the frames and the video will take.
+fn dry_run(args: &SceneArgs, bitrate: Option<&str>, config: &Config) -> Result<(), Box<dyn Error>> {
+    let screen = play_source(args, config, ".", |screen| {
+        screen.set_dry_run(true);
+        Ok(())
+    })?;
+    let fps = args.fps as f64;
+    println!(
+        "{} ({}x{}, {} fps)",
+        args.source,
+        screen.width(),
+        screen.height(),
+        args.fps
+    );
+    for (i, (start, frames)) in screen.timeline().iter().enumerate() {
+        println!(
+            "  #{:<3} {:>8.2}s - {:>8.2}s  {:>6} frames",
+            i + 1,
+            *start as f64 / fps,
+            (start + frames) as f64 / fps,
+            frames
+        );
+    }
+
+    let frames = screen.frame_count();
+    let duration = frames as f64 / fps;
+    let pixels = (screen.width() * screen.height()) as f64;
+    let video_bits = match bitrate.map(parse_bitrate) {
+        Some(bitrate) => bitrate? * duration,
+        None => VIDEO_BITS_PER_PIXEL * pixels * frames as f64,
+    };
+    println!("Total: {:.2}s, {} frames", duration, frames);
+    println!(
+        "Estimated frame storage: {:.1} MB",
+        frame_bytes_per_pixel(screen.frame_format()) * pixels * frames as f64 / 1e6
+    );
+    println!("Estimated video size: {:.1} MB", video_bits / 8e6);
+    Ok(())
+}
+
+/// Parses an ffmpeg bitrate like `2500k` or `5M` into bits per second.
+fn parse_bitrate(bitrate: &str) -> Result<f64, Box<dyn Error>> {
+    let (number, multiplier) = match bitrate.chars().last() {
+        Some('k') | Some('K') => (&bitrate[..bitrate.len() - 1], 1e3),
+        Some('m') | Some('M') => (&bitrate[..bitrate.len() - 1], 1e6),
+        _ => (bitrate, 1.0),
+    };
+    number
+        .parse::<f64>()
+        .map(|value| value * multiplier)
+        .map_err(|_| format!("Invalid bitrate {}", bitrate).into())
+}
+
+/// Returns the range of frames to render, converting times into frames, or None if the whole animation should be rendered.
+fn frame_range(args: &RenderArgs) -> Option<(u32, Option<u32>)> {
+    let to_frame = |time: f32| (time * args.scene.fps as f32).round() as u32;
+    let start = args.start_frame.or(args.start_time.map(to_frame));
+    let end = args.end_frame.or(args.end_time.map(to_frame));
+    if start.is_none() && end.is_none() {
+        return None;
+    }
+    Some((start.unwrap_or(0), end))
+}
+
+/// Returns the directory of an output file as a string, where the frames are rendered.
+fn output_directory(output: &Path) -> Result<String, Box<dyn Error>> {
+    Ok(output
         .parent()
         .ok_or("Invalid output directory")?
         .to_str()
         .ok_or("Invalid directory path")?
-        .to_string();
-
-    create_dir_all(format!("{}/tmp", directory))?;
-
-    let white = Rgb([255, 255, 255]);
-    let screen = Arc::new(Mutex::new(
-        Screen2D::new(
-            (-3.0, 3.0),
-            (-3.0, 3.0),
-            directory.clone(),
-            args.fps,
-            args.quality.resolution().values()[0] as u32,
-            args.quality.resolution().values()[1] as u32,
-        )
-        .unwrap(),
-    ));
-    let mut v = Vector2D::new(0.0, 1.0, white);
-    v.add_context(screen.clone())?;
-    v.rotate_then_scale(
-        2.0,
-        Matrix::new(vec![vec![1.0, 0.0], vec![1.0, 1.0]]).unwrap(),
+        .to_string())
+}
+
+/// Returns a hash of a scene's source and every option that changes its frames,
+/// so a render is only resumed from frames of the same scene.
+fn scene_hash(source: &[u8], args: &SceneArgs, config: &Config) -> u64 {
+    let settings = format!(
+        "{} {:?} {} {:?} {:?} {:?} {:?} {:?}",
+        args.fps,
+        args.quality,
+        args.transparent,
+        args.frame_format,
+        args.seed,
+        config.background(),
+        config.axis_style(),
+        config.theme().palette
+    );
+    hash_scene(&[source, settings.as_bytes()])
+}
+
+/// Renders the source file into the output video.
+fn render(args: &RenderArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    if args.dry_run {
+        return dry_run(&args.scene, args.encode.bitrate.as_deref(), config);
+    }
+    let source = std::fs::read(&args.scene.source)
+        .map_err(|err| format!("Couldn't read {}: {}", args.scene.source, err))?;
+    render_with(
+        args,
+        scene_hash(&source, &args.scene, config),
+        |directory, setup| play_source(&args.scene, config, directory, setup),
+    )
+}
+
+/// Renders the frames played by the play function into the output video, like [render] does for the source file.
+///
+/// The play function is called with the save directory of the frames and a setup for the screen, which restricts the rendered frames.
+/// Frames are saved in a directory of their own inside the output directory, removed once the video is encoded or the render fails,
+/// unless they're kept for partial renders, which save them in the output directory itself.
+/// Whole renders record their saved frames in a manifest for the scene with the specified hash,
+/// which a render with `--resume` continues from instead of starting over, so their frames are only removed once the video is encoded.
+/// Frame numbers are padded to the length of the last one, so the scene is played without rendering first to plan its frames.
+/// With `--chunk-frames` the video is rendered and encoded a chunk at a time, see [render_chunks].
+/// Statistics of the render are printed once the video is encoded, and saved next to it with `--stats`.
+///
+/// Returns an Err if anything goes wrong while playing or encoding and an Ok otherwise.
+fn render_with(
+    args: &RenderArgs,
+    scene_hash: u64,
+    play: impl Fn(&str, Setup) -> Result<Screen2D, Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let planned = play(".", &|screen| {
+        screen.set_dry_run(true);
+        Ok(())
+    })?
+    .frame_count();
+    let digits = Screen2D::digits_for(planned);
+    let directory = output_directory(&args.encode.output)?;
+    let range = frame_range(args);
+    let resumed = match args.resume {
+        true => RenderDirectory::resumable(&directory, scene_hash)?,
+        false => None,
+    };
+    let mut frames_directory = match resumed {
+        Some(resumed) => {
+            if !args.scene.quiet {
+                println!("Resuming the interrupted render in {}", resumed.path());
+            }
+            resumed
+        }
+        None => {
+            if args.resume && !args.scene.quiet {
+                println!("No interrupted render of this scene to resume, starting over");
+            }
+            if range.is_some() {
+                // Only part of the animation is rendered again, over the frames kept from a previous render
+                RenderDirectory::shared(&directory)
+            } else if args.keep_frames {
+                // Frames from a previous render would otherwise end up in this video
+                let shared = RenderDirectory::shared(&directory);
+                if Path::new(&shared.frames()).exists() {
+                    remove_dir_all(shared.frames())?;
+                }
+                shared
+            } else {
+                RenderDirectory::unique(&directory)?
+            }
+        }
+    };
+    if args.keep_frames {
+        frames_directory.keep();
+    }
+    if range.is_none() && args.chunk_frames.is_none() {
+        frames_directory.checkpoint();
+    }
+
+    let (screen, render_time, encode_time) = match args.chunk_frames {
+        Some(chunk) => render_chunks(args, frames_directory.path(), planned, chunk, digits, &play)?,
+        None => {
+            let started = Instant::now();
+            let screen = play(frames_directory.path(), &|screen| {
+                screen.set_frame_digits(digits)?;
+                match range {
+                    Some((start, end)) => screen.set_frame_range(start, end)?,
+                    None => screen.set_checkpoint(Some(scene_hash)),
+                }
+                Ok(())
+            })?;
+            let render_time = started.elapsed();
+            check_frames(&screen, 0..screen.frame_count(), range.is_some())?;
+            let encoding = Instant::now();
+            join_frames(&args.encode, &args.scene, &screen)?;
+            (screen, render_time, encoding.elapsed())
+        }
+    };
+    frames_directory.finish();
+    let frames = screen.frame_count();
+
+    let stats = RenderStats::new(
+        frames,
+        (
+            screen.width(),
+            screen.height(),
+            args.scene.fps,
+            screen.jobs(),
+        ),
+        render_time,
+        encode_time,
+        &args.encode.output,
     )?;
-    join_frames(&args, directory.clone())?;
+    if !args.scene.quiet {
+        println!("{}", stats);
+    }
+    if args.stats {
+        let path = stats.save(&args.encode.output)?;
+        if !args.scene.quiet {
+            println!("Statistics saved as {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Renders the frames played by the play function in chunks of the specified number of frames, encoding each chunk
+/// into a segment of the video and removing its frames before rendering the next one, then joins the segments into the output video.
+///
+/// Only one chunk of frames is ever on disk, along with the encoded segments, so long videos don't need room for every frame.
+///
+/// Returns an Err if anything goes wrong while playing or encoding and an Ok with the last screen played,
+/// the time spent rendering and the time spent encoding otherwise.
+fn render_chunks(
+    args: &RenderArgs,
+    save_directory: &str,
+    frames: u32,
+    chunk: u32,
+    digits: usize,
+    play: &impl Fn(&str, Setup) -> Result<Screen2D, Box<dyn Error>>,
+) -> Result<(Screen2D, Duration, Duration), Box<dyn Error>> {
+    let extension = args
+        .encode
+        .output
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("mp4");
+    let (mut render_time, mut encode_time) = (Duration::ZERO, Duration::ZERO);
+    let mut segments = Vec::new();
+    let mut last = None;
+    for start in (0..frames).step_by(chunk as usize) {
+        let end = frames.min(start.saturating_add(chunk));
+        let started = Instant::now();
+        let screen = play(save_directory, &|screen| {
+            screen.set_frame_digits(digits)?;
+            Ok(screen.set_frame_range(start, Some(end))?)
+        })?;
+        render_time += started.elapsed();
+        check_frames(&screen, start..end, false)?;
 
-    remove_dir_all(format!("{}/tmp", directory)).unwrap();
+        let encoding = Instant::now();
+        let segment =
+            Path::new(save_directory).join(format!("segment_{}.{}", segments.len(), extension));
+        encode_chunk(&args.encode, &args.scene, &screen, start..end, &segment)?;
+        encode_time += encoding.elapsed();
+        for frame in start..end {
+            remove_file(screen.frame_path(frame))?;
+        }
+        segments.push(segment);
+        if !args.scene.quiet {
+            println!("Encoded frames {} to {} of {}", start, end - 1, frames);
+        }
+        last = Some(screen);
+    }
+    let screen = last.ok_or("The animation has no frames")?;
+
+    let encoding = Instant::now();
+    let list = Path::new(save_directory).join("segments.txt");
+    concat_segments(&args.encode, &args.scene, &segments, &list)?;
+    encode_time += encoding.elapsed();
+    Ok((screen, render_time, encode_time))
+}
+
+/// Renders a quick preview of the source file, and renders it again every time it changes if watching.
+///
+/// Renders in low quality unless a quality is given, so the preview is ready as soon as possible.
+/// While watching, failed renders are reported without stopping, since the file is usually fixed in the next save,
+/// and watching stops once the render is cancelled.
+fn preview(args: &PreviewArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let mut scene = args.scene.clone();
+    scene.quality = Some(scene.quality.unwrap_or(Quality::LOW));
+    let render_args = RenderArgs {
+        scene,
+        encode: EncodeArgs {
+            output: args.output.clone(),
+            gif: false,
+            audio: None,
+            crf: None,
+            preset: Some(Preset::Ultrafast),
+            bitrate: None,
+        },
+        start_frame: None,
+        end_frame: None,
+        start_time: None,
+        end_time: None,
+        keep_frames: false,
+        dry_run: false,
+        stats: false,
+        resume: false,
+        chunk_frames: None,
+    };
+    if !args.watch {
+        return render(&render_args, config);
+    }
+
+    let source = Path::new(&args.scene.source);
+    let mut last_modified = None;
+    loop {
+        if cancellation().is_cancelled() {
+            return Ok(());
+        }
+        let modified = source.metadata().and_then(|meta| meta.modified()).ok();
+        if modified.is_some() && modified != last_modified {
+            last_modified = modified;
+            if let Err(err) = render(&render_args, config) {
+                if cancellation().is_cancelled() {
+                    return Err(err);
+                }
+                eprintln!("Render failed: {}", err);
+            }
+            println!("Watching {} for changes...", source.display());
+        }
+        sleep(Duration::from_millis(500));
+    }
+}
+
+/// Renders a single frame of the source file into the output image, the last frame if none is given.
+///
+/// Returns an Err if the frame is past the end of the animation or the image can't be saved and an Ok otherwise.
+fn still(args: &StillArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let frame = match args.frame.or(args
+        .time
+        .map(|time| (time * args.scene.fps as f32).round() as u32))
+    {
+        Some(frame) => frame,
+        None => {
+            let frames = play_source(&args.scene, config, ".", |screen| {
+                screen.set_dry_run(true);
+                Ok(())
+            })?
+            .frame_count();
+            frames.checked_sub(1).ok_or("The animation has no frames")?
+        }
+    };
+
+    let directory = output_directory(&args.output)?;
+    let frames_directory = RenderDirectory::unique(&directory)?;
+
+    let screen = play_source(&args.scene, config, frames_directory.path(), |screen| {
+        Ok(screen.set_frame_range(frame, Some(frame + 1))?)
+    })?;
+    let frames = screen.frame_count();
+    if frame >= frames {
+        return Err(format!(
+            "Frame {} is past the end of the animation ({} frames)",
+            frame, frames
+        )
+        .into());
+    }
+
+    let image = imageproc::image::open(screen.frame_path(frame))?;
+    if args.scene.transparent {
+        image.save(&args.output)?;
+    } else {
+        // Most formats other than PNG don't support an alpha channel
+        image.to_rgb8().save(&args.output)?;
+    }
+    if !args.scene.quiet {
+        println!("Frame {} saved as {}", frame, args.output.display());
+    }
     Ok(())
 }
 
+fn main() -> Result<(), Box<dyn Error>> {
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches)?;
+    let config = Config::load(Path::new("."))?;
+    config.apply(&mut args, &matches);
+    #[cfg(feature = "inspector")]
+    let inspecting = matches!(args.command, Subcommand::Inspect(_));
+    #[cfg(not(feature = "inspector"))]
+    let inspecting = false;
+    // The inspector's window is closed as usual instead
+    if !inspecting {
+        let token = cancellation().clone();
+        ctrlc::set_handler(move || {
+            if token.is_cancelled() {
+                // A second Ctrl-C quits right away, like when cleaning up hangs
+                std::process::exit(130);
+            }
+            eprintln!("Cancelling, press Ctrl-C again to quit right away");
+            token.cancel();
+        })?;
+    }
+
+    // Frames are still drawn on the CPU, but a missing GPU shouldn't go unnoticed
+    #[cfg(feature = "wgpu")]
+    if !matches!(args.command, Subcommand::Info(_)) {
+        if let Err(err) = mathvis::animation::gpu::GpuRasterizer::shared() {
+            eprintln!(
+                "GPU rendering unavailable, falling back to the CPU: {}",
+                err
+            );
+        }
+    }
+
+    match &args.command {
+        Subcommand::Render(args) => render(args, &config),
+        Subcommand::Preview(args) => preview(args, &config),
+        Subcommand::Watch(args) => preview(&args.clone().into(), &config),
+        Subcommand::Still(args) => still(args, &config),
+        Subcommand::Info(args) => dry_run(args, None, &config),
+        #[cfg(feature = "inspector")]
+        Subcommand::Inspect(args) => inspector::run(args, &config),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::api::util::{interpolate, Quality};
+    use std::sync::Arc;
 
-    use super::api::screen::Screen2D;
+    use clap::Parser;
+    use mathvis::api::util::CoordinateMap;
 
     use super::*;
     #[test]
@@ -91,8 +891,185 @@ mod tests {
         let screen = Arc::new(
             Screen2D::new((-10.0, 10.0), (-10.0, 10.0), String::new(), 30, 1920, 1080).unwrap(),
         );
-        let quality = Quality::HIGH;
-        let (x, y) = interpolate(quality, screen, (0.0, 0.0));
+        let (x, y) = CoordinateMap::new(&screen).unwrap().to_pixels((0.0, 0.0));
         assert!(x == 960.0 && y == 540.0);
     }
+
+    /// Parses the arguments of the render subcommand.
+    fn render_args(args: &[&str]) -> RenderArgs {
+        let args = Args::parse_from(["mathvis", "render", "source"].iter().chain(args));
+        match args.command {
+            Subcommand::Render(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_codec_args() {
+        let mp4 = render_args(&["--crf", "18", "--preset", "slow"]).encode;
+        let webm = render_args(&["-o", "out.webm", "--crf", "60"]).encode;
+        let invalid = render_args(&["--crf", "60"]).encode;
+        assert!(codec_args(&mp4, false).unwrap().ends_with(&[
+            String::from("-crf"),
+            String::from("18"),
+            String::from("-preset"),
+            String::from("slow")
+        ]));
+        assert!(codec_args(&webm, false)
+            .unwrap()
+            .contains(&String::from("libvpx-vp9")));
+        assert!(codec_args(&invalid, false).is_err());
+    }
+
+    #[test]
+    fn test_transparent_codec_args() {
+        let webm = render_args(&["-o", "out.webm"]).encode;
+        let mov = render_args(&["-o", "out.mov"]).encode;
+        let mp4 = render_args(&["-o", "out.mp4"]).encode;
+        assert!(codec_args(&webm, true)
+            .unwrap()
+            .contains(&String::from("yuva420p")));
+        assert!(codec_args(&mov, true)
+            .unwrap()
+            .contains(&String::from("prores_ks")));
+        assert!(codec_args(&mp4, true).is_err());
+    }
+
+    #[test]
+    fn test_frame_range() {
+        let frames = render_args(&["--start-frame", "10"]);
+        let times = render_args(&["--fps", "24", "--end-time", "2.5"]);
+        let whole = render_args(&[]);
+        assert_eq!(frame_range(&frames), Some((10, None)));
+        assert_eq!(frame_range(&times), Some((0, Some(60))));
+        assert_eq!(frame_range(&whole), None);
+    }
+
+    #[test]
+    fn test_render_directories() {
+        let output = std::env::temp_dir().join("mathvis_render_directories_test");
+        let output = output.to_str().unwrap();
+        let (first, second) = (
+            RenderDirectory::unique(output).unwrap(),
+            RenderDirectory::unique(output).unwrap(),
+        );
+        assert_ne!(first.path(), second.path());
+        std::fs::create_dir_all(second.frames()).unwrap();
+        let mut manifest = mathvis::animation::checkpoint::RenderManifest::new(3);
+        manifest.record(0..10);
+        manifest.save(second.path()).unwrap();
+
+        let resumed = RenderDirectory::resumable(output, 3).unwrap().unwrap();
+        assert_eq!(resumed.path(), second.path());
+        assert!(RenderDirectory::resumable(output, 4).unwrap().is_none());
+        // Directories are removed when dropped, unless they're kept
+        let path = first.path().to_string();
+        drop(first);
+        assert!(!Path::new(&path).exists());
+        std::mem::forget(second);
+        // or their render can be resumed and didn't finish
+        drop(resumed);
+        let mut resumed = RenderDirectory::resumable(output, 3).unwrap().unwrap();
+        resumed.finish();
+        drop(resumed);
+        assert!(RenderDirectory::resumable(output, 3).unwrap().is_none());
+        remove_dir_all(output).unwrap();
+    }
+
+    #[test]
+    fn test_check_frames() {
+        let directory = std::env::temp_dir().join("mathvis_check_frames_test");
+        let _ = remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("tmp")).unwrap();
+        let mut screen = Screen2D::new(
+            (-10.0, 10.0),
+            (-10.0, 10.0),
+            directory.to_str().unwrap().to_string(),
+            30,
+            16,
+            9,
+        )
+        .unwrap();
+        screen.set_frame_digits(4).unwrap();
+        let frame = |i: u32| PathBuf::from(screen.frame_path(i));
+        assert!(frame(7).ends_with("tmp/frame_0007.png"));
+        for i in [0, 1, 2, 5, 6, 8, 9] {
+            std::fs::write(frame(i), [1]).unwrap();
+        }
+        std::fs::write(frame(1), []).unwrap();
+        let err = check_frames(&screen, 0..8, false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Some frames weren't saved (missing 3-4, 7; empty 1), render the animation again"
+        );
+        // Only the frames of a chunk are checked
+        check_frames(&screen, 5..7, false).unwrap();
+
+        for i in [1, 3, 4, 7] {
+            std::fs::write(frame(i), [1]).unwrap();
+        }
+        check_frames(&screen, 0..8, true).unwrap();
+        // Frames past the end are left over from a longer render
+        assert!(!frame(8).exists() && !frame(9).exists());
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let directory = std::env::temp_dir().join("mathvis_render_stats_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        let output = directory.join("video.mp4");
+        std::fs::write(&output, [0; 2500]).unwrap();
+        let stats = RenderStats::new(
+            100,
+            (854, 480, 30, 4),
+            Duration::from_secs(2),
+            Duration::from_millis(500),
+            &output,
+        )
+        .unwrap();
+        assert_eq!(stats.ms_per_frame, 20.0);
+        assert_eq!(stats.output_bytes, 2500);
+        assert!(stats.to_string().contains("20.0 ms/frame with 4 threads"));
+
+        let path = stats.save(&output).unwrap();
+        assert_eq!(path, directory.join("video.stats.json"));
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(json["encode_seconds"], 0.5);
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let still = Args::parse_from(["mathvis", "still", "source", "--time", "1.5"]);
+        assert!(matches!(
+            still.command,
+            Subcommand::Still(StillArgs {
+                time: Some(1.5),
+                ..
+            })
+        ));
+        assert!(Args::try_parse_from([
+            "mathvis", "still", "source", "--frame", "1", "--time", "1"
+        ])
+        .is_err());
+        assert!(Args::try_parse_from(["mathvis", "preview", "source", "--crf", "18"]).is_err());
+        let Subcommand::Watch(watch) =
+            Args::parse_from(["mathvis", "watch", "source", "-o", "out.mp4"]).command
+        else {
+            panic!("watch should be parsed as its own subcommand");
+        };
+        let preview = PreviewArgs::from(watch);
+        assert!(preview.watch && preview.output == Path::new("out.mp4"));
+        assert!(Args::try_parse_from(["mathvis", "watch", "source", "--watch"]).is_err());
+        assert!(Args::try_parse_from(["mathvis", "source"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_bitrate() {
+        assert_eq!(parse_bitrate("2500k").unwrap(), 2_500_000.0);
+        assert_eq!(parse_bitrate("5M").unwrap(), 5_000_000.0);
+        assert!(parse_bitrate("fast").is_err());
+    }
 }
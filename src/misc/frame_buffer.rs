@@ -0,0 +1,41 @@
+use std::cell::RefCell;
+
+use imageproc::image::RgbImage;
+
+thread_local! {
+    /// Frame buffer of the current thread, reused by every frame rendered on it.
+    static FRAME_BUFFER: RefCell<RgbImage> = RefCell::new(RgbImage::new(0, 0));
+}
+
+/// Calls the function with the current thread's frame buffer, so render workers don't allocate a new image for every frame.
+///
+/// The buffer is only reallocated when the resolution changes, otherwise it still contains the previous frame and has to be cleared.
+pub(crate) fn with_frame_buffer<R>(
+    width: u32,
+    height: u32,
+    f: impl FnOnce(&mut RgbImage) -> R,
+) -> R {
+    FRAME_BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        if buffer.dimensions() != (width, height) {
+            *buffer = RgbImage::new(width, height);
+        }
+        f(&mut buffer)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use imageproc::image::Rgb;
+
+    use super::*;
+
+    #[test]
+    fn test_reuses_buffer() {
+        with_frame_buffer(4, 3, |img| img.put_pixel(0, 0, Rgb([1, 2, 3])));
+        let pixel = with_frame_buffer(4, 3, |img| *img.get_pixel(0, 0));
+        assert_eq!(pixel, Rgb([1, 2, 3]));
+        let resized = with_frame_buffer(2, 2, |img| (img.dimensions(), *img.get_pixel(0, 0)));
+        assert_eq!(resized, ((2, 2), Rgb([0, 0, 0])));
+    }
+}
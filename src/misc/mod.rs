@@ -1 +1,5 @@
+pub mod frame_buffer;
+pub mod progress;
+pub mod render;
+#[cfg(not(feature = "rayon"))]
 pub mod thread_pool;
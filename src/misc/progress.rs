@@ -0,0 +1,20 @@
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Creates a progress bar for rendering the frames of an animation, or a hidden one in quiet mode.
+///
+/// The bar shows the completed frames, the rendering speed and the estimated time left.
+pub(crate) fn frame_progress_bar(frames: u32, animation: usize, quiet: bool) -> ProgressBar {
+    if quiet {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new(frames as u64);
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{prefix} [{bar:40.cyan/blue}] {pos}/{len} frames ({per_sec}, ETA {eta})",
+        )
+        .unwrap()
+        .progress_chars("=> "),
+    );
+    bar.set_prefix(format!("Animation #{}", animation));
+    bar
+}
@@ -0,0 +1,255 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+
+use indicatif::ProgressBar;
+
+use crate::error::MathvisError;
+
+#[cfg(not(feature = "rayon"))]
+use std::{cell::Cell, sync::Arc};
+
+#[cfg(not(feature = "rayon"))]
+use super::thread_pool::ThreadPool;
+#[cfg(feature = "rayon")]
+use rayon::{iter::IntoParallelIterator, iter::ParallelIterator, ThreadPoolBuilder};
+
+#[cfg(not(feature = "rayon"))]
+thread_local! {
+    /// Whether the thread is rendering a frame for [render_frames], in which case [map_rows] doesn't start more threads,
+    /// since every core is already busy with a frame.
+    static IN_FRAME_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Renders a frame, turning a panic into an error for that frame so it's reported along with the other failed frames
+/// instead of taking down the whole render.
+fn render_caught<F>(render_frame: &F, i: u32) -> Result<(), MathvisError>
+where
+    F: Fn(u32) -> Result<(), MathvisError>,
+{
+    panic::catch_unwind(AssertUnwindSafe(|| render_frame(i))).unwrap_or_else(|payload| {
+        Err(MathvisError::Render(format!(
+            "panicked: {}",
+            panic_message(payload)
+        )))
+    })
+}
+
+/// Returns the message a thread panicked with, if it was a string.
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// Renders every frame in `indices` across the specified number of worker threads, advancing the progress bar for each one.
+///
+/// Uses the homemade [ThreadPool], or rayon's work-stealing pool when built with the `rayon` feature.
+///
+/// Every frame is rendered even if some of them fail, and a frame that panics fails like one that returns an error.
+/// Returns an Err with every failed frame and its reason if any of the frames couldn't be rendered and an Ok otherwise.
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn render_frames<F>(
+    indices: Vec<u32>,
+    jobs: usize,
+    render_frame: F,
+    progress: &ProgressBar,
+) -> Result<(), MathvisError>
+where
+    F: Fn(u32) -> Result<(), MathvisError> + Send + Sync + 'static,
+{
+    let render_frame = Arc::new(render_frame);
+    let thread_pool = ThreadPool::new(jobs).map_err(|err| MathvisError::Render(err.to_string()))?;
+    for &i in &indices {
+        let render_frame = Arc::clone(&render_frame);
+        let progress = progress.clone();
+        thread_pool.execute_returning(move || {
+            IN_FRAME_WORKER.with(|worker| worker.set(true));
+            let result = render_caught(&*render_frame, i);
+            IN_FRAME_WORKER.with(|worker| worker.set(false));
+            if result.is_ok() {
+                progress.inc(1);
+            }
+            result
+        })?;
+    }
+    let results = thread_pool.join()?;
+
+    failed_frames(
+        indices
+            .into_iter()
+            .zip(results)
+            .filter_map(|(i, result)| result.err().map(|err| (i, err)))
+            .collect(),
+    )
+}
+
+/// Renders every frame in `indices` across the specified number of worker threads, advancing the progress bar for each one.
+///
+/// Uses the homemade thread pool, or rayon's work-stealing pool when built with the `rayon` feature.
+///
+/// Every frame is rendered even if some of them fail, and a frame that panics fails like one that returns an error.
+/// Returns an Err with every failed frame and its reason if any of the frames couldn't be rendered and an Ok otherwise.
+#[cfg(feature = "rayon")]
+pub(crate) fn render_frames<F>(
+    indices: Vec<u32>,
+    jobs: usize,
+    render_frame: F,
+    progress: &ProgressBar,
+) -> Result<(), MathvisError>
+where
+    F: Fn(u32) -> Result<(), MathvisError> + Send + Sync + 'static,
+{
+    let thread_pool = ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|err| MathvisError::Render(err.to_string()))?;
+    let failed = thread_pool.install(|| {
+        indices
+            .into_par_iter()
+            .filter_map(|i| match render_caught(&render_frame, i) {
+                Ok(_) => {
+                    progress.inc(1);
+                    None
+                }
+                Err(err) => Some((i, err)),
+            })
+            .collect()
+    });
+    failed_frames(failed)
+}
+
+/// Computes `f` for every row from 0 up to `rows` across the specified number of worker threads, returning the results by row.
+///
+/// Uses the homemade [ThreadPool], or rayon's current pool when built with the `rayon` feature,
+/// and no threads at all when only one is requested or it's called while rendering a frame for [render_frames].
+///
+/// Returns an Err if any of the workers panicked and an Ok with the results otherwise.
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn map_rows<T, F>(rows: u32, jobs: usize, f: F) -> Result<Vec<T>, MathvisError>
+where
+    T: Send + 'static,
+    F: Fn(u32) -> T + Send + Sync + 'static,
+{
+    // Without threads to spare, like on wasm32 or inside a frame worker, the rows are computed in place
+    if jobs <= 1 || IN_FRAME_WORKER.with(Cell::get) {
+        return Ok((0..rows).map(f).collect());
+    }
+    let f = Arc::new(f);
+    let thread_pool = ThreadPool::new(jobs).map_err(|err| MathvisError::Render(err.to_string()))?;
+    for row in 0..rows {
+        let f = Arc::clone(&f);
+        thread_pool.execute_returning(move || f(row))?;
+    }
+    thread_pool.join()
+}
+
+/// Computes `f` for every row from 0 up to `rows` across the specified number of worker threads, returning the results by row.
+///
+/// Uses the homemade thread pool, or rayon's current pool when built with the `rayon` feature.
+///
+/// Returns an Err if any of the workers panicked and an Ok with the results otherwise.
+#[cfg(feature = "rayon")]
+pub(crate) fn map_rows<T, F>(rows: u32, _jobs: usize, f: F) -> Result<Vec<T>, MathvisError>
+where
+    T: Send + 'static,
+    F: Fn(u32) -> T + Send + Sync + 'static,
+{
+    // Frames are already rendered inside a pool, which the rows share instead of oversubscribing the cores
+    (0..rows)
+        .into_par_iter()
+        .map(|row| std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(row))))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| MathvisError::Render("A worker thread panicked".into()))
+}
+
+/// Returns an Err with the failed frames sorted by frame if there are any and an Ok otherwise.
+fn failed_frames(mut failed: Vec<(u32, MathvisError)>) -> Result<(), MathvisError> {
+    if failed.is_empty() {
+        return Ok(());
+    }
+    failed.sort_by_key(|(i, _)| *i);
+    Err(MathvisError::Frames(failed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_rows() {
+        let rows = map_rows(50, 4, |row| row * 2).unwrap();
+        assert_eq!(rows, (0..50).map(|row| row * 2).collect::<Vec<_>>());
+        assert_eq!(map_rows(3, 1, |row| row).unwrap(), [0, 1, 2]);
+        assert!(map_rows(10, 2, |row| if row == 3 {
+            panic!("row failed")
+        })
+        .is_err());
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    #[test]
+    fn test_map_rows_in_frame_worker() {
+        // Rows of a frame are computed on the thread rendering it, instead of a new pool per frame
+        render_frames(
+            (0..4).collect(),
+            2,
+            |_| {
+                let worker = std::thread::current().id();
+                let threads = map_rows(8, 4, |_| std::thread::current().id())?;
+                assert!(threads.iter().all(|&thread| thread == worker));
+                Ok(())
+            },
+            &ProgressBar::hidden(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_frames() {
+        let progress = ProgressBar::hidden();
+        render_frames((0..20).collect(), 4, |_| Ok(()), &progress).unwrap();
+        assert_eq!(progress.position(), 20);
+
+        let panicked = render_frames(
+            (0..5).collect(),
+            2,
+            |i| match i {
+                3 => panic!("broken"),
+                _ => Ok(()),
+            },
+            &ProgressBar::hidden(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            panicked.to_string(),
+            "1 frame(s) failed to render: frame 3: panicked: broken"
+        );
+
+        let failed = render_frames(
+            (0..20).collect(),
+            4,
+            |i| match i {
+                13 | 7 => Err(MathvisError::Render(String::from("broken"))),
+                _ => Ok(()),
+            },
+            &ProgressBar::hidden(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            failed.to_string(),
+            "2 frame(s) failed to render: frame 7: broken; frame 13: broken"
+        );
+        match failed {
+            MathvisError::Frames(frames) => {
+                assert_eq!(frames.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [7, 13])
+            }
+            err => panic!("Unexpected error {:?}", err),
+        }
+    }
+}
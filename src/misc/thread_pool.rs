@@ -1,70 +1,168 @@
 use std::{
     error::Error,
+    panic::{self, AssertUnwindSafe},
     sync::{
-        mpsc::{channel, Receiver, Sender},
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{channel, sync_channel, Receiver, Sender, SyncSender},
         Arc, Mutex,
     },
     thread::{self, JoinHandle},
 };
 
-type Job = Box<dyn FnOnce() + Send + 'static>;
+use super::render::panic_message;
+use crate::error::MathvisError;
+
+type Job<T> = Box<dyn FnOnce() -> T + Send + 'static>;
+/// A job numbered in the order it was sent.
+type Message<T> = (usize, Job<T>);
+/// The result of the job with the specified number, or the message it panicked with and the id of the worker running it.
+type Outcome<T> = (usize, Result<T, (usize, String)>);
+
+/// Number of jobs waiting in the queue per worker for pools created with [ThreadPool::new],
+/// enough to keep every worker busy without holding the state of every job at once.
+const QUEUE_PER_WORKER: usize = 2;
 
 struct Worker {
     id: usize,
     thread: Option<JoinHandle<()>>,
 }
 
-pub struct ThreadPool {
+/// A fixed number of worker threads running jobs in the order they're sent, whose results are collected by [ThreadPool::join].
+///
+/// Panics in jobs are caught, so the worker running one keeps going and the panic is reported by [ThreadPool::join].
+/// The queue of jobs waiting for a worker is bounded, so sending a job blocks while it's full
+/// and memory stays flat however many jobs are sent.
+pub struct ThreadPool<T: Send + 'static = ()> {
     workers: Vec<Worker>,
-    sender: Option<Sender<Job>>,
+    sender: Option<SyncSender<Message<T>>>,
+    results: Receiver<Outcome<T>>,
+    /// Number of jobs sent so far, which numbers the next one.
+    submitted: AtomicUsize,
 }
 
-impl ThreadPool {
+impl<T: Send + 'static> ThreadPool<T> {
     pub fn new(size: usize) -> Result<Self, Box<dyn Error>> {
+        Self::with_queue_capacity(size, size * QUEUE_PER_WORKER)
+    }
+
+    /// Creates a pool with the specified number of workers, where at most `capacity` jobs wait for a worker at once.
+    ///
+    /// Returns an Err if there are no workers or the capacity is 0 and an Ok with the pool otherwise.
+    pub fn with_queue_capacity(size: usize, capacity: usize) -> Result<Self, Box<dyn Error>> {
         if size <= 0 {
             return Err("Invalid size".into());
         }
-        let (sender, receiver) = channel();
+        if capacity == 0 {
+            return Err("Invalid queue capacity".into());
+        }
+        let (sender, receiver) = sync_channel(capacity);
         let receiver = Arc::new(Mutex::new(receiver));
+        let (result_sender, results) = channel();
         let mut workers = Vec::with_capacity(size);
 
         for id in 0..size {
-            workers.push(Worker::new(id, Arc::clone(&receiver)))
+            workers.push(Worker::new(
+                id,
+                Arc::clone(&receiver),
+                result_sender.clone(),
+            ))
         }
         Ok(Self {
             workers,
             sender: Some(sender),
+            results,
+            submitted: AtomicUsize::new(0),
         })
     }
 
-    pub fn execute<F>(&self, f: F)
+    /// Sends a job to the pool, whose result is collected by [ThreadPool::join] in the order the jobs were sent.
+    /// Blocks until there's room in the queue if it's full.
+    ///
+    /// Returns an Err if the pool was shut down and an Ok otherwise.
+    pub fn execute_returning<F>(&self, f: F) -> Result<(), MathvisError>
     where
-        F: FnOnce() + Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
     {
-        let job = Box::new(f);
-        self.sender.as_ref().unwrap().send(job).unwrap();
+        let shut_down = || MathvisError::Render("The thread pool was shut down".into());
+        let sender = self.sender.as_ref().ok_or_else(shut_down)?;
+        let index = self.submitted.fetch_add(1, Ordering::Relaxed);
+        let job: Job<T> = Box::new(f);
+        sender.send((index, job)).map_err(|_| shut_down())
     }
-}
 
-impl Drop for ThreadPool {
-    fn drop(&mut self) {
+    /// Stops accepting jobs and waits for the ones that were already sent to finish, then stops the workers.
+    /// Their results are kept for [ThreadPool::join], and calling this again does nothing.
+    ///
+    /// Returns an Err if a worker thread itself panicked, outside of any job, and an Ok otherwise.
+    pub fn shutdown(&mut self) -> Result<(), MathvisError> {
         drop(self.sender.take());
+        let mut panicked = Vec::new();
         for worker in &mut self.workers {
             if let Some(thread) = worker.thread.take() {
-                thread.join().unwrap();
+                if thread.join().is_err() {
+                    panicked.push(worker.id.to_string());
+                }
             }
         }
+        if panicked.is_empty() {
+            Ok(())
+        } else {
+            Err(MathvisError::Render(format!(
+                "Worker threads {} panicked",
+                panicked.join(", ")
+            )))
+        }
+    }
+
+    /// Waits for every job that was sent to the pool to finish, then stops the workers.
+    ///
+    /// Returns an Err with the message of every job that panicked if there's any, and an Ok with the result of every job
+    /// in the order they were sent otherwise.
+    pub fn join(mut self) -> Result<Vec<T>, MathvisError> {
+        self.shutdown()?;
+        let mut results: Vec<Option<T>> = (0..*self.submitted.get_mut()).map(|_| None).collect();
+        let mut panics = Vec::new();
+        for (index, outcome) in self.results.try_iter() {
+            match outcome {
+                Ok(result) => results[index] = Some(result),
+                Err((worker, message)) => panics.push(format!(
+                    "job {} panicked on worker {}: {}",
+                    index, worker, message
+                )),
+            }
+        }
+        if !panics.is_empty() {
+            return Err(MathvisError::Render(panics.join("; ")));
+        }
+        results
+            .into_iter()
+            .collect::<Option<Vec<T>>>()
+            .ok_or_else(|| MathvisError::Render("A job was lost by the thread pool".into()))
+    }
+}
+
+impl<T: Send + 'static> Drop for ThreadPool<T> {
+    /// Waits for the jobs that were already sent to finish, ignoring any panic since there's no one left to report it to.
+    fn drop(&mut self) {
+        let _ = self.shutdown();
     }
 }
 
 impl Worker {
-    fn new(id: usize, receiver: Arc<Mutex<Receiver<Job>>>) -> Self {
+    fn new<T: Send + 'static>(
+        id: usize,
+        receiver: Arc<Mutex<Receiver<Message<T>>>>,
+        results: Sender<Outcome<T>>,
+    ) -> Self {
         let thread = thread::spawn(move || loop {
+            // The lock is released before running the job, so a panicking job can't poison it
             let message = receiver.lock().unwrap().recv();
 
             match message {
-                Ok(job) => {
-                    job();
+                Ok((index, job)) => {
+                    let outcome = panic::catch_unwind(AssertUnwindSafe(job))
+                        .map_err(|payload| (id, panic_message(payload)));
+                    let _ = results.send((index, outcome));
                 }
                 Err(_) => {
                     break;
@@ -77,3 +175,145 @@ impl Worker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+
+    use super::*;
+
+    #[test]
+    fn test_join() {
+        let pool = ThreadPool::new(4).unwrap();
+        let counter = Arc::new(AtomicU32::new(0));
+        for _ in 0..100 {
+            let counter = Arc::clone(&counter);
+            pool.execute_returning(move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap();
+        }
+        assert!(pool.join().is_ok());
+        assert_eq!(counter.load(Ordering::Relaxed), 100);
+
+        let pool = ThreadPool::<()>::new(2).unwrap();
+        pool.execute_returning(|| panic!("job failed")).unwrap();
+        assert!(pool.join().is_err());
+    }
+
+    #[test]
+    fn test_panics() {
+        // A single worker keeps running jobs after one of them panics
+        let pool = ThreadPool::new(1).unwrap();
+        for i in 0..5 {
+            pool.execute_returning(move || {
+                if i == 2 {
+                    panic!("frame {} failed", i);
+                }
+                i
+            })
+            .unwrap();
+        }
+        match pool.join() {
+            Err(MathvisError::Render(message)) => {
+                assert_eq!(message, "job 2 panicked on worker 0: frame 2 failed")
+            }
+            _ => panic!("the panic wasn't reported"),
+        }
+    }
+
+    #[test]
+    fn test_shutdown() {
+        let mut pool = ThreadPool::new(2).unwrap();
+        let counter = Arc::new(AtomicU32::new(0));
+        for _ in 0..10 {
+            let counter = Arc::clone(&counter);
+            pool.execute_returning(move || {
+                thread::sleep(std::time::Duration::from_millis(5));
+                counter.fetch_add(1, Ordering::Relaxed)
+            })
+            .unwrap();
+        }
+        pool.shutdown().unwrap();
+        // Every job that was sent finished, and no more are accepted
+        assert_eq!(counter.load(Ordering::Relaxed), 10);
+        assert!(pool.execute_returning(|| 0).is_err());
+        assert!(pool.shutdown().is_ok());
+        assert_eq!(pool.join().unwrap().len(), 10);
+
+        // Dropping waits for jobs too
+        let pool = ThreadPool::<()>::new(2).unwrap();
+        for _ in 0..4 {
+            let counter = Arc::clone(&counter);
+            pool.execute_returning(move || {
+                thread::sleep(std::time::Duration::from_millis(5));
+                counter.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap();
+        }
+        pool.execute_returning(|| panic!("ignored")).unwrap();
+        drop(pool);
+        assert_eq!(counter.load(Ordering::Relaxed), 14);
+    }
+
+    #[test]
+    fn test_queue_capacity() {
+        assert!(ThreadPool::<()>::with_queue_capacity(1, 0).is_err());
+        let pool = ThreadPool::with_queue_capacity(1, 2).unwrap();
+        let (gate, wait) = channel::<()>();
+        let wait = Arc::new(Mutex::new(wait));
+        let sent = Arc::new(AtomicU32::new(0));
+
+        let checker = {
+            let sent = Arc::clone(&sent);
+            thread::spawn(move || {
+                // The worker is stuck on the first job and the queue holds two more, so the fourth can't be sent yet
+                thread::sleep(std::time::Duration::from_millis(100));
+                let before = sent.load(Ordering::Relaxed);
+                drop(gate);
+                before
+            })
+        };
+        for _ in 0..6 {
+            let wait = Arc::clone(&wait);
+            pool.execute_returning(move || {
+                let _ = wait.lock().unwrap().recv();
+            })
+            .unwrap();
+            sent.fetch_add(1, Ordering::Relaxed);
+        }
+        assert_eq!(checker.join().unwrap(), 3);
+        assert_eq!(pool.join().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_execute_returning() {
+        let pool = ThreadPool::new(4).unwrap();
+        for i in 0..50u32 {
+            pool.execute_returning(move || {
+                // Later jobs finish first
+                thread::sleep(std::time::Duration::from_micros(u64::from(50 - i) * 10));
+                if i % 7 == 0 {
+                    Err(format!("job {} failed", i))
+                } else {
+                    Ok(i * i)
+                }
+            })
+            .unwrap();
+        }
+        let results = pool.join().unwrap();
+        assert_eq!(results.len(), 50);
+        for (i, result) in results.into_iter().enumerate() {
+            let i = i as u32;
+            match result {
+                Ok(square) => assert_eq!(square, i * i),
+                Err(err) => assert_eq!(err, format!("job {} failed", i)),
+            }
+        }
+        assert!(ThreadPool::<u32>::new(1)
+            .unwrap()
+            .join()
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! Module containing the directories renders save their frames in, so renders into the same output folder don't clobber each other's frames.
+use std::{
+    error::Error,
+    fs::{create_dir_all, read_dir, remove_dir_all},
+};
+
+use mathvis::animation::checkpoint::RenderManifest;
+use tempfile::Builder;
+
+/// Prefix of the unique directories of renders that don't keep their frames.
+const PREFIX: &str = ".mathvis-render-";
+
+/// A directory a render saves its frames in, in its `tmp` directory like every save directory,
+/// which is removed when it's dropped unless it's kept.
+///
+/// The directory of a render that records its frames in a manifest is also kept if the render doesn't finish,
+/// so a failed or cancelled render can be resumed.
+pub(crate) struct RenderDirectory {
+    path: String,
+    keep: bool,
+    checkpointed: bool,
+    finished: bool,
+}
+
+impl RenderDirectory {
+    /// Returns the directory frames are kept in between renders, which is the output directory itself,
+    /// for renders that reuse or keep their frames.
+    pub(crate) fn shared(output_directory: &str) -> Self {
+        RenderDirectory {
+            path: output_directory.to_string(),
+            keep: true,
+            checkpointed: false,
+            finished: false,
+        }
+    }
+
+    /// Creates a new directory for a single render inside the output directory.
+    ///
+    /// Returns an Err if the directory can't be created and an Ok with it otherwise.
+    pub(crate) fn unique(output_directory: &str) -> Result<Self, Box<dyn Error>> {
+        create_dir_all(output_directory)?;
+        let path = Builder::new()
+            .prefix(PREFIX)
+            .tempdir_in(output_directory)?
+            .keep();
+        Ok(RenderDirectory {
+            path: path.to_str().ok_or("Invalid directory path")?.to_string(),
+            keep: false,
+            checkpointed: false,
+            finished: false,
+        })
+    }
+
+    /// Finds the directory of an interrupted render of the scene with the specified hash in the output directory,
+    /// looking in the shared directory first and then in the unique ones.
+    ///
+    /// Returns an Err if the output directory can't be read and an Ok with the directory, or None if there's no such render, otherwise.
+    pub(crate) fn resumable(
+        output_directory: &str,
+        scene_hash: u64,
+    ) -> Result<Option<Self>, Box<dyn Error>> {
+        if RenderManifest::load(output_directory, scene_hash)?.is_some() {
+            return Ok(Some(Self::shared(output_directory)));
+        }
+        let Ok(entries) = read_dir(output_directory) else {
+            return Ok(None);
+        };
+        for entry in entries {
+            let path = entry?.path();
+            let is_render = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(PREFIX));
+            let Some(path) = path.to_str().filter(|_| is_render && path.is_dir()) else {
+                continue;
+            };
+            // Other renders may be writing their manifest, so unreadable ones are skipped
+            if let Ok(Some(_)) = RenderManifest::load(path, scene_hash) {
+                return Ok(Some(RenderDirectory {
+                    path: path.to_string(),
+                    keep: false,
+                    checkpointed: true,
+                    finished: false,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the save directory of the render, which the frames are saved in the `tmp` directory of.
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the directory the frames are saved in.
+    pub(crate) fn frames(&self) -> String {
+        format!("{}/tmp", self.path)
+    }
+
+    /// Keeps the directory after the render instead of removing it.
+    pub(crate) fn keep(&mut self) {
+        self.keep = true;
+    }
+
+    /// Keeps the directory if the render fails or is cancelled, since it records its frames in a manifest to resume from,
+    /// until the render is finished.
+    pub(crate) fn checkpoint(&mut self) {
+        self.checkpointed = true;
+    }
+
+    /// Marks the render as finished, after which the directory is removed unless it's kept.
+    pub(crate) fn finish(&mut self) {
+        self.finished = true;
+    }
+}
+
+impl Drop for RenderDirectory {
+    fn drop(&mut self) {
+        if !self.keep && (self.finished || !self.checkpointed) {
+            let _ = remove_dir_all(&self.path);
+        }
+    }
+}
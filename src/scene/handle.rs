@@ -0,0 +1,190 @@
+//! Module containing a handle to the screen a scene is played on, so a scene can be set up in a single call.
+#![warn(missing_docs)]
+use std::{
+    fs::create_dir_all,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{
+    animation::{
+        clip::Animation, drawable::Drawable, renderer::Renderer, replace::ReplacementTransform,
+        show::Show2D,
+    },
+    api::{
+        screen::Screen2D,
+        util::{Number, Quality},
+    },
+    error::MathvisError,
+};
+
+/// A screen shared by every object of a scene, whose output directories already exist.
+///
+/// # Examples
+///
+/// ```no_run
+/// use imageproc::image::Rgb;
+/// use mathvis::animation::{show::Show2D, vector::Vector2D};
+/// use mathvis::api::{point::{Point, PointLike}, util::Quality};
+/// use mathvis::quick_scene;
+///
+/// let scene = quick_scene(((-3.0, 3.0), (-3.0, 3.0)), Quality::LOW, 30, "./output").unwrap();
+/// let mut v = Vector2D::new(1.0, 0.0, Rgb([255, 255, 255]));
+/// scene.add(&mut v).unwrap();
+/// scene.play(&v.move_to(1.0, Point::new(vec![0.0, 1.0]).unwrap())).unwrap();
+/// assert_eq!(scene.frame_count().unwrap(), 30);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scene {
+    context: Arc<Mutex<Screen2D>>,
+}
+
+impl Scene {
+    /// Creates a scene on the specified screen, creating the directory its frames are saved in.
+    ///
+    /// Nothing is created in a dry run, since no frames are saved.
+    ///
+    /// Returns an Err if the directory can't be created and an Ok with the scene otherwise.
+    pub fn new(screen: Screen2D) -> Result<Self, MathvisError> {
+        if !screen.dry_run {
+            create_dir_all(format!("{}/tmp", screen.save_directory))?;
+        }
+        Ok(Scene {
+            context: Arc::new(Mutex::new(screen)),
+        })
+    }
+
+    /// Returns the shared screen of the scene, for anything that needs the context directly.
+    pub fn context(&self) -> Arc<Mutex<Screen2D>> {
+        Arc::clone(&self.context)
+    }
+
+    /// Adds an object to the scene, so it can be animated on its screen.
+    ///
+    /// Returns an Err if the object can't be contained by the screen and an Ok otherwise.
+    pub fn add<T, S>(&self, object: &mut S) -> Result<(), MathvisError>
+    where
+        T: Number,
+        S: Show2D<T>,
+    {
+        object.add_context(self.context())
+    }
+
+    /// Renders an animation after everything played so far.
+    ///
+    /// Returns an Err if any frame fails to draw or save and an Ok otherwise.
+    pub fn play(&self, animation: &Animation) -> Result<(), MathvisError> {
+        Renderer::new(self.context())?.play(animation)
+    }
+
+    /// Plays a [ReplacementTransform] of the specified duration from an object into another one, which can be of a different kind,
+    /// and adds the new object to the scene so it's animated from then on in place of the old one.
+    ///
+    /// Returns an Err if the new object can't be contained by the screen or any frame fails to draw or save and an Ok otherwise.
+    pub fn replace<T, S>(
+        &self,
+        from: Arc<dyn Drawable>,
+        to: &mut S,
+        duration: f32,
+    ) -> Result<(), MathvisError>
+    where
+        T: Number,
+        S: Show2D<T> + Clone + 'static,
+    {
+        self.add(to)?;
+        let transform = ReplacementTransform::new(from, Arc::new(to.clone()));
+        self.play(&transform.animate(duration))
+    }
+
+    /// Pauses on the last frame rendered so far for the specified duration in seconds, repeating it without rendering it again.
+    ///
+    /// Returns an Err if nothing was rendered yet or any frame fails to save and an Ok otherwise.
+    pub fn freeze(&self, duration: f32) -> Result<(), MathvisError> {
+        let frames = (duration * self.lock()?.fps as f32) as u32;
+        Renderer::new(self.context())?.freeze(frames)
+    }
+
+    /// Returns the number of frames rendered so far.
+    ///
+    /// Returns an Err if the screen can't be locked and an Ok with the number of frames otherwise.
+    pub fn frame_count(&self) -> Result<u32, MathvisError> {
+        Ok(self.lock()?.frame_count())
+    }
+
+    /// Returns a copy of the current state of the screen, with the timeline of everything played so far.
+    ///
+    /// Returns an Err if the screen can't be locked and an Ok with the screen otherwise.
+    pub fn screen(&self) -> Result<Screen2D, MathvisError> {
+        Ok(self.lock()?.clone())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Screen2D>, MathvisError> {
+        self.context
+            .lock()
+            .map_err(|_| MathvisError::Render("Failed to lock context".into()))
+    }
+}
+
+/// Creates a scene with the specified axes, quality and fps, saving its frames in the `tmp` directory of the output directory.
+///
+/// Every other option of the screen has its default value, see [Screen2D::builder] for more control.
+///
+/// Returns an Err if the axes' limits aren't increasing, the fps is 0 or the directory can't be created and an Ok with the scene otherwise.
+pub fn quick_scene(
+    (x_axis, y_axis): ((f32, f32), (f32, f32)),
+    quality: Quality,
+    fps: u32,
+    output_dir: impl Into<String>,
+) -> Result<Scene, MathvisError> {
+    let screen = Screen2D::builder()
+        .x_axis(x_axis.0, x_axis.1)
+        .y_axis(y_axis.0, y_axis.1)
+        .quality(quality)
+        .fps(fps)
+        .save_directory(output_dir)
+        .build()?;
+    Scene::new(screen)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use imageproc::image::Rgb;
+
+    use crate::animation::vector::Vector2D;
+
+    use super::*;
+
+    #[test]
+    fn test_quick_scene() {
+        let directory = std::env::temp_dir().join("mathvis_quick_scene_test");
+        let scene = quick_scene(
+            ((-3.0, 3.0), (-3.0, 3.0)),
+            Quality::LOW,
+            30,
+            directory.to_str().unwrap(),
+        )
+        .unwrap();
+        assert!(directory.join("tmp").is_dir());
+        scene.context().lock().unwrap().set_quiet(true);
+
+        let mut vector = Vector2D::new(1.0, 1.0, Rgb([255, 255, 255]));
+        scene.add(&mut vector).unwrap();
+        assert!(scene
+            .add(&mut Vector2D::new(5.0, 0.0, Rgb([0, 0, 0])))
+            .is_err());
+        scene.play(&vector.wait(0.1)).unwrap();
+        assert_eq!(scene.frame_count().unwrap(), 3);
+        let mut replacement = Vector2D::new(-1.0, 2.0, Rgb([255, 0, 0]));
+        scene
+            .replace(Arc::new(vector), &mut replacement, 0.1)
+            .unwrap();
+        assert_eq!(scene.frame_count().unwrap(), 6);
+        scene.freeze(0.1).unwrap();
+        assert_eq!(scene.frame_count().unwrap(), 9);
+        assert!(directory.join("tmp/frame_008.png").is_file());
+        assert!(directory.join("tmp/frame_002.png").is_file());
+        assert!(quick_scene(((3.0, -3.0), (-3.0, 3.0)), Quality::LOW, 30, ".").is_err());
+        remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+//! Module containing loaders for JSON and YAML scene files, and for picking the right format from a file's extension.
+//!
+//! Both formats share the same schema, which mirrors [SceneDescription]:
+//!
+//! ```yaml
+//! x_axis: [-3, 3]
+//! y_axis: [-3, 3]
+//! quality: medium
+//! objects:
+//!   - name: v
+//!     type: vector
+//!     x: 0
+//!     y: 1
+//!     color: "#ff8800"
+//!   - name: f
+//!     type: function
+//!     function: sin(x) / x
+//!     domain: [-3, 3]
+//! animations:
+//!   - target: v
+//!     type: transform
+//!     duration: 2
+//!     matrix: [[1, 1], [0, 1]]
+//!   - target: f
+//!     type: wait
+//!     duration: 1
+//! ```
+#![warn(missing_docs)]
+use std::{error::Error, fs::read_to_string, path::Path};
+
+use serde::{Deserialize, Deserializer};
+
+use super::{script, SceneDescription};
+
+/// Parses a scene from a JSON string.
+///
+/// Returns an Err if the JSON doesn't match the scene schema or the scene is inconsistent and an Ok with the scene otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::scene::loader::from_json;
+///
+/// let scene = from_json(r#"{"objects": [{"name": "v", "type": "vector", "x": 1, "y": 0}]}"#).unwrap();
+/// assert_eq!(scene.objects[0].color, None);
+/// ```
+pub fn from_json(source: &str) -> Result<SceneDescription, Box<dyn Error>> {
+    let scene: SceneDescription = serde_json::from_str(source)?;
+    scene.validate()?;
+    Ok(scene)
+}
+
+/// Parses a scene from a YAML string.
+///
+/// Returns an Err if the YAML doesn't match the scene schema or the scene is inconsistent and an Ok with the scene otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::scene::loader::from_yaml;
+///
+/// let scene = from_yaml("objects:\n  - {name: v, type: vector, x: 1, y: 0, color: red}").unwrap();
+/// assert_eq!(scene.objects[0].color, Some([255, 0, 0]));
+/// ```
+pub fn from_yaml(source: &str) -> Result<SceneDescription, Box<dyn Error>> {
+    let scene: SceneDescription = serde_yaml::from_str(source)?;
+    scene.validate()?;
+    Ok(scene)
+}
+
+/// Loads a scene file, choosing the format from its extension.
+///
+/// Files ending in `.json` are read as JSON, `.yaml` and `.yml` as YAML, and anything else as MVScript.
+///
+/// Returns an Err if the file can't be read or parsed and an Ok with the scene otherwise.
+pub fn load(path: &Path) -> Result<SceneDescription, Box<dyn Error>> {
+    let source =
+        read_to_string(path).map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => from_json(&source),
+        Some("yaml") | Some("yml") => from_yaml(&source),
+        _ => script::parse(&source),
+    }
+}
+
+/// Deserializes a color written either as an `[r, g, b]` array or as a name or hex code string.
+pub(crate) fn deserialize_color<'de, D>(deserializer: D) -> Result<[u8; 3], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Color {
+        Rgb([u8; 3]),
+        Name(String),
+    }
+
+    match Color::deserialize(deserializer)? {
+        Color::Rgb(rgb) => Ok(rgb),
+        Color::Name(name) => script::parse_color(&name).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Deserializes a color like [deserialize_color], for colors that can be left out.
+pub(crate) fn deserialize_optional_color<'de, D>(
+    deserializer: D,
+) -> Result<Option<[u8; 3]>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_color(deserializer).map(Some)
+}
+
+/// Serializes an [Rgb](imageproc::image::Rgb) color as an `[r, g, b]` array, and deserializes it like [deserialize_color].
+pub(crate) mod rgb {
+    use imageproc::image::Rgb;
+    use serde::{Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S>(color: &Rgb<u8>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        color.0.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Rgb<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize_color(deserializer).map(Rgb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scene::{AnimationKind, ObjectKind};
+
+    use super::*;
+
+    #[test]
+    fn test_yaml() {
+        let scene = from_yaml(
+            "x_axis: [-5, 5]\nquality: low\nobjects:\n  - name: v\n    type: vector\n    x: 1\n    y: 2\n    color: [0, 128, 255]\nanimations:\n  - target: v\n    type: rotate\n    duration: 1.5\n    angle: 1.5\n",
+        )
+        .unwrap();
+        assert_eq!(scene.x_axis, (-5.0, 5.0));
+        assert_eq!(scene.y_axis, (-3.0, 3.0));
+        assert_eq!(scene.objects[0].kind, ObjectKind::Vector { x: 1.0, y: 2.0 });
+        assert_eq!(scene.objects[0].color, Some([0, 128, 255]));
+        assert_eq!(
+            scene.animations[0].kind,
+            AnimationKind::Rotate {
+                angle: 1.5,
+                center: (0.0, 0.0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let scene = script::parse("vector v 1 0 #00ff00\nmove_to v 1 2 2").unwrap();
+        let json = serde_json::to_string(&scene).unwrap();
+        assert_eq!(from_json(&json).unwrap(), scene);
+    }
+
+    #[test]
+    fn test_invalid_scene() {
+        assert!(from_json(
+            r#"{"animations": [{"target": "v", "type": "move_to", "duration": 1, "x": 0, "y": 0}]}"#
+        )
+        .is_err());
+        assert!(
+            from_json(r#"{"objects": [{"name": "v", "type": "circle", "x": 1, "y": 0}]}"#).is_err()
+        );
+        assert!(from_yaml("objects:\n  - {name: f, type: function, function: 'sin(x'}").is_err());
+        assert!(from_yaml(
+            "objects:\n  - {name: f, type: function, function: 'x^2'}\nanimations:\n  - {target: f, type: rotate, duration: 1, angle: 1}"
+        )
+        .is_err());
+        let scene = |duration: &str| {
+            from_yaml(&format!(
+                "objects:\n  - {{name: v, type: vector, x: 1, y: 0}}\nanimations:\n  - {{target: v, type: wait, duration: {}}}",
+                duration
+            ))
+        };
+        assert!(scene("1").is_ok());
+        for duration in [".nan", ".inf", "0"] {
+            assert!(scene(duration).is_err());
+        }
+    }
+}
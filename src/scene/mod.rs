@@ -0,0 +1,363 @@
+//! Module containing scene descriptions, which describe a whole animation (screen, objects and animations)
+//! without having to write any Rust code.
+#![warn(missing_docs)]
+pub mod handle;
+pub mod loader;
+pub mod script;
+#[cfg(feature = "rhai")]
+pub mod scripting;
+
+pub use handle::{quick_scene, Scene};
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use imageproc::image::Rgb;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animation::{
+        clip::Animation,
+        drawable::Drawable,
+        field::VectorField,
+        graph::FunctionGraph,
+        renderer::Renderer,
+        show::{Rotation, Show2D},
+        vector::Vector2D,
+    },
+    api::{
+        expression::{parse_field, parse_function},
+        matrix::Matrix,
+        point::{Point, PointLike},
+        screen::Screen2D,
+        util::Quality,
+    },
+    error::MathvisError,
+};
+
+/// A full description of an animation, usually loaded from a scene file.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::scene::script::parse;
+///
+/// let scene = parse("screen -3 3 -3 3\nvector v 1 0\nmove_to v 1 0 1").unwrap();
+/// assert_eq!(scene.x_axis, (-3.0, 3.0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneDescription {
+    /// Limits of the x axis.
+    #[serde(default = "default_axis")]
+    pub x_axis: (f32, f32),
+    /// Limits of the y axis.
+    #[serde(default = "default_axis")]
+    pub y_axis: (f32, f32),
+    /// Quality the scene should be rendered in, unless overridden by the command line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<Quality>,
+    /// Objects on the screen, in the order they were declared.
+    #[serde(default)]
+    pub objects: Vec<ObjectDescription>,
+    /// Animations to be played, in order.
+    #[serde(default)]
+    pub animations: Vec<AnimationDescription>,
+}
+
+fn default_axis() -> (f32, f32) {
+    (-3.0, 3.0)
+}
+
+/// A named object of the scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectDescription {
+    /// Name used by animations to refer to the object.
+    pub name: String,
+    /// Kind of object and its initial state.
+    #[serde(flatten)]
+    pub kind: ObjectKind,
+    /// Color the object is drawn with, or None to take the next color of the screen's [theme](crate::api::color::Theme).
+    #[serde(
+        default,
+        deserialize_with = "loader::deserialize_optional_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub color: Option<[u8; 3]>,
+}
+
+/// The kinds of objects that can be described in a scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ObjectKind {
+    /// A 2D vector with the specified components.
+    Vector {
+        /// The x component.
+        x: f64,
+        /// The y component.
+        y: f64,
+    },
+    /// The graph of a function of x, written as an [expression](crate::api::expression) like `sin(x) / x`.
+    Function {
+        /// The expression of the function.
+        function: String,
+        /// Where the function is drawn, across the whole x axis if there's none.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        domain: Option<(f64, f64)>,
+    },
+    /// A vector field over the whole screen, with its components written as [expressions](crate::api::expression) of x and y.
+    Field {
+        /// The expression of the x component.
+        x: String,
+        /// The expression of the y component.
+        y: String,
+    },
+}
+
+impl ObjectKind {
+    /// Returns the object as it's drawn, or None for vectors, which are animated on their own.
+    ///
+    /// Returns an Err if an expression or the domain is invalid and an Ok otherwise.
+    fn drawable(
+        &self,
+        color: Rgb<u8>,
+        x_axis: (f32, f32),
+        y_axis: (f32, f32),
+    ) -> Result<Option<Arc<dyn Drawable>>, MathvisError> {
+        Ok(Some(match self {
+            ObjectKind::Vector { .. } => return Ok(None),
+            ObjectKind::Function { function, domain } => {
+                let graph = FunctionGraph::new(parse_function(function)?, color);
+                Arc::new(match domain {
+                    Some((start, end)) => graph.with_domain(*start, *end)?,
+                    None => graph,
+                })
+            }
+            ObjectKind::Field { x, y } => Arc::new(VectorField::new(
+                parse_field(x, y)?,
+                (x_axis.0 as f64, y_axis.0 as f64),
+                (x_axis.1 as f64, y_axis.1 as f64),
+                color,
+            )?),
+        }))
+    }
+}
+
+/// An animation applied to one of the scene's objects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationDescription {
+    /// Name of the object being animated.
+    pub target: String,
+    /// Duration of the animation in seconds.
+    pub duration: f32,
+    /// What the animation does.
+    #[serde(flatten)]
+    pub kind: AnimationKind,
+}
+
+/// The kinds of animations that can be described in a scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnimationKind {
+    /// Moves the object to the specified point.
+    MoveTo {
+        /// The x coordinate of the destination.
+        x: f64,
+        /// The y coordinate of the destination.
+        y: f64,
+    },
+    /// Rotates the object by an angle in radians around a center.
+    Rotate {
+        /// Angle of the rotation in radians.
+        angle: f64,
+        /// Center of the rotation.
+        #[serde(default)]
+        center: (f64, f64),
+    },
+    /// Transforms the object by multiplying it by a matrix.
+    Transform {
+        /// Rows of the matrix.
+        matrix: Vec<Vec<f64>>,
+    },
+    /// Keeps the object still.
+    Wait,
+}
+
+impl SceneDescription {
+    /// Checks that the scene is consistent: increasing axis limits, unique object names, valid expressions,
+    /// animations that only refer to existing objects, only move vectors and have positive durations.
+    ///
+    /// Returns an Err describing the first problem found and an Ok otherwise.
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.x_axis.0 >= self.x_axis.1 || self.y_axis.0 >= self.y_axis.1 {
+            return Err("Axis limits must be increasing.".into());
+        }
+        for (i, object) in self.objects.iter().enumerate() {
+            if self.objects[..i]
+                .iter()
+                .any(|other| other.name == object.name)
+            {
+                return Err(
+                    format!("Object \"{}\" is defined more than once.", object.name).into(),
+                );
+            }
+            object
+                .kind
+                .drawable(
+                    Rgb(object.color.unwrap_or_default()),
+                    self.x_axis,
+                    self.y_axis,
+                )
+                .map_err(|err| format!("Object \"{}\" is invalid: {}", object.name, err))?;
+        }
+        for animation in &self.animations {
+            let Some(object) = self
+                .objects
+                .iter()
+                .find(|object| object.name == animation.target)
+            else {
+                return Err(format!("Object \"{}\" is not defined.", animation.target).into());
+            };
+            if !matches!(object.kind, ObjectKind::Vector { .. })
+                && animation.kind != AnimationKind::Wait
+            {
+                return Err(format!(
+                    "Object \"{}\" can only wait, since only vectors can be moved, rotated or transformed.",
+                    animation.target
+                )
+                .into());
+            }
+            if !(animation.duration > 0.0 && animation.duration.is_finite()) {
+                return Err("Animation durations must be positive.".into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a [Screen2D] with the axes of this scene.
+    ///
+    /// Returns an Err if the scene's axes are invalid and an Ok with the screen otherwise.
+    pub fn screen(
+        &self,
+        save_directory: String,
+        fps: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<Screen2D, Box<dyn Error>> {
+        Ok(Screen2D::new(
+            self.x_axis,
+            self.y_axis,
+            save_directory,
+            fps,
+            width,
+            height,
+        )?)
+    }
+
+    /// Plays every animation of the scene in order on the specified screen.
+    ///
+    /// Each animation starts where the previous animation on the same object ended.
+    ///
+    /// Returns an Err if an animation refers to an unknown object or if anything goes wrong while rendering and an Ok otherwise.
+    pub fn play(&self, screen: Arc<Mutex<Screen2D>>) -> Result<(), Box<dyn Error>> {
+        for animation in self.animations(screen.clone())? {
+            Renderer::new(screen.clone())?.play(&animation)?;
+        }
+        Ok(())
+    }
+
+    /// Returns every animation of the scene played one after the other, like [SceneDescription::play] renders them,
+    /// so frames can be drawn without rendering the whole scene.
+    ///
+    /// Returns an Err if an animation refers to an unknown object or an object doesn't fit on the screen and an Ok with the animation otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use mathvis::api::screen::Screen2D;
+    /// use mathvis::scene::script::parse;
+    ///
+    /// let scene = parse("screen -3 3 -3 3\nvector v 1 0\nmove_to v 1 0 1\nwait v 2").unwrap();
+    /// let screen = Arc::new(Mutex::new(Screen2D::builder().build().unwrap()));
+    /// assert_eq!(scene.animation(screen).unwrap().duration(), 3.0);
+    /// ```
+    pub fn animation(&self, screen: Arc<Mutex<Screen2D>>) -> Result<Animation, Box<dyn Error>> {
+        Ok(Animation::sequence(self.animations(screen)?))
+    }
+
+    /// Returns the animation of every object, in order, each one starting where the previous animation on the same object ended.
+    fn animations(&self, screen: Arc<Mutex<Screen2D>>) -> Result<Vec<Animation>, Box<dyn Error>> {
+        let mut objects: HashMap<&str, Vector2D<f64>> = HashMap::new();
+        let mut drawables: HashMap<&str, Arc<dyn Drawable>> = HashMap::new();
+        // Objects without a color take the screen's palette in the order they're declared
+        let colors: Vec<Rgb<u8>> = {
+            let screen = screen
+                .lock()
+                .map_err(|_| MathvisError::Render("Failed to lock context".into()))?;
+            (0..self.objects.len())
+                .map(|i| screen.object_color(i))
+                .collect()
+        };
+        for (object, default) in self.objects.iter().zip(colors) {
+            let color = object.color.map_or(default, Rgb);
+            if let ObjectKind::Vector { x, y } = object.kind {
+                let mut vector = Vector2D::new(x, y, color);
+                vector.add_context(screen.clone())?;
+                objects.insert(&object.name, vector);
+            } else if let Some(drawable) = object.kind.drawable(color, self.x_axis, self.y_axis)? {
+                drawables.insert(&object.name, drawable);
+            }
+        }
+
+        let mut animations = Vec::with_capacity(self.animations.len());
+        for animation in &self.animations {
+            if let Some(drawable) = drawables.get(animation.target.as_str()) {
+                if animation.kind != AnimationKind::Wait {
+                    return Err(format!(
+                        "Only vectors can be moved, rotated or transformed, but \"{}\" isn't a vector.",
+                        animation.target
+                    )
+                    .into());
+                }
+                animations.push(Animation::show(animation.duration, [drawable.clone()]));
+                continue;
+            }
+            let object = objects
+                .get(animation.target.as_str())
+                .ok_or_else(|| format!("Unknown object \"{}\".", animation.target))?;
+            let (x, y) = (object.x(), object.y());
+            let (played, end) = match &animation.kind {
+                AnimationKind::MoveTo { x, y } => (
+                    object.move_to(animation.duration, Point::new(vec![*x, *y])?),
+                    (*x, *y),
+                ),
+                AnimationKind::Rotate { angle, center } => (
+                    object.rotate(
+                        animation.duration,
+                        *angle,
+                        Point::new(vec![center.0, center.1])?,
+                    ),
+                    Rotation::new(*angle).end((x, y), *center),
+                ),
+                AnimationKind::Transform { matrix } => {
+                    let matrix = Matrix::new(matrix.clone())?;
+                    let transformed = (&matrix * object)?;
+                    (
+                        object.multiply_by_matrix(animation.duration, matrix)?,
+                        (transformed.x(), transformed.y()),
+                    )
+                }
+                AnimationKind::Wait => (object.wait(animation.duration), (x, y)),
+            };
+            animations.push(played);
+
+            let mut moved = Vector2D::new(end.0, end.1, object.color());
+            moved.add_context(screen.clone())?;
+            objects.insert(&animation.target, moved);
+        }
+        Ok(animations)
+    }
+}
@@ -0,0 +1,341 @@
+//! Module containing a parser for MVScript (.mvs) scene files.
+//!
+//! MVScript is a line based format where every line is a command followed by its arguments, separated by whitespace.
+//! Everything after a `#` that starts a word is a comment.
+//!
+//! ```text
+//! screen <xmin> <xmax> <ymin> <ymax>
+//! vector <name> <x> <y> [color]
+//! function <name> <expression> [<start> <end>] [color]
+//! field <name> <x expression> <y expression> [color]
+//! move_to <name> <duration> <x> <y>
+//! rotate <name> <duration> <angle> [<cx> <cy>]
+//! transform <name> <duration> <a> <b> <c> <d>
+//! wait <name> <duration>
+//! ```
+//!
+//! Functions and fields are written as [expressions](crate::api::expression) of x (and y), without spaces,
+//! like `sin(x)/x`, and can only `wait`, which shows them for its duration.
+//!
+//! Colors can be either the name of one of the [named colors](crate::api::color), like `red` or `teal`, or a hex code like `#ff8800`.
+//! Objects without a color take the next color of the screen's [theme](crate::api::color::Theme), in the order they're declared.
+#![warn(missing_docs)]
+use std::error::Error;
+
+use crate::api::{color::by_name, expression::Expression};
+
+use super::{AnimationDescription, AnimationKind, ObjectDescription, ObjectKind, SceneDescription};
+
+/// Parses the contents of an MVScript file into a [SceneDescription].
+///
+/// Returns an Err with the offending line if the script is invalid and an Ok with the scene otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::scene::script::parse;
+///
+/// let scene = parse("vector v 0 1 red\nrotate v 2 3.14").unwrap();
+/// assert_eq!(scene.objects.len(), 1);
+/// assert_eq!(scene.animations.len(), 1);
+/// ```
+pub fn parse(source: &str) -> Result<SceneDescription, Box<dyn Error>> {
+    let mut scene = SceneDescription {
+        x_axis: (-3.0, 3.0),
+        y_axis: (-3.0, 3.0),
+        quality: None,
+        objects: Vec::new(),
+        animations: Vec::new(),
+    };
+
+    for (index, line) in source.lines().enumerate() {
+        let words: Vec<&str> = line
+            .split_whitespace()
+            .take_while(|word| !word.starts_with('#') || is_hex_color(word))
+            .collect();
+        if words.is_empty() {
+            continue;
+        }
+        parse_line(&mut scene, &words).map_err(|err| format!("line {}: {}", index + 1, err))?;
+    }
+    Ok(scene)
+}
+
+fn parse_line(scene: &mut SceneDescription, words: &[&str]) -> Result<(), Box<dyn Error>> {
+    match words {
+        ["screen", xmin, xmax, ymin, ymax] => {
+            scene.x_axis = (xmin.parse()?, xmax.parse()?);
+            scene.y_axis = (ymin.parse()?, ymax.parse()?);
+            if scene.x_axis.0 >= scene.x_axis.1 || scene.y_axis.0 >= scene.y_axis.1 {
+                return Err("axis limits must be increasing".into());
+            }
+        }
+        ["vector", name, x, y, rest @ ..] if rest.len() <= 1 => push_object(
+            scene,
+            name,
+            ObjectKind::Vector {
+                x: x.parse()?,
+                y: y.parse()?,
+            },
+            rest.first(),
+        )?,
+        ["function", name, function, rest @ ..] if rest.len() <= 3 => {
+            Expression::parse(function, &["x"])?;
+            let (domain, color) = match rest {
+                [start, end, color @ ..] => (Some((start.parse()?, end.parse()?)), color.first()),
+                _ => (None, rest.first()),
+            };
+            push_object(
+                scene,
+                name,
+                ObjectKind::Function {
+                    function: function.to_string(),
+                    domain,
+                },
+                color,
+            )?
+        }
+        ["field", name, x, y, rest @ ..] if rest.len() <= 1 => {
+            Expression::parse(x, &["x", "y"])?;
+            Expression::parse(y, &["x", "y"])?;
+            push_object(
+                scene,
+                name,
+                ObjectKind::Field {
+                    x: x.to_string(),
+                    y: y.to_string(),
+                },
+                rest.first(),
+            )?
+        }
+        ["move_to", name, duration, x, y] => push_animation(
+            scene,
+            name,
+            duration,
+            AnimationKind::MoveTo {
+                x: x.parse()?,
+                y: y.parse()?,
+            },
+        )?,
+        ["rotate", name, duration, angle, center @ ..]
+            if center.is_empty() || center.len() == 2 =>
+        {
+            let center = match center {
+                [cx, cy] => (cx.parse()?, cy.parse()?),
+                _ => (0.0, 0.0),
+            };
+            push_animation(
+                scene,
+                name,
+                duration,
+                AnimationKind::Rotate {
+                    angle: angle.parse()?,
+                    center,
+                },
+            )?
+        }
+        ["transform", name, duration, a, b, c, d] => push_animation(
+            scene,
+            name,
+            duration,
+            AnimationKind::Transform {
+                matrix: vec![vec![a.parse()?, b.parse()?], vec![c.parse()?, d.parse()?]],
+            },
+        )?,
+        ["wait", name, duration] => push_animation(scene, name, duration, AnimationKind::Wait)?,
+        [command, ..] => {
+            return Err(format!("unknown command or wrong arguments for \"{}\"", command).into())
+        }
+        [] => {}
+    }
+    Ok(())
+}
+
+fn push_object(
+    scene: &mut SceneDescription,
+    name: &str,
+    kind: ObjectKind,
+    color: Option<&&str>,
+) -> Result<(), Box<dyn Error>> {
+    if scene.objects.iter().any(|object| object.name == name) {
+        return Err(format!("object \"{}\" is already defined", name).into());
+    }
+    scene.objects.push(ObjectDescription {
+        name: name.to_string(),
+        kind,
+        color: color.map(|color| parse_color(color)).transpose()?,
+    });
+    Ok(())
+}
+
+fn push_animation(
+    scene: &mut SceneDescription,
+    name: &str,
+    duration: &str,
+    kind: AnimationKind,
+) -> Result<(), Box<dyn Error>> {
+    let Some(object) = scene.objects.iter().find(|object| object.name == name) else {
+        return Err(format!("object \"{}\" is not defined", name).into());
+    };
+    if !matches!(object.kind, ObjectKind::Vector { .. }) && kind != AnimationKind::Wait {
+        return Err(format!("object \"{}\" isn't a vector, so it can only wait", name).into());
+    }
+    let duration: f32 = duration.parse()?;
+    if duration <= 0.0 {
+        return Err("duration must be positive".into());
+    }
+    scene.animations.push(AnimationDescription {
+        target: name.to_string(),
+        duration,
+        kind,
+    });
+    Ok(())
+}
+
+fn is_hex_color(word: &str) -> bool {
+    word.strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Parses a color name or hex code into its RGB values.
+///
+/// Returns an Err if the color isn't one of the named colors or a valid hex code and an Ok with its RGB values otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use mathvis::scene::script::parse_color;
+///
+/// assert_eq!(parse_color("#ff8800").unwrap(), [255, 136, 0]);
+/// assert_eq!(parse_color("cyan").unwrap(), [0, 255, 255]);
+/// assert_eq!(parse_color("orange").unwrap(), [255, 134, 47]);
+/// assert!(parse_color("chartreuse").is_err());
+/// assert!(parse_color("é12345").is_err());
+/// ```
+pub fn parse_color(color: &str) -> Result<[u8; 3], Box<dyn Error>> {
+    if is_hex_color(color) {
+        let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16);
+        return Ok([channel(1)?, channel(3)?, channel(5)?]);
+    }
+    by_name(color)
+        .map(|color| color.0)
+        .ok_or_else(|| format!("unknown color \"{}\"", color).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{
+        animation::renderer::render_frame,
+        api::{
+            color::{Theme, INK, WHITE},
+            screen::Screen2D,
+            util::CoordinateMap,
+        },
+    };
+
+    #[test]
+    fn test_parse() {
+        let scene = parse(
+            "# a comment\nscreen -5 5 -4 4\nvector v 1 2 #ff0000 # red vector\ntransform v 2 1 1 0 1\n",
+        )
+        .unwrap();
+        assert_eq!(scene.x_axis, (-5.0, 5.0));
+        assert_eq!(scene.y_axis, (-4.0, 4.0));
+        assert_eq!(scene.objects[0].color, Some([255, 0, 0]));
+        assert_eq!(
+            scene.animations[0].kind,
+            AnimationKind::Transform {
+                matrix: vec![vec![1.0, 1.0], vec![0.0, 1.0]]
+            }
+        );
+    }
+
+    #[test]
+    fn test_undefined_object() {
+        let err = parse("vector v 1 0\nmove_to w 1 0 0").unwrap_err();
+        assert!(err.to_string().starts_with("line 2"));
+    }
+
+    #[test]
+    fn test_wait() {
+        let scene = parse(
+            "vector v 1 0
+wait v 0.5",
+        )
+        .unwrap();
+        assert_eq!(scene.animations[0].kind, AnimationKind::Wait);
+        assert_eq!(scene.animations[0].duration, 0.5);
+        assert!(parse(
+            "vector v 1 0
+wait v"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_functions() {
+        let scene = parse(
+            "function f sin(x)/x -2 2 red
+function g x^2
+field r -y x teal
+wait f 1
+wait r 2",
+        )
+        .unwrap();
+        assert_eq!(
+            scene.objects[0].kind,
+            ObjectKind::Function {
+                function: "sin(x)/x".into(),
+                domain: Some((-2.0, 2.0))
+            }
+        );
+        assert_eq!(scene.objects[0].color, Some([255, 0, 0]));
+        assert_eq!(scene.objects[1].color, None);
+        assert_eq!(
+            scene.objects[2].kind,
+            ObjectKind::Field {
+                x: "-y".into(),
+                y: "x".into()
+            }
+        );
+        assert!(scene.validate().is_ok());
+        let screen = Screen2D::builder().build().unwrap();
+        let animation = scene.animation(Arc::new(Mutex::new(screen))).unwrap();
+        assert_eq!(animation.duration(), 3.0);
+
+        assert!(parse("function f sin(y)").is_err());
+        assert!(parse("function f x 1 0").unwrap().validate().is_err());
+        assert!(parse(
+            "function f x
+move_to f 1 0 0"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_light_theme() {
+        // Objects without a color take the theme's palette, so they don't disappear on a white background
+        let scene = parse("function f 1 red\nfunction g 2\nwait g 1").unwrap();
+        let mut screen = Screen2D::builder().resolution(854, 480).build().unwrap();
+        screen.set_theme(Theme::light());
+        let map = CoordinateMap::new(&screen).unwrap();
+        let animation = scene
+            .animation(Arc::new(Mutex::new(screen.clone())))
+            .unwrap();
+        let frame = render_frame(&animation, &screen, 0).unwrap();
+        let (x, y) = map.to_pixels((-2.5, 2.0));
+        assert_eq!(*frame.get_pixel(x as u32, y as u32), INK[1]);
+        assert_eq!(*frame.get_pixel(5, 5), WHITE);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(parse("screen 3 -3 -3 3").is_err());
+        assert!(parse("vector v one 0").is_err());
+        assert!(parse("vector v 1 0 chartreuse").is_err());
+        assert!(parse("vector v 1 0\nrotate v 1 3.14 0").is_err());
+    }
+}
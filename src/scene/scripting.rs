@@ -0,0 +1,315 @@
+//! Module containing a Rhai scripting engine that exposes the animation API to `.rhai` scripts.
+//! Only available with the `rhai` feature.
+//!
+//! Scripts are run imperatively against a single screen, and every animation is rendered as soon as it's called.
+//!
+//! ```text
+//! axes(-5, 5, -5, 5);
+//!
+//! let v = vector(1, 0, "#ff8800");
+//! v.rotate(2, PI() / 2);
+//! v.transform(1.5, matrix([[1, 1], [0, 1]]));
+//! v.move_to(1, v.x - 1, v.y);
+//! ```
+//!
+//! Available functions are `axes`, `vector`, `matrix`, `identity`, `rotation` and `shear`.
+//! Vectors created without a color take the next color of the screen's [theme](crate::api::color::Theme).
+//! Vectors have the `x` and `y` properties, the `move_to`, `rotate`, `transform` and `wait` animations and `dot`.
+//! Matrices support `*` with other matrices and vectors, `determinant`, `transpose` and `invert`.
+//! `random` returns a number between 0 and 1, reproducible with the `--seed` option.
+#![warn(missing_docs)]
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use imageproc::image::Rgb;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, INT};
+
+use crate::{
+    animation::{
+        clip::Animation,
+        renderer::Renderer,
+        show::{Rotation, Show2D},
+        vector::Vector2D,
+    },
+    api::{
+        matrix::Matrix,
+        point::{Point, PointLike},
+        random::sample,
+        screen::Screen2D,
+    },
+};
+
+use super::script::parse_color;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Converts a script number (either an integer or a float) into an f64.
+fn number(value: &Dynamic) -> ScriptResult<f64> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|int| int as f64))
+        .map_err(|type_name| format!("Expected a number but got {}", type_name).into())
+}
+
+/// Creates a vector with a context, so it can be animated right away.
+fn contextual_vector(
+    screen: &Arc<Mutex<Screen2D>>,
+    x: f64,
+    y: f64,
+    color: Rgb<u8>,
+) -> ScriptResult<Vector2D<f64>> {
+    let mut vector = Vector2D::new(x, y, color);
+    vector
+        .add_context(screen.clone())
+        .map_err(|err| err.to_string())?;
+    Ok(vector)
+}
+
+/// Renders an animation on the specified screen.
+fn play(screen: &Arc<Mutex<Screen2D>>, animation: &Animation) -> ScriptResult<()> {
+    Renderer::new(screen.clone())
+        .and_then(|renderer| renderer.play(animation))
+        .map_err(|err| err.to_string().into())
+}
+
+fn matrix_from_array(rows: Array) -> ScriptResult<Matrix<f64>> {
+    let values = rows
+        .into_iter()
+        .map(|row| {
+            row.into_typed_array::<Dynamic>()?
+                .iter()
+                .map(number)
+                .collect::<ScriptResult<Vec<f64>>>()
+        })
+        .collect::<ScriptResult<Vec<Vec<f64>>>>()?;
+    Matrix::new(values).map_err(|err| err.to_string().into())
+}
+
+/// Creates a Rhai engine with the animation API registered, bound to the specified screen.
+pub fn engine(screen: Arc<Mutex<Screen2D>>) -> Engine {
+    let mut engine = Engine::new();
+
+    let axes_screen = screen.clone();
+    engine.register_fn(
+        "axes",
+        move |xmin: Dynamic, xmax: Dynamic, ymin: Dynamic, ymax: Dynamic| -> ScriptResult<()> {
+            let mut screen = axes_screen.lock().map_err(|_| "Failed to lock screen")?;
+            screen
+                .change_dimensions(
+                    (number(&xmin)? as f32, number(&xmax)? as f32),
+                    (number(&ymin)? as f32, number(&ymax)? as f32),
+                )
+                .map_err(|err| err.to_string().into())
+        },
+    );
+
+    engine.register_type_with_name::<Vector2D<f64>>("Vector");
+    let vector_screen = screen.clone();
+    // Vectors without a color take the screen's palette in the order they're created
+    let uncolored = Arc::new(AtomicUsize::new(0));
+    engine.register_fn("vector", move |x: Dynamic, y: Dynamic| {
+        let color = vector_screen
+            .lock()
+            .map_err(|_| "Failed to lock screen")?
+            .object_color(uncolored.fetch_add(1, Ordering::Relaxed));
+        contextual_vector(&vector_screen, number(&x)?, number(&y)?, color)
+    });
+    let vector_screen = screen.clone();
+    engine.register_fn("vector", move |x: Dynamic, y: Dynamic, color: &str| {
+        let color = parse_color(color).map_err(|err| err.to_string())?;
+        contextual_vector(&vector_screen, number(&x)?, number(&y)?, Rgb(color))
+    });
+    engine.register_get("x", |v: &mut Vector2D<f64>| v.x());
+    engine.register_get("y", |v: &mut Vector2D<f64>| v.y());
+    engine.register_fn("dot", |v: &mut Vector2D<f64>, w: Vector2D<f64>| v.dot(w));
+    engine.register_fn("to_string", |v: &mut Vector2D<f64>| {
+        format!("({}, {})", v.x(), v.y())
+    });
+
+    let move_screen = screen.clone();
+    engine.register_fn(
+        "move_to",
+        move |v: &mut Vector2D<f64>,
+              duration: Dynamic,
+              x: Dynamic,
+              y: Dynamic|
+              -> ScriptResult<()> {
+            let (x, y) = (number(&x)?, number(&y)?);
+            let point = Point::new(vec![x, y]).map_err(|err| err.to_string())?;
+            play(&move_screen, &v.move_to(number(&duration)? as f32, point))?;
+            *v = contextual_vector(&move_screen, x, y, v.color())?;
+            Ok(())
+        },
+    );
+    let rotate_screen = screen.clone();
+    let rotate = move |v: &mut Vector2D<f64>, duration: f64, angle: f64, (cx, cy): (f64, f64)| {
+        let center = Point::new(vec![cx, cy]).map_err(|err| err.to_string())?;
+        play(&rotate_screen, &v.rotate(duration as f32, angle, center))?;
+        let (x, y) = Rotation::new(angle).end((v.x(), v.y()), (cx, cy));
+        *v = contextual_vector(&rotate_screen, x, y, v.color())?;
+        Ok(())
+    };
+    let rotate_around_origin = rotate.clone();
+    engine.register_fn(
+        "rotate",
+        move |v: &mut Vector2D<f64>, duration: Dynamic, angle: Dynamic| -> ScriptResult<()> {
+            rotate_around_origin(v, number(&duration)?, number(&angle)?, (0.0, 0.0))
+        },
+    );
+    engine.register_fn(
+        "rotate",
+        move |v: &mut Vector2D<f64>,
+              duration: Dynamic,
+              angle: Dynamic,
+              cx: Dynamic,
+              cy: Dynamic|
+              -> ScriptResult<()> {
+            rotate(
+                v,
+                number(&duration)?,
+                number(&angle)?,
+                (number(&cx)?, number(&cy)?),
+            )
+        },
+    );
+    let wait_screen = screen.clone();
+    engine.register_fn(
+        "wait",
+        move |v: &mut Vector2D<f64>, duration: Dynamic| -> ScriptResult<()> {
+            play(&wait_screen, &v.wait(number(&duration)? as f32))
+        },
+    );
+    let transform_screen = screen;
+    engine.register_fn(
+        "transform",
+        move |v: &mut Vector2D<f64>, duration: Dynamic, matrix: Matrix<f64>| -> ScriptResult<()> {
+            let transformed = (&matrix * &*v).map_err(|err| err.to_string())?;
+            let animation = v
+                .multiply_by_matrix(number(&duration)? as f32, matrix)
+                .map_err(|err| err.to_string())?;
+            play(&transform_screen, &animation)?;
+            *v = contextual_vector(
+                &transform_screen,
+                transformed.x(),
+                transformed.y(),
+                v.color(),
+            )?;
+            Ok(())
+        },
+    );
+
+    engine.register_type_with_name::<Matrix<f64>>("Matrix");
+    engine.register_fn("matrix", matrix_from_array);
+    engine.register_fn("identity", |dimensions: INT| -> ScriptResult<Matrix<f64>> {
+        usize::try_from(dimensions)
+            .ok()
+            .and_then(Matrix::identity)
+            .ok_or_else(|| "Identity matrices must have a positive dimension".into())
+    });
+    engine.register_fn("rotation", |angle: Dynamic| -> ScriptResult<Matrix<f64>> {
+        let angle = number(&angle)? as f32;
+        Ok(Matrix::<f32>::rotation_matrix_2d(angle).map(|val| val as f64))
+    });
+    engine.register_fn(
+        "shear",
+        |kx: Dynamic, ky: Dynamic| -> ScriptResult<Matrix<f64>> {
+            Ok(Matrix::shear_matrix_2d(number(&kx)?, number(&ky)?))
+        },
+    );
+    engine.register_fn("determinant", |m: &mut Matrix<f64>| -> ScriptResult<f64> {
+        m.determinant().map_err(|err| err.to_string().into())
+    });
+    engine.register_fn("transpose", |m: &mut Matrix<f64>| m.transpose());
+    engine.register_fn(
+        "invert",
+        |m: &mut Matrix<f64>| -> ScriptResult<Matrix<f64>> {
+            m.clone().invert_2d().map_err(|err| err.to_string().into())
+        },
+    );
+    engine.register_fn(
+        "*",
+        |a: Matrix<f64>, b: Matrix<f64>| -> ScriptResult<Matrix<f64>> {
+            (a * b).map_err(|err| err.to_string().into())
+        },
+    );
+    engine.register_fn(
+        "*",
+        |m: Matrix<f64>, v: Vector2D<f64>| -> ScriptResult<Vector2D<f64>> {
+            (m * v).map_err(|err| err.to_string().into())
+        },
+    );
+    engine.register_fn("to_string", |m: &mut Matrix<f64>| {
+        format!("{:?}", m.to_rows())
+    });
+    engine.register_fn("random", || sample::<f64>(1)[0]);
+
+    engine
+}
+
+/// Runs a Rhai script on the specified screen, rendering every animation it calls.
+///
+/// Returns an Err if the script fails to compile or any of its calls fail and an Ok otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use mathvis::api::screen::Screen2D;
+/// use mathvis::scene::scripting::run;
+///
+/// let screen = Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::from("."), 30, 854, 480).unwrap();
+/// run("let m = identity(2) * matrix([[2, 0], [0, 2]]); m.determinant();", Arc::new(Mutex::new(screen))).unwrap();
+/// ```
+pub fn run(source: &str, screen: Arc<Mutex<Screen2D>>) -> Result<(), Box<dyn Error>> {
+    engine(screen).run(source)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen() -> Arc<Mutex<Screen2D>> {
+        Arc::new(Mutex::new(
+            Screen2D::new((-3.0, 3.0), (-3.0, 3.0), String::new(), 30, 854, 480).unwrap(),
+        ))
+    }
+
+    #[test]
+    fn test_matrix_api() {
+        let engine = engine(screen());
+        let det: f64 = engine
+            .eval("(matrix([[1, 2], [3, 4]]) * identity(2)).transpose().determinant()")
+            .unwrap();
+        assert_eq!(det, -2.0);
+        let x: f64 = engine
+            .eval("(rotation(0.0) * vector(1, 2.5, \"red\")).y")
+            .unwrap();
+        assert_eq!(x, 2.5);
+        let x: f64 = engine
+            .eval("(shear(2, 0) * vector(1, 2.5, \"red\")).x")
+            .unwrap();
+        assert_eq!(x, 6.0);
+    }
+
+    #[test]
+    fn test_axes() {
+        let screen = screen();
+        run("axes(-10, 10, -5, 5.0);", screen.clone()).unwrap();
+        assert!(run("axes(5, -5, -5, 5);", screen.clone()).is_err());
+        assert_eq!(
+            <Screen2D as crate::api::screen::ScreenLike<f32>>::x_axis(&screen.lock().unwrap()),
+            (-10.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn test_out_of_bounds_vector() {
+        assert!(run("vector(10, 0);", screen()).is_err());
+    }
+}
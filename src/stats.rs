@@ -0,0 +1,99 @@
+//! Module containing the statistics reported after a render, which help tune the quality, fps and thread settings.
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    fs::write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Serialize;
+
+/// Timings and sizes of a finished render.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct RenderStats {
+    /// Number of frames in the video.
+    pub(crate) frames: u32,
+    /// Width of the frames in pixels.
+    pub(crate) width: u32,
+    /// Height of the frames in pixels.
+    pub(crate) height: u32,
+    pub(crate) fps: u32,
+    /// Number of worker threads frames were rendered with.
+    pub(crate) jobs: usize,
+    /// Wall time spent playing the scene and saving its frames, in seconds.
+    pub(crate) render_seconds: f64,
+    /// Average wall time per frame of the video, in milliseconds.
+    pub(crate) ms_per_frame: f64,
+    /// Wall time spent encoding the frames into the video, in seconds.
+    pub(crate) encode_seconds: f64,
+    /// Size of the output video in bytes.
+    pub(crate) output_bytes: u64,
+}
+
+impl RenderStats {
+    /// Creates the statistics of a render with the specified number of frames, timings and output video.
+    ///
+    /// Returns an Err if the output video can't be read and an Ok with the statistics otherwise.
+    pub(crate) fn new(
+        frames: u32,
+        (width, height, fps, jobs): (u32, u32, u32, usize),
+        render_time: Duration,
+        encode_time: Duration,
+        output: &Path,
+    ) -> Result<Self, Box<dyn Error>> {
+        let render_seconds = render_time.as_secs_f64();
+        Ok(RenderStats {
+            frames,
+            width,
+            height,
+            fps,
+            jobs,
+            render_seconds,
+            ms_per_frame: match frames {
+                0 => 0.0,
+                frames => render_seconds * 1000.0 / frames as f64,
+            },
+            encode_seconds: encode_time.as_secs_f64(),
+            output_bytes: output.metadata()?.len(),
+        })
+    }
+
+    /// Returns the path the statistics of a render are saved at, next to its output with a `.stats.json` extension.
+    pub(crate) fn path(output: &Path) -> PathBuf {
+        let mut name = output.file_stem().unwrap_or_default().to_os_string();
+        name.push(".stats.json");
+        output.with_file_name(name)
+    }
+
+    /// Saves the statistics as JSON next to the output video.
+    ///
+    /// Returns an Err if the file can't be written and an Ok with its path otherwise.
+    pub(crate) fn save(&self, output: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        let path = Self::path(output);
+        write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+impl Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Rendered {} frames ({}x{}, {} fps) in {:.2}s, {:.1} ms/frame with {} threads",
+            self.frames,
+            self.width,
+            self.height,
+            self.fps,
+            self.render_seconds,
+            self.ms_per_frame,
+            self.jobs
+        )?;
+        write!(
+            f,
+            "Encoded in {:.2}s, output size {:.1} MB",
+            self.encode_seconds,
+            self.output_bytes as f64 / 1e6
+        )
+    }
+}
@@ -0,0 +1,201 @@
+//! Module containing helpers to compare rendered frames against reference images, so changes to how objects are drawn
+//! show up as failing tests instead of going unnoticed until someone watches the video.
+//!
+//! Frames are rendered in memory with [render_frame](crate::animation::renderer::render_frame) and compared with
+//! [assert_frame_matches], which saves an image of the differences next to the reference when they don't match.
+#![warn(missing_docs)]
+use std::{env, path::Path};
+
+use imageproc::image::{open, Rgb, RgbImage};
+
+use crate::error::MathvisError;
+
+/// How much a channel of a pixel may be off before the pixel counts as different,
+/// so antialiasing and rounding differences between platforms don't fail comparisons.
+pub const PIXEL_THRESHOLD: u8 = 16;
+
+/// Environment variable that, when set, makes [assert_frame_matches] save frames as the new references instead of comparing them.
+pub const UPDATE_VARIABLE: &str = "MATHVIS_UPDATE_REFERENCES";
+
+/// Returns whether two pixels look different, meaning some channel is off by more than [PIXEL_THRESHOLD].
+fn differs(a: &Rgb<u8>, b: &Rgb<u8>) -> bool {
+    a.0.iter()
+        .zip(b.0.iter())
+        .any(|(x, y)| x.abs_diff(*y) > PIXEL_THRESHOLD)
+}
+
+/// Returns the fraction of pixels that look different between two frames, from 0 if they match to 1 if no pixel does.
+///
+/// Returns an Err if the frames have different dimensions and an Ok with the fraction otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::{Rgb, RgbImage};
+/// use mathvis::testing::frame_difference;
+///
+/// let black = RgbImage::new(4, 4);
+/// let mut dotted = black.clone();
+/// dotted.put_pixel(0, 0, Rgb([255, 255, 255]));
+/// // Slightly off pixels still match
+/// dotted.put_pixel(1, 0, Rgb([5, 5, 5]));
+/// assert_eq!(frame_difference(&dotted, &black).unwrap(), 1.0 / 16.0);
+/// assert!(frame_difference(&dotted, &RgbImage::new(2, 2)).is_err());
+/// ```
+pub fn frame_difference(actual: &RgbImage, expected: &RgbImage) -> Result<f64, MathvisError> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(MathvisError::DimensionMismatch(format!(
+            "The frame is {}x{} but the reference is {}x{}.",
+            actual.width(),
+            actual.height(),
+            expected.width(),
+            expected.height()
+        )));
+    }
+    let different = actual
+        .pixels()
+        .zip(expected.pixels())
+        .filter(|(a, b)| differs(a, b))
+        .count();
+    Ok(different as f64 / (actual.width() as f64 * actual.height() as f64).max(1.0))
+}
+
+/// Returns an image of the differences between two frames of the same dimensions:
+/// the reference dimmed to gray, with the pixels that look different in red.
+fn difference_image(actual: &RgbImage, expected: &RgbImage) -> RgbImage {
+    RgbImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let (a, b) = (actual.get_pixel(x, y), expected.get_pixel(x, y));
+        if differs(a, b) {
+            Rgb([255, 0, 0])
+        } else {
+            let [r, g, b] = b.0.map(u32::from);
+            let gray = ((r * 299 + g * 587 + b * 114) / 4000) as u8;
+            Rgb([gray; 3])
+        }
+    })
+}
+
+/// Panics unless a frame matches the reference image at the specified path, allowing a `tolerance` fraction of its pixels
+/// (from 0 to 1) to look different, where a pixel looks different if some channel is off by more than [PIXEL_THRESHOLD].
+///
+/// When they don't match, the frame and an image of the differences, with the pixels that don't match in red,
+/// are saved next to the reference, as `<name>.actual.png` and `<name>.diff.png`.
+/// When the `MATHVIS_UPDATE_REFERENCES` environment variable is set, the frame is saved as the reference instead,
+/// which is how references are first created and updated after intended changes.
+///
+/// # Panics
+///
+/// Panics if the tolerance isn't between 0 and 1, the reference can't be read, the dimensions differ
+/// or more pixels than allowed look different.
+///
+/// # Examples
+///
+/// ```
+/// use imageproc::image::{Rgb, RgbImage};
+/// use mathvis::testing::assert_frame_matches;
+///
+/// let reference = std::env::temp_dir().join("mathvis_doc_reference.png");
+/// let frame = RgbImage::from_pixel(8, 8, Rgb([30, 60, 90]));
+/// frame.save(&reference).unwrap();
+///
+/// let mut changed = frame.clone();
+/// changed.put_pixel(3, 3, Rgb([255, 255, 255]));
+/// assert_frame_matches(&changed, &reference, 0.05);
+/// # std::fs::remove_file(reference).unwrap();
+/// ```
+pub fn assert_frame_matches(img: &RgbImage, reference_path: impl AsRef<Path>, tolerance: f64) {
+    let reference_path = reference_path.as_ref();
+    assert!(
+        (0.0..=1.0).contains(&tolerance),
+        "The tolerance must be between 0 and 1, but it was {tolerance}."
+    );
+    if env::var_os(UPDATE_VARIABLE).is_some() {
+        img.save(reference_path).unwrap_or_else(|e| {
+            panic!(
+                "Couldn't save the reference {}: {e}",
+                reference_path.display()
+            )
+        });
+        return;
+    }
+    compare_with_reference(img, reference_path, tolerance);
+}
+
+/// Does the comparison behind [assert_frame_matches] without looking at [UPDATE_VARIABLE],
+/// so it behaves the same whatever the environment of the process is.
+fn compare_with_reference(img: &RgbImage, reference_path: &Path, tolerance: f64) {
+    let expected = open(reference_path)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Couldn't read the reference {}: {e}. Set {UPDATE_VARIABLE} to create it.",
+                reference_path.display()
+            )
+        })
+        .to_rgb8();
+    let difference = frame_difference(img, &expected).unwrap_or_else(|e| panic!("{e}"));
+    if difference <= tolerance {
+        return;
+    }
+    let sibling = |suffix: &str| reference_path.with_extension(format!("{suffix}.png"));
+    let (actual_path, diff_path) = (sibling("actual"), sibling("diff"));
+    // The comparison has already failed, so failing to save these shouldn't hide why
+    let _ = img.save(&actual_path);
+    let _ = difference_image(img, &expected).save(&diff_path);
+    panic!(
+        "The frame doesn't match the reference {}: {:.2}% of its pixels are different, more than the {:.2}% allowed. \
+        The frame was saved to {} and the differences to {}.",
+        reference_path.display(),
+        difference * 100.0,
+        tolerance * 100.0,
+        actual_path.display(),
+        diff_path.display()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, panic::catch_unwind};
+
+    use super::*;
+
+    #[test]
+    fn test_compare_with_reference() {
+        // Named after the process so concurrent runs don't share references
+        let directory =
+            env::temp_dir().join(format!("mathvis_testing_test_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let reference = directory.join("square.png");
+        let frame = RgbImage::from_fn(10, 10, |x, y| {
+            if (2..8).contains(&x) && (2..8).contains(&y) {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([0, 0, 0])
+            }
+        });
+        frame.save(&reference).unwrap();
+        compare_with_reference(&frame, &reference, 0.0);
+
+        // A square moved right by one pixel differs along its left and right edges
+        let mut moved = RgbImage::new(10, 10);
+        for (x, y, pixel) in frame.enumerate_pixels() {
+            if x + 1 < 10 {
+                moved.put_pixel(x + 1, y, *pixel);
+            }
+        }
+        assert_eq!(frame_difference(&moved, &frame).unwrap(), 0.12);
+        compare_with_reference(&moved, &reference, 0.15);
+        assert!(catch_unwind(|| compare_with_reference(&moved, &reference, 0.1)).is_err());
+        let diff = open(directory.join("square.diff.png")).unwrap().to_rgb8();
+        assert_eq!(*diff.get_pixel(2, 4), Rgb([255, 0, 0]));
+        assert_eq!(*diff.get_pixel(4, 4), Rgb([63, 63, 63]));
+        assert_eq!(
+            open(directory.join("square.actual.png")).unwrap().to_rgb8(),
+            moved
+        );
+        assert!(
+            catch_unwind(|| compare_with_reference(&frame, &directory.join("none.png"), 0.0))
+                .is_err()
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+}